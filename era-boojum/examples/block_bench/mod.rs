@@ -0,0 +1,608 @@
+//! Shared setup of the block phase benchmarks: command line options, the gate configuration
+//! common to all phases, and the setup/prove/verify run over the synthesized circuit.
+#![allow(dead_code)]
+
+use std::alloc::Global;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use boojum::algebraic_props::round_function::AbsorptionModeOverwrite;
+use boojum::algebraic_props::sponge::{GoldilocksPoseidon2Sponge, GoldilocksPoseidonSponge};
+use boojum::config::{CSConfig, DevCSConfig, SetupCSConfig};
+use boojum::cs::cs_builder::{new_builder, CsBuilder, CsBuilderImpl};
+use boojum::cs::cs_builder_reference::CsReferenceImplementationBuilder;
+use boojum::cs::cs_builder_verifier::CsVerifierBuilder;
+use boojum::cs::gates::*;
+use boojum::cs::implementations::pow::{NoPow, PoWRunner};
+use boojum::cs::implementations::prover::ProofConfig;
+use boojum::cs::implementations::reference_cs::CSReferenceAssembly;
+use boojum::cs::implementations::setup::FinalizationHintsForProver;
+use boojum::cs::implementations::setup_cache::{
+    load_setup, save_setup, FullSetup, SetupFingerprint,
+};
+use boojum::cs::implementations::transcript::{
+    GoldilocksPoisedon2Transcript, GoldilocksPoisedonTranscript, Transcript,
+};
+use boojum::cs::oracle::TreeHasher;
+use boojum::cs::traits::cs::ConstraintSystem;
+use boojum::cs::traits::gate::GatePlacementStrategy;
+use boojum::cs::{CSGeometry, GateConfigurationHolder, LookupParameters, StaticToolboxHolder};
+use boojum::dag::resolvers::mt::sorters::record_storage::{FileRecordSource, FileRecordWriter};
+use boojum::dag::{
+    CircuitResolver, CircuitResolverOpts, DefaultCircuitResolver, PlaybackCircuitResolver,
+    RecordingCircuitResolver,
+};
+use boojum::field::goldilocks::{GoldilocksExt2, GoldilocksField};
+use boojum::implementations::poseidon2::Poseidon2Goldilocks;
+use boojum::log;
+use boojum::worker::Worker;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use structopt::StructOpt;
+
+pub type F = GoldilocksField;
+type RCfg = <DevCSConfig as CSConfig>::ResolverConfig;
+
+pub const GEOMETRY: CSGeometry = CSGeometry {
+    num_columns_under_copy_permutation: 80,
+    num_witness_columns: 0,
+    num_constant_columns: 4,
+    max_allowed_constraint_degree: 4,
+};
+
+// the quotient of the block circuits needs LDEs of this degree, whatever the FRI LDE factor
+const MIN_SETUP_LDE_FACTOR: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hasher {
+    Poseidon,
+    Poseidon2,
+}
+
+impl FromStr for Hasher {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "poseidon" => Ok(Hasher::Poseidon),
+            "poseidon2" => Ok(Hasher::Poseidon2),
+            _ => Err(format!(
+                "unknown hasher {}, expected poseidon or poseidon2",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pow {
+    None,
+    Blake2s,
+    Keccak,
+    Poseidon2,
+}
+
+impl FromStr for Pow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Pow::None),
+            "blake2s" => Ok(Pow::Blake2s),
+            "keccak" => Ok(Pow::Keccak),
+            "poseidon2" => Ok(Pow::Poseidon2),
+            _ => Err(format!(
+                "unknown PoW runner {}, expected none, blake2s, keccak or poseidon2",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, StructOpt)]
+pub struct BenchOptions {
+    /// Number of worker threads, all cores by default
+    #[structopt(long)]
+    pub threads: Option<usize>,
+
+    /// FRI LDE factor, i.e. the inverse rate of the code
+    #[structopt(long, default_value = "8")]
+    pub lde_factor: usize,
+
+    /// Size of the Merkle tree caps
+    #[structopt(long, default_value = "16")]
+    pub cap_size: usize,
+
+    /// Algebraic hash used for the Merkle trees and the transcript: poseidon or poseidon2
+    #[structopt(long, default_value = "poseidon2")]
+    pub hasher: Hasher,
+
+    /// Hash used for the proof of work: none, blake2s, keccak or poseidon2
+    #[structopt(long, default_value = "none")]
+    pub pow: Pow,
+
+    /// Number of leading zeros of the proof of work
+    #[structopt(long, default_value = "0")]
+    pub pow_bits: u32,
+
+    /// Seed of the circuit inputs of the first block, incremented for every next block
+    #[structopt(long, default_value = "42")]
+    pub seed: u64,
+
+    /// Number of blocks to prove
+    #[structopt(long, default_value = "1")]
+    pub blocks: usize,
+
+    /// File to record the witness resolution order to if it doesn't exist or was recorded for
+    /// another circuit, and to play it back from otherwise. `<circuit>.resolution` by default
+    #[structopt(long, parse(from_os_str))]
+    pub resolution_record: Option<PathBuf>,
+
+    /// Resolve the witness with the live sorter for every block, without recording
+    #[structopt(long)]
+    pub live: bool,
+
+    /// File to load the setup from if it was saved for the same circuit and parameters, and to
+    /// save it to otherwise. The setup is recomputed on every run if not given
+    #[structopt(long, parse(from_os_str))]
+    pub setup_cache: Option<PathBuf>,
+
+    /// Print the variables, gates, lookups and constants used by every section of the circuit
+    #[structopt(long)]
+    pub costs: bool,
+}
+
+impl BenchOptions {
+    pub fn worker(&self) -> Worker {
+        match self.threads {
+            Some(num_threads) => Worker::new_with_num_threads(num_threads),
+            None => Worker::new(),
+        }
+    }
+
+    fn resolution_record_path(&self, circuit_name: &str) -> PathBuf {
+        self.resolution_record
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("{}.resolution", circuit_name)))
+    }
+
+    fn proof_config(&self) -> ProofConfig {
+        assert!(
+            self.lde_factor.is_power_of_two() && self.lde_factor > 1,
+            "LDE factor must be a power of two above 1"
+        );
+        assert!(
+            (self.pow == Pow::None) == (self.pow_bits == 0),
+            "PoW bits must be given together with a PoW runner"
+        );
+
+        ProofConfig {
+            fri_lde_factor: self.lde_factor,
+            merkle_tree_cap_size: self.cap_size,
+            pow_bits: self.pow_bits,
+            ..Default::default()
+        }
+    }
+}
+
+/// Gates and lookup arguments of all the block phases, shared by the prover and the verifier
+pub fn configure<
+    T: CsBuilderImpl<F, T>,
+    GC: GateConfigurationHolder<F>,
+    TB: StaticToolboxHolder,
+>(
+    builder: CsBuilder<T, F, GC, TB>,
+    lookup_parameters: LookupParameters,
+) -> CsBuilder<T, F, impl GateConfigurationHolder<F>, impl StaticToolboxHolder> {
+    let builder = builder.allow_lookup(lookup_parameters);
+
+    let builder = ConstantsAllocatorGate::configure_builder(
+        builder,
+        GatePlacementStrategy::UseGeneralPurposeColumns,
+    );
+    let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
+        builder,
+        GatePlacementStrategy::UseGeneralPurposeColumns,
+    );
+    let builder = ReductionGate::<F, 4>::configure_builder(
+        builder,
+        GatePlacementStrategy::UseGeneralPurposeColumns,
+    );
+    let builder = DotProductGate::<4>::configure_builder(
+        builder,
+        GatePlacementStrategy::UseGeneralPurposeColumns,
+    );
+    let builder = UIntXAddGate::<16>::configure_builder(
+        builder,
+        GatePlacementStrategy::UseGeneralPurposeColumns,
+    );
+    let builder = UIntXAddGate::<32>::configure_builder(
+        builder,
+        GatePlacementStrategy::UseGeneralPurposeColumns,
+    );
+    let builder =
+        U8x4FMAGate::configure_builder(builder, GatePlacementStrategy::UseGeneralPurposeColumns);
+    let builder = BooleanConstraintGate::configure_builder(
+        builder,
+        GatePlacementStrategy::UseGeneralPurposeColumns,
+    );
+    let builder =
+        SelectionGate::configure_builder(builder, GatePlacementStrategy::UseGeneralPurposeColumns);
+    let builder =
+        NopGate::configure_builder(builder, GatePlacementStrategy::UseGeneralPurposeColumns);
+
+    builder
+}
+
+/// A block phase circuit. All blocks share its structure, only the inputs drawn from `rng` differ
+pub trait BlockCircuit {
+    const NAME: &'static str;
+    const LOOKUP_PARAMETERS: LookupParameters;
+    const MAX_TRACE_LEN: usize;
+    const MAX_VARIABLES: usize;
+
+    fn add_tables<CS: ConstraintSystem<F>>(cs: &mut CS);
+    fn synthesize<CS: ConstraintSystem<F>>(cs: &mut CS, rng: &mut StdRng);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ResolutionMode {
+    Live,
+    Recording,
+    Playback,
+}
+
+fn synthesize_into_assembly<C: BlockCircuit, CR: CircuitResolver<F, RCfg>>(
+    resolver_arg: CR::Arg,
+    rng: &mut StdRng,
+    report_costs: bool,
+) -> (
+    CSReferenceAssembly<F, F, DevCSConfig, Global>,
+    FinalizationHintsForProver,
+) {
+    let builder_impl =
+        CsReferenceImplementationBuilder::<F, F, DevCSConfig, CR>::new(GEOMETRY, C::MAX_TRACE_LEN);
+    let builder = new_builder::<_, F>(builder_impl);
+    let builder = configure(builder, C::LOOKUP_PARAMETERS);
+    let mut owned_cs = builder.build(resolver_arg);
+    if report_costs {
+        owned_cs.enable_cost_accounting();
+    }
+
+    C::add_tables(&mut owned_cs);
+    C::synthesize(&mut owned_cs, rng);
+
+    if let Some(report) = owned_cs.cost_report() {
+        println!("{} costs:\n{}", C::NAME, report);
+    }
+
+    let (_, finalization_hint) = owned_cs.pad_and_shrink();
+    // waits for the witness to be resolved
+    let assembly = owned_cs.into_assembly::<Global>();
+
+    (assembly, finalization_hint)
+}
+
+/// Structure of the circuit, synthesized without a witness: the shape together with the digest of
+/// the wiring and the constants. A resolution record is only played back for the circuit it was
+/// recorded with, otherwise the playback would misplace the resolvers. The block inputs are
+/// witnesses, so every block has the structure synthesized here from the first one
+fn circuit_fingerprint<C: BlockCircuit>(options: &BenchOptions) -> Vec<u8> {
+    let builder_impl =
+        CsReferenceImplementationBuilder::<F, F, SetupCSConfig>::new(GEOMETRY, C::MAX_TRACE_LEN);
+    let builder = new_builder::<_, F>(builder_impl);
+    let builder = configure(builder, C::LOOKUP_PARAMETERS);
+    let mut owned_cs = builder.build(CircuitResolverOpts::new(C::MAX_VARIABLES));
+
+    C::add_tables(&mut owned_cs);
+    C::synthesize(&mut owned_cs, &mut StdRng::seed_from_u64(options.seed));
+    let _ = owned_cs.pad_and_shrink();
+    let assembly = owned_cs.into_assembly::<Global>();
+
+    // the resolution order doesn't depend on the prover parameters, so they are left out
+    let SetupFingerprint {
+        geometry,
+        lookup_parameters,
+        trace_len,
+        public_inputs,
+        general_purpose_gates,
+        specialized_gates,
+        lookup_tables,
+        layout_digest,
+        ..
+    } = assembly.setup_fingerprint::<GoldilocksPoseidon2Sponge<AbsorptionModeOverwrite>>(
+        MIN_SETUP_LDE_FACTOR,
+        1,
+    );
+
+    bincode::serialize(&(
+        geometry,
+        lookup_parameters,
+        trace_len,
+        public_inputs,
+        general_purpose_gates,
+        specialized_gates,
+        lookup_tables,
+        layout_digest,
+    ))
+    .expect("circuit fingerprint is serializable")
+}
+
+fn synthesize_block<C: BlockCircuit>(
+    options: &BenchOptions,
+    block: usize,
+    record_path: &Path,
+    fingerprint: &[u8],
+) -> (
+    CSReferenceAssembly<F, F, DevCSConfig, Global>,
+    FinalizationHintsForProver,
+    ResolutionMode,
+) {
+    let mut rng = StdRng::seed_from_u64(options.seed + block as u64);
+    let resolver_opts = CircuitResolverOpts::new(C::MAX_VARIABLES);
+    // all the blocks have the same shape
+    let report_costs = options.costs && block == 0;
+
+    if options.live {
+        let (assembly, finalization_hint) = synthesize_into_assembly::<
+            C,
+            DefaultCircuitResolver<F, RCfg>,
+        >(resolver_opts, &mut rng, report_costs);
+
+        return (assembly, finalization_hint, ResolutionMode::Live);
+    }
+
+    let source = match FileRecordSource::open(record_path) {
+        Ok(source) if source.fingerprint() != fingerprint => {
+            log!(
+                "Resolution record {} was made for another circuit, recording it again",
+                record_path.display()
+            );
+            None
+        }
+        Ok(source) => Some(source),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        // e.g. written in an older format
+        Err(err) if err.kind() == std::io::ErrorKind::InvalidData => {
+            log!(
+                "Resolution record {} can't be played back ({}), recording it again",
+                record_path.display(),
+                err
+            );
+            None
+        }
+        Err(err) => panic!(
+            "failed to read resolution record {}: {}",
+            record_path.display(),
+            err
+        ),
+    };
+
+    match source {
+        Some(source) => {
+            let (assembly, finalization_hint) = synthesize_into_assembly::<
+                C,
+                PlaybackCircuitResolver<F, RCfg>,
+            >(source, &mut rng, report_costs);
+
+            (assembly, finalization_hint, ResolutionMode::Playback)
+        }
+        None => {
+            let writer = FileRecordWriter::with_fingerprint(record_path, fingerprint.to_vec());
+            let (assembly, finalization_hint) = synthesize_into_assembly::<
+                C,
+                RecordingCircuitResolver<F, RCfg>,
+            >(
+                (resolver_opts, writer), &mut rng, report_costs
+            );
+
+            (assembly, finalization_hint, ResolutionMode::Recording)
+        }
+    }
+}
+
+/// Proves and verifies `options.blocks` instances of the circuit with the hasher and PoW chosen
+/// in `options`, then reports the time spent generating the witness of each
+pub fn run<C: BlockCircuit>(options: &BenchOptions) {
+    match options.hasher {
+        Hasher::Poseidon => run_with_pow::<
+            C,
+            GoldilocksPoseidonSponge<AbsorptionModeOverwrite>,
+            GoldilocksPoisedonTranscript,
+        >(options),
+        Hasher::Poseidon2 => run_with_pow::<
+            C,
+            GoldilocksPoseidon2Sponge<AbsorptionModeOverwrite>,
+            GoldilocksPoisedon2Transcript,
+        >(options),
+    }
+}
+
+fn run_with_pow<
+    C: BlockCircuit,
+    T: TreeHasher<F, Output = TR::CompatibleCap>,
+    TR: Transcript<F, TransciptParameters = (), CompatibleCap = [F; 4]>,
+>(
+    options: &BenchOptions,
+) {
+    match options.pow {
+        Pow::None => run_with::<C, T, TR, NoPow>(options),
+        Pow::Blake2s => run_with::<C, T, TR, boojum::blake2::Blake2s256>(options),
+        Pow::Keccak => run_with::<C, T, TR, boojum::sha3::Keccak256>(options),
+        Pow::Poseidon2 => run_with::<C, T, TR, Poseidon2Goldilocks>(options),
+    }
+}
+
+fn run_with<
+    C: BlockCircuit,
+    T: TreeHasher<F, Output = TR::CompatibleCap>,
+    TR: Transcript<F, TransciptParameters = (), CompatibleCap = [F; 4]>,
+    POW: PoWRunner,
+>(
+    options: &BenchOptions,
+) {
+    let worker = options.worker();
+    let prover_config = options.proof_config();
+    let setup_lde_factor = std::cmp::max(options.lde_factor, MIN_SETUP_LDE_FACTOR);
+    let record_path = options.resolution_record_path(C::NAME);
+    // only needed to check the record before playing it back
+    let now = std::time::Instant::now();
+    let fingerprint = if options.live {
+        Vec::new()
+    } else {
+        circuit_fingerprint::<C>(options)
+    };
+    let fingerprint_time = now.elapsed();
+
+    let builder = new_builder::<_, F>(CsVerifierBuilder::<F, GoldilocksExt2>::new_from_parameters(
+        GEOMETRY,
+    ));
+    let builder = configure(builder, C::LOOKUP_PARAMETERS);
+    let verifier = builder.build(());
+
+    let mut full_setup = None;
+    let mut witness_times = Vec::with_capacity(options.blocks);
+    for block in 0..options.blocks {
+        let now = std::time::Instant::now();
+        let (mut assembly, finalization_hint, mode) =
+            synthesize_block::<C>(options, block, &record_path, &fingerprint);
+        let witness_time = now.elapsed();
+        log!(
+            "Block {}: witness generated with {:?} resolution, taken {:?}",
+            block,
+            mode,
+            witness_time
+        );
+        witness_times.push((mode, witness_time));
+
+        let report = assembly.check_if_satisfied_detailed(&worker);
+        assert!(report.is_satisfied(), "{}", report);
+
+        // all blocks share the circuit, so the setup is only obtained once
+        let full_setup = full_setup.get_or_insert_with(|| {
+            let now = std::time::Instant::now();
+            let full_setup = load_or_compute_setup::<T>(
+                options,
+                &assembly,
+                finalization_hint,
+                &worker,
+                setup_lde_factor,
+            );
+            log!("Setup is done, taken {:?}", now.elapsed());
+
+            full_setup
+        });
+
+        let witness_set = assembly.take_witness_using_hints(
+            &worker,
+            &full_setup.vars_hint,
+            &full_setup.wits_hint,
+        );
+
+        log!("Proving");
+        let now = std::time::Instant::now();
+        let proof = assembly.prove_cpu_basic::<GoldilocksExt2, TR, T, POW>(
+            &worker,
+            witness_set,
+            &full_setup.base_setup,
+            &full_setup.setup,
+            &full_setup.setup_tree,
+            &full_setup.vk,
+            prover_config.clone(),
+            (),
+        );
+        log!("Proving is done, taken {:?}", now.elapsed());
+
+        let mut buffer = Vec::new();
+        bincode::serialize_into(&mut buffer, &proof).unwrap();
+        println!("Proof size: {}", buffer.len());
+
+        log!("Verifying");
+        let now = std::time::Instant::now();
+        assert!(verifier.verify::<T, TR, POW>((), &full_setup.vk, &proof));
+        log!("Verifying is done, taken {:?}", now.elapsed());
+    }
+
+    report_witness_times(&witness_times, fingerprint_time);
+}
+
+fn load_or_compute_setup<T: TreeHasher<F, Output = [F; 4]>>(
+    options: &BenchOptions,
+    assembly: &CSReferenceAssembly<F, F, DevCSConfig, Global>,
+    finalization_hint: FinalizationHintsForProver,
+    worker: &Worker,
+    setup_lde_factor: usize,
+) -> FullSetup<F, F, T> {
+    let fingerprint = assembly.setup_fingerprint::<T>(setup_lde_factor, options.cap_size);
+
+    if let Some(path) = options.setup_cache.as_ref().filter(|path| path.exists()) {
+        match load_setup(path, &fingerprint) {
+            Ok(full_setup) => {
+                log!("Setup is loaded from {}", path.display());
+                return full_setup;
+            }
+            Err(err) => log!("Setup in {} is recomputed: {}", path.display(), err),
+        }
+    }
+
+    let full_setup = assembly.get_full_setup_with_hint::<T>(
+        worker,
+        finalization_hint,
+        setup_lde_factor,
+        options.cap_size,
+    );
+
+    if let Some(path) = options.setup_cache.as_ref() {
+        save_setup(path, &full_setup, &fingerprint)
+            .unwrap_or_else(|err| panic!("failed to save setup {}: {}", path.display(), err));
+        log!("Setup is saved to {}", path.display());
+    }
+
+    full_setup
+}
+
+/// `fingerprint_time` is the synthesis of the circuit without a witness that precedes any
+/// recording or playback, it is paid once per run
+fn report_witness_times(witness_times: &[(ResolutionMode, Duration)], fingerprint_time: Duration) {
+    let stats = |mode: ResolutionMode| {
+        let times: Vec<_> = witness_times
+            .iter()
+            .filter(|(el, _)| *el == mode)
+            .map(|(_, time)| *time)
+            .collect();
+        let mean = (!times.is_empty()).then(|| times.iter().sum::<Duration>() / times.len() as u32);
+
+        (times.len(), mean)
+    };
+
+    println!("Witness generation over {} blocks:", witness_times.len());
+    for mode in [
+        ResolutionMode::Live,
+        ResolutionMode::Recording,
+        ResolutionMode::Playback,
+    ] {
+        if let (num_blocks, Some(mean)) = stats(mode) {
+            println!("  {:?}: {} blocks, {:?} per block", mode, num_blocks, mean);
+        }
+    }
+
+    // recording resolves with the live sorter, so either is a baseline for playback
+    let baseline = stats(ResolutionMode::Recording)
+        .1
+        .or(stats(ResolutionMode::Live).1);
+    if let (Some(baseline), (num_blocks, Some(playback))) =
+        (baseline, stats(ResolutionMode::Playback))
+    {
+        let saved = baseline.saturating_sub(playback);
+        println!(
+            "  Playback saves {:?} per block ({:.1}%), {:?} over {} blocks after {:?} spent on \
+             checking the resolution record",
+            saved,
+            100.0 * saved.as_secs_f64() / baseline.as_secs_f64(),
+            (saved * num_blocks as u32).saturating_sub(fingerprint_time),
+            num_blocks,
+            fingerprint_time
+        );
+    }
+}
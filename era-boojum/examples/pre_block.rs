@@ -0,0 +1,89 @@
+//! Proves the pre-block phase: 256-bit comparisons and divisions.
+//!
+//! `cargo run --release --example pre_block -- --help` lists the options.
+#![feature(allocator_api)]
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+use boojum::cs::traits::cs::ConstraintSystem;
+use boojum::cs::LookupParameters;
+use boojum::ethereum_types::U256;
+use boojum::gadgets::boolean::Boolean;
+use boojum::gadgets::i256::Int256;
+use boojum::gadgets::tables::{create_range_check_16_bits_table, RangeCheck16BitsTable};
+use boojum::gadgets::traits::allocatable::CSAllocatable;
+use boojum::gadgets::u256::UInt256;
+use rand::rngs::StdRng;
+use rand::Rng;
+use structopt::StructOpt;
+
+use block_bench::{BenchOptions, BlockCircuit, F};
+
+mod block_bench;
+
+struct PreBlock;
+
+impl BlockCircuit for PreBlock {
+    const NAME: &'static str = "pre_block";
+    const LOOKUP_PARAMETERS: LookupParameters =
+        LookupParameters::UseSpecializedColumnsWithTableIdAsConstant {
+            width: 1,
+            num_repetitions: 10,
+            share_table_id: true,
+        };
+    const MAX_TRACE_LEN: usize = 1 << 18;
+    const MAX_VARIABLES: usize = 1 << 22;
+
+    fn add_tables<CS: ConstraintSystem<F>>(cs: &mut CS) {
+        let table = create_range_check_16_bits_table();
+        cs.add_lookup_table::<RangeCheck16BitsTable, 1>(table);
+    }
+
+    fn synthesize<CS: ConstraintSystem<F>>(cs: &mut CS, rng: &mut StdRng) {
+        let boolean_false = Boolean::allocated_constant(cs, false);
+
+        let random_uint256 = UInt256::allocate(cs, U256(rng.gen()));
+        let random_int256 = Int256 {
+            inner: random_uint256,
+        };
+
+        let one_u256 = UInt256::allocated_constant(cs, U256::one());
+
+        let (random_uint256_minus_one, _) = random_uint256.overflowing_sub(cs, &one_u256);
+
+        // Cmp
+        cs.push_namespace("cmp");
+        for _ in 0..1280 {
+            let _is_less = random_uint256.lt(cs, &random_uint256_minus_one);
+        }
+        cs.pop_namespace();
+
+        // Asserted Cmp
+        cs.push_namespace("asserted_cmp");
+        for _ in 0..1024 {
+            let is_less = random_uint256.lt(cs, &random_uint256_minus_one);
+            Boolean::enforce_equal(cs, &is_less, &boolean_false);
+        }
+        cs.pop_namespace();
+
+        // Is Negative
+        cs.push_namespace("is_negative");
+        for _ in 0..256 {
+            let _is_negative = random_int256.is_negative(cs);
+        }
+        cs.pop_namespace();
+
+        // Div
+        cs.push_namespace("div");
+        for _ in 0..256 {
+            let dividend = UInt256::allocate(cs, U256(rng.gen()));
+            let divisor = UInt256::allocate(cs, U256(rng.gen()) >> 128);
+            let (_quotient, _remainder) = dividend.div_rem(cs, &divisor);
+        }
+        cs.pop_namespace();
+    }
+}
+
+fn main() {
+    block_bench::run::<PreBlock>(&BenchOptions::from_args());
+}
@@ -0,0 +1,140 @@
+//! Proves the transaction loop phase: Poseidon2 permutations, bit (de)compositions and 160-bit
+//! arithmetic.
+//!
+//! `cargo run --release --example tx_loop -- --help` lists the options.
+#![feature(allocator_api)]
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+use boojum::cs::gates::ConstantAllocatableCS;
+use boojum::cs::traits::cs::ConstraintSystem;
+use boojum::cs::{LookupParameters, Variable};
+use boojum::ethereum_types::Address;
+use boojum::field::Field;
+use boojum::gadgets::boolean::Boolean;
+use boojum::gadgets::i160::Int160;
+use boojum::gadgets::num::Num;
+use boojum::gadgets::tables::{create_range_check_16_bits_table, RangeCheck16BitsTable};
+use boojum::gadgets::traits::allocatable::CSAllocatable;
+use boojum::gadgets::traits::round_function::CircuitRoundFunction;
+use boojum::gadgets::u160::UInt160;
+use boojum::implementations::poseidon2::Poseidon2Goldilocks;
+use rand::rngs::StdRng;
+use rand::Rng;
+use structopt::StructOpt;
+
+use block_bench::{BenchOptions, BlockCircuit, F};
+
+mod block_bench;
+
+struct TxLoop;
+
+impl BlockCircuit for TxLoop {
+    const NAME: &'static str = "tx_loop";
+    const LOOKUP_PARAMETERS: LookupParameters =
+        LookupParameters::UseSpecializedColumnsWithTableIdAsConstant {
+            width: 1,
+            num_repetitions: 10,
+            share_table_id: true,
+        };
+    const MAX_TRACE_LEN: usize = 1 << 20;
+    const MAX_VARIABLES: usize = 1 << 23;
+
+    fn add_tables<CS: ConstraintSystem<F>>(cs: &mut CS) {
+        let table = create_range_check_16_bits_table();
+        cs.add_lookup_table::<RangeCheck16BitsTable, 1>(table);
+    }
+
+    fn synthesize<CS: ConstraintSystem<F>>(cs: &mut CS, rng: &mut StdRng) {
+        let boolean_false = Boolean::allocated_constant(cs, false);
+
+        // the rollup values are 160 bits wide
+        let random_uint160 = UInt160::allocate(cs, Address(rng.gen()));
+        let random_int160 = Int160 {
+            inner: random_uint160,
+        };
+
+        let one_u160 = UInt160::allocated_constant(cs, Address::from_low_u64_be(1));
+
+        let (random_uint160_minus_one, _) = random_uint160.overflowing_sub(cs, &one_u160);
+
+        // Poseidon2
+        cs.push_namespace("poseidon2");
+        for _ in 0..5652 {
+            let mut inputs = [Variable::placeholder(); 12];
+            for (idx, dst) in inputs[..8].iter_mut().enumerate() {
+                *dst =
+                    cs.alloc_single_variable_from_witness(F::from_u64_with_reduction(idx as u64));
+            }
+            let capacity_var = cs.allocate_constant(F::ZERO);
+            for dst in inputs[8..].iter_mut() {
+                *dst = capacity_var;
+            }
+
+            let _round_function_result = Poseidon2Goldilocks::compute_round_function(cs, inputs);
+        }
+        cs.pop_namespace();
+
+        // ToBinary
+        cs.push_namespace("to_binary");
+        let mut bits = vec![];
+        for _ in 0..65 {
+            bits = random_uint160.to_le_bits(cs);
+        }
+        cs.pop_namespace();
+
+        // FromBinary
+        cs.push_namespace("from_binary");
+        for _ in 0..236 {
+            let recovered = UInt160::from_le_bits(cs, &bits);
+            for (limb, recovered) in random_uint160.inner.iter().zip(recovered.inner.iter()) {
+                Num::enforce_equal(cs, &limb.into_num(), &recovered.into_num());
+            }
+        }
+        cs.pop_namespace();
+
+        // Div
+        cs.push_namespace("div");
+        for _ in 0..13 {
+            let dividend = UInt160::allocate(cs, Address(rng.gen()));
+            let mut divisor = Address(rng.gen());
+            divisor.0[..10].fill(0);
+            let divisor = UInt160::allocate(cs, divisor);
+            let (_quotient, _remainder) = dividend.div_rem(cs, &divisor);
+        }
+        cs.pop_namespace();
+
+        // Abs
+        cs.push_namespace("abs");
+        for _ in 0..4 {
+            let _abs = random_int160.abs(cs);
+        }
+        cs.pop_namespace();
+
+        // Cmp
+        cs.push_namespace("cmp");
+        for _ in 0..49 {
+            let _is_less = random_uint160.lt(cs, &random_uint160_minus_one);
+        }
+        cs.pop_namespace();
+
+        // Asserted Cmp
+        cs.push_namespace("asserted_cmp");
+        for _ in 0..19 {
+            let is_less = random_uint160.lt(cs, &random_uint160_minus_one);
+            Boolean::enforce_equal(cs, &is_less, &boolean_false);
+        }
+        cs.pop_namespace();
+
+        // Is Negative
+        cs.push_namespace("is_negative");
+        for _ in 0..6 {
+            let _is_negative = random_int160.is_negative(cs);
+        }
+        cs.pop_namespace();
+    }
+}
+
+fn main() {
+    block_bench::run::<TxLoop>(&BenchOptions::from_args());
+}
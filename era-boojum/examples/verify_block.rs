@@ -0,0 +1,156 @@
+//! Proves the block verification phase: Keccak256 over the block data, a Poseidon2 permutation,
+//! bit (de)compositions and 256-bit comparisons.
+//!
+//! `cargo run --release --example verify_block -- --help` lists the options.
+#![feature(allocator_api)]
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+use boojum::config::CSConfig;
+use boojum::cs::gates::ConstantAllocatableCS;
+use boojum::cs::traits::cs::ConstraintSystem;
+use boojum::cs::{LookupParameters, Variable};
+use boojum::ethereum_types::U256;
+use boojum::field::Field;
+use boojum::gadgets::boolean::Boolean;
+use boojum::gadgets::keccak256::keccak256;
+use boojum::gadgets::num::Num;
+use boojum::gadgets::tables::{
+    create_and8_table, create_byte_split_table, create_xor8_table, And8Table, ByteSplitTable,
+    Xor8Table,
+};
+use boojum::gadgets::traits::allocatable::CSAllocatable;
+use boojum::gadgets::traits::round_function::CircuitRoundFunction;
+use boojum::gadgets::traits::witnessable::WitnessHookable;
+use boojum::gadgets::u256::UInt256;
+use boojum::gadgets::u8::UInt8;
+use boojum::implementations::poseidon2::Poseidon2Goldilocks;
+use boojum::sha3::Digest;
+use rand::rngs::StdRng;
+use rand::Rng;
+use structopt::StructOpt;
+
+use block_bench::{BenchOptions, BlockCircuit, F};
+
+mod block_bench;
+
+struct VerifyBlock;
+
+impl BlockCircuit for VerifyBlock {
+    const NAME: &'static str = "verify_block";
+    const LOOKUP_PARAMETERS: LookupParameters =
+        LookupParameters::UseSpecializedColumnsWithTableIdAsConstant {
+            width: 3,
+            num_repetitions: 5,
+            share_table_id: true,
+        };
+    const MAX_TRACE_LEN: usize = 1 << 18;
+    const MAX_VARIABLES: usize = 1 << 22;
+
+    fn add_tables<CS: ConstraintSystem<F>>(cs: &mut CS) {
+        let table = create_xor8_table();
+        cs.add_lookup_table::<Xor8Table, 3>(table);
+        let table = create_and8_table();
+        cs.add_lookup_table::<And8Table, 3>(table);
+        let table = create_byte_split_table::<F, 1>();
+        cs.add_lookup_table::<ByteSplitTable<1>, 3>(table);
+        let table = create_byte_split_table::<F, 2>();
+        cs.add_lookup_table::<ByteSplitTable<2>, 3>(table);
+        let table = create_byte_split_table::<F, 3>();
+        cs.add_lookup_table::<ByteSplitTable<3>, 3>(table);
+        let table = create_byte_split_table::<F, 4>();
+        cs.add_lookup_table::<ByteSplitTable<4>, 3>(table);
+    }
+
+    fn synthesize<CS: ConstraintSystem<F>>(cs: &mut CS, rng: &mut StdRng) {
+        let boolean_false = Boolean::allocated_constant(cs, false);
+
+        let random_uint256 = UInt256::allocate(cs, U256(rng.gen()));
+
+        let one_u256 = UInt256::allocated_constant(cs, U256::one());
+
+        let (random_uint256_minus_one, _) = random_uint256.overflowing_sub(cs, &one_u256);
+
+        // 2 rounds of Keccak256, each over 242 bytes
+        cs.push_namespace("keccak256");
+        for _ in 0..2 {
+            let input: Vec<u8> = (0..242).map(|_| rng.gen()).collect();
+            let reference_output = boojum::sha3::Keccak256::digest(&input);
+
+            let mut circuit_input = vec![];
+            for chunk in input.chunks(2) {
+                match *chunk {
+                    [first, second] => {
+                        circuit_input.extend(UInt8::allocate_pair(cs, [first, second]))
+                    }
+                    [last] => circuit_input.push(UInt8::allocate_checked(cs, last)),
+                    _ => unreachable!(),
+                }
+            }
+
+            let output = keccak256(cs, &circuit_input);
+            // there is no witness when only the shape of the circuit is synthesized
+            if <CS::Config as CSConfig>::WitnessConfig::EVALUATE_WITNESS {
+                let output = output.witness_hook(&*cs)().unwrap();
+                assert_eq!(output.as_slice(), reference_output.as_slice());
+            }
+        }
+        cs.pop_namespace();
+
+        // Poseidon2
+        cs.push_namespace("poseidon2");
+        for _ in 0..1 {
+            let mut inputs = [Variable::placeholder(); 12];
+            for (idx, dst) in inputs[..8].iter_mut().enumerate() {
+                *dst =
+                    cs.alloc_single_variable_from_witness(F::from_u64_with_reduction(idx as u64));
+            }
+            let capacity_var = cs.allocate_constant(F::ZERO);
+            for dst in inputs[8..].iter_mut() {
+                *dst = capacity_var;
+            }
+
+            let _round_function_result = Poseidon2Goldilocks::compute_round_function(cs, inputs);
+        }
+        cs.pop_namespace();
+
+        // ToBinary
+        cs.push_namespace("to_binary");
+        let mut bits = vec![];
+        for _ in 0..84 {
+            bits = random_uint256.to_le_bits_using_table(cs);
+        }
+        cs.pop_namespace();
+
+        // FromBinary
+        cs.push_namespace("from_binary");
+        for _ in 0..484 {
+            let recovered = UInt256::from_le_bits(cs, &bits);
+            for (limb, recovered) in random_uint256.inner.iter().zip(recovered.inner.iter()) {
+                Num::enforce_equal(cs, &limb.into_num(), &recovered.into_num());
+            }
+        }
+        cs.pop_namespace();
+
+        // Cmp
+        cs.push_namespace("cmp");
+        for _ in 0..3 {
+            let (_, _minuend_is_less_than_subtrahend) =
+                random_uint256.overflowing_sub(cs, &random_uint256_minus_one);
+        }
+        cs.pop_namespace();
+
+        // Asserted Cmp
+        cs.push_namespace("asserted_cmp");
+        for _ in 0..1 {
+            let (_, minuend_is_less_than_subtrahend) =
+                random_uint256.overflowing_sub(cs, &random_uint256_minus_one);
+            Boolean::enforce_equal(cs, &minuend_is_less_than_subtrahend, &boolean_false);
+        }
+        cs.pop_namespace();
+    }
+}
+
+fn main() {
+    block_bench::run::<VerifyBlock>(&BenchOptions::from_args());
+}
@@ -23,6 +23,7 @@ use super::{
 };
 use super::{CSGeometry, GateTypeEntry, LookupParameters, Tool, Variable, Witness};
 use crate::cs::implementations::evaluator_data::*;
+use crate::cs::implementations::namespaces::CellNamespaces;
 use crate::cs::traits::gate::GatePlacementStrategy;
 
 pub struct CsReferenceImplementationBuilder<
@@ -455,6 +456,8 @@ impl<
             evaluation_data_over_general_purpose_columns,
             evaluation_data_over_specialized_columns,
             specialized_gates_rough_stats: HashMap::with_capacity(16),
+            namespaces: CellNamespaces::default(),
+            cost_accounting: None,
             gates_application_sets,
             copy_permutation_data,
             witness_placement_data,
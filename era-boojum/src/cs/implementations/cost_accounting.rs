@@ -0,0 +1,303 @@
+//! Breakdown of the circuit cost over the namespaces pushed with
+//! `ConstraintSystem::push_namespace`, enabled with
+//! `CSReferenceImplementation::enable_cost_accounting`.
+use std::collections::{BTreeMap, HashMap};
+
+use super::namespaces::CellNamespaces;
+use super::reference_cs::CSReferenceImplementation;
+use super::*;
+
+use crate::config::CSConfig;
+use crate::cs::{GateConfigurationHolder, StaticToolboxHolder};
+use crate::dag::CircuitResolver;
+use crate::field::SmallField;
+
+/// What was allocated and placed in a scope
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScopeCost {
+    pub variables: usize,
+    pub witnesses: usize,
+    /// Constants requested by the gates, per row or per instance
+    pub constants: usize,
+    /// Rows taken in the general purpose columns, per gate
+    pub general_purpose_rows: BTreeMap<String, usize>,
+    /// Instances placed in the general purpose columns, per gate. Gates that fit several times
+    /// into a row place more instances than they take rows
+    pub general_purpose_instances: BTreeMap<String, usize>,
+    /// Instances placed in the specialized columns, per gate
+    pub specialized_instances: BTreeMap<String, usize>,
+    /// Enforced lookups, per table
+    pub lookups: BTreeMap<String, usize>,
+}
+
+impl ScopeCost {
+    pub fn add_assign(&mut self, other: &Self) {
+        self.variables += other.variables;
+        self.witnesses += other.witnesses;
+        self.constants += other.constants;
+        for (dst, src) in [
+            (&mut self.general_purpose_rows, &other.general_purpose_rows),
+            (
+                &mut self.general_purpose_instances,
+                &other.general_purpose_instances,
+            ),
+            (
+                &mut self.specialized_instances,
+                &other.specialized_instances,
+            ),
+            (&mut self.lookups, &other.lookups),
+        ] {
+            for (name, count) in src.iter() {
+                *dst.entry(name.clone()).or_default() += count;
+            }
+        }
+    }
+
+    pub fn total_general_purpose_rows(&self) -> usize {
+        self.general_purpose_rows.values().sum()
+    }
+}
+
+/// Costs of the scopes themselves, keyed by the namespace that was active
+#[derive(Clone, Debug, Default)]
+pub struct CostAccounting {
+    costs: HashMap<Option<u32>, ScopeCost>,
+}
+
+impl CostAccounting {
+    #[inline]
+    pub fn current_mut(&mut self, namespaces: &CellNamespaces) -> &mut ScopeCost {
+        self.costs.entry(namespaces.current()).or_default()
+    }
+
+    pub fn report(&self, namespaces: &CellNamespaces) -> CostReport {
+        let mut scopes: BTreeMap<Vec<u32>, ScopeCost> = BTreeMap::new();
+        scopes.insert(vec![], ScopeCost::default());
+
+        // every scope includes the costs of the nested ones
+        for (id, cost) in self.costs.iter() {
+            let path = id.map(|id| namespaces.ancestry(id)).unwrap_or_default();
+            for depth in 0..=path.len() {
+                scopes
+                    .entry(path[..depth].to_vec())
+                    .or_default()
+                    .add_assign(cost);
+            }
+        }
+
+        let scopes = scopes
+            .into_iter()
+            .map(|(path, cost)| {
+                let name = match path.last() {
+                    Some(id) => namespaces.path(*id).to_owned(),
+                    None => String::new(),
+                };
+
+                (path.len(), name, cost)
+            })
+            .collect();
+
+        CostReport { scopes }
+    }
+}
+
+/// Costs of every scope including the nested ones, in the order the scopes were first pushed
+#[derive(Clone, Debug)]
+pub struct CostReport {
+    /// Depth, full path and cost of every scope, starting with the whole circuit at depth 0
+    pub scopes: Vec<(usize, String, ScopeCost)>,
+}
+
+impl CostReport {
+    pub fn scope(&self, path: &str) -> Option<&ScopeCost> {
+        self.scopes
+            .iter()
+            .find(|(_, name, _)| name == path)
+            .map(|(_, _, cost)| cost)
+    }
+
+    pub fn total(&self) -> &ScopeCost {
+        &self.scopes[0].2
+    }
+}
+
+impl std::fmt::Display for CostReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (depth, path, cost) in self.scopes.iter() {
+            let indent = "  ".repeat(*depth);
+            let name = match path.rsplit_once('/') {
+                Some((_, name)) => name,
+                None if *depth == 0 => "circuit",
+                None => path,
+            };
+            writeln!(
+                f,
+                "{}{}: {} rows, {} variables, {} witnesses, {} constants",
+                indent,
+                name,
+                cost.total_general_purpose_rows(),
+                cost.variables,
+                cost.witnesses,
+                cost.constants
+            )?;
+            for (gate, instances) in cost.general_purpose_instances.iter() {
+                let rows = cost.general_purpose_rows.get(gate).copied().unwrap_or(0);
+                writeln!(
+                    f,
+                    "{}  | {}: {} rows, {} instances",
+                    indent, gate, rows, instances
+                )?;
+            }
+            for (gate, instances) in cost.specialized_instances.iter() {
+                writeln!(
+                    f,
+                    "{}  | {}: {} specialized instances",
+                    indent, gate, instances
+                )?;
+            }
+            for (table, lookups) in cost.lookups.iter() {
+                writeln!(f, "{}  | {}: {} lookups", indent, table, lookups)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<
+        F: SmallField,
+        P: field::traits::field_like::PrimeFieldLikeVectorized<Base = F>,
+        CFG: CSConfig,
+        GC: GateConfigurationHolder<F>,
+        T: StaticToolboxHolder,
+        CR: CircuitResolver<F, CFG::ResolverConfig>,
+    > CSReferenceImplementation<F, P, CFG, GC, T, CR>
+{
+    /// Starts counting what is allocated and placed from now on, per namespace. Should be called
+    /// outside of any namespace, as the ones pushed before are not tracked without it in
+    /// the proving configurations
+    pub fn enable_cost_accounting(&mut self) {
+        if self.cost_accounting.is_none() {
+            self.cost_accounting = Some(CostAccounting::default());
+        }
+    }
+
+    pub fn cost_report(&self) -> Option<CostReport> {
+        self.cost_accounting
+            .as_ref()
+            .map(|el| el.report(&self.namespaces))
+    }
+}
+
+/// Type name without the module paths, keeping the generic parameters
+pub fn short_type_name<T: ?Sized>() -> String {
+    let full = std::any::type_name::<T>();
+    let mut result = String::with_capacity(full.len());
+    let mut identifier_start = 0;
+    let mut chars = full.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            result.truncate(identifier_start);
+        } else {
+            result.push(ch);
+            if ch.is_alphanumeric() == false && ch != '_' {
+                identifier_start = result.len();
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::DevCSConfig;
+    use crate::cs::cs_builder::*;
+    use crate::cs::cs_builder_reference::CsReferenceImplementationBuilder;
+    use crate::cs::gates::fma_gate_without_constant::*;
+    use crate::cs::gates::{ConstantAllocatableCS, ConstantsAllocatorGate, NopGate};
+    use crate::cs::traits::cs::ConstraintSystem;
+    use crate::cs::traits::gate::GatePlacementStrategy;
+    use crate::cs::CSGeometry;
+    use crate::dag::CircuitResolverOpts;
+    use crate::field::goldilocks::GoldilocksField;
+    use crate::field::{Field, U64Representable};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn short_names() {
+        assert_eq!(
+            short_type_name::<FmaGateInBaseFieldWithoutConstant<F>>(),
+            "FmaGateInBaseFieldWithoutConstant<GoldilocksField>"
+        );
+        assert_eq!(short_type_name::<[Vec<u8>; 2]>(), "[Vec<u8>; 2]");
+    }
+
+    #[test]
+    fn costs_are_nested() {
+        let geometry = CSGeometry {
+            num_columns_under_copy_permutation: 8,
+            num_witness_columns: 0,
+            num_constant_columns: 2,
+            max_allowed_constraint_degree: 4,
+        };
+
+        let builder_impl = CsReferenceImplementationBuilder::<F, F, DevCSConfig>::new(geometry, 64);
+        let builder = new_builder::<_, F>(builder_impl);
+        let builder = ConstantsAllocatorGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder =
+            NopGate::configure_builder(builder, GatePlacementStrategy::UseGeneralPurposeColumns);
+        let mut cs = builder.build(CircuitResolverOpts::new(256));
+        cs.enable_cost_accounting();
+
+        let a = cs.alloc_single_variable_from_witness(F::from_u64_unchecked(1));
+
+        cs.push_namespace("outer");
+        let mut previous = a;
+        for _ in 0..4 {
+            previous = FmaGateInBaseFieldWithoutConstant::compute_fma(
+                &mut cs,
+                F::TWO,
+                (previous, a),
+                F::ONE,
+                a,
+            );
+        }
+        cs.push_namespace("inner");
+        let _ = cs.allocate_constant(F::from_u64_unchecked(7));
+        cs.pop_namespace();
+        cs.pop_namespace();
+
+        let report = cs.cost_report().unwrap();
+
+        let fma_name = short_type_name::<FmaGateInBaseFieldWithoutConstant<F>>();
+        let inner = report.scope("outer/inner").unwrap();
+        assert_eq!(inner.variables, 1);
+        assert_eq!(inner.general_purpose_rows.get(&fma_name), None);
+
+        let outer = report.scope("outer").unwrap();
+        assert_eq!(outer.variables, 4 + 1);
+        // two instances per row
+        assert_eq!(outer.general_purpose_rows[&fma_name], 2);
+        assert_eq!(outer.general_purpose_instances[&fma_name], 4);
+        assert_eq!(
+            outer.total_general_purpose_rows(),
+            2 + inner.total_general_purpose_rows()
+        );
+
+        assert_eq!(report.total().variables, 1 + outer.variables);
+        assert_eq!(report.scopes.len(), 3);
+        assert_eq!(report.scopes[0].0, 0);
+        assert_eq!(report.scopes[2].0, 2);
+    }
+}
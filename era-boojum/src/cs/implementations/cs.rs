@@ -22,6 +22,7 @@ use crate::cs::traits::gate::Gate;
 
 use crate::dag::CSWitnessValues;
 
+use crate::cs::implementations::cost_accounting::short_type_name;
 use crate::cs::implementations::reference_cs::*;
 
 impl<
@@ -63,6 +64,9 @@ impl<
     fn alloc_variable_without_value(&mut self) -> Variable {
         let var = Variable::from_variable_index(self.next_available_place_idx);
         self.next_available_place_idx += 1;
+        if let Some(costs) = self.cost_accounting.as_mut() {
+            costs.current_mut(&self.namespaces).variables += 1;
+        }
 
         var
     }
@@ -71,6 +75,9 @@ impl<
         debug_assert!(N < u32::MAX as usize);
         let current_idx = self.next_available_place_idx;
         self.next_available_place_idx += N as u64;
+        if let Some(costs) = self.cost_accounting.as_mut() {
+            costs.current_mut(&self.namespaces).variables += N;
+        }
 
         let result: [Variable; N] =
             std::array::from_fn(|i| Variable::from_variable_index(current_idx + (i as u64)));
@@ -81,6 +88,9 @@ impl<
     fn alloc_witness_without_value(&mut self) -> Witness {
         let wit = Witness::from_witness_index(self.next_available_place_idx);
         self.next_available_place_idx += 1;
+        if let Some(costs) = self.cost_accounting.as_mut() {
+            costs.current_mut(&self.namespaces).witnesses += 1;
+        }
 
         wit
     }
@@ -88,6 +98,9 @@ impl<
     fn alloc_multiple_witnesses_without_values<const N: usize>(&mut self) -> [Witness; N] {
         let current_idx = self.next_available_place_idx;
         self.next_available_place_idx += N as u64;
+        if let Some(costs) = self.cost_accounting.as_mut() {
+            costs.current_mut(&self.namespaces).witnesses += N;
+        }
 
         let result: [Witness; N] =
             std::array::from_fn(|i| Witness::from_witness_index(current_idx + (i as u64)));
@@ -332,6 +345,30 @@ impl<
             .expect("gate must be allowed")
     }
 
+    fn push_namespace(&mut self, name: &str) {
+        // tags are only kept for debugging, where the circuit is checked for satisfiability,
+        // or to attribute the costs
+        if self.cost_accounting.is_none()
+            && (<Self::Config as CSConfig>::DebugConfig::PERFORM_RUNTIME_ASSERTS == false
+                || <Self::Config as CSConfig>::SetupConfig::KEEP_SETUP == false)
+        {
+            return;
+        }
+
+        self.namespaces.push(name);
+    }
+
+    fn pop_namespace(&mut self) {
+        if self.cost_accounting.is_none()
+            && (<Self::Config as CSConfig>::DebugConfig::PERFORM_RUNTIME_ASSERTS == false
+                || <Self::Config as CSConfig>::SetupConfig::KEEP_SETUP == false)
+        {
+            return;
+        }
+
+        self.namespaces.pop();
+    }
+
     #[inline]
     fn next_available_row(&self) -> usize {
         self.next_available_row
@@ -353,6 +390,7 @@ impl<
         }
 
         self.copy_permutation_data[column][row] = var;
+        self.tag_cell(column, row);
     }
     #[inline]
     fn place_constants<const N: usize>(
@@ -375,6 +413,10 @@ impl<
             return;
         }
 
+        if let Some(costs) = self.cost_accounting.as_mut() {
+            costs.current_mut(&self.namespaces).constants += N;
+        }
+
         if offset == 0 {
             if row < self.constants_requested_per_row.len() {
                 // some gates may reuse constants
@@ -431,6 +473,14 @@ impl<
             std::any::type_name::<G>()
         );
 
+        if let Some(costs) = self.cost_accounting.as_mut() {
+            *costs
+                .current_mut(&self.namespaces)
+                .general_purpose_instances
+                .entry(short_type_name::<G>())
+                .or_default() += 1;
+        }
+
         if <Self::Config as CSConfig>::SetupConfig::KEEP_SETUP == false {
             // we do not care about placement
             return;
@@ -457,6 +507,13 @@ impl<
             self.next_available_row += 1;
             debug_assert!(self.gates_application_sets.len() == row);
             self.gates_application_sets.push(idx);
+            if let Some(costs) = self.cost_accounting.as_mut() {
+                *costs
+                    .current_mut(&self.namespaces)
+                    .general_purpose_rows
+                    .entry(short_type_name::<G>())
+                    .or_default() += 1;
+            }
         } else {
             debug_assert!(matches!(
                 gate.placement_type(),
@@ -541,6 +598,7 @@ impl<
             self.copy_permutation_data[offset].push(var);
             debug_assert_eq!(self.copy_permutation_data[offset].len(), row + 1);
         }
+        self.tag_cell(offset, row);
     }
     #[inline]
     fn place_witness_specialized<G: Gate<F>>(
@@ -609,6 +667,14 @@ impl<
             std::any::type_name::<G>()
         );
 
+        if let Some(costs) = self.cost_accounting.as_mut() {
+            *costs
+                .current_mut(&self.namespaces)
+                .specialized_instances
+                .entry(short_type_name::<G>())
+                .or_default() += 1;
+        }
+
         if <Self::Config as CSConfig>::SetupConfig::KEEP_SETUP == false {
             // we do not care about placement
             return;
@@ -654,6 +720,10 @@ impl<
             std::any::type_name::<G>()
         );
 
+        if let Some(costs) = self.cost_accounting.as_mut() {
+            costs.current_mut(&self.namespaces).constants += N;
+        }
+
         // we are already given a repetition number, so we just need to get the offset
         let placement_strategy = self
             .gates_configuration
@@ -802,6 +872,7 @@ impl<
                 self.copy_permutation_data[offset].push(*var);
                 debug_assert_eq!(self.copy_permutation_data[offset].len(), row + 1);
             }
+            self.tag_cell(offset, row);
             offset += 1;
         }
     }
@@ -858,6 +929,15 @@ impl<
     }
 
     fn enforce_lookup<const N: usize>(&mut self, table_id: u32, keys_and_values: &[Variable; N]) {
+        if self.cost_accounting.is_some() {
+            let table_name = self.get_table(table_id).name().to_owned();
+            let costs = self.cost_accounting.as_mut().unwrap();
+            *costs
+                .current_mut(&self.namespaces)
+                .lookups
+                .entry(table_name)
+                .or_default() += 1;
+        }
         match self.lookup_parameters {
             LookupParameters::NoLookup => {
                 panic!("Lookup is not allowed for that CS");
@@ -935,6 +1015,7 @@ impl<
 
         for (offset, var) in var.iter().enumerate() {
             self.copy_permutation_data[starting_column + offset][row] = *var;
+            self.tag_cell(starting_column + offset, row);
         }
     }
 
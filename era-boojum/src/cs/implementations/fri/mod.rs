@@ -31,6 +31,8 @@ use crate::fft::bitreverse_enumeration_inplace;
 
 use crate::field::{Field, FieldExtension};
 
+pub mod security;
+
 pub struct FriOracles<
     F: SmallField,
     H: TreeHasher<F>,
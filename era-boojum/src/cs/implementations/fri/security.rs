@@ -0,0 +1,261 @@
+// Bits of security reached by a FRI configuration, in two regimes:
+// - conjectured (ethSTARK paper): every query gives `log2(lde_factor)` bits on top of the PoW bits,
+// capped by the size of the field we draw challenges from. This is what `ProofConfig::security_level`
+// is met against by `compute_fri_schedule`
+// - provable: proximity gaps of RS codes up to the Johnson bound (BCIKS20, Theorem 8.3, as used in the
+// ethSTARK paper). Every query only gives about `log2(lde_factor) / 2` bits, and the commit phase
+// (batching of all the opened polynomials and every folding step) has an error that grows with the domain size
+
+// Note that FRI challenges are drawn from the quadratic extension, so the field size is `2 * F::CHAR_BITS`
+
+use super::*;
+use crate::cs::implementations::prover::{compute_fri_schedule, ProofConfig};
+
+/// Largest Johnson proximity parameter `m` we try when maximizing the provable security
+const MAX_JOHNSON_M: usize = 1 << 10;
+
+pub fn fri_field_bits<F: SmallField>() -> usize {
+    2 * F::CHAR_BITS
+}
+
+pub fn conjectured_security_bits(
+    field_bits: usize,
+    rate_log_two: u32,
+    num_queries: usize,
+    pow_bits: u32,
+) -> usize {
+    let query_bits = num_queries * rate_log_two as usize + pow_bits as usize;
+
+    std::cmp::min(field_bits, query_bits)
+}
+
+/// Minimum number of queries to reach `security_bits` of conjectured security, or `None`
+/// if it's above the field size. There is always at least one query, however many bits the PoW
+/// gives
+pub fn min_conjectured_num_queries(
+    security_bits: usize,
+    field_bits: usize,
+    rate_log_two: u32,
+    pow_bits: u32,
+) -> Option<usize> {
+    if security_bits > field_bits || rate_log_two == 0 {
+        return None;
+    }
+
+    let num_queries = security_bits
+        .saturating_sub(pow_bits as usize)
+        .div_ceil(rate_log_two as usize);
+
+    Some(std::cmp::max(num_queries, 1))
+}
+
+/// Parameters of a single FRI instance that define its provable soundness
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FriSoundnessParams {
+    pub field_bits: usize,
+    pub rate_log_two: u32,
+    pub domain_size_log_two: u32,
+    pub folding_schedule: Vec<usize>,
+    pub num_queries: usize,
+    pub pow_bits: u32,
+    // number of polynomials combined into the first FRI oracle
+    pub num_polys: usize,
+}
+
+impl FriSoundnessParams {
+    pub fn provable_security_bits(&self) -> f64 {
+        (3..=MAX_JOHNSON_M)
+            .map(|m| {
+                let m = m as f64;
+                f64::min(
+                    self.commit_phase_security_bits(m),
+                    self.query_phase_security_bits(m),
+                )
+            })
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Minimum number of queries to reach `security_bits` of provable security, or `None`
+    /// if the commit phase alone is below it
+    pub fn min_provable_num_queries(&self, security_bits: usize) -> Option<usize> {
+        // commit phase error is the smallest for the smallest `m`, and we can make query phase
+        // error as small as we want
+        if self.commit_phase_security_bits(3.0) < security_bits as f64 {
+            return None;
+        }
+
+        let mut params = self.clone();
+        params.num_queries = 1;
+        while params.provable_security_bits() < security_bits as f64 {
+            params.num_queries += 1;
+        }
+
+        Some(params.num_queries)
+    }
+
+    fn commit_phase_security_bits(&self, m: f64) -> f64 {
+        let rate = 1.0 / (1u64 << self.rate_log_two) as f64;
+        let lde_size = (1u64 << (self.domain_size_log_two + self.rate_log_two)) as f64;
+        let batching_error = (m + 0.5).powi(7) / (3.0 * rate.powf(1.5)) * lde_size * lde_size;
+        let folding_error = (2.0 * m + 1.0) * (lde_size + 1.0) / rate.sqrt();
+        let num_batched = std::cmp::max(self.num_polys, 2) - 1;
+        let total_arity: usize = self.folding_schedule.iter().map(|&el| 1 << el).sum();
+        let error = batching_error * num_batched as f64 + folding_error * total_arity as f64;
+
+        self.field_bits as f64 - error.log2()
+    }
+
+    // every query passes with probability at most `sqrt(rate) * (1 + 1/2m)`
+    fn query_phase_security_bits(&self, m: f64) -> f64 {
+        let bits_per_query = self.rate_log_two as f64 / 2.0 - (1.0 + 0.5 / m).log2();
+
+        bits_per_query * self.num_queries as f64 + self.pow_bits as f64
+    }
+}
+
+impl ProofConfig {
+    /// FRI parameters that the prover will derive from this config for a trace of `domain_size`,
+    /// when `num_polys` polynomials are opened
+    pub fn fri_soundness_params<F: SmallField>(
+        &self,
+        domain_size: usize,
+        num_polys: usize,
+    ) -> FriSoundnessParams {
+        assert!(domain_size.is_power_of_two());
+        assert!(self.fri_lde_factor.is_power_of_two());
+
+        let (pow_bits, num_queries, folding_schedule, _) = compute_fri_schedule(
+            self.security_level as u32,
+            self.merkle_tree_cap_size,
+            self.pow_bits,
+            self.fri_lde_factor.trailing_zeros(),
+            domain_size.trailing_zeros(),
+        );
+
+        FriSoundnessParams {
+            field_bits: fri_field_bits::<F>(),
+            rate_log_two: self.fri_lde_factor.trailing_zeros(),
+            domain_size_log_two: domain_size.trailing_zeros(),
+            folding_schedule,
+            num_queries,
+            pow_bits,
+            num_polys,
+        }
+    }
+
+    pub fn conjectured_security_bits<F: SmallField>(&self, domain_size: usize) -> usize {
+        let params = self.fri_soundness_params::<F>(domain_size, 1);
+
+        conjectured_security_bits(
+            params.field_bits,
+            params.rate_log_two,
+            params.num_queries,
+            params.pow_bits,
+        )
+    }
+
+    pub fn provable_security_bits<F: SmallField>(
+        &self,
+        domain_size: usize,
+        num_polys: usize,
+    ) -> f64 {
+        self.fri_soundness_params::<F>(domain_size, num_polys)
+            .provable_security_bits()
+    }
+
+    /// Checks the FRI instance the prover runs, `params`, against the `security_level` of this
+    /// config: the number of queries has to reach it under the conjectured regime, independently
+    /// of how the schedule was derived. The level reached under the provable regime is reported
+    pub fn check_security(&self, params: &FriSoundnessParams) {
+        let min_num_queries = min_conjectured_num_queries(
+            self.security_level,
+            params.field_bits,
+            params.rate_log_two,
+            params.pow_bits,
+        )
+        .unwrap_or_else(|| {
+            panic!(
+                "target security {} is not reachable with LDE factor 2^{} and challenges from \
+                 a {}-bit field",
+                self.security_level, params.rate_log_two, params.field_bits
+            )
+        });
+        assert!(
+            params.num_queries >= min_num_queries,
+            "FRI makes {} queries with {} PoW bits, but target security {} needs {}",
+            params.num_queries,
+            params.pow_bits,
+            self.security_level,
+            min_num_queries
+        );
+
+        let conjectured = conjectured_security_bits(
+            params.field_bits,
+            params.rate_log_two,
+            params.num_queries,
+            params.pow_bits,
+        );
+        // the provable level is below the target for the usual configurations, so it's only
+        // reported
+        let provable = params.provable_security_bits();
+        log!(
+            "FRI params reach {} bits of conjectured and {:.1} bits of provable security",
+            conjectured,
+            provable
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::field::goldilocks::GoldilocksField;
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn test_conjectured_security() {
+        let field_bits = fri_field_bits::<F>();
+        assert_eq!(field_bits, 128);
+
+        assert_eq!(conjectured_security_bits(field_bits, 2, 40, 20), 100);
+        assert_eq!(conjectured_security_bits(field_bits, 2, 100, 20), 128);
+        assert_eq!(
+            min_conjectured_num_queries(100, field_bits, 2, 20),
+            Some(40)
+        );
+        assert_eq!(
+            min_conjectured_num_queries(100, field_bits, 3, 20),
+            Some(27)
+        );
+        assert_eq!(min_conjectured_num_queries(129, field_bits, 2, 20), None);
+        // PoW alone doesn't make FRI sound
+        assert_eq!(min_conjectured_num_queries(10, field_bits, 2, 20), Some(1));
+
+        // default config derives the number of queries from the security level
+        let config = ProofConfig::default();
+        assert!(config.conjectured_security_bits::<F>(1 << 20) >= config.security_level);
+    }
+
+    #[test]
+    fn test_provable_security() {
+        let config = ProofConfig::default();
+        let params = config.fri_soundness_params::<F>(1 << 20, 200);
+
+        let provable = params.provable_security_bits();
+        assert!(provable > 0.0 && provable < config.security_level as f64);
+
+        let mut more_queries = params.clone();
+        more_queries.num_queries *= 2;
+        assert!(more_queries.provable_security_bits() > provable);
+
+        let min_queries = params.min_provable_num_queries(50).unwrap();
+        let mut enough = params.clone();
+        enough.num_queries = min_queries;
+        assert!(enough.provable_security_bits() >= 50.0);
+        enough.num_queries -= 1;
+        assert!(enough.provable_security_bits() < 50.0);
+
+        assert_eq!(params.min_provable_num_queries(100), None);
+    }
+}
@@ -63,6 +63,19 @@ impl<F: SmallField> LookupTableWrapper<F> {
         }
     }
 
+    pub fn name(&self) -> &str {
+        match self {
+            Self::W1(inner) => inner.name(),
+            Self::W2(inner) => inner.name(),
+            Self::W3(inner) => inner.name(),
+            Self::W4(inner) => inner.name(),
+            Self::W5(inner) => inner.name(),
+            Self::W6(inner) => inner.name(),
+            Self::W7(inner) => inner.name(),
+            Self::W8(inner) => inner.name(),
+        }
+    }
+
     pub fn table_size(&self) -> usize {
         match self {
             Self::W1(inner) => inner.table_size(),
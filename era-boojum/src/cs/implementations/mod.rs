@@ -3,6 +3,7 @@ use super::*;
 pub mod buffering_source;
 pub mod convenience;
 pub mod copy_permutation;
+pub mod cost_accounting;
 pub mod cs;
 pub mod evaluator_data;
 pub mod fast_serialization;
@@ -12,6 +13,7 @@ pub mod lookup_argument;
 pub mod lookup_argument_in_ext;
 pub mod lookup_placement;
 pub mod lookup_table;
+pub mod namespaces;
 pub mod polynomial;
 pub mod polynomial_storage;
 pub mod pow;
@@ -20,6 +22,7 @@ pub mod prover;
 pub mod reference_cs;
 pub mod satisfiability_test;
 pub mod setup;
+pub mod setup_cache;
 pub mod setup_storage;
 pub mod transcript;
 pub mod utils;
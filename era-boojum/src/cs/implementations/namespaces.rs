@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+/// Gadget-level tags of the trace cells, pushed with `ConstraintSystem::push_namespace`.
+/// Nested namespaces are joined with `/`, and every copiable cell placed while a namespace
+/// is active is tagged with its full path, if the configuration performs runtime asserts
+#[derive(Clone, Debug, Default)]
+pub struct CellNamespaces {
+    stack: Vec<(String, Option<u32>)>,
+    paths: Vec<String>,
+    parents: Vec<Option<u32>>,
+    path_ids: HashMap<String, u32>,
+    current: Option<u32>,
+    // namespace id of every cell, by column and then row, `UNTAGGED` for the cells placed
+    // outside of any namespace
+    cells: Vec<Vec<u32>>,
+}
+
+const UNTAGGED: u32 = u32::MAX;
+
+impl CellNamespaces {
+    pub fn push(&mut self, name: &str) {
+        let path = match self.stack.last() {
+            Some((parent, _)) => format!("{}/{}", parent, name),
+            None => name.to_owned(),
+        };
+        let id = match self.path_ids.get(&path) {
+            Some(id) => *id,
+            None => {
+                let id = self.paths.len() as u32;
+                self.paths.push(path.clone());
+                self.parents.push(self.current);
+                self.path_ids.insert(path.clone(), id);
+
+                id
+            }
+        };
+
+        self.stack.push((path, self.current));
+        self.current = Some(id);
+    }
+
+    pub fn pop(&mut self) {
+        let (_, previous) = self
+            .stack
+            .pop()
+            .expect("namespace must be pushed before it's popped");
+        self.current = previous;
+    }
+
+    #[inline(always)]
+    pub fn tag_cell(&mut self, column: usize, row: usize) {
+        if let Some(id) = self.current {
+            if self.cells.len() <= column {
+                self.cells.resize_with(column + 1, Vec::new);
+            }
+            let tags = &mut self.cells[column];
+            if tags.len() <= row {
+                tags.resize(row + 1, UNTAGGED);
+            }
+            tags[row] = id;
+        }
+    }
+
+    pub fn namespace_of_cell(&self, column: usize, row: usize) -> Option<&str> {
+        self.cells
+            .get(column)
+            .and_then(|tags| tags.get(row))
+            .filter(|id| **id != UNTAGGED)
+            .map(|id| self.paths[*id as usize].as_str())
+    }
+
+    pub fn current(&self) -> Option<u32> {
+        self.current
+    }
+
+    pub fn path(&self, id: u32) -> &str {
+        &self.paths[id as usize]
+    }
+
+    /// Ids of the namespaces `id` is nested in, from the outermost one to `id` itself
+    pub fn ancestry(&self, id: u32) -> Vec<u32> {
+        let mut result = vec![id];
+        while let Some(parent) = self.parents[*result.last().unwrap() as usize] {
+            result.push(parent);
+        }
+        result.reverse();
+
+        result
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().all(|tags| tags.is_empty())
+    }
+}
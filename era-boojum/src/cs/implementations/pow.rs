@@ -3,6 +3,9 @@ use blake2::Digest;
 use sha3::Keccak256;
 
 use super::*;
+use crate::field::goldilocks::GoldilocksField;
+use crate::field::Field;
+use crate::implementations::poseidon2::{poseidon2_permutation, Poseidon2Goldilocks};
 
 pub trait PoWRunner: 'static + Send + Sync {
     fn run_from_field_elements<F: SmallField>(seed: Vec<F>, pow_bits: u32, worker: &Worker) -> u64 {
@@ -223,3 +226,194 @@ impl PoWRunner for Keccak256 {
         u64::from_le_bytes(le_bytes).trailing_zeros() >= pow_bits
     }
 }
+
+const POSEIDON2_NO_RESULT: u64 = u64::MAX;
+const POSEIDON2_ROUNDS_PER_INVOCATION: usize = 1 << 16u32;
+
+// Algebraic PoW, so it's cheap to verify in the recursive verifier. We absorb the seed into
+// a zero-initialized state by overwriting the first 8 elements and permuting, then do the same
+// for the challenge as two 32-bit words, and require the trailing zeroes from the first element
+// of the resulting state
+impl Poseidon2Goldilocks {
+    pub const POW_RATE: usize = 8;
+
+    fn pow_seed_state(seed: &[GoldilocksField]) -> [GoldilocksField; 12] {
+        let mut state = [GoldilocksField::ZERO; 12];
+        for chunk in seed.chunks(Self::POW_RATE) {
+            state[..chunk.len()].copy_from_slice(chunk);
+            poseidon2_permutation(&mut state);
+        }
+
+        state
+    }
+
+    fn pow_output(seed_state: &[GoldilocksField; 12], challenge: u64) -> u64 {
+        let mut state = *seed_state;
+        state[0] = GoldilocksField::from_u64_unchecked(challenge as u32 as u64);
+        state[1] = GoldilocksField::from_u64_unchecked(challenge >> 32);
+        poseidon2_permutation(&mut state);
+
+        state[0].as_u64_reduced()
+    }
+
+    fn seed_from_bytes(seed: &[u8]) -> Vec<GoldilocksField> {
+        // 32-bit words are always canonical
+        seed.chunks(4)
+            .map(|chunk| {
+                let mut le_bytes = [0u8; 4];
+                le_bytes[..chunk.len()].copy_from_slice(chunk);
+                GoldilocksField::from_u64_unchecked(u32::from_le_bytes(le_bytes) as u64)
+            })
+            .collect()
+    }
+
+    fn seed_from_field_elements<F: SmallField>(seed: Vec<F>) -> Vec<GoldilocksField> {
+        seed.into_iter()
+            .map(|el| GoldilocksField::from_nonreduced_u64(el.as_u64_reduced()))
+            .collect()
+    }
+
+    pub fn run_from_goldilocks_elements(
+        seed: Vec<GoldilocksField>,
+        pow_bits: u32,
+        worker: &Worker,
+    ) -> u64 {
+        assert!(pow_bits <= 32);
+
+        let seed_state = Self::pow_seed_state(&seed);
+
+        if pow_bits <= POSEIDON2_ROUNDS_PER_INVOCATION.trailing_zeros() {
+            // serial case
+            log!("Do serial PoW");
+            for challenge in 0u64..(POSEIDON2_NO_RESULT - 1) {
+                if Self::pow_output(&seed_state, challenge).trailing_zeros() >= pow_bits {
+                    return challenge;
+                }
+            }
+        }
+
+        use std::sync::atomic::AtomicU64;
+        use std::sync::atomic::Ordering;
+
+        let result = std::sync::Arc::new(AtomicU64::new(POSEIDON2_NO_RESULT));
+
+        log!("Do parallel PoW");
+
+        let pow_rounds_per_invocation = POSEIDON2_ROUNDS_PER_INVOCATION as u64;
+        // it's good to parallelize
+        let num_workers = worker.num_cores as u64;
+        worker.scope(0, |scope, _| {
+            for worker_idx in 0..num_workers {
+                let result = std::sync::Arc::clone(&result);
+                scope.spawn(move |_| {
+                    for i in
+                        0..((POSEIDON2_NO_RESULT - 1) / num_workers / pow_rounds_per_invocation)
+                    {
+                        let base = (worker_idx + i * num_workers) * pow_rounds_per_invocation;
+                        let current_flag = result.load(Ordering::Relaxed);
+                        if current_flag == POSEIDON2_NO_RESULT {
+                            for j in 0..pow_rounds_per_invocation {
+                                let challenge_u64 = base + j;
+                                if Self::pow_output(&seed_state, challenge_u64).trailing_zeros()
+                                    >= pow_bits
+                                {
+                                    let _ = result.compare_exchange(
+                                        POSEIDON2_NO_RESULT,
+                                        challenge_u64,
+                                        Ordering::Acquire,
+                                        Ordering::Relaxed,
+                                    );
+
+                                    break;
+                                }
+                            }
+                        } else {
+                            break;
+                        }
+                    }
+                })
+            }
+        });
+
+        let challenge_u64 = result.load(Ordering::SeqCst);
+
+        assert!(Self::verify_from_goldilocks_elements(
+            seed,
+            pow_bits,
+            challenge_u64
+        ));
+
+        challenge_u64
+    }
+
+    pub fn verify_from_goldilocks_elements(
+        seed: Vec<GoldilocksField>,
+        pow_bits: u32,
+        challenge: u64,
+    ) -> bool {
+        let seed_state = Self::pow_seed_state(&seed);
+
+        Self::pow_output(&seed_state, challenge).trailing_zeros() >= pow_bits
+    }
+}
+
+impl PoWRunner for Poseidon2Goldilocks {
+    fn run_from_field_elements<F: SmallField>(seed: Vec<F>, pow_bits: u32, worker: &Worker) -> u64 {
+        Self::run_from_goldilocks_elements(Self::seed_from_field_elements(seed), pow_bits, worker)
+    }
+
+    fn run_from_bytes(seed: Vec<u8>, pow_bits: u32, worker: &Worker) -> u64 {
+        Self::run_from_goldilocks_elements(Self::seed_from_bytes(&seed), pow_bits, worker)
+    }
+
+    fn verify_from_field_elements<F: SmallField>(
+        seed: Vec<F>,
+        pow_bits: u32,
+        challenge: u64,
+    ) -> bool {
+        Self::verify_from_goldilocks_elements(
+            Self::seed_from_field_elements(seed),
+            pow_bits,
+            challenge,
+        )
+    }
+
+    fn verify_from_bytes(seed: Vec<u8>, pow_bits: u32, challenge: u64) -> bool {
+        Self::verify_from_goldilocks_elements(Self::seed_from_bytes(&seed), pow_bits, challenge)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_poseidon2_pow() {
+        let worker = Worker::new_with_num_threads(4);
+        let seed: Vec<GoldilocksField> = (0..5)
+            .map(|el| GoldilocksField::from_u64_unchecked(el * 1_000_003))
+            .collect();
+
+        // serial search
+        let challenge = Poseidon2Goldilocks::run_from_field_elements(seed.clone(), 10, &worker);
+        assert!(Poseidon2Goldilocks::verify_from_field_elements(
+            seed.clone(),
+            10,
+            challenge
+        ));
+
+        // parallel search
+        let challenge = Poseidon2Goldilocks::run_from_field_elements(seed.clone(), 18, &worker);
+        assert!(Poseidon2Goldilocks::verify_from_field_elements(
+            seed.clone(),
+            18,
+            challenge
+        ));
+        // PoW is bound to the seed
+        let mut other_seed = seed.clone();
+        other_seed[0].add_assign(&GoldilocksField::ONE);
+        assert!(!Poseidon2Goldilocks::verify_from_field_elements(
+            other_seed, 18, challenge
+        ));
+    }
+}
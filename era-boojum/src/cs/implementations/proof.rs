@@ -115,6 +115,10 @@ impl<F: SmallField, H: TreeHasher<F>> OracleQuery<F, H> {
 use crate::field::ExtensionField;
 use crate::field::FieldExtension;
 
+/// The challenges live in a quadratic extension of the base field. The degree is fixed: the
+/// prover, the verifier and the recursive verifier all store extension elements as pairs of
+/// coefficients (see `final_fri_monomials`), so a higher degree extension for larger challenge
+/// spaces needs all three generalised together and is not supported yet
 #[derive(derivative::Derivative, serde::Serialize, serde::Deserialize)]
 #[derivative(Clone(bound = ""), Debug(bound = ""), Hash(bound = ""))]
 #[serde(bound = "H::Output: serde::Serialize + serde::de::DeserializeOwned")]
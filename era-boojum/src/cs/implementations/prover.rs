@@ -25,6 +25,7 @@ use super::transcript::Transcript;
 use super::verifier::VerificationKey;
 use super::*;
 use crate::cs::implementations::buffering_source::*;
+use crate::cs::implementations::fri::security::{fri_field_bits, FriSoundnessParams};
 use crate::cs::implementations::proof::SingleRoundQueries;
 use crate::cs::implementations::transcript::BoolsBuffer;
 use crate::cs::traits::gate::GatePlacementStrategy;
@@ -2087,6 +2088,16 @@ impl<
             domain_size.trailing_zeros(),
         );
 
+        proof_config.check_security(&FriSoundnessParams {
+            field_bits: fri_field_bits::<F>(),
+            rate_log_two: lde_factor_for_fri.trailing_zeros(),
+            domain_size_log_two: domain_size.trailing_zeros(),
+            folding_schedule: interpolation_log2s_schedule.clone(),
+            num_queries,
+            pow_bits: new_pow_bits,
+            num_polys: total_num_challenges,
+        });
+
         dbg!(&interpolation_log2s_schedule);
         dbg!(cap_size);
 
@@ -3,7 +3,9 @@ use self::witness::WitnessVec;
 
 use super::*;
 use crate::config::*;
+use crate::cs::implementations::cost_accounting::CostAccounting;
 use crate::cs::implementations::evaluator_data::*;
+use crate::cs::implementations::namespaces::CellNamespaces;
 use crate::cs::implementations::setup::FinalizationHintsForProver;
 use crate::cs::traits::gate::GateColumnsCleanupFunction;
 use crate::cs::traits::gate::GatePlacementStrategy;
@@ -71,6 +73,8 @@ pub struct CSReferenceImplementation<
     pub(crate) public_inputs: Vec<(usize, usize)>,
 
     pub(crate) specialized_gates_rough_stats: HashMap<TypeId, usize>,
+    pub(crate) namespaces: CellNamespaces,
+    pub(crate) cost_accounting: Option<CostAccounting>,
 
     pub(crate) static_toolbox: T,
     pub(crate) gates_configuration: GC,
@@ -108,6 +112,7 @@ pub struct CSReferenceAssembly<
     pub evaluation_data_over_specialized_columns: EvaluationDataOverSpecializedColumns<F, P>,
 
     pub specialized_gates_rough_stats: HashMap<TypeId, usize>,
+    pub namespaces: CellNamespaces,
 
     pub public_inputs: Vec<(usize, usize)>,
 
@@ -179,6 +184,15 @@ impl<
         }
     }
 
+    // the cell tags only serve the report of `check_if_satisfied_detailed`, so they are not
+    // kept when the namespaces are pushed for the cost accounting alone
+    #[inline(always)]
+    pub(crate) fn tag_cell(&mut self, column: usize, row: usize) {
+        if CFG::DebugConfig::PERFORM_RUNTIME_ASSERTS {
+            self.namespaces.tag_cell(column, row);
+        }
+    }
+
     pub(crate) fn lookups_tables_total_len(&self) -> usize {
         self.lookup_tables.iter().map(|el| el.table_size()).sum()
     }
@@ -210,6 +224,7 @@ impl<
             lookup_tables,
             lookup_multiplicities,
             specialized_gates_rough_stats,
+            namespaces,
             public_inputs,
             gates_configuration,
             evaluation_data_over_general_purpose_columns,
@@ -247,6 +262,7 @@ impl<
             lookup_multiplicities,
             witness: None,
             specialized_gates_rough_stats,
+            namespaces,
             evaluation_data_over_general_purpose_columns,
             evaluation_data_over_specialized_columns,
             public_inputs,
@@ -12,14 +12,118 @@ use crate::cs::traits::gate::GatePlacementStrategy;
 type RCFG = <DevCSConfig as CSConfig>::ResolverConfig;
 
 impl<F: SmallField, A: GoodAllocator> CSReferenceAssembly<F, F, DevCSConfig, A> {
+    /// Checks every gate instance of the trace and logs the unsatisfied ones, which
+    /// `check_if_satisfied_detailed` returns instead
     pub fn check_if_satisfied(&mut self, worker: &Worker) -> bool {
+        let report = self.check_if_satisfied_detailed(worker);
+        if report.is_satisfied() == false {
+            log!("{}", report);
+        }
+
+        report.is_satisfied()
+    }
+}
+
+// cycles through constants may span the whole trace, so only the first cells are reported
+const MAX_REPORTED_COPIES: usize = 16;
+
+/// A variable of an unsatisfied gate instance, with the cells it is copied to. Values are read
+/// per variable, so copy-permutation cycles of the reference assembly always hold. The cycle
+/// instead shows the other gates that use the offending value
+#[derive(Clone, Debug)]
+pub struct CopyCycle<F: SmallField> {
+    pub variable: Variable,
+    pub value: F,
+    /// Column of the variable in the unsatisfied instance
+    pub column: usize,
+    /// `(column, row)` of the cells that hold the same variable, at most `MAX_REPORTED_COPIES`
+    pub copies: Vec<(usize, usize)>,
+    pub cycle_len: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct UnsatisfiedGate<F: SmallField> {
+    pub row: usize,
+    pub gate: String,
+    pub in_specialized_columns: bool,
+    /// Instance within the row, or repetition for gates over specialized columns
+    pub instance: usize,
+    /// Index and value of every nonzero term of the instance
+    pub violated_terms: Vec<(usize, F)>,
+    /// Namespace the variables of the instance were placed in, if any was pushed
+    pub namespace: Option<String>,
+    pub cycles: Vec<CopyCycle<F>>,
+}
+
+impl<F: SmallField> std::fmt::Display for UnsatisfiedGate<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "row {}: instance {} of gate {}",
+            self.row, self.instance, self.gate
+        )?;
+        if self.in_specialized_columns {
+            write!(f, " (specialized columns)")?;
+        }
+        if let Some(namespace) = self.namespace.as_ref() {
+            write!(f, " in {}", namespace)?;
+        }
+        for (idx, value) in self.violated_terms.iter() {
+            write!(f, "\n  term {} = {}", idx, value)?;
+        }
+        for cycle in self.cycles.iter() {
+            write!(
+                f,
+                "\n  column {}: {:?} = {}, copied to {} cells {:?}",
+                cycle.column,
+                cycle.variable,
+                cycle.value,
+                cycle.cycle_len - 1,
+                &cycle.copies
+            )?;
+            if cycle.copies.len() + 1 < cycle.cycle_len {
+                write!(f, "...")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SatisfiabilityReport<F: SmallField> {
+    pub unsatisfied: Vec<UnsatisfiedGate<F>>,
+}
+
+impl<F: SmallField> SatisfiabilityReport<F> {
+    pub fn is_satisfied(&self) -> bool {
+        self.unsatisfied.is_empty()
+    }
+}
+
+impl<F: SmallField> std::fmt::Display for SatisfiabilityReport<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_satisfied() {
+            return write!(f, "All gates are satisfied");
+        }
+
+        write!(f, "{} unsatisfied gate instances", self.unsatisfied.len())?;
+        for el in self.unsatisfied.iter() {
+            write!(f, "\n{}", el)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: SmallField, A: GoodAllocator> CSReferenceAssembly<F, F, DevCSConfig, A> {
+    /// Reports all the unsatisfied gate instances, with the namespaces pushed while the circuit
+    /// was synthesized
+    pub fn check_if_satisfied_detailed(&mut self, worker: &Worker) -> SatisfiabilityReport<F> {
         let (constants, selectors_placement, _) = self.create_constant_setup_polys(worker);
         let (_deg, num_constants_for_general_purpose_columns) = selectors_placement.compute_stats();
-        log!("Constants are ready");
         let variables = self.materialize_variables_polynomials(worker);
-        log!("Variables are ready");
         let witness = self.materialize_witness_polynomials(worker);
-        log!("Witnesses are ready");
 
         let view = SatisfiabilityCheckRowView::from_storages(variables, witness, constants);
         let mut view_over_general_purpose_columns = view.subset(
@@ -29,7 +133,6 @@ impl<F: SmallField, A: GoodAllocator> CSReferenceAssembly<F, F, DevCSConfig, A>
         );
 
         let mut paths_mappings = vec![];
-
         for (idx, gate) in self
             .evaluation_data_over_general_purpose_columns
             .evaluators_over_general_purpose_columns
@@ -47,6 +150,8 @@ impl<F: SmallField, A: GoodAllocator> CSReferenceAssembly<F, F, DevCSConfig, A>
             paths_mappings.push(path);
         }
 
+        // unsatisfied instances along with the range of columns of their variables
+        let mut unsatisfied = vec![];
         let mut dst = vec![];
 
         for (row, gate_idx) in self.gates_application_sets.iter().enumerate() {
@@ -55,104 +160,39 @@ impl<F: SmallField, A: GoodAllocator> CSReferenceAssembly<F, F, DevCSConfig, A>
             let evaluator = &self
                 .evaluation_data_over_general_purpose_columns
                 .evaluators_over_general_purpose_columns[*gate_idx];
-            // log!("On row {} there is a gate {}", row, &evaluator.debug_name);
-            let path = &paths_mappings[*gate_idx];
-            let constants_placement_offset = path.len();
-
             let num_terms = evaluator.num_quotient_terms;
             if num_terms == 0 {
-                // skip row
                 view_over_general_purpose_columns.advance_manually();
                 continue;
             }
-            let gate_debug_name = &evaluator.debug_name;
-            let num_constants_used = evaluator.num_required_constants;
-            let mut this_view = view_over_general_purpose_columns.clone();
 
-            let placement = evaluator.placement_type;
+            let constants_placement_offset = paths_mappings[*gate_idx].len();
+            let mut this_view = view_over_general_purpose_columns.clone();
             let evaluation_fn = &**evaluator
                 .rowwise_satisfiability_function
                 .as_ref()
                 .expect("must exist");
-            match placement {
+            evaluation_fn.evaluate_over_general_purpose_columns(
+                &mut this_view,
+                &mut dst,
+                constants_placement_offset,
+                &mut (),
+            );
+
+            let variables_per_instance = match evaluator.placement_type {
                 GatePlacementType::UniqueOnRow => {
-                    evaluation_fn.evaluate_over_general_purpose_columns(
-                        &mut this_view,
-                        &mut dst,
-                        constants_placement_offset,
-                        &mut (),
-                    );
-
-                    for (idx, term) in dst.iter().enumerate() {
-                        if term.is_zero() == false {
-                            panic!(
-                                "Unsatisfied at row {} with value {} for term number {} for unique instance of gate {}",
-                                row, term, idx, gate_debug_name
-                            );
-                        }
-                    }
+                    self.parameters.num_columns_under_copy_permutation
                 }
                 GatePlacementType::MultipleOnRow { per_chunk_offset } => {
-                    let mut t_view = view_over_general_purpose_columns.clone();
-                    evaluation_fn.evaluate_over_general_purpose_columns(
-                        &mut this_view,
-                        &mut dst,
-                        constants_placement_offset,
-                        &mut (),
-                    );
-
-                    for (chunk_idx, terms) in dst.chunks(num_terms).enumerate() {
-                        for (idx, term) in terms.iter().enumerate() {
-                            if term.is_zero() == false {
-                                let mut vars = vec![];
-                                let mut wits = vec![];
-                                let mut constants = vec![];
-                                let start_variables = per_chunk_offset.variables_offset * chunk_idx;
-                                let start_witnesses = per_chunk_offset.witnesses_offset * chunk_idx;
-
-                                for i in 0..per_chunk_offset.variables_offset {
-                                    vars.push(t_view.get_variable_value(start_variables + i));
-                                }
-
-                                for i in 0..per_chunk_offset.witnesses_offset {
-                                    wits.push(t_view.get_witness_value(start_witnesses + i));
-                                }
-
-                                t_view.constants_offset = constants_placement_offset;
-
-                                for i in 0..num_constants_used {
-                                    constants.push(t_view.get_constant_value(i));
-                                }
-
-                                dbg!(vars);
-                                dbg!(wits);
-                                dbg!(&constants);
-
-                                let mut vars = vec![];
-                                let mut wits = vec![];
-                                let start_variables = per_chunk_offset.variables_offset * chunk_idx;
-                                let start_witnesses = per_chunk_offset.witnesses_offset * chunk_idx;
-
-                                for i in 0..per_chunk_offset.variables_offset {
-                                    vars.push(self.copy_permutation_data[start_variables + i][row]);
-                                }
-
-                                for i in 0..per_chunk_offset.witnesses_offset {
-                                    wits.push(
-                                        self.witness_placement_data[start_witnesses + i][row],
-                                    );
-                                }
-
-                                dbg!(vars);
-                                dbg!(wits);
-
-                                panic!(
-                                    "Unsatisfied at row {} with value {} for term number {} for subinstance number {} of gate {}",
-                                    row, term, idx, chunk_idx, gate_debug_name
-                                );
-                            }
-                        }
-                    }
+                    per_chunk_offset.variables_offset
+                }
+            };
+            for (instance, terms) in dst.chunks(num_terms).enumerate() {
+                let start = variables_per_instance * instance;
+                if let Some(el) =
+                    unsatisfied_instance(row, &evaluator.debug_name, false, instance, terms)
+                {
+                    unsatisfied.push((el, start..(start + variables_per_instance)));
                 }
             }
 
@@ -161,10 +201,7 @@ impl<F: SmallField, A: GoodAllocator> CSReferenceAssembly<F, F, DevCSConfig, A>
 
         // now specialized rows
         {
-            // we expect our gates to be narrow, so we do not need to buffer row, and instead
-            // we can evaluate over limited set of columns
             use crate::cs::traits::evaluator::GenericDynamicEvaluatorOverSpecializedColumns;
-            let mut specialized_placement_data = vec![];
             let mut evaluation_functions: Vec<
                 &dyn GenericDynamicEvaluatorOverSpecializedColumns<
                     F,
@@ -174,7 +211,7 @@ impl<F: SmallField, A: GoodAllocator> CSReferenceAssembly<F, F, DevCSConfig, A>
                 >,
             > = vec![];
             let mut views = vec![];
-            let mut evaluator_names = vec![];
+            let mut evaluators_data = vec![];
 
             for (idx, (gate_type_id, evaluator)) in self
                 .evaluation_data_over_specialized_columns
@@ -191,156 +228,65 @@ impl<F: SmallField, A: GoodAllocator> CSReferenceAssembly<F, F, DevCSConfig, A>
                 if gate_type_id == &std::any::TypeId::of::<LookupFormalGate>() {
                     continue;
                 }
-                assert!(
-                    evaluator.total_quotient_terms_over_all_repetitions != 0,
-                    "evaluator {} has not contribution to quotient",
-                    &evaluator.debug_name,
-                );
-                log!(
-                    "Will be evaluating {} over specialized columns",
-                    &evaluator.debug_name
-                );
 
-                let num_terms = evaluator.num_quotient_terms;
                 let placement_strategy = self
                     .placement_strategies
                     .get(gate_type_id)
                     .copied()
                     .expect("gate must be allowed");
                 let GatePlacementStrategy::UseSpecializedColumns {
-                    num_repetitions,
-                    share_constants,
+                    num_repetitions, ..
                 } = placement_strategy
                 else {
                     unreachable!();
                 };
 
-                let total_terms = num_terms * num_repetitions;
-
-                let (initial_offset, per_repetition_offset, total_constants_available) = self
+                let (initial_offset, per_repetition_offset, _) = self
                     .evaluation_data_over_specialized_columns
                     .offsets_for_specialized_evaluators[idx];
-
-                let placement_data = (
-                    num_repetitions,
-                    share_constants,
-                    initial_offset,
-                    per_repetition_offset,
-                    total_constants_available,
-                    total_terms,
-                );
-
-                specialized_placement_data.push(placement_data);
-                let t = evaluator
-                    .columnwise_satisfiability_function
-                    .as_ref()
-                    .expect("must be properly configured");
-                evaluation_functions.push(&**t);
-
-                let (
-                    num_repetitions,
-                    share_constants,
-                    initial_offset,
-                    per_repetition_offset,
-                    _total_constants_available,
-                    _total_terms,
-                ) = placement_data;
-
-                // we self-check again
-                if share_constants {
-                    assert_eq!(per_repetition_offset.constants_offset, 0);
-                }
                 let mut final_offset = initial_offset;
                 for _ in 0..num_repetitions {
                     final_offset.add_offset(&per_repetition_offset);
                 }
 
-                let source = view.subset(
+                views.push(view.subset(
                     initial_offset.variables_offset..final_offset.variables_offset,
                     initial_offset.witnesses_offset..final_offset.witnesses_offset,
                     (num_constants_for_general_purpose_columns + initial_offset.constants_offset)
                         ..(num_constants_for_general_purpose_columns
                             + final_offset.constants_offset),
+                ));
+                evaluation_functions.push(
+                    &**evaluator
+                        .columnwise_satisfiability_function
+                        .as_ref()
+                        .expect("must be properly configured"),
                 );
-
-                views.push(source);
-
-                evaluator_names.push(&evaluator.debug_name);
+                evaluators_data.push((
+                    &evaluator.debug_name,
+                    evaluator.num_quotient_terms,
+                    initial_offset.variables_offset,
+                    per_repetition_offset.variables_offset,
+                ));
             }
 
             for row in 0..self.max_trace_len {
-                for (((placement_data, evaluation_fn), source), evaluator_name) in
-                    specialized_placement_data
-                        .iter()
-                        .zip(evaluation_functions.iter())
-                        .zip(views.iter_mut())
-                        .zip(evaluator_names.iter())
+                for ((evaluation_fn, source), evaluator_data) in evaluation_functions
+                    .iter()
+                    .zip(views.iter_mut())
+                    .zip(evaluators_data.iter())
                 {
-                    let (
-                        num_repetitions,
-                        _share_constants,
-                        _initial_offset,
-                        _per_repetition_offset,
-                        _total_constants_available,
-                        total_terms,
-                    ) = *placement_data;
-
-                    let num_terms = total_terms / num_repetitions;
+                    let (gate_name, num_terms, initial_column, columns_per_repetition) =
+                        *evaluator_data;
 
                     dst.clear();
-
                     evaluation_fn.evaluate_over_columns(source, &mut dst, &mut ());
-                    for (chunk_idx, terms) in dst.chunks(num_terms).enumerate() {
-                        for (idx, term) in terms.iter().enumerate() {
-                            if term.is_zero() == false {
-                                panic!(
-                                "Unsatisfied at row {} with value {} for term number {} for subinstance number {} of gate {}",
-                                row, term, idx, chunk_idx, evaluator_name
-                            );
-
-                                // let mut vars = vec![];
-                                // let mut wits = vec![];
-                                // let mut constants = vec![];
-                                // let start_variables = per_chunk_offset.variables_offset * chunk_idx;
-                                // let start_witnesses = per_chunk_offset.witnesses_offset * chunk_idx;
-
-                                // for i in 0..per_chunk_offset.variables_offset {
-                                //     vars.push(t_view.get_variable_value(start_variables + i));
-                                // }
-
-                                // for i in 0..per_chunk_offset.witnesses_offset {
-                                //     wits.push(t_view.get_witness_value(start_witnesses + i));
-                                // }
-
-                                // for i in 0..self.parameters.num_constant_columns {
-                                //     constants.push(t_view.get_constant_value(i));
-                                // }
-
-                                // dbg!(vars);
-                                // dbg!(wits);
-                                // dbg!(&constants[constants_placement_offset..]);
-
-                                // let mut vars = vec![];
-                                // let mut wits = vec![];
-                                // let start_variables = per_chunk_offset.variables_offset * chunk_idx;
-                                // let start_witnesses = per_chunk_offset.witnesses_offset * chunk_idx;
-
-                                // for i in 0..per_chunk_offset.variables_offset {
-                                //     vars.push(self.copy_permutation_data[start_variables + i][row]);
-                                // }
-
-                                // for i in 0..per_chunk_offset.witnesses_offset {
-                                //     wits.push(self.witness_placement_data[start_variables + i][row]);
-                                // }
-
-                                // dbg!(vars);
-                                // dbg!(wits);
-
-                                // panic!(
-                                //     "Unsatisfied at row {} with value {} for term number {} for subinstance number {} of gate {}",
-                                //     row, term, idx, chunk_idx, gate_debug_name
-                                // );
-                            }
+                    for (repetition, terms) in dst.chunks(num_terms).enumerate() {
+                        let start = initial_column + columns_per_repetition * repetition;
+                        if let Some(el) =
+                            unsatisfied_instance(row, gate_name, true, repetition, terms)
+                        {
+                            unsatisfied.push((el, start..(start + columns_per_repetition)));
                         }
                     }
 
@@ -349,6 +295,186 @@ impl<F: SmallField, A: GoodAllocator> CSReferenceAssembly<F, F, DevCSConfig, A>
             }
         }
 
-        true
+        // attribute the instances to namespaces and collect the cycles of their variables
+        let mut cycles = HashMap::new();
+        for (el, columns) in unsatisfied.iter_mut() {
+            for column in columns.clone() {
+                let Some(variable) = self.copy_permutation_data[column].get(el.row).copied() else {
+                    continue;
+                };
+                if variable.is_placeholder() {
+                    continue;
+                }
+                if el.namespace.is_none() {
+                    el.namespace = self
+                        .namespaces
+                        .namespace_of_cell(column, el.row)
+                        .map(|name| name.to_owned());
+                }
+                cycles.insert(variable, (0usize, vec![]));
+                el.cycles.push(CopyCycle {
+                    variable,
+                    value: view.variables[column].storage[el.row],
+                    column,
+                    copies: vec![],
+                    cycle_len: 0,
+                });
+            }
+        }
+
+        if cycles.is_empty() == false {
+            for (column, variables) in self.copy_permutation_data.iter().enumerate() {
+                for (row, variable) in variables.iter().enumerate() {
+                    if let Some((cycle_len, copies)) = cycles.get_mut(variable) {
+                        *cycle_len += 1;
+                        if copies.len() < MAX_REPORTED_COPIES + 1 {
+                            copies.push((column, row));
+                        }
+                    }
+                }
+            }
+        }
+
+        let unsatisfied = unsatisfied
+            .into_iter()
+            .map(|(mut el, _)| {
+                for cycle in el.cycles.iter_mut() {
+                    let (cycle_len, copies) = &cycles[&cycle.variable];
+                    cycle.cycle_len = *cycle_len;
+                    cycle.copies = copies
+                        .iter()
+                        .copied()
+                        .filter(|place| *place != (cycle.column, el.row))
+                        .take(MAX_REPORTED_COPIES)
+                        .collect();
+                }
+
+                el
+            })
+            .collect();
+
+        SatisfiabilityReport { unsatisfied }
+    }
+}
+
+fn unsatisfied_instance<F: SmallField>(
+    row: usize,
+    gate_name: &str,
+    in_specialized_columns: bool,
+    instance: usize,
+    terms: &[F],
+) -> Option<UnsatisfiedGate<F>> {
+    let violated_terms: Vec<_> = terms
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|(_, term)| term.is_zero() == false)
+        .collect();
+    if violated_terms.is_empty() {
+        return None;
+    }
+
+    Some(UnsatisfiedGate {
+        row,
+        gate: gate_name.to_owned(),
+        in_specialized_columns,
+        instance,
+        violated_terms,
+        namespace: None,
+        cycles: vec![],
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::alloc::Global;
+
+    use super::*;
+    use crate::cs::cs_builder::*;
+    use crate::cs::cs_builder_reference::CsReferenceImplementationBuilder;
+    use crate::cs::gates::fma_gate_without_constant::*;
+    use crate::cs::gates::{ConstantsAllocatorGate, NopGate};
+    use crate::dag::CircuitResolverOpts;
+    use crate::field::goldilocks::GoldilocksField;
+    use crate::field::{Field, U64Representable};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn detailed_check_names_the_failing_gadget() {
+        let geometry = CSGeometry {
+            num_columns_under_copy_permutation: 8,
+            num_witness_columns: 0,
+            num_constant_columns: 2,
+            max_allowed_constraint_degree: 4,
+        };
+
+        let builder_impl = CsReferenceImplementationBuilder::<F, F, DevCSConfig>::new(geometry, 16);
+        let builder = new_builder::<_, F>(builder_impl);
+        let builder = ConstantsAllocatorGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder =
+            NopGate::configure_builder(builder, GatePlacementStrategy::UseGeneralPurposeColumns);
+        let mut cs = builder.build(CircuitResolverOpts::new(64));
+
+        let a = cs.alloc_single_variable_from_witness(F::from_u64_unchecked(1));
+        let b = cs.alloc_single_variable_from_witness(F::from_u64_unchecked(2));
+        let c = cs.alloc_single_variable_from_witness(F::from_u64_unchecked(3));
+        let _ = FmaGateInBaseFieldWithoutConstant::compute_fma(
+            &mut cs,
+            F::TWO,
+            (a, b),
+            F::MINUS_ONE,
+            c,
+        );
+
+        cs.push_namespace("gadget");
+        cs.push_namespace("inner");
+        // 2 * 1 * 2 - 3 is not 5
+        let d = cs.alloc_single_variable_from_witness(F::from_u64_unchecked(5));
+        let gate = FmaGateInBaseFieldWithoutConstant {
+            params: FmaGateInBaseWithoutConstantParams {
+                coeff_for_quadtaric_part: F::TWO,
+                linear_term_coeff: F::MINUS_ONE,
+            },
+            quadratic_part: (a, b),
+            linear_part: c,
+            rhs_part: d,
+        };
+        gate.add_to_cs(&mut cs);
+        cs.pop_namespace();
+        cs.pop_namespace();
+
+        cs.pad_and_shrink();
+        let mut assembly = cs.into_assembly::<Global>();
+        let report = assembly.check_if_satisfied_detailed(&Worker::new_with_num_threads(1));
+
+        assert_eq!(report.unsatisfied.len(), 1, "{}", report);
+        let unsatisfied = &report.unsatisfied[0];
+        assert_eq!(unsatisfied.instance, 1);
+        assert_eq!(unsatisfied.namespace.as_deref(), Some("gadget/inner"));
+        assert_eq!(unsatisfied.violated_terms.len(), 1);
+
+        let rhs_cycle = unsatisfied
+            .cycles
+            .iter()
+            .find(|el| el.variable == d)
+            .unwrap();
+        assert_eq!(rhs_cycle.value, F::from_u64_unchecked(5));
+        assert_eq!(rhs_cycle.cycle_len, 1);
+        // `a` is used by both instances
+        let a_cycle = unsatisfied
+            .cycles
+            .iter()
+            .find(|el| el.variable == a)
+            .unwrap();
+        assert_eq!(a_cycle.cycle_len, 2);
+        assert_eq!(a_cycle.copies, vec![(0, unsatisfied.row)]);
     }
 }
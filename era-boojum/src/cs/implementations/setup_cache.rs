@@ -0,0 +1,509 @@
+//! Saving the full setup of a circuit to disk and loading it back, so that proving runs over the
+//! same circuit don't recompute it.
+use std::alloc::Global;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use super::*;
+
+use crate::config::CSConfig;
+use crate::cs::implementations::fast_serialization::MemcopySerializable;
+use crate::cs::implementations::hints::{DenseVariablesCopyHint, DenseWitnessCopyHint};
+use crate::cs::implementations::polynomial_storage::{SetupBaseStorage, SetupStorage};
+use crate::cs::implementations::reference_cs::CSReferenceAssembly;
+use crate::cs::implementations::setup::FinalizationHintsForProver;
+use crate::cs::implementations::verifier::VerificationKey;
+use crate::cs::oracle::merkle_tree::MerkleTreeWithCap;
+use crate::cs::oracle::TreeHasher;
+use crate::cs::traits::evaluator::TypeErasedGateEvaluationFunction;
+use crate::cs::traits::GoodAllocator;
+use crate::cs::{CSGeometry, LookupParameters};
+use crate::field::{SmallField, U64Representable};
+use crate::worker::Worker;
+
+// Layout:
+// - magic, version: u32 little-endian
+// - fingerprint, finalization hint, verification key: bincode
+// - base setup, setup, setup tree, variables hint, witness hint: memcopy serialization
+const SETUP_MAGIC: &[u8; 4] = b"BJSU";
+const SETUP_FORMAT_VERSION: u32 = 2;
+
+/// Shape of the circuit a setup is computed for: the geometry, the gates and tables it is
+/// configured with, the trace it is padded to, the parameters of the setup oracle and a digest
+/// of the placement of the gates, which tells apart circuits that only differ in their wiring
+/// or constants
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SetupFingerprint {
+    pub geometry: CSGeometry,
+    pub lookup_parameters: LookupParameters,
+    pub trace_len: usize,
+    pub public_inputs: Vec<(usize, usize)>,
+    pub general_purpose_gates: Vec<String>,
+    pub specialized_gates: Vec<String>,
+    // width and size of every table
+    pub lookup_tables: Vec<(usize, usize)>,
+    pub fri_lde_factor: usize,
+    pub cap_size: usize,
+    pub tree_hasher: String,
+    /// SHA-256 of the copy-permutation columns, the gate selectors and the constants of every
+    /// row. An assembly made with `into_assembly_for_repeated_proving` has no gates placed, so
+    /// it leaves the digest out and only checks the shape
+    pub layout_digest: Option<[u8; 32]>,
+}
+
+impl SetupFingerprint {
+    /// Whether a setup saved with the `saved` fingerprint can be used for this circuit
+    pub fn matches(&self, saved: &Self) -> bool {
+        let Self {
+            geometry,
+            lookup_parameters,
+            trace_len,
+            public_inputs,
+            general_purpose_gates,
+            specialized_gates,
+            lookup_tables,
+            fri_lde_factor,
+            cap_size,
+            tree_hasher,
+            layout_digest,
+        } = self;
+
+        *geometry == saved.geometry
+            && *lookup_parameters == saved.lookup_parameters
+            && *trace_len == saved.trace_len
+            && *public_inputs == saved.public_inputs
+            && *general_purpose_gates == saved.general_purpose_gates
+            && *specialized_gates == saved.specialized_gates
+            && *lookup_tables == saved.lookup_tables
+            && *fri_lde_factor == saved.fri_lde_factor
+            && *cap_size == saved.cap_size
+            && *tree_hasher == saved.tree_hasher
+            && (layout_digest.is_none() || *layout_digest == saved.layout_digest)
+    }
+}
+
+/// Everything `prove_from_witness_vec_and_precomputations` needs besides the witness, together
+/// with the hint to recreate the assembly with `into_assembly_for_repeated_proving`
+pub struct FullSetup<
+    F: SmallField,
+    P: field::traits::field_like::PrimeFieldLikeVectorized<Base = F>,
+    H: TreeHasher<F>,
+> {
+    pub finalization_hint: FinalizationHintsForProver,
+    pub base_setup: SetupBaseStorage<F, P, Global, Global>,
+    pub setup: SetupStorage<F, P, Global, Global>,
+    pub vk: VerificationKey<F, H>,
+    pub setup_tree: MerkleTreeWithCap<F, H, Global, Global>,
+    pub vars_hint: DenseVariablesCopyHint,
+    pub wits_hint: DenseWitnessCopyHint,
+}
+
+impl<
+        F: SmallField,
+        P: field::traits::field_like::PrimeFieldLikeVectorized<Base = F>,
+        CFG: CSConfig,
+        A: GoodAllocator,
+    > CSReferenceAssembly<F, P, CFG, A>
+{
+    pub fn setup_fingerprint<H: TreeHasher<F>>(
+        &self,
+        fri_lde_factor: usize,
+        merkle_tree_cap_size: usize,
+    ) -> SetupFingerprint {
+        let gate_names = |evaluators: &[TypeErasedGateEvaluationFunction<F, P>]| -> Vec<String> {
+            evaluators.iter().map(|el| el.unique_name.clone()).collect()
+        };
+
+        SetupFingerprint {
+            geometry: self.parameters,
+            lookup_parameters: self.lookup_parameters,
+            trace_len: self.max_trace_len,
+            public_inputs: self.public_inputs.clone(),
+            general_purpose_gates: gate_names(
+                &self
+                    .evaluation_data_over_general_purpose_columns
+                    .evaluators_over_general_purpose_columns,
+            ),
+            specialized_gates: gate_names(
+                &self
+                    .evaluation_data_over_specialized_columns
+                    .evaluators_over_specialized_columns,
+            ),
+            lookup_tables: self
+                .lookup_tables
+                .iter()
+                .map(|el| (el.width(), el.table_size()))
+                .collect(),
+            fri_lde_factor,
+            cap_size: merkle_tree_cap_size,
+            tree_hasher: std::any::type_name::<H>().to_owned(),
+            layout_digest: (self.next_available_place_idx != 0).then(|| self.layout_digest()),
+        }
+    }
+
+    fn layout_digest(&self) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        for column in self.copy_permutation_data.iter() {
+            for variable in column.iter() {
+                hasher.update(variable.0.to_le_bytes());
+            }
+        }
+        for column in self.witness_placement_data.iter() {
+            for witness in column.iter() {
+                hasher.update(witness.0.to_le_bytes());
+            }
+        }
+        for gate_idx in self.gates_application_sets.iter() {
+            hasher.update((*gate_idx as u64).to_le_bytes());
+        }
+        // rows have different numbers of constants, so the lengths go in too
+        let constants = self
+            .constants_requested_per_row
+            .iter()
+            .map(|el| &el[..])
+            .chain(
+                self.constants_for_gates_in_specialized_mode
+                    .iter()
+                    .map(|el| &el[..]),
+            );
+        for row in constants {
+            hasher.update((row.len() as u64).to_le_bytes());
+            for constant in row.iter() {
+                hasher.update(constant.as_u64_reduced().to_le_bytes());
+            }
+        }
+
+        hasher.finalize().into()
+    }
+
+    /// Same as `get_full_setup`, keeping the hint the assembly was finalized with
+    pub fn get_full_setup_with_hint<H: TreeHasher<F>>(
+        &self,
+        worker: &Worker,
+        finalization_hint: FinalizationHintsForProver,
+        fri_lde_factor: usize,
+        merkle_tree_cap_size: usize,
+    ) -> FullSetup<F, P, H> {
+        let (base_setup, setup, vk, setup_tree, vars_hint, wits_hint) =
+            self.get_full_setup(worker, fri_lde_factor, merkle_tree_cap_size);
+
+        FullSetup {
+            finalization_hint,
+            base_setup,
+            setup,
+            vk,
+            setup_tree,
+            vars_hint,
+            wits_hint,
+        }
+    }
+}
+
+fn read_header<R: Read>(
+    mut src: R,
+) -> Result<(SetupFingerprint, FinalizationHintsForProver), Box<dyn Error>> {
+    let mut magic = [0u8; 4];
+    src.read_exact(&mut magic).map_err(Box::new)?;
+    if &magic != SETUP_MAGIC {
+        return Err(Box::<dyn Error>::from("not a circuit setup"));
+    }
+
+    let mut version = [0u8; 4];
+    src.read_exact(&mut version).map_err(Box::new)?;
+    let version = u32::from_le_bytes(version);
+    if version != SETUP_FORMAT_VERSION {
+        return Err(Box::<dyn Error>::from(format!(
+            "setup format version {} is not supported, expected {}",
+            version, SETUP_FORMAT_VERSION
+        )));
+    }
+
+    let fingerprint: SetupFingerprint = bincode::deserialize_from(&mut src)?;
+    let finalization_hint: FinalizationHintsForProver = bincode::deserialize_from(&mut src)?;
+
+    Ok((fingerprint, finalization_hint))
+}
+
+impl<
+        F: SmallField,
+        P: field::traits::field_like::PrimeFieldLikeVectorized<Base = F>,
+        H: TreeHasher<F>,
+    > FullSetup<F, P, H>
+where
+    VerificationKey<F, H>: serde::Serialize + serde::de::DeserializeOwned,
+    MerkleTreeWithCap<F, H, Global, Global>: MemcopySerializable,
+{
+    pub fn write_into<W: Write>(
+        &self,
+        fingerprint: &SetupFingerprint,
+        mut dst: W,
+    ) -> Result<(), Box<dyn Error>> {
+        dst.write_all(SETUP_MAGIC).map_err(Box::new)?;
+        dst.write_all(&SETUP_FORMAT_VERSION.to_le_bytes())
+            .map_err(Box::new)?;
+
+        bincode::serialize_into(&mut dst, fingerprint)?;
+        bincode::serialize_into(&mut dst, &self.finalization_hint)?;
+        bincode::serialize_into(&mut dst, &self.vk)?;
+
+        self.base_setup.write_into_buffer(&mut dst)?;
+        self.setup.write_into_buffer(&mut dst)?;
+        self.setup_tree.write_into_buffer(&mut dst)?;
+        self.vars_hint.write_into_buffer(&mut dst)?;
+        self.wits_hint.write_into_buffer(&mut dst)?;
+
+        Ok(())
+    }
+
+    /// Fails if the setup was computed for a circuit of another shape than `expected`
+    pub fn read_from<R: Read>(
+        mut src: R,
+        expected: &SetupFingerprint,
+    ) -> Result<Self, Box<dyn Error>> {
+        let (fingerprint, finalization_hint) = read_header(&mut src)?;
+        if !expected.matches(&fingerprint) {
+            return Err(Box::<dyn Error>::from(format!(
+                "setup was computed for another circuit: expected {:?}, got {:?}",
+                expected, fingerprint
+            )));
+        }
+
+        let vk: VerificationKey<F, H> = bincode::deserialize_from(&mut src)?;
+
+        let base_setup = MemcopySerializable::read_from_buffer(&mut src)?;
+        let setup = MemcopySerializable::read_from_buffer(&mut src)?;
+        let setup_tree = MemcopySerializable::read_from_buffer(&mut src)?;
+        let vars_hint = MemcopySerializable::read_from_buffer(&mut src)?;
+        let wits_hint = MemcopySerializable::read_from_buffer(&mut src)?;
+
+        let new = Self {
+            finalization_hint,
+            base_setup,
+            setup,
+            vk,
+            setup_tree,
+            vars_hint,
+            wits_hint,
+        };
+
+        Ok(new)
+    }
+}
+
+pub fn save_setup<
+    F: SmallField,
+    P: field::traits::field_like::PrimeFieldLikeVectorized<Base = F>,
+    H: TreeHasher<F>,
+>(
+    path: impl AsRef<Path>,
+    full_setup: &FullSetup<F, P, H>,
+    fingerprint: &SetupFingerprint,
+) -> Result<(), Box<dyn Error>>
+where
+    VerificationKey<F, H>: serde::Serialize + serde::de::DeserializeOwned,
+    MerkleTreeWithCap<F, H, Global, Global>: MemcopySerializable,
+{
+    let mut dst = BufWriter::new(File::create(path).map_err(Box::new)?);
+    full_setup.write_into(fingerprint, &mut dst)?;
+    dst.flush().map_err(Box::new)?;
+
+    Ok(())
+}
+
+pub fn load_setup<
+    F: SmallField,
+    P: field::traits::field_like::PrimeFieldLikeVectorized<Base = F>,
+    H: TreeHasher<F>,
+>(
+    path: impl AsRef<Path>,
+    expected: &SetupFingerprint,
+) -> Result<FullSetup<F, P, H>, Box<dyn Error>>
+where
+    VerificationKey<F, H>: serde::Serialize + serde::de::DeserializeOwned,
+    MerkleTreeWithCap<F, H, Global, Global>: MemcopySerializable,
+{
+    let src = BufReader::new(File::open(path).map_err(Box::new)?);
+
+    FullSetup::read_from(src, expected)
+}
+
+/// Reads only the finalization hint of a saved setup. It is required to create the assembly
+/// with `into_assembly_for_repeated_proving`, whose fingerprint is then checked by `load_setup`
+pub fn load_finalization_hint(
+    path: impl AsRef<Path>,
+) -> Result<FinalizationHintsForProver, Box<dyn Error>> {
+    let src = BufReader::new(File::open(path).map_err(Box::new)?);
+    let (_, finalization_hint) = read_header(src)?;
+
+    Ok(finalization_hint)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algebraic_props::round_function::AbsorptionModeOverwrite;
+    use crate::algebraic_props::sponge::GoldilocksPoseidonSponge;
+    use crate::config::DevCSConfig;
+    use crate::cs::cs_builder::*;
+    use crate::cs::cs_builder_reference::CsReferenceImplementationBuilder;
+    use crate::cs::cs_builder_verifier::CsVerifierBuilder;
+    use crate::cs::gates::{ConstantsAllocatorGate, FmaGateInBaseFieldWithoutConstant, NopGate};
+    use crate::cs::implementations::pow::NoPow;
+    use crate::cs::implementations::prover::ProofConfig;
+    use crate::cs::implementations::reference_cs::CSReferenceImplementation;
+    use crate::cs::implementations::transcript::GoldilocksPoisedonTranscript;
+    use crate::cs::traits::cs::ConstraintSystem;
+    use crate::cs::traits::gate::GatePlacementStrategy;
+    use crate::cs::{GateConfigurationHolder, StaticToolboxHolder};
+    use crate::dag::CircuitResolverOpts;
+    use crate::field::goldilocks::{GoldilocksExt2, GoldilocksField};
+    use crate::field::{Field, U64Representable};
+
+    type F = GoldilocksField;
+    type H = GoldilocksPoseidonSponge<AbsorptionModeOverwrite>;
+
+    const GEOMETRY: CSGeometry = CSGeometry {
+        num_columns_under_copy_permutation: 8,
+        num_witness_columns: 0,
+        num_constant_columns: 2,
+        max_allowed_constraint_degree: 4,
+    };
+    const LDE_FACTOR: usize = 4;
+    const CAP_SIZE: usize = 4;
+
+    fn configure<
+        T: CsBuilderImpl<F, T>,
+        GC: GateConfigurationHolder<F>,
+        TB: StaticToolboxHolder,
+    >(
+        builder: CsBuilder<T, F, GC, TB>,
+    ) -> CsBuilder<T, F, impl GateConfigurationHolder<F>, impl StaticToolboxHolder> {
+        let builder = ConstantsAllocatorGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+
+        NopGate::configure_builder(builder, GatePlacementStrategy::UseGeneralPurposeColumns)
+    }
+
+    fn new_cs() -> CSReferenceImplementation<
+        F,
+        F,
+        DevCSConfig,
+        impl GateConfigurationHolder<F>,
+        impl StaticToolboxHolder,
+    > {
+        let builder_impl =
+            CsReferenceImplementationBuilder::<F, F, DevCSConfig>::new(GEOMETRY, 128);
+        let builder = configure(new_builder::<_, F>(builder_impl));
+
+        builder.build(CircuitResolverOpts::new(512))
+    }
+
+    fn synthesize(cs: &mut impl ConstraintSystem<F>, input: u64) {
+        let mut previous = cs.alloc_single_variable_from_witness(F::from_u64_unchecked(input));
+        for _ in 0..50 {
+            let b = cs.alloc_single_variable_from_witness(F::from_u64_unchecked(2));
+            previous = FmaGateInBaseFieldWithoutConstant::compute_fma(
+                cs,
+                F::TWO,
+                (previous, b),
+                F::MINUS_ONE,
+                previous,
+            );
+        }
+    }
+
+    #[test]
+    fn saved_setup_proves_repeatedly() {
+        let worker = Worker::new_with_num_threads(1);
+
+        let mut cs = new_cs();
+        synthesize(&mut cs, 1);
+        let (_, finalization_hint) = cs.pad_and_shrink();
+        let assembly = cs.into_assembly::<Global>();
+
+        let fingerprint = assembly.setup_fingerprint::<H>(LDE_FACTOR, CAP_SIZE);
+        let full_setup = assembly.get_full_setup_with_hint::<H>(
+            &worker,
+            finalization_hint,
+            LDE_FACTOR,
+            CAP_SIZE,
+        );
+
+        let mut buffer = vec![];
+        full_setup.write_into(&fingerprint, &mut buffer).unwrap();
+
+        let other_fingerprint = assembly.setup_fingerprint::<H>(LDE_FACTOR, CAP_SIZE * 2);
+        assert!(FullSetup::<F, F, H>::read_from(&buffer[..], &other_fingerprint).is_err());
+
+        // a circuit wired differently
+        let mut cs = new_cs();
+        synthesize(&mut cs, 1);
+        let a = cs.alloc_single_variable_from_witness(F::ONE);
+        let _ = FmaGateInBaseFieldWithoutConstant::compute_fma(&mut cs, F::ONE, (a, a), F::ZERO, a);
+        let _ = cs.pad_and_shrink();
+        let other_fingerprint = cs
+            .into_assembly::<Global>()
+            .setup_fingerprint::<H>(LDE_FACTOR, CAP_SIZE);
+        assert_ne!(other_fingerprint.layout_digest, fingerprint.layout_digest);
+        assert!(FullSetup::<F, F, H>::read_from(&buffer[..], &other_fingerprint).is_err());
+
+        // the proving run only knows the shape of the circuit
+        let (_, finalization_hint) = read_header(&buffer[..]).unwrap();
+        let assembly = new_cs().into_assembly_for_repeated_proving::<Global>(&finalization_hint);
+        let loaded = FullSetup::<F, F, H>::read_from(
+            &buffer[..],
+            &assembly.setup_fingerprint::<H>(LDE_FACTOR, CAP_SIZE),
+        )
+        .unwrap();
+        assert_eq!(
+            loaded.vk.setup_merkle_tree_cap,
+            full_setup.vk.setup_merkle_tree_cap
+        );
+
+        // and gets the witness of another instance
+        let mut cs = new_cs();
+        synthesize(&mut cs, 5);
+        cs.pad_and_shrink_using_hint(&loaded.finalization_hint);
+        let witness = cs.into_assembly::<Global>().witness.unwrap();
+
+        let proof_config = ProofConfig {
+            fri_lde_factor: LDE_FACTOR,
+            merkle_tree_cap_size: CAP_SIZE,
+            pow_bits: 0,
+            ..Default::default()
+        };
+        let proof = assembly.prove_from_witness_vec_and_precomputations::<
+            GoldilocksExt2,
+            GoldilocksPoisedonTranscript,
+            H,
+            NoPow,
+        >(
+            &witness,
+            proof_config,
+            &loaded.base_setup,
+            &loaded.setup,
+            &loaded.setup_tree,
+            &loaded.vk,
+            &loaded.vars_hint,
+            &loaded.wits_hint,
+            (),
+            &worker,
+        );
+
+        let builder = configure(new_builder::<_, F>(
+            CsVerifierBuilder::<F, GoldilocksExt2>::new_from_parameters(GEOMETRY),
+        ));
+        let verifier = builder.build(());
+        assert!(verifier.verify::<H, GoldilocksPoisedonTranscript, NoPow>((), &loaded.vk, &proof));
+    }
+}
@@ -213,6 +213,11 @@ pub trait ConstraintSystem<F: SmallField>: Send + Sync {
             .unwrap_or_else(|| panic!("gate {} must be allowed", std::any::type_name::<G>()))
     }
 
+    // Gadget-level tags for diagnostics: everything placed until the matching `pop_namespace`
+    // is attributed to `name`, nested under the namespaces that are already pushed
+    fn push_namespace(&mut self, _name: &str) {}
+    fn pop_namespace(&mut self) {}
+
     // When we declare a circuit (not just variables, but their concrete locations)
     // we need few things to
     fn next_available_row(&self) -> usize;
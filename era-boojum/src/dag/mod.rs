@@ -1,4 +1,6 @@
-use self::resolvers::mt::sorters::sorter_live::LiveResolverSorter;
+use self::resolvers::mt::sorters::record_storage::{FileRecordSource, FileRecordWriter};
+use self::resolvers::mt::sorters::sorter_live::{LiveRecordingResolverSorter, LiveResolverSorter};
+use self::resolvers::mt::sorters::sorter_playback::PlaybackResolverSorter;
 use std::fmt::Debug;
 use std::hint::spin_loop;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -145,4 +147,11 @@ pub type StCircuitResolver<F, CFG> = resolvers::StCircuitResolver<F, CFG>;
 pub type MtCircuitResolver<F, CFG> =
     resolvers::MtCircuitResolver<F, LiveResolverSorter<F, CFG>, CFG>;
 
+// Resolves as the default one, and writes the resolution order to a file once done
+pub type RecordingCircuitResolver<F, CFG> =
+    resolvers::MtCircuitResolver<F, LiveRecordingResolverSorter<F, CFG, FileRecordWriter>, CFG>;
+// Replays the resolution order recorded for the same circuit, skipping the sorting
+pub type PlaybackCircuitResolver<F, CFG> =
+    resolvers::MtCircuitResolver<F, PlaybackResolverSorter<F, FileRecordSource, CFG>, CFG>;
+
 pub type DefaultCircuitResolver<F, CFG> = MtCircuitResolver<F, CFG>;
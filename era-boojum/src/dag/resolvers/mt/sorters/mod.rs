@@ -13,6 +13,7 @@ use crate::{
 
 use super::{resolution_window::RWConfig, ResolverCommonData, ResolverComms};
 
+pub mod record_storage;
 pub mod sorter_live;
 pub mod sorter_playback;
 
@@ -0,0 +1,287 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use super::{
+    ResolutionRecord, ResolutionRecordItem, ResolutionRecordSource, ResolutionRecordWriter,
+};
+
+// Layout, all integers little-endian:
+// - magic, version: u32
+// - fingerprint length: u64, fingerprint bytes
+// - registrations_count, values_count, items count: u64
+// - per item: added_at, accepted_at: u32, order_len: u64, order_ix: u32, parallelism: u16
+const RECORD_MAGIC: &[u8; 4] = b"BJRR";
+const RECORD_FORMAT_VERSION: u32 = 2;
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+fn read_array<R: Read, const N: usize>(src: &mut R) -> std::io::Result<[u8; N]> {
+    let mut buffer = [0u8; N];
+    src.read_exact(&mut buffer)?;
+
+    Ok(buffer)
+}
+
+fn read_u16<R: Read>(src: &mut R) -> std::io::Result<u16> {
+    read_array(src).map(u16::from_le_bytes)
+}
+
+fn read_u32<R: Read>(src: &mut R) -> std::io::Result<u32> {
+    read_array(src).map(u32::from_le_bytes)
+}
+
+fn read_u64<R: Read>(src: &mut R) -> std::io::Result<u64> {
+    read_array(src).map(u64::from_le_bytes)
+}
+
+impl ResolutionRecord {
+    /// Writes the record after `fingerprint`, which identifies the circuit it was made for. Its
+    /// content is up to the caller, the resolver doesn't interpret it
+    pub fn write_into<W: Write>(&self, fingerprint: &[u8], dst: &mut W) -> std::io::Result<()> {
+        dst.write_all(RECORD_MAGIC)?;
+        dst.write_all(&RECORD_FORMAT_VERSION.to_le_bytes())?;
+        dst.write_all(&(fingerprint.len() as u64).to_le_bytes())?;
+        dst.write_all(fingerprint)?;
+        dst.write_all(&(self.registrations_count as u64).to_le_bytes())?;
+        dst.write_all(&(self.values_count as u64).to_le_bytes())?;
+        dst.write_all(&(self.items.len() as u64).to_le_bytes())?;
+
+        for item in self.items.iter() {
+            dst.write_all(&item.added_at.to_le_bytes())?;
+            dst.write_all(&item.accepted_at.to_le_bytes())?;
+            dst.write_all(&(item.order_len as u64).to_le_bytes())?;
+            dst.write_all(&u32::from(item.order_ix).to_le_bytes())?;
+            dst.write_all(&item.parallelism.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a record written by `write_into`, together with its fingerprint
+    pub fn read_from<R: Read>(src: &mut R) -> std::io::Result<(Self, Vec<u8>)> {
+        let magic: [u8; 4] = read_array(src)?;
+        if &magic != RECORD_MAGIC {
+            return Err(invalid_data("not a resolution record".to_owned()));
+        }
+
+        let version = read_u32(src)?;
+        if version != RECORD_FORMAT_VERSION {
+            return Err(invalid_data(format!(
+                "resolution record format version {} is not supported, expected {}",
+                version, RECORD_FORMAT_VERSION
+            )));
+        }
+
+        let fingerprint_len = read_u64(src)? as usize;
+        let mut fingerprint = vec![0u8; fingerprint_len];
+        src.read_exact(&mut fingerprint)?;
+
+        let registrations_count = read_u64(src)? as usize;
+        let values_count = read_u64(src)? as usize;
+        let num_items = read_u64(src)? as usize;
+
+        let mut items = Vec::with_capacity(num_items);
+        for _ in 0..num_items {
+            items.push(ResolutionRecordItem {
+                added_at: read_u32(src)?,
+                accepted_at: read_u32(src)?,
+                order_len: read_u64(src)? as usize,
+                order_ix: read_u32(src)?.into(),
+                parallelism: read_u16(src)?,
+            });
+        }
+
+        let record = Self {
+            items,
+            registrations_count,
+            values_count,
+        };
+
+        Ok((record, fingerprint))
+    }
+}
+
+/// Persists the record of the live sorter once the resolution is complete
+pub struct FileRecordWriter {
+    path: PathBuf,
+    fingerprint: Vec<u8>,
+}
+
+impl FileRecordWriter {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self::with_fingerprint(path, Vec::new())
+    }
+
+    /// Stores `fingerprint` in the header, for `FileRecordSource::fingerprint` to tell whether
+    /// the record was made for the circuit about to be played back
+    pub fn with_fingerprint<P: AsRef<Path>>(path: P, fingerprint: Vec<u8>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            fingerprint,
+        }
+    }
+}
+
+impl ResolutionRecordWriter for FileRecordWriter {
+    fn store(&mut self, record: &ResolutionRecord) {
+        let file = File::create(&self.path).unwrap_or_else(|err| {
+            panic!(
+                "failed to create resolution record {}: {}",
+                self.path.display(),
+                err
+            )
+        });
+        let mut dst = BufWriter::new(file);
+        record
+            .write_into(&self.fingerprint, &mut dst)
+            .and_then(|_| dst.flush())
+            .unwrap_or_else(|err| {
+                panic!(
+                    "failed to write resolution record {}: {}",
+                    self.path.display(),
+                    err
+                )
+            });
+    }
+}
+
+/// Record written by `FileRecordWriter`, loaded to drive the playback sorter
+pub struct FileRecordSource {
+    record: ResolutionRecord,
+    fingerprint: Vec<u8>,
+}
+
+impl FileRecordSource {
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let mut src = BufReader::new(File::open(path)?);
+        let (record, fingerprint) = ResolutionRecord::read_from(&mut src)?;
+
+        Ok(Self {
+            record,
+            fingerprint,
+        })
+    }
+
+    /// The fingerprint the record was written with, empty if none was given
+    pub fn fingerprint(&self) -> &[u8] {
+        &self.fingerprint
+    }
+}
+
+impl ResolutionRecordSource for FileRecordSource {
+    fn get(&self) -> &ResolutionRecord {
+        &self.record
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::{DoPerformRuntimeAsserts, Resolver};
+    use crate::cs::traits::cs::DstBuffer;
+    use crate::cs::{Place, Variable};
+    use crate::dag::resolvers::mt::sorters::sorter_live::LiveRecordingResolverSorter;
+    use crate::dag::resolvers::mt::sorters::sorter_playback::PlaybackResolverSorter;
+    use crate::dag::resolvers::mt::sorters::ResolverSortingMode;
+    use crate::dag::resolvers::MtCircuitResolver;
+    use crate::dag::{CircuitResolverOpts, WitnessSource as _};
+    use crate::field::goldilocks::GoldilocksField;
+    use crate::field::Field;
+
+    type F = GoldilocksField;
+    type Cfg = Resolver<DoPerformRuntimeAsserts>;
+
+    fn populate<RS: ResolverSortingMode<F>>(
+        resolver: &mut MtCircuitResolver<F, RS, Cfg>,
+        init_value: u64,
+    ) -> Vec<Place> {
+        let places: Vec<_> = (0..8)
+            .map(|idx| Place::from_variable(Variable::from_variable_index(idx)))
+            .collect();
+
+        resolver.set_value(places[0], F::from_u64_with_reduction(init_value));
+        for pair in places.windows(2) {
+            resolver.add_resolution(
+                &pair[..1],
+                &pair[1..],
+                |ins: &[F], outs: &mut DstBuffer<F>| {
+                    let mut value = ins[0];
+                    value.double();
+                    outs.push(value);
+                },
+            );
+        }
+
+        places
+    }
+
+    #[test]
+    fn record_file_playback() {
+        let path = std::env::temp_dir().join(format!(
+            "boojum_resolution_record_{}.bin",
+            std::process::id()
+        ));
+
+        let mut storage = MtCircuitResolver::<
+            F,
+            LiveRecordingResolverSorter<F, Cfg, FileRecordWriter>,
+            Cfg,
+        >::new((
+            CircuitResolverOpts {
+                max_variables: 100,
+                desired_parallelism: 16,
+            },
+            FileRecordWriter::with_fingerprint(&path, b"doubling chain".to_vec()),
+        ));
+        let _ = populate(&mut storage, 1);
+        storage.wait_till_resolved();
+        let recorded = storage.retrieve_sequence().clone();
+
+        let source = FileRecordSource::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(source.fingerprint(), b"doubling chain");
+        assert_eq!(
+            source.get().registrations_count,
+            recorded.registrations_count
+        );
+        assert_eq!(source.get().values_count, recorded.values_count);
+        assert_eq!(source.get().items.len(), recorded.items.len());
+
+        // playback of the same circuit over a different input
+        let mut storage =
+            MtCircuitResolver::<F, PlaybackResolverSorter<F, FileRecordSource, Cfg>, Cfg>::new(
+                source,
+            );
+        let places = populate(&mut storage, 3);
+        storage.wait_till_resolved();
+
+        for (idx, place) in places.into_iter().enumerate() {
+            assert_eq!(
+                storage.get_value_unchecked(place),
+                F::from_u64_with_reduction(3u64 << idx)
+            );
+        }
+    }
+
+    #[test]
+    fn record_format_is_checked() {
+        let record = ResolutionRecord::new(0, 0, 0);
+        let mut buffer = vec![];
+        record.write_into(&[1, 2, 3], &mut buffer).unwrap();
+        let (_, fingerprint) = ResolutionRecord::read_from(&mut &buffer[..]).unwrap();
+        assert_eq!(fingerprint, [1, 2, 3]);
+
+        let mut wrong_version = buffer.clone();
+        wrong_version[4] += 1;
+        assert!(ResolutionRecord::read_from(&mut &wrong_version[..]).is_err());
+
+        let mut wrong_magic = buffer;
+        wrong_magic[0] = 0;
+        assert!(ResolutionRecord::read_from(&mut &wrong_magic[..]).is_err());
+    }
+}
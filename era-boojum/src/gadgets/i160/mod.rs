@@ -0,0 +1,198 @@
+use super::*;
+use crate::cs::traits::cs::ConstraintSystem;
+use crate::cs::traits::cs::DstBuffer;
+use crate::cs::Variable;
+use crate::field::SmallField;
+use crate::gadgets::boolean::Boolean;
+use crate::gadgets::signed_int::{
+    div_rem_limbs, overflowing_add_limbs, overflowing_sub_limbs, signed_int,
+};
+use crate::gadgets::traits::allocatable::{CSAllocatable, CSAllocatableExt, CSPlaceholder};
+use crate::gadgets::traits::castable::WitnessCastable;
+use crate::gadgets::traits::encodable::CircuitVarLengthEncodable;
+use crate::gadgets::traits::selectable::{MultiSelectable, Selectable};
+use crate::gadgets::traits::witnessable::WitnessHookable;
+use crate::gadgets::u160::{decompose_address_as_u32x5, recompose_address_from_u32x5, UInt160};
+use crate::gadgets::u32::UInt32;
+use ethereum_types::Address;
+
+signed_int!(
+    /// Signed 160-bit integer in two's complement. The witness is the two's complement bit
+    /// pattern, big-endian like the addresses
+    Int160,
+    UInt160,
+    Address,
+    5,
+    decompose_address_as_u32x5,
+    recompose_address_from_u32x5
+);
+
+#[cfg(test)]
+mod test {
+    use std::alloc::Global;
+
+    use super::*;
+    use crate::config::DevCSConfig;
+    use crate::cs::cs_builder::new_builder;
+    use crate::cs::cs_builder_reference::*;
+    use crate::cs::gates::*;
+    use crate::cs::traits::gate::GatePlacementStrategy;
+    use crate::cs::CSGeometry;
+    use crate::dag::CircuitResolverOpts;
+    use crate::field::goldilocks::GoldilocksField;
+    use crate::gadgets::tables::xor8::{create_xor8_table, Xor8Table};
+    use crate::worker::Worker;
+    use ethereum_types::U256;
+    use rand::{Rng, SeedableRng};
+
+    type F = GoldilocksField;
+
+    fn from_i128(value: i128) -> Address {
+        let abs = U256::from(value.unsigned_abs());
+        let value = if value < 0 {
+            (!abs).overflowing_add(U256::one()).0
+        } else {
+            abs
+        };
+        let mut bytes = [0u8; 32];
+        value.to_big_endian(&mut bytes);
+
+        Address::from_slice(&bytes[12..])
+    }
+
+    fn to_i128(value: Address) -> i128 {
+        let value = U256::from_big_endian(value.as_bytes());
+        if value.bit(159) {
+            let abs = (U256::one() << 160) - value;
+            -(abs.as_u128() as i128)
+        } else {
+            value.as_u128() as i128
+        }
+    }
+
+    fn floor_div_rem(a: i128, b: i128) -> (i128, i128) {
+        if b == 0 {
+            return (0, a);
+        }
+        let (mut quotient, mut remainder) = (a / b, a % b);
+        if remainder != 0 && ((remainder < 0) != (b < 0)) {
+            quotient -= 1;
+            remainder += b;
+        }
+
+        (quotient, remainder)
+    }
+
+    #[test]
+    fn test_signed_arithmetic() {
+        let geometry = CSGeometry {
+            num_columns_under_copy_permutation: 60,
+            num_witness_columns: 0,
+            num_constant_columns: 4,
+            max_allowed_constraint_degree: 4,
+        };
+
+        let builder_impl =
+            CsReferenceImplementationBuilder::<F, F, DevCSConfig>::new(geometry, 1 << 18);
+        let builder = new_builder::<_, F>(builder_impl);
+
+        let builder = builder.allow_lookup(
+            crate::cs::LookupParameters::UseSpecializedColumnsWithTableIdAsConstant {
+                width: 3,
+                num_repetitions: 5,
+                share_table_id: true,
+            },
+        );
+        let builder = ConstantsAllocatorGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = ReductionGate::<F, 4>::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = UIntXAddGate::<32>::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = U8x4FMAGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = SelectionGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder =
+            NopGate::configure_builder(builder, GatePlacementStrategy::UseGeneralPurposeColumns);
+
+        let mut owned_cs = builder.build(CircuitResolverOpts::new(1 << 22));
+
+        let table = create_xor8_table();
+        owned_cs.add_lookup_table::<Xor8Table, 3>(table);
+
+        let cs = &mut owned_cs;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut values = vec![(-7, 2), (7, -2), (-7, -2), (-5, 0)];
+        for _ in 0..4 {
+            let a = rng.gen::<i64>() as i128;
+            let b = rng.gen::<i32>() as i128;
+            values.push((a, b));
+        }
+
+        for (a_value, b_value) in values {
+            let a = Int160::allocate(cs, from_i128(a_value));
+            let b = Int160::allocate(cs, from_i128(b_value));
+
+            let is_negative = a.is_negative(cs).witness_hook(&*cs)().unwrap();
+            assert_eq!(is_negative, a_value < 0);
+            let abs = a.abs(cs).witness_hook(&*cs)().unwrap();
+            assert_eq!(to_i128(abs), a_value.abs());
+
+            let (sum, of) = a.overflowing_add(cs, &b);
+            assert_eq!(
+                to_i128(sum.witness_hook(&*cs)().unwrap()),
+                a_value + b_value
+            );
+            assert!(!of.witness_hook(&*cs)().unwrap());
+            let (difference, of) = a.overflowing_sub(cs, &b);
+            assert_eq!(
+                to_i128(difference.witness_hook(&*cs)().unwrap()),
+                a_value - b_value
+            );
+            assert!(!of.witness_hook(&*cs)().unwrap());
+
+            let lt = a.lt(cs, &b).witness_hook(&*cs)().unwrap();
+            assert_eq!(lt, a_value < b_value);
+
+            let (quotient, remainder) = a.div_rem_floor(cs, &b);
+            let quotient = to_i128(quotient.witness_hook(&*cs)().unwrap());
+            let remainder = to_i128(remainder.witness_hook(&*cs)().unwrap());
+            assert_eq!((quotient, remainder), floor_div_rem(a_value, b_value));
+        }
+
+        // overflows at the edges of the range
+        let mut max_value = Address::repeat_byte(0xff);
+        max_value.0[0] = 0x7f;
+        let max = Int160::allocate(cs, max_value);
+        let one = Int160::allocate(cs, from_i128(1));
+        let (sum, of) = max.overflowing_add(cs, &one);
+        let sum = sum.witness_hook(&*cs)().unwrap();
+        assert_eq!(U256::from_big_endian(sum.as_bytes()), U256::one() << 159);
+        assert!(of.witness_hook(&*cs)().unwrap());
+        let min = Int160::allocate(cs, sum);
+        let is_negative = min.is_negative(cs).witness_hook(&*cs)().unwrap();
+        assert!(is_negative);
+
+        drop(cs);
+        owned_cs.pad_and_shrink();
+        let mut owned_cs = owned_cs.into_assembly::<Global>();
+        let worker = Worker::new_with_num_threads(8);
+        assert!(owned_cs.check_if_satisfied(&worker));
+    }
+}
@@ -1,2 +1,3 @@
 pub mod lc;
 pub mod limbs_decompose;
+pub mod var_length;
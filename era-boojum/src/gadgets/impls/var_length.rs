@@ -0,0 +1,111 @@
+use crate::config::*;
+use crate::cs::gates::ConstantAllocatableCS;
+use crate::cs::traits::cs::{ConstraintSystem, DstBuffer};
+use crate::cs::{Place, Variable};
+use crate::field::SmallField;
+use crate::gadgets::boolean::Boolean;
+use crate::gadgets::num::Num;
+use crate::gadgets::u32::UInt32;
+
+/// Marks the position `len` among `0..=max_len`: exactly one of the returned booleans is set,
+/// and it's the one at index `len`. Enforces `len <= max_len` along the way.
+/// Costs a booleanity check per position and two linear combinations, instead of a comparison
+/// per position
+#[must_use]
+pub fn length_marker<F: SmallField, CS: ConstraintSystem<F>>(
+    cs: &mut CS,
+    len: UInt32<F>,
+    max_len: usize,
+) -> Vec<Boolean<F>> {
+    let num_positions = max_len + 1;
+    let outputs: Vec<Variable> = (0..num_positions)
+        .map(|_| cs.alloc_variable_without_value())
+        .collect();
+
+    if <CS::Config as CSConfig>::WitnessConfig::EVALUATE_WITNESS {
+        let value_fn =
+            move |inputs: &[F], output_buffer: &mut DstBuffer<'_, '_, F>| {
+                let len = inputs[0].as_u64_reduced() as usize;
+                // nothing is marked for a length out of range, so the sums below do not hold
+                output_buffer.extend((0..num_positions).map(|idx| {
+                    if idx == len {
+                        F::ONE
+                    } else {
+                        F::ZERO
+                    }
+                }));
+            };
+
+        let outputs: Vec<Place> = outputs.iter().map(|el| Place::from_variable(*el)).collect();
+        cs.set_values_with_dependencies_vararg(
+            &[Place::from_variable(len.get_variable())],
+            &outputs,
+            value_fn,
+        );
+    }
+
+    let marker: Vec<_> = outputs
+        .into_iter()
+        .map(|el| Boolean::from_variable_checked(cs, el))
+        .collect();
+
+    // exactly one position is marked, and it's the one at `len`
+    let one = cs.allocate_constant(F::ONE);
+    let mut terms: Vec<_> = marker
+        .iter()
+        .map(|el| (el.get_variable(), F::ONE))
+        .collect();
+    terms.push((one, F::MINUS_ONE));
+    Num::enforce_zero_for_linear_combination(cs, &terms);
+
+    let mut terms: Vec<_> = marker
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(idx, el)| (el.get_variable(), F::from_u64_unchecked(idx as u64)))
+        .collect();
+    terms.push((len.get_variable(), F::MINUS_ONE));
+    Num::enforce_zero_for_linear_combination(cs, &terms);
+
+    marker
+}
+
+/// Whether a position comes before the marked one, for every position of the `marker`
+#[must_use]
+pub fn is_before_marker<F: SmallField, CS: ConstraintSystem<F>>(
+    cs: &mut CS,
+    marker: &[Boolean<F>],
+) -> Vec<Boolean<F>> {
+    let mut result = Vec::with_capacity(marker.len());
+    let mut is_before = Num::allocated_constant(cs, F::ONE);
+    for el in marker.iter() {
+        // the marker has a single set position, so it's cleared only once
+        is_before = is_before.sub(cs, &el.into_num());
+        result.push(unsafe { Boolean::from_variable_unchecked(is_before.get_variable()) });
+    }
+
+    result
+}
+
+/// Sum of the marked positions in `range`, which is set if the marked one is there
+#[must_use]
+pub fn is_marked_in_range<F: SmallField, CS: ConstraintSystem<F>>(
+    cs: &mut CS,
+    marker: &[Boolean<F>],
+    range: std::ops::Range<usize>,
+) -> Boolean<F> {
+    let range = range.start.min(marker.len())..range.end.min(marker.len());
+    match range.len() {
+        0 => Boolean::allocated_constant(cs, false),
+        1 => marker[range.start],
+        _ => {
+            let terms: Vec<_> = marker[range]
+                .iter()
+                .map(|el| (el.get_variable(), F::ONE))
+                .collect();
+            let sum = Num::linear_combination(cs, &terms);
+
+            unsafe { Boolean::from_variable_unchecked(sum.get_variable()) }
+        }
+    }
+}
@@ -2,6 +2,10 @@ use super::*;
 use crate::config::*;
 use crate::cs::traits::cs::ConstraintSystem;
 use crate::cs::Variable;
+use crate::gadgets::impls::var_length::{is_before_marker, is_marked_in_range, length_marker};
+use crate::gadgets::num::Num;
+use crate::gadgets::traits::selectable::Selectable;
+use crate::gadgets::u32::UInt32;
 use crate::gadgets::u8::UInt8;
 use std::mem::MaybeUninit;
 
@@ -109,6 +113,111 @@ pub fn keccak256<F: SmallField, CS: ConstraintSystem<F>>(
     unsafe { result.map(|el| el.assume_init()) }
 }
 
+/// Keccak-256 of the first `len` bytes of `input`, where `len` is only known in-circuit and at
+/// most `max_len`. Bytes missing from `input` up to `max_len` are taken as zeroes. The padding
+/// is selected per byte and the digest is selected after the block where the message ends, so
+/// the circuit only depends on `max_len`
+pub fn keccak256_var_len<F: SmallField, CS: ConstraintSystem<F>>(
+    cs: &mut CS,
+    input: &[UInt8<F>],
+    max_len: usize,
+    len: UInt32<F>,
+) -> [UInt8<F>; KECCAK256_DIGEST_SIZE] {
+    use crate::cs::gates::ConstantAllocatableCS;
+
+    assert!(input.len() <= max_len);
+    assert!(max_len <= u32::MAX as usize);
+
+    let block_size = KECCAK_RATE_BYTES;
+    let num_blocks = max_len / block_size + 1;
+
+    let marker = length_marker(cs, len, max_len);
+    let in_message = is_before_marker(cs, &marker);
+
+    let zero = UInt8::zero(cs);
+    let mut padded_message = Vec::with_capacity(num_blocks * block_size);
+    let mut is_last_block = Vec::with_capacity(num_blocks);
+    for block_idx in 0..num_blocks {
+        // the message ends in the last block, which always has space for the padding
+        let block_start = block_idx * block_size;
+        let is_last = is_marked_in_range(cs, &marker, block_start..(block_start + block_size));
+        is_last_block.push(is_last);
+
+        for offset in 0..block_size {
+            let idx = block_start + offset;
+            // 0x01 right after the message and 0x80 at the end of the last block,
+            // which merge into 0x81 if the message leaves a single byte for padding
+            let mut padding = vec![];
+            if idx <= max_len {
+                padding.push((marker[idx].get_variable(), F::ONE));
+            }
+            if offset == block_size - 1 {
+                padding.push((is_last.get_variable(), F::from_u64_unchecked(0x80)));
+            }
+            let padding = if padding.is_empty() {
+                zero
+            } else {
+                let padding = Num::linear_combination(cs, &padding);
+                unsafe { UInt8::from_variable_unchecked(padding.get_variable()) }
+            };
+
+            let byte = if idx < max_len {
+                let input_byte = input.get(idx).copied().unwrap_or(zero);
+                UInt8::conditionally_select(cs, in_message[idx], &input_byte, &padding)
+            } else {
+                padding
+            };
+            padded_message.push(byte.get_variable());
+        }
+    }
+
+    use self::round_function::*;
+    use crate::gadgets::blake2s::mixing_function::xor_many;
+
+    let zero = cs.allocate_constant(F::ZERO);
+    let mut state = [[[zero; BYTES_PER_WORD]; LANE_WIDTH]; LANE_WIDTH];
+    let mut digest = None;
+    for (block, is_last) in padded_message
+        .array_chunks::<KECCAK_RATE_BYTES>()
+        .zip(is_last_block.into_iter())
+    {
+        // absorb into state
+        for i in 0..LANE_WIDTH {
+            for j in 0..LANE_WIDTH {
+                if i + LANE_WIDTH * j < (KECCAK_RATE_BYTES / BYTES_PER_WORD) {
+                    let tmp = block
+                        .array_chunks::<BYTES_PER_WORD>()
+                        .nth(i + LANE_WIDTH * j)
+                        .unwrap();
+                    state[i][j] = xor_many(cs, &state[i][j], tmp);
+                }
+            }
+        }
+        keccak_256_round_function(cs, &mut state);
+
+        let mut block_digest = [MaybeUninit::<UInt8<F>>::uninit(); KECCAK256_DIGEST_SIZE];
+        for (i, dst) in block_digest.array_chunks_mut::<8>().enumerate() {
+            for (dst, src) in dst.iter_mut().zip(state[i][0].iter()) {
+                let tmp = unsafe { UInt8::from_variable_unchecked(*src) };
+                dst.write(tmp);
+            }
+        }
+        let block_digest = unsafe { block_digest.map(|el| el.assume_init()) };
+
+        digest = match digest {
+            None => Some(block_digest),
+            Some(digest) => Some(Selectable::conditionally_select(
+                cs,
+                is_last,
+                &block_digest,
+                &digest,
+            )),
+        };
+    }
+
+    digest.expect("must absorb at least one block")
+}
+
 #[cfg(test)]
 mod test {
     use std::alloc::Global;
@@ -116,7 +225,10 @@ mod test {
     use super::*;
     use crate::{
         cs::{
-            gates::{ConstantsAllocatorGate, FmaGateInBaseFieldWithoutConstant, ReductionGate},
+            gates::{
+                ConstantsAllocatorGate, FmaGateInBaseFieldWithoutConstant, ReductionGate,
+                SelectionGate,
+            },
             CSGeometry,
         },
         dag::CircuitResolverOpts,
@@ -157,6 +269,104 @@ mod test {
         test_keccak256(10 * 135 + 135);
     }
 
+    #[test]
+    fn test_var_len_block_boundaries() {
+        // a single byte of padding is 0x81, and a full block takes another one
+        test_keccak256_var_len(2 * 136, &[0, 1, 134, 135, 136, 137, 271, 2 * 136]);
+    }
+
+    fn test_keccak256_var_len(max_len: usize, lengths: &[usize]) {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let mut input = vec![];
+        for _ in 0..max_len {
+            let byte: u8 = rng.gen();
+            input.push(byte);
+        }
+
+        let geometry = CSGeometry {
+            num_columns_under_copy_permutation: 20,
+            num_witness_columns: 0,
+            num_constant_columns: 4,
+            max_allowed_constraint_degree: 4,
+        };
+
+        use crate::cs::cs_builder_reference::*;
+        let builder_impl =
+            CsReferenceImplementationBuilder::<F, F, DevCSConfig>::new(geometry, 1 << 18);
+        use crate::cs::cs_builder::new_builder;
+        let builder = new_builder::<_, F>(builder_impl);
+
+        let builder = builder.allow_lookup(
+            crate::cs::LookupParameters::UseSpecializedColumnsWithTableIdAsConstant {
+                width: 3,
+                num_repetitions: 5,
+                share_table_id: true,
+            },
+        );
+
+        let builder = ConstantsAllocatorGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = ReductionGate::<F, 4>::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = SelectionGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+
+        let mut owned_cs = builder.build(CircuitResolverOpts::new(1 << 20));
+
+        // add tables
+        let table = create_xor8_table();
+        owned_cs.add_lookup_table::<Xor8Table, 3>(table);
+
+        let table = create_and8_table();
+        owned_cs.add_lookup_table::<And8Table, 3>(table);
+
+        let table = create_byte_split_table::<F, 1>();
+        owned_cs.add_lookup_table::<ByteSplitTable<1>, 3>(table);
+        let table = create_byte_split_table::<F, 2>();
+        owned_cs.add_lookup_table::<ByteSplitTable<2>, 3>(table);
+        let table = create_byte_split_table::<F, 3>();
+        owned_cs.add_lookup_table::<ByteSplitTable<3>, 3>(table);
+        let table = create_byte_split_table::<F, 4>();
+        owned_cs.add_lookup_table::<ByteSplitTable<4>, 3>(table);
+
+        let mut circuit_input = vec![];
+
+        let cs = &mut owned_cs;
+
+        let mut it = input.array_chunks::<2>();
+        for pair in &mut it {
+            let pair = UInt8::allocate_pair(cs, *pair);
+            circuit_input.extend(pair);
+        }
+
+        for len in lengths.iter().copied() {
+            let mut hasher = sha3::Keccak256::new();
+            hasher.update(&input[..len]);
+            let reference_output = hasher.finalize();
+
+            let circuit_len = UInt32::allocate_checked(cs, len as u32);
+            let output = keccak256_var_len(cs, &circuit_input, max_len, circuit_len);
+            let output = hex::encode((output.witness_hook(&*cs))().unwrap());
+            let reference_output = hex::encode(reference_output.as_slice());
+            assert_eq!(output, reference_output, "length {}", len);
+        }
+
+        drop(cs);
+        let _owned_cs = owned_cs.into_assembly::<Global>();
+    }
+
     fn test_keccak256(len: usize) {
         use rand::{Rng, SeedableRng};
         let mut rng = rand::rngs::StdRng::seed_from_u64(42);
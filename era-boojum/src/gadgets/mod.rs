@@ -8,6 +8,8 @@ pub mod num;
 // pub mod poseidon;
 pub mod blake2s;
 pub mod curves;
+pub mod i160;
+pub mod i256;
 pub mod keccak256;
 pub mod non_native_field;
 pub mod poseidon2;
@@ -15,6 +17,7 @@ pub mod queue;
 pub mod recursion;
 pub mod round_function;
 pub mod sha256;
+mod signed_int;
 pub mod tables;
 pub mod traits;
 pub mod u16;
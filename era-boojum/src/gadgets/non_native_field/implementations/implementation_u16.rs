@@ -1146,864 +1146,3 @@ impl<F: SmallField, T: pairing::ff::PrimeField, const N: usize> Selectable<F>
         }
     }
 }
-
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::algebraic_props::round_function::AbsorptionModeOverwrite;
-    use crate::algebraic_props::sponge::GoldilocksPoseidon2Sponge;
-    use crate::cs::gates::*;
-    use crate::cs::implementations::pow::NoPow;
-    use crate::cs::implementations::prover::ProofConfig;
-    use crate::cs::implementations::transcript::GoldilocksPoisedonTranscript;
-    use crate::cs::traits::gate::GatePlacementStrategy;
-    use crate::cs::*;
-    use crate::dag::CircuitResolverOpts;
-    use crate::field::goldilocks::{GoldilocksExt2, GoldilocksField};
-    use crate::gadgets::tables::range_check_16_bits::{
-        create_range_check_16_bits_table, RangeCheck16BitsTable,
-    };
-    use crate::gadgets::traits::round_function::CircuitRoundFunction;
-    use crate::implementations::poseidon2::Poseidon2Goldilocks;
-    use crate::log;
-    use crate::worker::Worker;
-    use cs_builder::{CsBuilder, CsBuilderImpl};
-    use cs_builder_verifier::CsVerifierBuilder;
-    use pairing::ff::{Field, PrimeField};
-    use rand::Rng;
-    use serde::Serialize;
-    use sha3::Digest;
-    use std::alloc::Global;
-    use tables::{
-        create_and8_table, create_byte_split_table, create_xor8_table, And8Table, ByteSplitTable,
-        Xor8Table,
-    };
-    use u256::UInt256;
-
-    type F = GoldilocksField;
-    type Ext = pairing::bn256::Fq;
-    type NN = NonNativeFieldOverU16<F, Ext, 16>;
-    type Params = NonNativeFieldOverU16Params<Ext, 16>;
-
-    #[test]
-    fn pre_block() {
-        let geometry = CSGeometry {
-            num_columns_under_copy_permutation: 80,
-            num_witness_columns: 0,
-            num_constant_columns: 4,
-            max_allowed_constraint_degree: 4,
-        };
-
-        use crate::config::DevCSConfig;
-        type RCfg = <DevCSConfig as CSConfig>::ResolverConfig;
-        use crate::cs::cs_builder_reference::*;
-        let builder_impl =
-            CsReferenceImplementationBuilder::<F, F, DevCSConfig>::new(geometry, 1 << 18);
-        use crate::cs::cs_builder::new_builder;
-        let builder = new_builder::<_, F>(builder_impl);
-
-        fn configure<
-            T: CsBuilderImpl<F, T>,
-            GC: GateConfigurationHolder<F>,
-            TB: StaticToolboxHolder,
-        >(
-            builder: CsBuilder<T, F, GC, TB>,
-        ) -> CsBuilder<T, F, impl GateConfigurationHolder<F>, impl StaticToolboxHolder> {
-            let builder = builder.allow_lookup(
-                crate::cs::LookupParameters::UseSpecializedColumnsWithTableIdAsConstant {
-                    width: 1,
-                    num_repetitions: 10,
-                    share_table_id: true,
-                },
-            );
-
-            let builder = ConstantsAllocatorGate::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = ReductionGate::<F, 4>::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = DotProductGate::<4>::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = UIntXAddGate::<16>::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = UIntXAddGate::<32>::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = SelectionGate::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = NopGate::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-
-            builder
-        }
-
-        let builder = configure(builder);
-        let mut owned_cs = builder.build(CircuitResolverOpts::new(1 << 22));
-
-        ///////// Add Tables
-        /////////
-        /////////
-        /////////
-        /////////
-        /////////
-        let table = create_range_check_16_bits_table();
-        owned_cs.add_lookup_table::<RangeCheck16BitsTable, 1>(table);
-
-        ///////// Constraints
-        /////////
-        /////////
-        /////////
-        /////////
-        /////////
-        let cs = &mut owned_cs;
-
-        let boolean_false = Boolean::allocated_constant(cs, false);
-        let boolean_true = Boolean::allocated_constant(cs, true);
-
-        // let a_value = Ext::from_str("91343852333181432387730302044767688728495783935").unwrap();
-        let a_value = Ext::from_str("4294967295").unwrap();
-        // let b_value = Ext::from_str("456").unwrap();
-        let b_value = Ext::from_str("254").unwrap();
-
-        let nnf_params = std::sync::Arc::new(NonNativeFieldOverU16Params::<Ext, 16>::create());
-
-        let mut a = NonNativeFieldOverU16::<F, Ext, 16>::allocate_checked(cs, a_value, &nnf_params);
-        let mut b = NonNativeFieldOverU16::<F, Ext, 16>::allocate_checked(cs, b_value, &nnf_params);
-
-        let mut neg_limit: [u8; 32] = [0; 32];
-        neg_limit[20] = 1; // 2^160
-        let neg_limit_bytes_as_uint_8 = neg_limit.map(|byte| {
-            let byte_allocated = cs.allocate_constant(F::from_nonreduced_u64(byte as u64));
-            u8::UInt8::<F>::from_variable_checked(cs, byte_allocated)
-        });
-        let neg_limit_u256 = UInt256::from_le_bytes(cs, neg_limit_bytes_as_uint_8);
-
-        let mut rng = rand::thread_rng();
-        let mut random_bytes: [u8; 32] = rng.gen();
-        let bytes_as_uint_8 = random_bytes.map(|byte| {
-            let byte_allocated = cs.allocate_constant(F::from_nonreduced_u64(byte as u64));
-            u8::UInt8::<F>::from_variable_checked(cs, byte_allocated)
-        });
-        let random_uint256 = UInt256::from_le_bytes(cs, bytes_as_uint_8);
-
-        let mut one_bytes: [u8; 32] = [0; 32];
-        one_bytes[0] = 1;
-        let one_bytes_as_uint_8 = one_bytes.map(|byte| {
-            let byte_allocated = cs.allocate_constant(F::from_nonreduced_u64(byte as u64));
-            u8::UInt8::<F>::from_variable_checked(cs, byte_allocated)
-        });
-        let one_u256 = UInt256::from_le_bytes(cs, one_bytes_as_uint_8);
-
-        let (random_uint256_minus_one, _) = random_uint256.overflowing_sub(cs, &one_u256);
-
-        // Cmp
-        for _ in 0..1280 {
-            let (_, minuend_is_less_than_subtrahend) =
-                random_uint256.overflowing_sub(cs, &random_uint256_minus_one);
-        }
-
-        // Asserted Cmp
-        for _ in 0..1024 {
-            let (_, minuend_is_less_than_subtrahend) =
-                random_uint256.overflowing_sub(cs, &random_uint256_minus_one);
-            Boolean::enforce_equal(cs, &minuend_is_less_than_subtrahend, &boolean_false);
-        }
-
-        // Is Negative
-        for _ in 0..256 {
-            let (_, minuend_is_positive) = random_uint256.overflowing_sub(cs, &neg_limit_u256);
-        }
-
-        // Div
-        for _ in 0..256 {
-            let mut a =
-                NonNativeFieldOverU16::<F, Ext, 16>::allocate_checked(cs, a_value, &nnf_params);
-
-            let mut b =
-                NonNativeFieldOverU16::<F, Ext, 16>::allocate_checked(cs, b_value, &nnf_params);
-
-            let c = a.div_unchecked(cs, &mut b);
-        }
-
-        ///////// PROVE
-        ///////// Drop mutable reference "cs"
-        ///////// Pad & shrink owned_cs
-        ///////// Perform setup
-        ///////// Resolve witness
-        ///////// Configure Prover
-        ///////// Define Sponge and Transcript Field
-
-        drop(cs);
-
-        let worker = Worker::new_with_num_threads(8);
-
-        let (_, padding_hint) = owned_cs.pad_and_shrink();
-        let mut owned_cs = owned_cs.into_assembly::<Global>();
-        assert!(owned_cs.check_if_satisfied(&worker));
-
-        let quotient_lde_degree = 8;
-        let cap_size = 16;
-        let (base_setup, setup, vk, setup_tree, vars_hint, wits_hint) =
-            owned_cs.get_full_setup::<T>(&worker, quotient_lde_degree, cap_size);
-
-        let witness_set = owned_cs.take_witness_using_hints(&worker, &vars_hint, &wits_hint);
-        log!("Witness is resolved");
-
-        log!("Proving");
-        let now = std::time::Instant::now();
-
-        let fri_lde_degree = 8;
-        let prover_config = ProofConfig {
-            fri_lde_factor: fri_lde_degree,
-            pow_bits: 0,
-            ..Default::default()
-        };
-
-        type TR = GoldilocksPoisedonTranscript;
-        type T = GoldilocksPoseidon2Sponge<AbsorptionModeOverwrite>;
-        let proof = owned_cs.prove_cpu_basic::<GoldilocksExt2, TR, T, NoPow>(
-            &worker,
-            witness_set,
-            &base_setup,
-            &setup,
-            &setup_tree,
-            &vk,
-            prover_config,
-            (),
-        );
-        log!("Proving is done, taken {:?}", now.elapsed());
-
-        let mut buffer = Vec::new();
-        bincode::serialize_into(&mut buffer, &proof).unwrap();
-        println!("Proof size: {}", buffer.len());
-
-        ///////// VERIFY
-        /////////
-        /////////
-        /////////
-        /////////
-        log!("Verifying");
-        let now = std::time::Instant::now();
-
-        let builder = new_builder::<_, F>(
-            CsVerifierBuilder::<F, GoldilocksExt2>::new_from_parameters(geometry),
-        );
-
-        let builder = configure(builder);
-        let verifier = builder.build(());
-
-        log!("Verifying is done, taken {:?}", now.elapsed());
-        assert!(verifier.verify::<T, TR, NoPow>((), &vk, &proof));
-    }
-
-    #[test]
-    fn tx_loop() {
-        let geometry = CSGeometry {
-            num_columns_under_copy_permutation: 80,
-            num_witness_columns: 0,
-            num_constant_columns: 4,
-            max_allowed_constraint_degree: 4,
-        };
-
-        use crate::config::DevCSConfig;
-        type RCfg = <DevCSConfig as CSConfig>::ResolverConfig;
-        use crate::cs::cs_builder_reference::*;
-        let builder_impl =
-            CsReferenceImplementationBuilder::<F, F, DevCSConfig>::new(geometry, 1 << 20);
-        use crate::cs::cs_builder::new_builder;
-        let builder = new_builder::<_, F>(builder_impl);
-
-        fn configure<
-            T: CsBuilderImpl<F, T>,
-            GC: GateConfigurationHolder<F>,
-            TB: StaticToolboxHolder,
-        >(
-            builder: CsBuilder<T, F, GC, TB>,
-        ) -> CsBuilder<T, F, impl GateConfigurationHolder<F>, impl StaticToolboxHolder> {
-            let builder = builder.allow_lookup(
-                crate::cs::LookupParameters::UseSpecializedColumnsWithTableIdAsConstant {
-                    width: 1,
-                    num_repetitions: 10,
-                    share_table_id: true,
-                },
-            );
-
-            let builder = ConstantsAllocatorGate::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = ReductionGate::<F, 4>::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = DotProductGate::<4>::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = UIntXAddGate::<16>::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = UIntXAddGate::<32>::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = SelectionGate::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = NopGate::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-
-            builder
-        }
-
-        let builder = configure(builder);
-        let mut owned_cs = builder.build(CircuitResolverOpts::new(1 << 23));
-
-        ///////// Add Tables
-        /////////
-        /////////
-        /////////
-        /////////
-        /////////
-        let table = create_range_check_16_bits_table();
-        owned_cs.add_lookup_table::<RangeCheck16BitsTable, 1>(table);
-
-        ///////// Constraints
-        /////////
-        /////////
-        /////////
-        /////////
-        /////////
-        let cs = &mut owned_cs;
-
-        let boolean_false = Boolean::allocated_constant(cs, false);
-        let boolean_true = Boolean::allocated_constant(cs, true);
-
-        // let a_value = Ext::from_str("91343852333181432387730302044767688728495783935").unwrap();
-        let a_value = Ext::from_str("4294967295").unwrap();
-        // let b_value = Ext::from_str("456").unwrap();
-        let b_value = Ext::from_str("254").unwrap();
-
-        let nnf_params = std::sync::Arc::new(NonNativeFieldOverU16Params::<Ext, 16>::create());
-
-        let mut a = NonNativeFieldOverU16::<F, Ext, 16>::allocate_checked(cs, a_value, &nnf_params);
-        let mut b = NonNativeFieldOverU16::<F, Ext, 16>::allocate_checked(cs, b_value, &nnf_params);
-
-        let mut neg_limit: [u8; 32] = [0; 32];
-        neg_limit[20] = 1; // 2^160
-        let neg_limit_bytes_as_uint_8 = neg_limit.map(|byte| {
-            let byte_allocated = cs.allocate_constant(F::from_nonreduced_u64(byte as u64));
-            u8::UInt8::<F>::from_variable_checked(cs, byte_allocated)
-        });
-        let neg_limit_u256 = UInt256::from_le_bytes(cs, neg_limit_bytes_as_uint_8);
-
-        let mut rng = rand::thread_rng();
-        let mut random_bytes: [u8; 32] = rng.gen();
-        let bytes_as_uint_8 = random_bytes.map(|byte| {
-            let byte_allocated = cs.allocate_constant(F::from_nonreduced_u64(byte as u64));
-            u8::UInt8::<F>::from_variable_checked(cs, byte_allocated)
-        });
-        let random_uint256 = UInt256::from_le_bytes(cs, bytes_as_uint_8);
-
-        let mut one_bytes: [u8; 32] = [0; 32];
-        one_bytes[0] = 1;
-        let one_bytes_as_uint_8 = one_bytes.map(|byte| {
-            let byte_allocated = cs.allocate_constant(F::from_nonreduced_u64(byte as u64));
-            u8::UInt8::<F>::from_variable_checked(cs, byte_allocated)
-        });
-        let one_u256 = UInt256::from_le_bytes(cs, one_bytes_as_uint_8);
-
-        let (random_uint256_minus_one, _) = random_uint256.overflowing_sub(cs, &one_u256);
-
-        // Poseidon2
-        for _ in 0..5652 {
-            let mut inputs = [Variable::placeholder(); 12];
-            let mut state = [<F as crate::field::Field>::ZERO; 12];
-            for (idx, dst) in inputs[..8].iter_mut().enumerate() {
-                let value = <F as crate::field::Field>::from_u64_with_reduction(idx as u64);
-                let var = cs.alloc_single_variable_from_witness(value);
-                state[idx] = value;
-                *dst = var;
-            }
-
-            let capacity_var = cs.allocate_constant(<F as crate::field::Field>::ZERO);
-            for dst in inputs[8..].iter_mut() {
-                *dst = capacity_var;
-            }
-
-            let round_function_result = Poseidon2Goldilocks::compute_round_function(cs, inputs);
-
-            let circuit_result = cs
-                .get_value_for_multiple(Place::from_variables(round_function_result))
-                .wait()
-                .unwrap();
-        }
-
-        // ToBytes
-        let mut a_bytes = [[u8::UInt8::zero(cs), u8::UInt8::zero(cs)]; 16];
-        for _ in 0..65 {
-            a_bytes = a.limbs.map(|limb| {
-                let limb_as_u16 = u16::UInt16::from_variable_checked(cs, limb);
-                let bytes_of_current_limb = limb_as_u16.decompose_into_bytes(cs);
-                bytes_of_current_limb
-            });
-        }
-
-        // FromBytes
-        for _ in 0..236 {
-            let a_recovered_limbs = a_bytes.map(|bytes_of_limb| {
-                let limb = u16::UInt16::from_le_bytes(cs, bytes_of_limb);
-                limb
-            });
-
-            a.limbs
-                .iter()
-                .zip(a_recovered_limbs.iter().map(|limb| limb.variable))
-                .for_each(|(a, recovered)| {
-                    Num::enforce_equal(cs, &Num::from_variable(*a), &Num::from_variable(recovered));
-                });
-        }
-
-        // Div
-        for _ in 0..13 {
-            let mut a =
-                NonNativeFieldOverU16::<F, Ext, 16>::allocate_checked(cs, a_value, &nnf_params);
-            let mut b =
-                NonNativeFieldOverU16::<F, Ext, 16>::allocate_checked(cs, b_value, &nnf_params);
-
-            let c = a.div_unchecked(cs, &mut b);
-        }
-
-        // Abs
-        for _ in 0..4 {
-            let (_, minuend_is_positive) = random_uint256.overflowing_sub(cs, &neg_limit_u256);
-            let (random_uint256_negated, _) = random_uint256.overflowing_add(cs, &neg_limit_u256);
-
-            let t = Selectable::conditionally_select(
-                cs,
-                minuend_is_positive,
-                &random_uint256,
-                &random_uint256_negated,
-            );
-        }
-
-        // Cmp
-        for _ in 0..49 {
-            let (_, minuend_is_less_than_subtrahend) =
-                random_uint256.overflowing_sub(cs, &random_uint256_minus_one);
-        }
-
-        // Asserted Cmp
-        for _ in 0..19 {
-            let (_, minuend_is_less_than_subtrahend) =
-                random_uint256.overflowing_sub(cs, &random_uint256_minus_one);
-            Boolean::enforce_equal(cs, &minuend_is_less_than_subtrahend, &boolean_false);
-        }
-
-        // is_negative
-        for _ in 0..6 {
-            let (_, minuend_is_positive) = random_uint256.overflowing_sub(cs, &neg_limit_u256);
-        }
-
-        ///////// PROVE
-        ///////// Drop mutable reference "cs"
-        ///////// Pad & shrink owned_cs
-        ///////// Perform setup
-        ///////// Resolve witness
-        ///////// Configure Prover
-        ///////// Define Sponge and Transcript Field
-
-        drop(cs);
-
-        let worker = Worker::new_with_num_threads(8);
-
-        let (_, padding_hint) = owned_cs.pad_and_shrink();
-        let mut owned_cs = owned_cs.into_assembly::<Global>();
-        assert!(owned_cs.check_if_satisfied(&worker));
-
-        let quotient_lde_degree = 8;
-        let cap_size = 16;
-        let (base_setup, setup, vk, setup_tree, vars_hint, wits_hint) =
-            owned_cs.get_full_setup::<T>(&worker, quotient_lde_degree, cap_size);
-
-        let witness_set = owned_cs.take_witness_using_hints(&worker, &vars_hint, &wits_hint);
-        log!("Witness is resolved");
-
-        log!("Proving");
-        let now = std::time::Instant::now();
-
-        let fri_lde_degree = 8;
-        let prover_config = ProofConfig {
-            fri_lde_factor: fri_lde_degree,
-            pow_bits: 0,
-            ..Default::default()
-        };
-
-        type TR = GoldilocksPoisedonTranscript;
-        type T = GoldilocksPoseidon2Sponge<AbsorptionModeOverwrite>;
-        let proof = owned_cs.prove_cpu_basic::<GoldilocksExt2, TR, T, NoPow>(
-            &worker,
-            witness_set,
-            &base_setup,
-            &setup,
-            &setup_tree,
-            &vk,
-            prover_config,
-            (),
-        );
-        log!("Proving is done, taken {:?}", now.elapsed());
-
-        let mut buffer = Vec::new();
-        bincode::serialize_into(&mut buffer, &proof).unwrap();
-        println!("Proof size: {}", buffer.len());
-
-        ///////// VERIFY
-        /////////
-        /////////
-        /////////
-        /////////
-        log!("Verifying");
-        let now = std::time::Instant::now();
-
-        let builder = new_builder::<_, F>(
-            CsVerifierBuilder::<F, GoldilocksExt2>::new_from_parameters(geometry),
-        );
-
-        let builder = configure(builder);
-        let verifier = builder.build(());
-
-        log!("Verifying is done, taken {:?}", now.elapsed());
-        assert!(verifier.verify::<T, TR, NoPow>((), &vk, &proof));
-    }
-
-    #[test]
-    fn verify_block() {
-        let geometry = CSGeometry {
-            num_columns_under_copy_permutation: 80,
-            num_witness_columns: 0,
-            num_constant_columns: 4,
-            max_allowed_constraint_degree: 4,
-        };
-
-        use crate::config::DevCSConfig;
-        type RCfg = <DevCSConfig as CSConfig>::ResolverConfig;
-        use crate::cs::cs_builder_reference::*;
-        let builder_impl =
-            CsReferenceImplementationBuilder::<F, F, DevCSConfig>::new(geometry, 1 << 18);
-        use crate::cs::cs_builder::new_builder;
-        let builder = new_builder::<_, F>(builder_impl);
-
-        fn configure<
-            T: CsBuilderImpl<F, T>,
-            GC: GateConfigurationHolder<F>,
-            TB: StaticToolboxHolder,
-        >(
-            builder: CsBuilder<T, F, GC, TB>,
-        ) -> CsBuilder<T, F, impl GateConfigurationHolder<F>, impl StaticToolboxHolder> {
-            let builder = builder.allow_lookup(
-                crate::cs::LookupParameters::UseSpecializedColumnsWithTableIdAsConstant {
-                    width: 3,
-                    num_repetitions: 5,
-                    share_table_id: true,
-                },
-            );
-
-            let builder = ConstantsAllocatorGate::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = ReductionGate::<F, 4>::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = UIntXAddGate::<16>::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = UIntXAddGate::<32>::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-            let builder = NopGate::configure_builder(
-                builder,
-                GatePlacementStrategy::UseGeneralPurposeColumns,
-            );
-
-            builder
-        }
-
-        let builder = configure(builder);
-        let mut owned_cs = builder.build(CircuitResolverOpts::new(1 << 22));
-
-        ///////// Add Tables
-        /////////
-        /////////
-        /////////
-        /////////
-        /////////
-
-        // let table = create_range_check_16_bits_table();
-        // owned_cs.add_lookup_table::<RangeCheck16BitsTable, 1>(table);
-
-        let table = create_xor8_table();
-        owned_cs.add_lookup_table::<Xor8Table, 3>(table);
-        let table = create_and8_table();
-        owned_cs.add_lookup_table::<And8Table, 3>(table);
-        let table = create_byte_split_table::<F, 1>();
-        owned_cs.add_lookup_table::<ByteSplitTable<1>, 3>(table);
-        let table = create_byte_split_table::<F, 2>();
-        owned_cs.add_lookup_table::<ByteSplitTable<2>, 3>(table);
-        let table = create_byte_split_table::<F, 3>();
-        owned_cs.add_lookup_table::<ByteSplitTable<3>, 3>(table);
-        let table = create_byte_split_table::<F, 4>();
-        owned_cs.add_lookup_table::<ByteSplitTable<4>, 3>(table);
-
-        ///////// Constraints
-        /////////
-        /////////
-        /////////
-        /////////
-        /////////
-        let cs = &mut owned_cs;
-
-        let boolean_false = Boolean::allocated_constant(cs, false);
-        let boolean_true = Boolean::allocated_constant(cs, true);
-
-        // let a_value = Ext::from_str("91343852333181432387730302044767688728495783935").unwrap();
-        let a_value = Ext::from_str("4294967295").unwrap();
-        let b_value = Ext::from_str("254").unwrap();
-
-        let nnf_params = std::sync::Arc::new(NonNativeFieldOverU16Params::<Ext, 16>::create());
-
-        let mut a = NonNativeFieldOverU16::<F, Ext, 16>::allocate_checked(cs, a_value, &nnf_params);
-        let mut b = NonNativeFieldOverU16::<F, Ext, 16>::allocate_checked(cs, b_value, &nnf_params);
-
-        let mut neg_limit: [u8; 32] = [0; 32];
-        neg_limit[20] = 1; // 2^160
-        let neg_limit_bytes_as_uint_8 = neg_limit.map(|byte| {
-            let byte_allocated = cs.allocate_constant(F::from_nonreduced_u64(byte as u64));
-            u8::UInt8::<F>::from_variable_checked(cs, byte_allocated)
-        });
-        let neg_limit_u256 = UInt256::from_le_bytes(cs, neg_limit_bytes_as_uint_8);
-
-        let mut rng = rand::thread_rng();
-        let mut random_bytes: [u8; 32] = rng.gen();
-        let bytes_as_uint_8 = random_bytes.map(|byte| {
-            let byte_allocated = cs.allocate_constant(F::from_nonreduced_u64(byte as u64));
-            u8::UInt8::<F>::from_variable_checked(cs, byte_allocated)
-        });
-        let random_uint256 = UInt256::from_le_bytes(cs, bytes_as_uint_8);
-
-        let mut one_bytes: [u8; 32] = [0; 32];
-        one_bytes[0] = 1;
-        let one_bytes_as_uint_8 = one_bytes.map(|byte| {
-            let byte_allocated = cs.allocate_constant(F::from_nonreduced_u64(byte as u64));
-            u8::UInt8::<F>::from_variable_checked(cs, byte_allocated)
-        });
-        let one_u256 = UInt256::from_le_bytes(cs, one_bytes_as_uint_8);
-
-        let (random_uint256_minus_one, _) = random_uint256.overflowing_sub(cs, &one_u256);
-
-        // 2 Rounds of Keccak each with 242 bytes
-        for _ in 0..2 {
-            let mut input = vec![];
-            for _ in 0..242 {
-                let byte: u8 = rng.gen();
-                input.push(byte);
-            }
-
-            let mut hasher = sha3::Keccak256::new();
-            hasher.update(&input);
-            let reference_output = hasher.finalize();
-
-            let mut circuit_input = vec![];
-
-            let mut it = input.array_chunks::<2>();
-            for pair in &mut it {
-                let pair = u8::UInt8::allocate_pair(cs, *pair);
-                circuit_input.extend(pair);
-            }
-
-            for el in it.remainder() {
-                let el = u8::UInt8::allocate_checked(cs, *el);
-                circuit_input.push(el);
-            }
-
-            let output = keccak256::keccak256(cs, &circuit_input);
-            let output = hex::encode((output.witness_hook(&*cs))().unwrap());
-            let reference_output = hex::encode(reference_output.as_slice());
-            assert_eq!(output, reference_output);
-        }
-
-        // Poseidon2
-        for _ in 0..1 {
-            let mut inputs = [Variable::placeholder(); 12];
-            let mut state = [<F as crate::field::Field>::ZERO; 12];
-            for (idx, dst) in inputs[..8].iter_mut().enumerate() {
-                let value = <F as crate::field::Field>::from_u64_with_reduction(idx as u64);
-                let var = cs.alloc_single_variable_from_witness(value);
-                state[idx] = value;
-                *dst = var;
-            }
-
-            let capacity_var = cs.allocate_constant(<F as crate::field::Field>::ZERO);
-            for dst in inputs[8..].iter_mut() {
-                *dst = capacity_var;
-            }
-
-            let round_function_result = Poseidon2Goldilocks::compute_round_function(cs, inputs);
-
-            let circuit_result = cs
-                .get_value_for_multiple(Place::from_variables(round_function_result))
-                .wait()
-                .unwrap();
-        }
-
-        // ToBytes
-        let mut a_bytes = [[u8::UInt8::zero(cs), u8::UInt8::zero(cs)]; 16];
-        for _ in 0..84 {
-            a_bytes = a.limbs.map(|limb| {
-                let limb_as_u16 = u16::UInt16::from_variable_checked(cs, limb);
-                let bytes_of_current_limb = limb_as_u16.decompose_into_bytes(cs);
-                bytes_of_current_limb
-            });
-        }
-
-        // FromBytes
-        for _ in 0..484 {
-            let a_recovered_limbs = a_bytes.map(|bytes_of_limb| {
-                let limb = u16::UInt16::from_le_bytes(cs, bytes_of_limb);
-                limb
-            });
-
-            a.limbs
-                .iter()
-                .zip(a_recovered_limbs.iter().map(|limb| limb.variable))
-                .for_each(|(a, recovered)| {
-                    Num::enforce_equal(cs, &Num::from_variable(*a), &Num::from_variable(recovered));
-                });
-        }
-
-        // Cmp
-        for _ in 0..3 {
-            let (_, minuend_is_less_than_subtrahend) =
-                random_uint256.overflowing_sub(cs, &random_uint256_minus_one);
-        }
-
-        // Asserted Cmp
-        for _ in 0..1 {
-            let (_, minuend_is_less_than_subtrahend) =
-                random_uint256.overflowing_sub(cs, &random_uint256_minus_one);
-            Boolean::enforce_equal(cs, &minuend_is_less_than_subtrahend, &boolean_false);
-        }
-
-        // // Div
-        // for _ in 0..1 {
-        //     let mut a =
-        //         NonNativeFieldOverU16::<F, Ext, 16>::allocate_checked(cs, a_value, &nnf_params);
-
-        //     let mut b =
-        //         NonNativeFieldOverU16::<F, Ext, 16>::allocate_checked(cs, b_value, &nnf_params);
-
-        //     let c = a.div_unchecked(cs, &mut b);
-        // }
-
-        ///////// PROVE
-        ///////// Drop mutable reference "cs"
-        ///////// Pad & shrink owned_cs
-        ///////// Perform setup
-        ///////// Resolve witness
-        ///////// Configure Prover
-        ///////// Define Sponge and Transcript Field
-
-        drop(cs);
-
-        let worker = Worker::new_with_num_threads(8);
-
-        let (_, padding_hint) = owned_cs.pad_and_shrink();
-        let mut owned_cs = owned_cs.into_assembly::<Global>();
-        assert!(owned_cs.check_if_satisfied(&worker));
-
-        let quotient_lde_degree = 8;
-        let cap_size = 16;
-        let (base_setup, setup, vk, setup_tree, vars_hint, wits_hint) =
-            owned_cs.get_full_setup::<T>(&worker, quotient_lde_degree, cap_size);
-
-        let witness_set = owned_cs.take_witness_using_hints(&worker, &vars_hint, &wits_hint);
-        log!("Witness is resolved");
-
-        log!("Proving");
-        let now = std::time::Instant::now();
-
-        let fri_lde_degree = 8;
-        let prover_config = ProofConfig {
-            fri_lde_factor: fri_lde_degree,
-            pow_bits: 0,
-            ..Default::default()
-        };
-
-        type TR = GoldilocksPoisedonTranscript;
-        type T = GoldilocksPoseidon2Sponge<AbsorptionModeOverwrite>;
-        let proof = owned_cs.prove_cpu_basic::<GoldilocksExt2, TR, T, NoPow>(
-            &worker,
-            witness_set,
-            &base_setup,
-            &setup,
-            &setup_tree,
-            &vk,
-            prover_config,
-            (),
-        );
-        log!("Proving is done, taken {:?}", now.elapsed());
-
-        let mut buffer = Vec::new();
-        bincode::serialize_into(&mut buffer, &proof).unwrap();
-        println!("Proof size: {}", buffer.len());
-
-        ///////// VERIFY
-        /////////
-        /////////
-        /////////
-        /////////
-        log!("Verifying");
-        let now = std::time::Instant::now();
-
-        let builder = new_builder::<_, F>(
-            CsVerifierBuilder::<F, GoldilocksExt2>::new_from_parameters(geometry),
-        );
-
-        let builder = configure(builder);
-        let verifier = builder.build(());
-
-        log!("Verifying is done, taken {:?}", now.elapsed());
-        assert!(verifier.verify::<T, TR, NoPow>((), &vk, &proof));
-    }
-}
@@ -302,6 +302,9 @@ impl<F: SmallField> Num<F> {
         }
     }
 
+    /// Little-endian bits of the value. The recomposition is collapsed by `ReductionGate`, or
+    /// by a tree of `ReductionByPowersGate`, and the bits are checked by `BooleanConstraintGate`
+    /// if it's allowed, so use `UInt8::to_le_bits_using_table` where lookups are cheaper
     #[must_use]
     pub fn spread_into_bits<CS: ConstraintSystem<F>, const LIMIT: usize>(
         &self,
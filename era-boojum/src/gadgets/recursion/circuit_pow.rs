@@ -1,20 +1,205 @@
 use super::*;
 use crate::cs::implementations::pow::{NoPow, PoWRunner};
+use crate::cs::traits::cs::ConstraintSystem;
+use crate::field::goldilocks::GoldilocksField;
+use crate::gadgets::boolean::Boolean;
+use crate::gadgets::num::Num;
+use crate::gadgets::traits::round_function::CircuitRoundFunction;
+use crate::implementations::poseidon2::Poseidon2Goldilocks;
 
-pub trait CircuitPowRunner<F: SmallField> {}
+pub trait CircuitPowRunner<F: SmallField> {
+    /// Checks the PoW in-circuit. The challenge is given as two 32-bit words (low first),
+    /// same as it's absorbed into the transcript
+    fn verify_from_field_elements<CS: ConstraintSystem<F>>(
+        cs: &mut CS,
+        seed: Vec<Num<F>>,
+        pow_bits: u32,
+        challenge: [Num<F>; 2],
+    ) -> Boolean<F>;
+}
 
 pub trait RecursivePoWRunner<F: SmallField>: PoWRunner {
     type CircuitReflection: CircuitPowRunner<F>;
 }
 
+/// Recomposes the challenge witnessed as 64 bits into two 32-bit words, so they are range checked
+pub fn pow_challenge_into_words<F: SmallField, CS: ConstraintSystem<F>>(
+    cs: &mut CS,
+    challenge_bits: &[Boolean<F>; 64],
+) -> [Num<F>; 2] {
+    assert!(F::CAPACITY_BITS >= 32);
+
+    std::array::from_fn(|word_idx| {
+        let input: Vec<_> = challenge_bits[word_idx * 32..][..32]
+            .iter()
+            .enumerate()
+            .map(|(shift, bit)| (bit.get_variable(), F::from_u64_unchecked(1u64 << shift)))
+            .collect();
+
+        Num::linear_combination(cs, &input)
+    })
+}
+
 #[derive(Derivative)]
 #[derivative(Clone, Copy, Debug, Default(bound = ""))]
 pub struct CircuitNoPow<F: SmallField> {
     _marker: std::marker::PhantomData<F>,
 }
 
-impl<F: SmallField> CircuitPowRunner<F> for CircuitNoPow<F> {}
+impl<F: SmallField> CircuitPowRunner<F> for CircuitNoPow<F> {
+    fn verify_from_field_elements<CS: ConstraintSystem<F>>(
+        _cs: &mut CS,
+        _seed: Vec<Num<F>>,
+        pow_bits: u32,
+        _challenge: [Num<F>; 2],
+    ) -> Boolean<F> {
+        assert_eq!(pow_bits, 0);
+        unreachable!()
+    }
+}
 
 impl<F: SmallField> RecursivePoWRunner<F> for NoPow {
     type CircuitReflection = CircuitNoPow<F>;
 }
+
+#[derive(Derivative)]
+#[derivative(Clone, Copy, Debug, Default)]
+pub struct CircuitPoseidon2Pow;
+
+// mirrors the native `PoWRunner` for `Poseidon2Goldilocks`
+impl CircuitPowRunner<GoldilocksField> for CircuitPoseidon2Pow {
+    fn verify_from_field_elements<CS: ConstraintSystem<GoldilocksField>>(
+        cs: &mut CS,
+        seed: Vec<Num<GoldilocksField>>,
+        pow_bits: u32,
+        challenge: [Num<GoldilocksField>; 2],
+    ) -> Boolean<GoldilocksField> {
+        assert!(pow_bits > 0 && pow_bits <= 32);
+
+        let mut state = Poseidon2Goldilocks::create_empty_state(cs);
+        for chunk in seed.chunks(Poseidon2Goldilocks::POW_RATE) {
+            for (dst, src) in state.iter_mut().zip(chunk.iter()) {
+                *dst = src.get_variable();
+            }
+            state = Poseidon2Goldilocks::compute_round_function(cs, state);
+        }
+
+        state[0] = challenge[0].get_variable();
+        state[1] = challenge[1].get_variable();
+        state = Poseidon2Goldilocks::compute_round_function(cs, state);
+
+        let output = Num::from_variable(state[0]);
+        let output_bits = output.spread_into_bits::<CS, 64>(cs);
+        let any_low_bit_set = Boolean::multi_or(cs, &output_bits[..pow_bits as usize]);
+
+        any_low_bit_set.negated(cs)
+    }
+}
+
+impl RecursivePoWRunner<GoldilocksField> for Poseidon2Goldilocks {
+    type CircuitReflection = CircuitPoseidon2Pow;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::DevCSConfig;
+    use crate::cs::cs_builder::new_builder;
+    use crate::cs::cs_builder_reference::CsReferenceImplementationBuilder;
+    use crate::cs::gates::*;
+    use crate::cs::CSGeometry;
+    use crate::dag::CircuitResolverOpts;
+    use crate::gadgets::traits::allocatable::CSAllocatable;
+    use crate::gadgets::traits::witnessable::WitnessHookable;
+    use crate::worker::Worker;
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn test_poseidon2_circuit_pow() {
+        let geometry = CSGeometry {
+            num_columns_under_copy_permutation: 132,
+            num_witness_columns: 0,
+            num_constant_columns: 4,
+            max_allowed_constraint_degree: 8,
+        };
+
+        let builder_impl =
+            CsReferenceImplementationBuilder::<F, F, DevCSConfig>::new(geometry, 1 << 12);
+        let builder = new_builder::<_, F>(builder_impl);
+
+        type Poseidon2Gate = Poseidon2FlattenedGate<F, 8, 12, 4, Poseidon2Goldilocks>;
+
+        let builder = ConstantsAllocatorGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = BooleanConstraintGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = Poseidon2Gate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = ReductionGate::<F, 4>::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = ZeroCheckGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+            false,
+        );
+        let builder =
+            NopGate::configure_builder(builder, GatePlacementStrategy::UseGeneralPurposeColumns);
+
+        let mut owned_cs = builder.build(CircuitResolverOpts::new(1 << 16));
+        let cs = &mut owned_cs;
+
+        let worker = Worker::new_with_num_threads(4);
+        let pow_bits = 12;
+        // longer than the rate, to have more than one absorption
+        let seed: Vec<F> = (0..10)
+            .map(|el| F::from_u64_unchecked(el * 1_000_003 + 7))
+            .collect();
+        let challenge =
+            Poseidon2Goldilocks::run_from_field_elements(seed.clone(), pow_bits, &worker);
+
+        let mut wrong_challenge = challenge + 1;
+        while Poseidon2Goldilocks::verify_from_field_elements(
+            seed.clone(),
+            pow_bits,
+            wrong_challenge,
+        ) {
+            wrong_challenge += 1;
+        }
+
+        let seed: Vec<_> = seed.into_iter().map(|el| Num::allocate(cs, el)).collect();
+        for (candidate, expected) in [(challenge, true), (wrong_challenge, false)] {
+            let bits: [_; 64] =
+                std::array::from_fn(|shift| Boolean::allocate(cs, (candidate >> shift) & 1 == 1));
+            let words = pow_challenge_into_words(cs, &bits);
+            assert_eq!(
+                words.map(|el| el.witness_hook(cs)().unwrap()),
+                [
+                    F::from_u64_unchecked(candidate as u32 as u64),
+                    F::from_u64_unchecked(candidate >> 32)
+                ]
+            );
+
+            let is_valid =
+                CircuitPoseidon2Pow::verify_from_field_elements(cs, seed.clone(), pow_bits, words);
+            assert_eq!(is_valid.witness_hook(cs)().unwrap(), expected);
+        }
+
+        drop(cs);
+        owned_cs.pad_and_shrink();
+        let mut owned_cs = owned_cs.into_assembly::<std::alloc::Global>();
+        assert!(owned_cs.check_if_satisfied(&worker));
+    }
+}
@@ -30,7 +30,9 @@ use crate::gadgets::recursion::recursive_tree_hasher::*;
 use crate::gadgets::recursion::recursive_verifier_builder::TypeErasedGateEvaluationRecursiveVerificationFunction;
 use std::alloc::Global;
 
-use crate::gadgets::recursion::circuit_pow::RecursivePoWRunner;
+use crate::gadgets::recursion::circuit_pow::{
+    pow_challenge_into_words, CircuitPowRunner, RecursivePoWRunner,
+};
 
 fn materialize_powers_serial<
     F: SmallField,
@@ -1498,9 +1500,18 @@ impl<F: SmallField, EXT: FieldExtension<2, BaseField = F>, CS: ConstraintSystem<
             if num_challenges % F::CHAR_BITS != 0 {
                 num_challenges += 1;
             }
-            let _challenges: Vec<_> = transcript.get_multiple_challenges(cs, num_challenges);
+            let challenges: Vec<_> = transcript.get_multiple_challenges(cs, num_challenges);
+
+            let pow_challenge = pow_challenge_into_words(cs, &proof.pow_challenge);
+            let pow_is_valid = POW::CircuitReflection::verify_from_field_elements(
+                cs,
+                challenges,
+                new_pow_bits,
+                pow_challenge,
+            );
+            validity_flags.push(pow_is_valid);
 
-            todo!()
+            transcript.witness_field_elements(cs, &pow_challenge);
         }
 
         let max_needed_bits = (fixed_parameters.domain_size
@@ -2197,7 +2208,7 @@ mod test {
     use crate::algebraic_props::round_function::AbsorptionModeOverwrite;
     use crate::algebraic_props::sponge::GoldilocksPoseidon2Sponge;
     use crate::config::{CSConfig, DevCSConfig};
-    use crate::cs::cs_builder::new_builder;
+    use crate::cs::cs_builder::{new_builder, CsBuilder, CsBuilderImpl};
     use crate::cs::cs_builder_reference::CsReferenceImplementationBuilder;
     use crate::cs::cs_builder_verifier::CsVerifierBuilder;
     use crate::cs::gates::Poseidon2FlattenedGate;
@@ -2206,9 +2217,11 @@ mod test {
     use crate::cs::implementations::transcript::*;
     use crate::dag::CircuitResolverOpts;
     use crate::field::goldilocks::{GoldilocksExt2, GoldilocksField};
+    use crate::field::{Field, U64Representable};
     use crate::gadgets::recursion::recursive_verifier_builder::CsRecursiveVerifierBuilder;
     use crate::gadgets::traits::witnessable::WitnessHookable;
     use crate::implementations::poseidon2::Poseidon2Goldilocks;
+    use crate::worker::Worker;
 
     #[test]
     fn test_recursive_verification() {
@@ -2473,4 +2486,179 @@ mod test {
 
         dbg!(cs.next_available_row());
     }
+
+    #[test]
+    fn test_recursive_verification_with_poseidon2_pow() {
+        type F = GoldilocksField;
+        type P = GoldilocksField;
+        type TR = GoldilocksPoisedon2Transcript;
+        type R = Poseidon2Goldilocks;
+        type Ctr = CircuitAlgebraicSpongeBasedTranscript<GoldilocksField, 8, 12, 4, R>;
+        type Ext = GoldilocksExt2;
+        type H = GoldilocksPoseidon2Sponge<AbsorptionModeOverwrite>;
+        type RH = CircuitGoldilocksPoseidon2Sponge;
+
+        fn configure<
+            T: CsBuilderImpl<F, T>,
+            GC: GateConfigurationHolder<F>,
+            TB: StaticToolboxHolder,
+        >(
+            builder: CsBuilder<T, F, GC, TB>,
+        ) -> CsBuilder<T, F, impl GateConfigurationHolder<F>, impl StaticToolboxHolder> {
+            let builder = ConstantsAllocatorGate::configure_builder(
+                builder,
+                GatePlacementStrategy::UseGeneralPurposeColumns,
+            );
+            let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
+                builder,
+                GatePlacementStrategy::UseGeneralPurposeColumns,
+            );
+            let builder = ZeroCheckGate::configure_builder(
+                builder,
+                GatePlacementStrategy::UseGeneralPurposeColumns,
+                false,
+            );
+            let builder = NopGate::configure_builder(
+                builder,
+                GatePlacementStrategy::UseGeneralPurposeColumns,
+            );
+
+            builder
+        }
+
+        // prove a small circuit with a nonzero PoW
+        let geometry = CSGeometry {
+            num_columns_under_copy_permutation: 8,
+            num_witness_columns: 0,
+            num_constant_columns: 2,
+            max_allowed_constraint_degree: 4,
+        };
+        let builder_impl =
+            CsReferenceImplementationBuilder::<F, P, DevCSConfig>::new(geometry, 1 << 8);
+        let builder = new_builder::<_, F>(builder_impl);
+        let builder = configure(builder);
+        let mut cs = builder.build(CircuitResolverOpts::new(1 << 12));
+
+        let mut previous = None;
+        for _ in 0..100 {
+            let a = previous
+                .take()
+                .unwrap_or_else(|| cs.alloc_single_variable_from_witness(F::from_u64_unchecked(1)));
+            let b = cs.alloc_single_variable_from_witness(F::from_u64_unchecked(2));
+            let c = cs.alloc_single_variable_from_witness(F::from_u64_unchecked(3));
+            let d = FmaGateInBaseFieldWithoutConstant::compute_fma(
+                &mut cs,
+                F::TWO,
+                (a, b),
+                F::MINUS_ONE,
+                c,
+            );
+            previous = Some(ZeroCheckGate::check_if_zero(&mut cs, d));
+        }
+
+        cs.pad_and_shrink();
+        let worker = Worker::new_with_num_threads(4);
+        let cs = cs.into_assembly::<std::alloc::Global>();
+
+        let proof_config = ProofConfig {
+            fri_lde_factor: 8,
+            merkle_tree_cap_size: 4,
+            pow_bits: 10,
+            ..Default::default()
+        };
+        let (proof, vk) =
+            cs.prove_one_shot::<Ext, TR, H, Poseidon2Goldilocks>(&worker, proof_config, ());
+
+        // any other nonce changes the query indices, so the proof must be rejected
+        let mut proof_with_wrong_nonce = proof.clone();
+        proof_with_wrong_nonce.pow_challenge ^= 1;
+
+        let builder_impl =
+            CsVerifierBuilder::<F, Ext>::new_from_parameters(vk.fixed_parameters.parameters);
+        let builder = new_builder::<_, F>(builder_impl);
+        let builder = configure(builder);
+        let verifier = builder.build(());
+
+        assert!(verifier.verify::<H, TR, Poseidon2Goldilocks>((), &vk, &proof));
+        assert!(!verifier.verify::<H, TR, Poseidon2Goldilocks>((), &vk, &proof_with_wrong_nonce));
+
+        // verify both recursively
+        let geometry = CSGeometry {
+            num_columns_under_copy_permutation: 132,
+            num_witness_columns: 0,
+            num_constant_columns: 4,
+            max_allowed_constraint_degree: 8,
+        };
+        let builder_impl =
+            CsReferenceImplementationBuilder::<F, P, DevCSConfig>::new(geometry, 1 << 16);
+        let builder = new_builder::<_, F>(builder_impl);
+
+        type Poseidon2Gate = Poseidon2FlattenedGate<GoldilocksField, 8, 12, 4, Poseidon2Goldilocks>;
+
+        let builder = ConstantsAllocatorGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = BooleanConstraintGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = Poseidon2Gate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = SelectionGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = ReductionGate::<F, 4>::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = ZeroCheckGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+            false,
+        );
+        let builder =
+            NopGate::configure_builder(builder, GatePlacementStrategy::UseGeneralPurposeColumns);
+
+        let mut cs = builder.build(CircuitResolverOpts::new(1 << 22));
+
+        let builder_impl = CsRecursiveVerifierBuilder::<'_, F, Ext, _>::new_from_parameters(
+            &mut cs,
+            vk.fixed_parameters.parameters,
+        );
+        let builder = new_builder::<_, F>(builder_impl);
+        let builder = configure(builder);
+        let verifier = builder.build(());
+
+        use crate::gadgets::traits::allocatable::CSAllocatable;
+
+        let allocated_vk = AllocatedVerificationKey::<F, RH>::allocate(&mut cs, vk.clone());
+        for (proof, expected) in [(proof, true), (proof_with_wrong_nonce, false)] {
+            let allocated_proof = AllocatedProof::<F, RH, Ext>::allocate_from_witness(
+                &mut cs,
+                Some(proof.clone()),
+                &verifier,
+                &vk.fixed_parameters,
+                &proof.proof_config,
+            );
+
+            let (is_valid, _) = verifier.verify::<RH, TR, Ctr, Poseidon2Goldilocks>(
+                &mut cs,
+                (),
+                &allocated_proof,
+                &vk.fixed_parameters,
+                &proof.proof_config,
+                &allocated_vk,
+            );
+
+            assert_eq!(is_valid.witness_hook(&cs)().unwrap(), expected);
+        }
+    }
 }
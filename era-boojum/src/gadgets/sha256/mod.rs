@@ -4,8 +4,12 @@ use crate::cs::gates::{
 };
 use crate::cs::traits::cs::ConstraintSystem;
 use crate::cs::Variable;
+use crate::gadgets::impls::var_length::{is_before_marker, is_marked_in_range, length_marker};
+use crate::gadgets::num::Num;
+use crate::gadgets::traits::selectable::Selectable;
 use crate::gadgets::u32::UInt32;
 use crate::gadgets::u8::UInt8;
+use std::mem::MaybeUninit;
 
 pub mod round_function;
 
@@ -104,6 +108,103 @@ pub fn sha256<F: SmallField, CS: ConstraintSystem<F>>(
     unsafe { output.map(|el| UInt8::from_variable_unchecked(el)) }
 }
 
+/// SHA-256 of the first `len` bytes of `input`, where `len` is only known in-circuit and at most
+/// `max_len`. Bytes missing from `input` up to `max_len` are taken as zeroes. The padding is
+/// selected per byte and the digest is selected after the block where the message ends, so
+/// the circuit only depends on `max_len`
+pub fn sha256_var_len<F: SmallField, CS: ConstraintSystem<F>>(
+    cs: &mut CS,
+    input: &[UInt8<F>],
+    max_len: usize,
+    len: UInt32<F>,
+) -> [UInt8<F>; SHA256_DIGEST_SIZE] {
+    assert!(input.len() <= max_len);
+    // so the bit length fits into 32 bits
+    assert!(max_len < 1 << 29);
+
+    let num_blocks = (max_len + 1 + 8 + SHA256_BLOCK_SIZE - 1) / SHA256_BLOCK_SIZE;
+
+    let marker = length_marker(cs, len, max_len);
+    let in_message = is_before_marker(cs, &marker);
+
+    let bit_length = Num::linear_combination(cs, &[(len.get_variable(), F::from_u64_unchecked(8))]);
+    let bit_length_be =
+        UInt32::from_variable_checked(cs, bit_length.get_variable()).to_be_bytes(cs);
+
+    let zero = UInt8::zero(cs);
+    let mut full_message = Vec::with_capacity(num_blocks * SHA256_BLOCK_SIZE);
+    let mut is_last_block = Vec::with_capacity(num_blocks);
+    for block_idx in 0..num_blocks {
+        // the message ends in the last block with space for 0x80 and the length after it
+        let block_start = block_idx * SHA256_BLOCK_SIZE;
+        let is_last = is_marked_in_range(
+            cs,
+            &marker,
+            block_start.saturating_sub(8)..(block_start + 56),
+        );
+        is_last_block.push(is_last);
+
+        for offset in 0..SHA256_BLOCK_SIZE {
+            let idx = block_start + offset;
+            let mut padding = vec![];
+            if idx <= max_len {
+                padding.push((marker[idx].get_variable(), F::from_u64_unchecked(0x80)));
+            }
+            // the length takes 8 big-endian bytes, the upper 4 of them are zero
+            if offset >= SHA256_BLOCK_SIZE - 4 {
+                let length_byte = bit_length_be[offset - (SHA256_BLOCK_SIZE - 4)];
+                let length_byte = length_byte.mask(cs, is_last);
+                padding.push((length_byte.get_variable(), F::ONE));
+            }
+            // at most one of the terms is nonzero
+            let padding = if padding.is_empty() {
+                zero
+            } else {
+                let padding = Num::linear_combination(cs, &padding);
+                unsafe { UInt8::from_variable_unchecked(padding.get_variable()) }
+            };
+
+            let byte = if idx < max_len {
+                let input_byte = input.get(idx).copied().unwrap_or(zero);
+                UInt8::conditionally_select(cs, in_message[idx], &input_byte, &padding)
+            } else {
+                padding
+            };
+            full_message.push(byte);
+        }
+    }
+
+    let mut state = ivs_as_uint32(cs);
+    let mut digest = None;
+    for (input_bytes, is_last) in full_message
+        .array_chunks::<SHA256_BLOCK_SIZE>()
+        .zip(is_last_block.into_iter())
+    {
+        let mut message_block = [MaybeUninit::uninit(); 16];
+        for (dst, src) in message_block
+            .iter_mut()
+            .zip(input_bytes.array_chunks::<4>())
+        {
+            dst.write(UInt32::from_be_bytes(cs, *src));
+        }
+        let message_block = unsafe { message_block.map(|el| el.assume_init()) };
+
+        let block_digest =
+            self::round_function::round_function_over_uint32(cs, &mut state, &message_block);
+        digest = match digest {
+            None => Some(block_digest),
+            Some(digest) => Some(Selectable::conditionally_select(
+                cs,
+                is_last,
+                &block_digest,
+                &digest,
+            )),
+        };
+    }
+
+    digest.expect("must hash at least one block")
+}
+
 #[cfg(test)]
 mod test {
     use std::alloc::Global;
@@ -112,9 +213,9 @@ mod test {
     use crate::{
         config::CSConfig,
         cs::{
-            gates::{ConstantsAllocatorGate, NopGate, ReductionGate},
+            gates::{ConstantsAllocatorGate, NopGate, ReductionGate, SelectionGate},
             implementations::{
-                pow::NoPow,
+                pow::{NoPow, PoWRunner},
                 transcript::{Blake2sTranscript, Transcript},
             },
             oracle::TreeHasher,
@@ -252,13 +353,125 @@ mod test {
         assert!(owned_cs.check_if_satisfied(&worker));
     }
 
+    #[test]
+    fn test_var_len_block_boundaries() {
+        // the padding fits into one block up to 55 bytes, and takes the next one from 56
+        test_sha256_var_len(
+            3 * 64,
+            &[0, 1, 55, 56, 63, 64, 65, 119, 120, 127, 128, 3 * 64],
+        );
+    }
+    #[test]
+    fn test_var_len_short_input() {
+        test_sha256_var_len(64 - 9, &[0, 42, 64 - 9]);
+    }
+
+    fn test_sha256_var_len(max_len: usize, lengths: &[usize]) {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let mut input = vec![];
+        for _ in 0..max_len {
+            let byte: u8 = rng.gen();
+            input.push(byte);
+        }
+
+        let geometry = CSGeometry {
+            num_columns_under_copy_permutation: 20,
+            num_witness_columns: 0,
+            num_constant_columns: 4,
+            max_allowed_constraint_degree: 4,
+        };
+
+        use crate::config::DevCSConfig;
+        use crate::cs::cs_builder_reference::*;
+        let builder_impl =
+            CsReferenceImplementationBuilder::<F, F, DevCSConfig>::new(geometry, 1 << 18);
+        use crate::cs::cs_builder::new_builder;
+        let builder = new_builder::<_, F>(builder_impl);
+
+        let builder = builder.allow_lookup(
+            crate::cs::LookupParameters::UseSpecializedColumnsWithTableIdAsConstant {
+                width: 4,
+                num_repetitions: 5,
+                share_table_id: true,
+            },
+        );
+
+        let builder = ConstantsAllocatorGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = ReductionGate::<F, 4>::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = SelectionGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder =
+            NopGate::configure_builder(builder, GatePlacementStrategy::UseGeneralPurposeColumns);
+
+        let mut owned_cs = builder.build(CircuitResolverOpts::new(1 << 20));
+
+        // add tables
+        let table = create_tri_xor_table();
+        owned_cs.add_lookup_table::<TriXor4Table, 4>(table);
+
+        let table = create_ch4_table();
+        owned_cs.add_lookup_table::<Ch4Table, 4>(table);
+
+        let table = create_maj4_table();
+        owned_cs.add_lookup_table::<Maj4Table, 4>(table);
+
+        let table = create_4bit_chunk_split_table::<F, 1>();
+        owned_cs.add_lookup_table::<Split4BitChunkTable<1>, 4>(table);
+
+        let table = create_4bit_chunk_split_table::<F, 2>();
+        owned_cs.add_lookup_table::<Split4BitChunkTable<2>, 4>(table);
+
+        let mut circuit_input = vec![];
+
+        let cs = &mut owned_cs;
+
+        for el in input.iter() {
+            let el = UInt8::allocate_checked(cs, *el);
+            circuit_input.push(el);
+        }
+
+        for len in lengths.iter().copied() {
+            let mut hasher = sha2::Sha256::new();
+            hasher.update(&input[..len]);
+            let reference_output = hasher.finalize();
+
+            let circuit_len = UInt32::allocate_checked(cs, len as u32);
+            let output = sha256_var_len(cs, &circuit_input, max_len, circuit_len);
+            let output = hex::encode((output.witness_hook(&*cs))().unwrap());
+            let reference_output = hex::encode(reference_output.as_slice());
+            assert_eq!(output, reference_output, "length {}", len);
+        }
+
+        drop(cs);
+        owned_cs.pad_and_shrink();
+        let mut owned_cs = owned_cs.into_assembly::<Global>();
+        use crate::worker::Worker;
+        let worker = Worker::new_with_num_threads(8);
+        assert!(owned_cs.check_if_satisfied(&worker));
+    }
+
     type P = crate::field::goldilocks::MixedGL;
 
     // Notes on benches:
     // - we ignore equality asserts because we are lazy, but those are negligible contribution compared to sha256 itself
     // - we use random input (not zeroes), because constant propagation would not help much anyway, and it's more realistic case
     // - allocation (8-bit constraints on bytes) are included in the proof, but why not?
-    // - PoW is turned off, because 2^20 bits for blake2s PoW is 30 ms anyway, negligible
+    // - PoW is turned off, because 2^20 bits for blake2s PoW is 30 ms anyway, negligible. The exception is
+    // recursive mode with Poseidon2 PoW, where grinding is cheap to verify in-circuit and saves queries
 
     #[test]
     #[ignore]
@@ -266,7 +479,7 @@ mod test {
         use crate::blake2::Blake2s256;
         type TreeHash = Blake2s256;
         type Transcript = Blake2sTranscript;
-        prove_sha256::<TreeHash, Transcript>(8 * (1 << 10));
+        prove_sha256::<TreeHash, Transcript, NoPow>(8 * (1 << 10), 0);
     }
 
     #[test]
@@ -278,7 +491,7 @@ mod test {
 
         type TreeHash = GoldilocksPoseidonSponge<AbsorptionModeOverwrite>;
         type Transcript = GoldilocksPoisedonTranscript;
-        prove_sha256::<TreeHash, Transcript>(8 * (1 << 10));
+        prove_sha256::<TreeHash, Transcript, NoPow>(8 * (1 << 10), 0);
     }
 
     #[test]
@@ -290,14 +503,30 @@ mod test {
 
         type TreeHash = GoldilocksPoseidon2Sponge<AbsorptionModeOverwrite>;
         type Transcript = GoldilocksPoisedonTranscript;
-        prove_sha256::<TreeHash, Transcript>(8 * (1 << 10));
+        prove_sha256::<TreeHash, Transcript, NoPow>(8 * (1 << 10), 0);
+    }
+
+    #[test]
+    #[ignore]
+    fn run_sha256_prover_recursive_mode_poseidon2_with_pow() {
+        use crate::algebraic_props::round_function::AbsorptionModeOverwrite;
+        use crate::algebraic_props::sponge::GoldilocksPoseidon2Sponge;
+        use crate::cs::implementations::transcript::GoldilocksPoisedonTranscript;
+        use crate::implementations::poseidon2::Poseidon2Goldilocks;
+
+        type TreeHash = GoldilocksPoseidon2Sponge<AbsorptionModeOverwrite>;
+        type Transcript = GoldilocksPoisedonTranscript;
+        // ~20 bits of PoW save 7 of 34 queries at LDE factor 8
+        prove_sha256::<TreeHash, Transcript, Poseidon2Goldilocks>(8 * (1 << 10), 20);
     }
 
     fn prove_sha256<
         T: TreeHasher<GoldilocksField, Output = TR::CompatibleCap>,
         TR: Transcript<GoldilocksField, TransciptParameters = ()>,
+        POW: PoWRunner,
     >(
         len: usize,
+        pow_bits: u32,
     ) {
         use crate::config::SetupCSConfig;
         use crate::cs::implementations::prover::ProofConfig;
@@ -311,7 +540,7 @@ mod test {
         let cap_size = 16;
         let prover_config = ProofConfig {
             fri_lde_factor: fri_lde_degree,
-            pow_bits: 0, // not important in practice for anything. 2^20 Blake2s POW uses 30ms
+            pow_bits,
             ..Default::default()
         };
 
@@ -513,7 +742,7 @@ mod test {
 
         let now = std::time::Instant::now();
 
-        let proof = owned_cs.prove_cpu_basic::<GoldilocksExt2, TR, T, NoPow>(
+        let proof = owned_cs.prove_cpu_basic::<GoldilocksExt2, TR, T, POW>(
             &worker,
             witness_set,
             &base_setup,
@@ -532,7 +761,7 @@ mod test {
         let builder = configure(builder);
         let verifier = builder.build(());
 
-        let is_valid = verifier.verify::<T, TR, NoPow>((), &vk, &proof);
+        let is_valid = verifier.verify::<T, TR, POW>((), &vk, &proof);
 
         assert!(is_valid);
     }
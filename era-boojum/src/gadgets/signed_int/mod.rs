@@ -0,0 +1,416 @@
+//! Shared implementation of the two's complement signed integers over the unsigned ones.
+//! Everything works on the little-endian `UInt32` limbs, so the same code serves any width
+
+use crate::cs::traits::cs::ConstraintSystem;
+use crate::field::SmallField;
+use crate::gadgets::boolean::Boolean;
+use crate::gadgets::u256::UInt256;
+use crate::gadgets::u32::UInt32;
+
+pub(crate) fn overflowing_add_limbs<F: SmallField, CS: ConstraintSystem<F>, const N: usize>(
+    cs: &mut CS,
+    a: &[UInt32<F>; N],
+    b: &[UInt32<F>; N],
+) -> ([UInt32<F>; N], Boolean<F>) {
+    let mut carry_out = Boolean::allocated_constant(cs, false);
+    let mut result = *a; // any uninit would be fine too
+    for ((a, b), dst) in a.iter().zip(b.iter()).zip(result.iter_mut()) {
+        let (c, carry) = a.overflowing_add_with_carry_in(cs, *b, carry_out);
+        *dst = c;
+        carry_out = carry;
+    }
+
+    (result, carry_out)
+}
+
+pub(crate) fn overflowing_sub_limbs<F: SmallField, CS: ConstraintSystem<F>, const N: usize>(
+    cs: &mut CS,
+    a: &[UInt32<F>; N],
+    b: &[UInt32<F>; N],
+) -> ([UInt32<F>; N], Boolean<F>) {
+    let mut borrow_out = Boolean::allocated_constant(cs, false);
+    let mut result = *a; // any uninit would be fine too
+    for ((a, b), dst) in a.iter().zip(b.iter()).zip(result.iter_mut()) {
+        let (c, borrow) = a.overflowing_sub_with_borrow_in(cs, *b, borrow_out);
+        *dst = c;
+        borrow_out = borrow;
+    }
+
+    (result, borrow_out)
+}
+
+/// Unsigned division of at most 256-bit values with the semantics of `UInt256::div_rem`.
+/// The quotient and remainder do not exceed the zero extended operands, so they have zero high
+/// limbs whenever the division is constrained
+pub(crate) fn div_rem_limbs<F: SmallField, CS: ConstraintSystem<F>, const N: usize>(
+    cs: &mut CS,
+    a: &[UInt32<F>; N],
+    b: &[UInt32<F>; N],
+) -> ([UInt32<F>; N], [UInt32<F>; N]) {
+    debug_assert!(N <= 8);
+    let zero = UInt32::zero(cs);
+    let mut a_extended = UInt256 { inner: [zero; 8] };
+    a_extended.inner[..N].copy_from_slice(a);
+    let mut b_extended = UInt256 { inner: [zero; 8] };
+    b_extended.inner[..N].copy_from_slice(b);
+
+    let (quotient, remainder) = a_extended.div_rem(cs, &b_extended);
+
+    (
+        quotient.inner[..N].try_into().unwrap(),
+        remainder.inner[..N].try_into().unwrap(),
+    )
+}
+
+/// Declares a signed integer `$name` over the unsigned `$unsigned` of `$num_limbs` limbs with
+/// the witness `$witness`, which `$decompose` and `$recompose` convert to and from the limbs.
+/// The invoking module brings the gadget traits and the limb helpers above into scope
+macro_rules! signed_int {
+    (
+        $(#[$attr:meta])*
+        $name:ident,
+        $unsigned:ident,
+        $witness:ty,
+        $num_limbs:literal,
+        $decompose:path,
+        $recompose:path
+    ) => {
+        $(#[$attr])*
+        #[derive(Derivative)]
+        #[derivative(Clone, Copy, Debug, Hash)]
+        pub struct $name<F: SmallField> {
+            pub inner: $unsigned<F>,
+        }
+
+        impl<F: SmallField> CSAllocatable<F> for $name<F> {
+            type Witness = $witness;
+            fn placeholder_witness() -> Self::Witness {
+                <$witness>::zero()
+            }
+
+            #[inline(always)]
+            fn allocate_without_value<CS: ConstraintSystem<F>>(cs: &mut CS) -> Self {
+                let inner = $unsigned::allocate_without_value(cs);
+
+                Self { inner }
+            }
+
+            fn allocate<CS: ConstraintSystem<F>>(cs: &mut CS, witness: Self::Witness) -> Self {
+                let inner = $unsigned::allocate(cs, witness);
+
+                Self { inner }
+            }
+
+            fn allocate_constant<CS: ConstraintSystem<F>>(
+                cs: &mut CS,
+                witness: Self::Witness,
+            ) -> Self {
+                let inner = $unsigned::allocate_constant(cs, witness);
+
+                Self { inner }
+            }
+        }
+
+        impl<F: SmallField> CSAllocatableExt<F> for $name<F> {
+            const INTERNAL_STRUCT_LEN: usize = $num_limbs;
+
+            fn witness_from_set_of_values(values: [F; Self::INTERNAL_STRUCT_LEN]) -> Self::Witness {
+                $recompose(values.map(|el| <u32 as WitnessCastable<F, F>>::cast_from_source(el)))
+            }
+
+            // we should be able to allocate without knowing values yet
+            fn create_without_value<CS: ConstraintSystem<F>>(cs: &mut CS) -> Self {
+                Self::allocate_without_value(cs)
+            }
+
+            fn flatten_as_variables(&self) -> [Variable; Self::INTERNAL_STRUCT_LEN]
+            where
+                [(); Self::INTERNAL_STRUCT_LEN]:,
+            {
+                self.inner.inner.map(|el| el.get_variable())
+            }
+
+            fn set_internal_variables_values(
+                witness: Self::Witness,
+                dst: &mut DstBuffer<'_, '_, F>,
+            ) {
+                $decompose(witness).map(|el| UInt32::set_internal_variables_values(el, dst));
+            }
+        }
+
+        impl<F: SmallField> Selectable<F> for $name<F> {
+            #[must_use]
+            fn conditionally_select<CS: ConstraintSystem<F>>(
+                cs: &mut CS,
+                flag: Boolean<F>,
+                a: &Self,
+                b: &Self,
+            ) -> Self {
+                let inner = Selectable::conditionally_select(cs, flag, &a.inner, &b.inner);
+
+                Self { inner }
+            }
+        }
+
+        impl<F: SmallField> $name<F> {
+            #[must_use]
+            pub fn allocated_constant<CS: ConstraintSystem<F>>(
+                cs: &mut CS,
+                constant: $witness,
+            ) -> Self {
+                let inner = $unsigned::allocated_constant(cs, constant);
+
+                Self { inner }
+            }
+
+            #[must_use]
+            pub fn zero<CS: ConstraintSystem<F>>(cs: &mut CS) -> Self {
+                Self::allocated_constant(cs, <$witness>::zero())
+            }
+
+            #[must_use]
+            pub fn is_negative<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> Boolean<F> {
+                // the top limb is at least 2^31
+                let sign_bit = UInt32::allocated_constant(cs, 1u32 << 31);
+                let (_, borrow) = self.inner.inner[$num_limbs - 1].overflowing_sub(cs, sign_bit);
+
+                borrow.negated(cs)
+            }
+
+            /// Wraps for the minimal value
+            #[must_use]
+            pub fn neg<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> Self {
+                let zero = $unsigned::zero(cs);
+                let (inner, _) = overflowing_sub_limbs(cs, &zero.inner, &self.inner.inner);
+
+                Self {
+                    inner: $unsigned { inner },
+                }
+            }
+
+            /// Wraps for the minimal value, use `unsigned_abs` for the exact one
+            #[must_use]
+            pub fn abs<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> Self {
+                let is_negative = self.is_negative(cs);
+                let negated = self.neg(cs);
+
+                Self::conditionally_select(cs, is_negative, &negated, self)
+            }
+
+            #[must_use]
+            pub fn unsigned_abs<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> $unsigned<F> {
+                // the wrapped minimal value is exactly the modulus halved as unsigned
+                self.abs(cs).inner
+            }
+
+            /// Wrapping addition, and whether it overflowed as a signed one
+            #[must_use]
+            pub fn overflowing_add<CS: ConstraintSystem<F>>(
+                &self,
+                cs: &mut CS,
+                other: &Self,
+            ) -> (Self, Boolean<F>) {
+                let (inner, _) = overflowing_add_limbs(cs, &self.inner.inner, &other.inner.inner);
+                let result = Self {
+                    inner: $unsigned { inner },
+                };
+
+                // overflows if the operands have the same sign, and the result has the other one
+                let self_is_negative = self.is_negative(cs);
+                let other_is_negative = other.is_negative(cs);
+                let result_is_negative = result.is_negative(cs);
+                let signs_differ = self_is_negative.xor(cs, other_is_negative);
+                let same_signs = signs_differ.negated(cs);
+                let sign_changed = self_is_negative.xor(cs, result_is_negative);
+                let of = same_signs.and(cs, sign_changed);
+
+                (result, of)
+            }
+
+            /// Wrapping subtraction, and whether it overflowed as a signed one
+            #[must_use]
+            pub fn overflowing_sub<CS: ConstraintSystem<F>>(
+                &self,
+                cs: &mut CS,
+                other: &Self,
+            ) -> (Self, Boolean<F>) {
+                let (inner, _) = overflowing_sub_limbs(cs, &self.inner.inner, &other.inner.inner);
+                let result = Self {
+                    inner: $unsigned { inner },
+                };
+
+                // overflows if the operands have different signs, and the result has the sign
+                // of `other`
+                let self_is_negative = self.is_negative(cs);
+                let other_is_negative = other.is_negative(cs);
+                let result_is_negative = result.is_negative(cs);
+                let signs_differ = self_is_negative.xor(cs, other_is_negative);
+                let sign_changed = self_is_negative.xor(cs, result_is_negative);
+                let of = signs_differ.and(cs, sign_changed);
+
+                (result, of)
+            }
+
+            #[must_use]
+            pub fn lt<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Boolean<F> {
+                let self_is_negative = self.is_negative(cs);
+                let other_is_negative = other.is_negative(cs);
+                let signs_differ = self_is_negative.xor(cs, other_is_negative);
+                // for the same signs the two's complement keeps the order
+                let unsigned_lt = self.inner.lt(cs, &other.inner);
+
+                Selectable::conditionally_select(cs, signs_differ, &self_is_negative, &unsigned_lt)
+            }
+
+            #[must_use]
+            pub fn le<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Boolean<F> {
+                other.lt(cs, self).negated(cs)
+            }
+
+            #[must_use]
+            pub fn gt<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Boolean<F> {
+                other.lt(cs, self)
+            }
+
+            #[must_use]
+            pub fn ge<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Boolean<F> {
+                self.lt(cs, other).negated(cs)
+            }
+
+            #[must_use]
+            pub fn min<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Self {
+                let self_is_smaller = self.lt(cs, other);
+
+                Self::conditionally_select(cs, self_is_smaller, self, other)
+            }
+
+            #[must_use]
+            pub fn max<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Self {
+                let self_is_smaller = self.lt(cs, other);
+
+                Self::conditionally_select(cs, self_is_smaller, other, self)
+            }
+
+            /// Returns `(quotient, remainder)` with the quotient rounded towards negative
+            /// infinity, so the remainder has the sign of `other`. Division by zero gives zero
+            /// quotient and the remainder equal to `self`, and the minimal value divided by -1
+            /// wraps to itself
+            #[must_use]
+            pub fn div_rem_floor<CS: ConstraintSystem<F>>(
+                &self,
+                cs: &mut CS,
+                other: &Self,
+            ) -> (Self, Self) {
+                let self_is_negative = self.is_negative(cs);
+                let other_is_negative = other.is_negative(cs);
+                let self_abs = self.unsigned_abs(cs);
+                let other_abs = other.unsigned_abs(cs);
+                let (quotient_abs, remainder_abs) =
+                    div_rem_limbs(cs, &self_abs.inner, &other_abs.inner);
+
+                // truncated division rounds towards zero, so for operands of different signs
+                // and a nonzero remainder the quotient moves one further from zero
+                let signs_differ = self_is_negative.xor(cs, other_is_negative);
+                let remainder_is_zero = $unsigned {
+                    inner: remainder_abs,
+                }
+                .is_zero(cs);
+                let remainder_is_nonzero = remainder_is_zero.negated(cs);
+                let divisor_is_zero = other_abs.is_zero(cs);
+                let divisor_is_nonzero = divisor_is_zero.negated(cs);
+                let round_down = Boolean::multi_and(
+                    cs,
+                    &[signs_differ, remainder_is_nonzero, divisor_is_nonzero],
+                );
+
+                let zero = UInt32::zero(cs);
+                let mut one = [zero; $num_limbs];
+                one[0] = UInt32::allocated_constant(cs, 1);
+                let (quotient_abs_rounded, _) = overflowing_add_limbs(cs, &quotient_abs, &one);
+                let quotient_abs = Selectable::conditionally_select(
+                    cs,
+                    round_down,
+                    &$unsigned {
+                        inner: quotient_abs_rounded,
+                    },
+                    &$unsigned {
+                        inner: quotient_abs,
+                    },
+                );
+                let (remainder_abs_rounded, _) =
+                    overflowing_sub_limbs(cs, &other_abs.inner, &remainder_abs);
+                let remainder_abs = Selectable::conditionally_select(
+                    cs,
+                    round_down,
+                    &$unsigned {
+                        inner: remainder_abs_rounded,
+                    },
+                    &$unsigned {
+                        inner: remainder_abs,
+                    },
+                );
+
+                let quotient = Self {
+                    inner: quotient_abs,
+                };
+                let negated_quotient = quotient.neg(cs);
+                let quotient =
+                    Self::conditionally_select(cs, signs_differ, &negated_quotient, &quotient);
+
+                let remainder_is_negative = Selectable::conditionally_select(
+                    cs,
+                    divisor_is_nonzero,
+                    &other_is_negative,
+                    &self_is_negative,
+                );
+                let remainder = Self {
+                    inner: remainder_abs,
+                };
+                let negated_remainder = remainder.neg(cs);
+                let remainder = Self::conditionally_select(
+                    cs,
+                    remainder_is_negative,
+                    &negated_remainder,
+                    &remainder,
+                );
+
+                (quotient, remainder)
+            }
+        }
+
+        impl<F: SmallField> WitnessHookable<F> for $name<F> {
+            fn witness_hook<CS: ConstraintSystem<F>>(
+                &self,
+                cs: &CS,
+            ) -> Box<dyn FnOnce() -> Option<Self::Witness>> {
+                self.inner.witness_hook(cs)
+            }
+        }
+
+        // multiselect doesn't make much sense here because we can do parallel over chunks,
+        // so we degrade to default impl via normal select
+        impl<F: SmallField> MultiSelectable<F> for $name<F> {}
+
+        impl<F: SmallField> CircuitVarLengthEncodable<F> for $name<F> {
+            #[inline(always)]
+            fn encoding_length(&self) -> usize {
+                $num_limbs
+            }
+            fn encode_to_buffer<CS: ConstraintSystem<F>>(
+                &self,
+                cs: &mut CS,
+                dst: &mut Vec<Variable>,
+            ) {
+                CircuitVarLengthEncodable::<F>::encode_to_buffer(&self.inner, cs, dst);
+            }
+        }
+
+        impl<F: SmallField> CSPlaceholder<F> for $name<F> {
+            fn placeholder<CS: ConstraintSystem<F>>(cs: &mut CS) -> Self {
+                Self::zero(cs)
+            }
+        }
+    };
+}
+
+pub(crate) use signed_int;
@@ -3,13 +3,20 @@ use crate::cs::traits::cs::ConstraintSystem;
 use crate::cs::traits::cs::DstBuffer;
 use crate::cs::Variable;
 use crate::field::SmallField;
+use crate::gadgets::blake2s::mixing_function::merge_byte_using_table;
 use crate::gadgets::boolean::Boolean;
+use crate::gadgets::num::Num;
+use crate::gadgets::tables::ByteSplitTable;
 use crate::gadgets::traits::allocatable::CSAllocatable;
 use crate::gadgets::traits::allocatable::CSAllocatableExt;
 use crate::gadgets::traits::witnessable::WitnessHookable;
+use crate::gadgets::u256::{decompose_u256_as_u32x8, UInt256};
 use crate::gadgets::u32::UInt32;
+use crate::gadgets::u512::UInt512;
 use crate::gadgets::u8::UInt8;
-use ethereum_types::Address;
+use ethereum_types::{Address, U256};
+
+use crate::config::*;
 
 #[derive(Derivative)]
 #[derivative(Clone, Copy, Debug, Hash)]
@@ -177,6 +184,290 @@ impl<F: SmallField> UInt160<F> {
 
         bytes
     }
+
+    #[must_use]
+    pub fn from_le_bytes<CS: ConstraintSystem<F>>(cs: &mut CS, bytes: [UInt8<F>; 20]) -> Self {
+        let mut inner = [std::mem::MaybeUninit::uninit(); 5];
+        for (dst, src) in inner.iter_mut().zip(bytes.array_chunks::<4>()) {
+            dst.write(UInt32::from_le_bytes(cs, *src));
+        }
+
+        let inner = unsafe { inner.map(|el| el.assume_init()) };
+
+        Self { inner }
+    }
+
+    #[must_use]
+    pub fn from_be_bytes<CS: ConstraintSystem<F>>(cs: &mut CS, bytes: [UInt8<F>; 20]) -> Self {
+        let mut inner = [std::mem::MaybeUninit::uninit(); 5];
+        for (dst, src) in inner.iter_mut().rev().zip(bytes.array_chunks::<4>()) {
+            dst.write(UInt32::from_be_bytes(cs, *src));
+        }
+
+        let inner = unsafe { inner.map(|el| el.assume_init()) };
+
+        Self { inner }
+    }
+
+    #[must_use]
+    pub fn is_odd<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> Boolean<F> {
+        self.inner[0].into_num().spread_into_bits::<CS, 32>(cs)[0]
+    }
+
+    /// Zero extends the value, no constraints are needed
+    #[must_use]
+    pub fn to_u256<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> UInt256<F> {
+        let zero = UInt32::zero(cs);
+        let mut inner = [zero; 8];
+        inner[..5].copy_from_slice(&self.inner);
+
+        UInt256 { inner }
+    }
+
+    #[must_use]
+    pub fn overflowing_add<CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+        other: &Self,
+    ) -> (Self, Boolean<F>) {
+        let mut carry_out = Boolean::allocated_constant(cs, false);
+        let mut result = *self; // any uninit would be fine too
+        for ((a, b), dst) in self
+            .inner
+            .iter()
+            .zip(other.inner.iter())
+            .zip(result.inner.iter_mut())
+        {
+            let (c, carry) = (*a).overflowing_add_with_carry_in(cs, *b, carry_out);
+            *dst = c;
+            carry_out = carry;
+        }
+
+        (result, carry_out)
+    }
+
+    #[must_use]
+    pub fn overflowing_sub<CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+        other: &Self,
+    ) -> (Self, Boolean<F>) {
+        let mut borrow_out = Boolean::allocated_constant(cs, false);
+        let mut result = *self; // any uninit would be fine too
+        for ((a, b), dst) in self
+            .inner
+            .iter()
+            .zip(other.inner.iter())
+            .zip(result.inner.iter_mut())
+        {
+            let (c, borrow) = (*a).overflowing_sub_with_borrow_in(cs, *b, borrow_out);
+            *dst = c;
+            borrow_out = borrow;
+        }
+
+        (result, borrow_out)
+    }
+
+    // Returns the result of multiplication between two 160-bit unsigned
+    // integers as a 320-bit wide integer in the low limbs of `UInt512`,
+    // multiplying only the given number of low limbs of the operands
+    #[must_use]
+    pub fn widening_mul<CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+        other: &Self,
+        self_limbs: usize,
+        other_limbs: usize,
+    ) -> UInt512<F> {
+        assert!(self_limbs <= 5 && other_limbs <= 5);
+
+        let zero = UInt32::allocated_constant(cs, 0);
+        let mut remainders = vec![UInt32::<F>::zero(cs); self_limbs + other_limbs];
+
+        for i in 0..self_limbs {
+            let mut carry = UInt32::allocated_constant(cs, 0);
+            for j in 0..other_limbs {
+                let res = UInt32::fma_with_carry(
+                    cs,
+                    self.inner[i],
+                    other.inner[j],
+                    if i == 0 { zero } else { remainders[i + j] },
+                    carry,
+                );
+                (remainders[i + j], carry) = (res[0].0, res[1].0);
+            }
+            remainders[i + other_limbs] = carry;
+        }
+
+        let mut inner = [UInt32::<F>::zero(cs); 16];
+        inner[..self_limbs + other_limbs].copy_from_slice(&remainders);
+        UInt512 { inner }
+    }
+
+    #[must_use]
+    pub fn to_le_bits<CS: ConstraintSystem<F>>(self, cs: &mut CS) -> Vec<Boolean<F>> {
+        self.inner.iter().flat_map(|el| el.to_le_bits(cs)).collect()
+    }
+
+    /// See `UInt32::to_le_bits_using_table`
+    #[must_use]
+    pub fn to_le_bits_using_table<CS: ConstraintSystem<F>>(self, cs: &mut CS) -> Vec<Boolean<F>> {
+        self.inner
+            .iter()
+            .flat_map(|el| el.to_le_bits_using_table(cs))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn from_le_bits<CS: ConstraintSystem<F>>(cs: &mut CS, bits: &[Boolean<F>]) -> Self {
+        assert_eq!(bits.len(), 160);
+
+        let inner =
+            std::array::from_fn(|idx| UInt32::from_le_bits(cs, &bits[idx * 32..(idx + 1) * 32]));
+
+        Self { inner }
+    }
+
+    #[must_use]
+    pub fn div2<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> Self {
+        let byte_split_id = cs
+            .get_table_id_for_marker::<ByteSplitTable<1>>()
+            .expect("table should exist");
+        let mut bytes = self.to_le_bytes(cs);
+        let mut bit: Option<Variable> = None;
+        bytes.iter_mut().rev().for_each(|b| {
+            let res = cs.perform_lookup::<1, 2>(byte_split_id, &[b.get_variable()]);
+            let mut shifted = res[1];
+            let new_bit = res[0];
+            if let Some(top_bit) = bit {
+                shifted = merge_byte_using_table::<_, _, 7>(cs, shifted, top_bit);
+            }
+            *b = UInt8 {
+                variable: shifted,
+                _marker: std::marker::PhantomData,
+            };
+            bit = Some(new_bit);
+        });
+        Self::from_le_bytes(cs, bytes)
+    }
+
+    /// Returns `(quotient, remainder)`. Division by zero gives zero quotient and the remainder
+    /// equal to `self`
+    #[must_use]
+    pub fn div_rem<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> (Self, Self) {
+        let outputs = cs.alloc_multiple_variables_without_values::<10>();
+
+        if <CS::Config as CSConfig>::WitnessConfig::EVALUATE_WITNESS {
+            let value_fn = move |inputs: &[F], output_buffer: &mut DstBuffer<'_, '_, F>| {
+                debug_assert!(F::CAPACITY_BITS >= 32);
+                let a: [F; 5] = inputs[..5].try_into().unwrap();
+                let b: [F; 5] = inputs[5..].try_into().unwrap();
+                let a = <Address as WitnessCastable<F, [F; 5]>>::cast_from_source(a);
+                let b = <Address as WitnessCastable<F, [F; 5]>>::cast_from_source(b);
+                let a = U256::from_big_endian(a.as_bytes());
+                let b = U256::from_big_endian(b.as_bytes());
+                let (quotient, remainder) = if b.is_zero() {
+                    (U256::zero(), a)
+                } else {
+                    a.div_mod(b)
+                };
+
+                for value in [quotient, remainder] {
+                    let chunks = decompose_u256_as_u32x8(value);
+                    output_buffer.extend(
+                        chunks[..5]
+                            .iter()
+                            .map(|el| F::from_u64_unchecked(*el as u64)),
+                    );
+                }
+            };
+
+            let mut dependencies = Vec::with_capacity(10);
+            dependencies.extend(Place::from_variables(
+                self.inner.map(|el| el.get_variable()),
+            ));
+            dependencies.extend(Place::from_variables(
+                other.inner.map(|el| el.get_variable()),
+            ));
+
+            cs.set_values_with_dependencies_vararg(
+                &dependencies,
+                &Place::from_variables(outputs),
+                value_fn,
+            );
+        }
+
+        let quotient = Self {
+            inner: std::array::from_fn(|idx| UInt32::from_variable_checked(cs, outputs[idx])),
+        };
+        let remainder = Self {
+            inner: std::array::from_fn(|idx| UInt32::from_variable_checked(cs, outputs[5 + idx])),
+        };
+
+        // self = quotient * other + remainder, so the product must fit into 160 bits and
+        // adding the remainder must not overflow
+        let zero = UInt32::zero(cs);
+        let product = quotient.widening_mul(cs, other, 5, 5);
+        for limb in product.inner[5..10].iter() {
+            Num::enforce_equal(cs, &limb.into_num(), &zero.into_num());
+        }
+        let product = Self {
+            inner: product.inner[..5].try_into().unwrap(),
+        };
+        let (sum, of) = product.overflowing_add(cs, &remainder);
+
+        let boolean_false = Boolean::allocated_constant(cs, false);
+        Boolean::enforce_equal(cs, &of, &boolean_false);
+        for (sum_limb, limb) in sum.inner.iter().zip(self.inner.iter()) {
+            Num::enforce_equal(cs, &sum_limb.into_num(), &limb.into_num());
+        }
+
+        // remainder < other, unless we divide by zero, in which case the quotient is pinned to 0
+        let divisor_is_zero = other.is_zero(cs);
+        let divisor_is_nonzero = divisor_is_zero.negated(cs);
+        let remainder_is_smaller = remainder.lt(cs, other);
+        remainder_is_smaller.conditionally_enforce_true(cs, divisor_is_nonzero);
+        let quotient_is_zero = quotient.is_zero(cs);
+        quotient_is_zero.conditionally_enforce_true(cs, divisor_is_zero);
+
+        (quotient, remainder)
+    }
+
+    #[must_use]
+    pub fn lt<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Boolean<F> {
+        let (_, borrow) = self.overflowing_sub(cs, other);
+
+        borrow
+    }
+
+    #[must_use]
+    pub fn le<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Boolean<F> {
+        other.lt(cs, self).negated(cs)
+    }
+
+    #[must_use]
+    pub fn gt<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Boolean<F> {
+        other.lt(cs, self)
+    }
+
+    #[must_use]
+    pub fn ge<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Boolean<F> {
+        self.lt(cs, other).negated(cs)
+    }
+
+    #[must_use]
+    pub fn min<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Self {
+        let self_is_smaller = self.lt(cs, other);
+
+        Self::conditionally_select(cs, self_is_smaller, self, other)
+    }
+
+    #[must_use]
+    pub fn max<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Self {
+        let self_is_smaller = self.lt(cs, other);
+
+        Self::conditionally_select(cs, self_is_smaller, other, self)
+    }
 }
 
 use crate::gadgets::traits::selectable::Selectable;
@@ -264,3 +555,221 @@ impl<F: SmallField> CSPlaceholder<F> for UInt160<F> {
         Self::zero(cs)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::alloc::Global;
+
+    use super::*;
+    use crate::cs::cs_builder::new_builder;
+    use crate::cs::cs_builder_reference::*;
+    use crate::cs::gates::*;
+    use crate::cs::implementations::reference_cs::CSReferenceImplementation;
+    use crate::cs::traits::gate::GatePlacementStrategy;
+    use crate::cs::{CSGeometry, GateConfigurationHolder, StaticToolboxHolder};
+    use crate::dag::CircuitResolverOpts;
+    use crate::field::goldilocks::GoldilocksField;
+    use crate::gadgets::tables::byte_split::create_byte_split_table;
+    use crate::gadgets::tables::xor8::{create_xor8_table, Xor8Table};
+    use crate::worker::Worker;
+    use ethereum_types::U512;
+    use rand::{Rng, SeedableRng};
+
+    type F = GoldilocksField;
+
+    fn create_test_cs() -> CSReferenceImplementation<
+        F,
+        F,
+        DevCSConfig,
+        impl GateConfigurationHolder<F>,
+        impl StaticToolboxHolder,
+    > {
+        let geometry = CSGeometry {
+            num_columns_under_copy_permutation: 60,
+            num_witness_columns: 0,
+            num_constant_columns: 4,
+            max_allowed_constraint_degree: 4,
+        };
+
+        let builder_impl =
+            CsReferenceImplementationBuilder::<F, F, DevCSConfig>::new(geometry, 1 << 16);
+        let builder = new_builder::<_, F>(builder_impl);
+
+        let builder = builder.allow_lookup(
+            crate::cs::LookupParameters::UseSpecializedColumnsWithTableIdAsConstant {
+                width: 3,
+                num_repetitions: 5,
+                share_table_id: true,
+            },
+        );
+        let builder = ConstantsAllocatorGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = ReductionGate::<F, 4>::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = UIntXAddGate::<32>::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = U8x4FMAGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = ZeroCheckGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+            false,
+        );
+        let builder = SelectionGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder =
+            NopGate::configure_builder(builder, GatePlacementStrategy::UseGeneralPurposeColumns);
+
+        let mut owned_cs = builder.build(CircuitResolverOpts::new(1 << 20));
+
+        let table = create_xor8_table();
+        owned_cs.add_lookup_table::<Xor8Table, 3>(table);
+        let table = create_byte_split_table::<F, 1>();
+        owned_cs.add_lookup_table::<ByteSplitTable<1>, 3>(table);
+        let table = create_byte_split_table::<F, 7>();
+        owned_cs.add_lookup_table::<ByteSplitTable<7>, 3>(table);
+
+        owned_cs
+    }
+
+    fn address_as_u256(address: Address) -> U256 {
+        U256::from_big_endian(address.as_bytes())
+    }
+
+    fn test_values() -> Vec<(Address, Address)> {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut values = vec![
+            (Address::zero(), Address::zero()),
+            (Address::zero(), Address::repeat_byte(0xff)),
+            (Address::repeat_byte(0xff), Address::zero()),
+            (Address::repeat_byte(0xff), Address::repeat_byte(0xfe)),
+            (Address::repeat_byte(0xff), Address::from_low_u64_be(1)),
+        ];
+        for _ in 0..8 {
+            let a = Address(rng.gen());
+            // same high limbs to exercise the borrow chain
+            let mut b = a;
+            b.0[19] = rng.gen();
+            values.push((a, b));
+            // divisors of all the widths
+            let width: usize = rng.gen_range(1..=160);
+            let b = address_as_u256(Address(rng.gen())) >> (160 - width);
+            values.push((a, Address::from_slice(&<[u8; 32]>::from(b)[12..])));
+        }
+
+        values
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let mut owned_cs = create_test_cs();
+        let cs = &mut owned_cs;
+
+        for (a_value, b_value) in test_values() {
+            let a = UInt160::allocate(cs, a_value);
+            let b = UInt160::allocate(cs, b_value);
+            let (a_int, b_int) = (address_as_u256(a_value), address_as_u256(b_value));
+
+            let lt = a.lt(cs, &b).witness_hook(&*cs)().unwrap();
+            let le = a.le(cs, &b).witness_hook(&*cs)().unwrap();
+            let gt = a.gt(cs, &b).witness_hook(&*cs)().unwrap();
+            let ge = a.ge(cs, &b).witness_hook(&*cs)().unwrap();
+            assert_eq!(
+                [lt, le, gt, ge],
+                [a_int < b_int, a_int <= b_int, a_int > b_int, a_int >= b_int]
+            );
+
+            let min = a.min(cs, &b).witness_hook(&*cs)().unwrap();
+            let max = a.max(cs, &b).witness_hook(&*cs)().unwrap();
+            assert_eq!(
+                (address_as_u256(min), address_as_u256(max)),
+                (a_int.min(b_int), a_int.max(b_int))
+            );
+        }
+
+        drop(cs);
+        owned_cs.pad_and_shrink();
+        let mut owned_cs = owned_cs.into_assembly::<Global>();
+        let worker = Worker::new_with_num_threads(8);
+        assert!(owned_cs.check_if_satisfied(&worker));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut owned_cs = create_test_cs();
+        let cs = &mut owned_cs;
+
+        let modulus = U256::one() << 160;
+        for (a_value, b_value) in test_values() {
+            let a = UInt160::allocate(cs, a_value);
+            let b = UInt160::allocate(cs, b_value);
+            let (a_int, b_int) = (address_as_u256(a_value), address_as_u256(b_value));
+
+            let (sum, of) = a.overflowing_add(cs, &b);
+            let sum = address_as_u256(sum.witness_hook(&*cs)().unwrap());
+            let of = of.witness_hook(&*cs)().unwrap();
+            assert_eq!(
+                (sum, of),
+                ((a_int + b_int) % modulus, a_int + b_int >= modulus)
+            );
+
+            let (diff, uf) = a.overflowing_sub(cs, &b);
+            let diff = address_as_u256(diff.witness_hook(&*cs)().unwrap());
+            let uf = uf.witness_hook(&*cs)().unwrap();
+            assert_eq!(
+                (diff, uf),
+                ((a_int + modulus - b_int) % modulus, a_int < b_int)
+            );
+
+            let (low, high) = a.widening_mul(cs, &b, 5, 5).witness_hook(&*cs)().unwrap();
+            let product = a_int.full_mul(b_int);
+            assert_eq!(U512::from(low) + (U512::from(high) << 256), product);
+
+            let (quotient, remainder) = a.div_rem(cs, &b);
+            let expected = if b_int.is_zero() {
+                (U256::zero(), a_int)
+            } else {
+                a_int.div_mod(b_int)
+            };
+            let quotient = address_as_u256(quotient.witness_hook(&*cs)().unwrap());
+            let remainder = address_as_u256(remainder.witness_hook(&*cs)().unwrap());
+            assert_eq!((quotient, remainder), expected);
+
+            let half = a.div2(cs).witness_hook(&*cs)().unwrap();
+            assert_eq!(address_as_u256(half), a_int >> 1);
+            let is_odd = a.is_odd(cs).witness_hook(&*cs)().unwrap();
+            assert_eq!(is_odd, a_int.bit(0));
+
+            let le_bytes = a.to_le_bytes(cs);
+            let be_bytes = a.to_be_bytes(cs);
+            let from_le = UInt160::from_le_bytes(cs, le_bytes).witness_hook(&*cs)().unwrap();
+            let from_be = UInt160::from_be_bytes(cs, be_bytes).witness_hook(&*cs)().unwrap();
+            assert_eq!((from_le, from_be), (a_value, a_value));
+
+            let extended = a.to_u256(cs);
+            assert_eq!(extended.witness_hook(&*cs)().unwrap(), a_int);
+            let truncated = extended.to_u160(cs).witness_hook(&*cs)().unwrap();
+            assert_eq!(truncated, a_value);
+        }
+
+        drop(cs);
+        owned_cs.pad_and_shrink();
+        let mut owned_cs = owned_cs.into_assembly::<Global>();
+        let worker = Worker::new_with_num_threads(8);
+        assert!(owned_cs.check_if_satisfied(&worker));
+    }
+}
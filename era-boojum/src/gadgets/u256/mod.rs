@@ -4,11 +4,13 @@ use crate::cs::traits::cs::DstBuffer;
 use crate::field::SmallField;
 use crate::gadgets::blake2s::mixing_function::merge_byte_using_table;
 use crate::gadgets::boolean::Boolean;
+use crate::gadgets::num::Num;
 use crate::gadgets::tables::ByteSplitTable;
 use crate::gadgets::traits::allocatable::CSAllocatable;
 use crate::gadgets::traits::allocatable::CSAllocatableExt;
 use crate::gadgets::traits::witnessable::CSWitnessable;
 use crate::gadgets::traits::witnessable::WitnessHookable;
+use crate::gadgets::u160::UInt160;
 use crate::gadgets::u32::UInt32;
 use crate::gadgets::u512::UInt512;
 use crate::gadgets::u8::UInt8;
@@ -297,6 +299,19 @@ impl<F: SmallField> UInt256<F> {
         Self { inner }
     }
 
+    /// Drops the high limbs, enforcing them to be zero
+    #[must_use]
+    pub fn to_u160<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> UInt160<F> {
+        let zero = UInt32::zero(cs);
+        for limb in self.inner[5..].iter() {
+            Num::enforce_equal(cs, &limb.into_num(), &zero.into_num());
+        }
+
+        UInt160 {
+            inner: self.inner[..5].try_into().unwrap(),
+        }
+    }
+
     #[must_use]
     pub fn is_zero<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> Boolean<F> {
         let limbs_are_zero = self.inner.map(|el| el.is_zero(cs));
@@ -329,6 +344,30 @@ impl<F: SmallField> UInt256<F> {
         bytes
     }
 
+    #[must_use]
+    pub fn to_le_bits<CS: ConstraintSystem<F>>(self, cs: &mut CS) -> Vec<Boolean<F>> {
+        self.inner.iter().flat_map(|el| el.to_le_bits(cs)).collect()
+    }
+
+    /// See `UInt32::to_le_bits_using_table`
+    #[must_use]
+    pub fn to_le_bits_using_table<CS: ConstraintSystem<F>>(self, cs: &mut CS) -> Vec<Boolean<F>> {
+        self.inner
+            .iter()
+            .flat_map(|el| el.to_le_bits_using_table(cs))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn from_le_bits<CS: ConstraintSystem<F>>(cs: &mut CS, bits: &[Boolean<F>]) -> Self {
+        assert_eq!(bits.len(), 256);
+
+        let inner =
+            std::array::from_fn(|idx| UInt32::from_le_bits(cs, &bits[idx * 32..(idx + 1) * 32]));
+
+        Self { inner }
+    }
+
     #[must_use]
     pub fn div2<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> Self {
         let byte_split_id = cs
@@ -351,6 +390,116 @@ impl<F: SmallField> UInt256<F> {
         });
         Self::from_le_bytes(cs, bytes)
     }
+
+    /// Returns `(quotient, remainder)`. Division by zero gives zero quotient and the remainder
+    /// equal to `self`
+    #[must_use]
+    pub fn div_rem<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> (Self, Self) {
+        let outputs = cs.alloc_multiple_variables_without_values::<16>();
+
+        if <CS::Config as CSConfig>::WitnessConfig::EVALUATE_WITNESS {
+            let value_fn = move |inputs: &[F], output_buffer: &mut DstBuffer<'_, '_, F>| {
+                debug_assert!(F::CAPACITY_BITS >= 32);
+                let a: [F; 8] = inputs[..8].try_into().unwrap();
+                let b: [F; 8] = inputs[8..].try_into().unwrap();
+                let a = <U256 as WitnessCastable<F, [F; 8]>>::cast_from_source(a);
+                let b = <U256 as WitnessCastable<F, [F; 8]>>::cast_from_source(b);
+                let (quotient, remainder) = if b.is_zero() {
+                    (U256::zero(), a)
+                } else {
+                    a.div_mod(b)
+                };
+
+                for value in [quotient, remainder] {
+                    let chunks = decompose_u256_as_u32x8(value);
+                    output_buffer.extend(chunks.map(|el| F::from_u64_unchecked(el as u64)));
+                }
+            };
+
+            let mut dependencies = Vec::with_capacity(16);
+            dependencies.extend(Place::from_variables(
+                self.inner.map(|el| el.get_variable()),
+            ));
+            dependencies.extend(Place::from_variables(
+                other.inner.map(|el| el.get_variable()),
+            ));
+
+            cs.set_values_with_dependencies_vararg(
+                &dependencies,
+                &Place::from_variables(outputs),
+                value_fn,
+            );
+        }
+
+        let quotient = Self {
+            inner: std::array::from_fn(|idx| UInt32::from_variable_checked(cs, outputs[idx])),
+        };
+        let remainder = Self {
+            inner: std::array::from_fn(|idx| UInt32::from_variable_checked(cs, outputs[8 + idx])),
+        };
+
+        // self = quotient * other + remainder, and it must fit into 256 bits
+        let zero = UInt32::zero(cs);
+        let product = quotient.widening_mul(cs, other, 8, 8);
+        let mut remainder_extended = UInt512 { inner: [zero; 16] };
+        remainder_extended.inner[..8].copy_from_slice(&remainder.inner);
+        let (sum, of) = product.overflowing_add(cs, &remainder_extended);
+
+        let boolean_false = Boolean::allocated_constant(cs, false);
+        Boolean::enforce_equal(cs, &of, &boolean_false);
+        for (sum_limb, limb) in sum.inner[..8].iter().zip(self.inner.iter()) {
+            Num::enforce_equal(cs, &sum_limb.into_num(), &limb.into_num());
+        }
+        for sum_limb in sum.inner[8..].iter() {
+            Num::enforce_equal(cs, &sum_limb.into_num(), &zero.into_num());
+        }
+
+        // remainder < other, unless we divide by zero, in which case the quotient is pinned to 0
+        let divisor_is_zero = other.is_zero(cs);
+        let divisor_is_nonzero = divisor_is_zero.negated(cs);
+        let remainder_is_smaller = remainder.lt(cs, other);
+        remainder_is_smaller.conditionally_enforce_true(cs, divisor_is_nonzero);
+        let quotient_is_zero = quotient.is_zero(cs);
+        quotient_is_zero.conditionally_enforce_true(cs, divisor_is_zero);
+
+        (quotient, remainder)
+    }
+
+    #[must_use]
+    pub fn lt<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Boolean<F> {
+        let (_, borrow) = self.overflowing_sub(cs, other);
+
+        borrow
+    }
+
+    #[must_use]
+    pub fn le<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Boolean<F> {
+        other.lt(cs, self).negated(cs)
+    }
+
+    #[must_use]
+    pub fn gt<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Boolean<F> {
+        other.lt(cs, self)
+    }
+
+    #[must_use]
+    pub fn ge<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Boolean<F> {
+        self.lt(cs, other).negated(cs)
+    }
+
+    #[must_use]
+    pub fn min<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Self {
+        let self_is_smaller = self.lt(cs, other);
+
+        Self::conditionally_select(cs, self_is_smaller, self, other)
+    }
+
+    #[must_use]
+    pub fn max<CS: ConstraintSystem<F>>(&self, cs: &mut CS, other: &Self) -> Self {
+        let self_is_smaller = self.lt(cs, other);
+
+        Self::conditionally_select(cs, self_is_smaller, other, self)
+    }
 }
 
 use crate::cs::Variable;
@@ -422,3 +571,242 @@ impl<F: SmallField> CSPlaceholder<F> for UInt256<F> {
         Self::zero(cs)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::alloc::Global;
+
+    use super::*;
+    use crate::cs::cs_builder::new_builder;
+    use crate::cs::cs_builder_reference::*;
+    use crate::cs::gates::*;
+    use crate::cs::implementations::cost_accounting::short_type_name;
+    use crate::cs::implementations::reference_cs::CSReferenceImplementation;
+    use crate::cs::traits::gate::GatePlacementStrategy;
+    use crate::cs::{CSGeometry, GateConfigurationHolder, StaticToolboxHolder};
+    use crate::dag::CircuitResolverOpts;
+    use crate::field::goldilocks::GoldilocksField;
+    use crate::gadgets::tables::byte_split::create_byte_split_table;
+    use crate::gadgets::tables::xor8::{create_xor8_table, Xor8Table};
+    use crate::worker::Worker;
+    use rand::{Rng, SeedableRng};
+
+    type F = GoldilocksField;
+
+    fn create_test_cs() -> CSReferenceImplementation<
+        F,
+        F,
+        DevCSConfig,
+        impl GateConfigurationHolder<F>,
+        impl StaticToolboxHolder,
+    > {
+        let geometry = CSGeometry {
+            num_columns_under_copy_permutation: 60,
+            num_witness_columns: 0,
+            num_constant_columns: 4,
+            max_allowed_constraint_degree: 4,
+        };
+
+        let builder_impl =
+            CsReferenceImplementationBuilder::<F, F, DevCSConfig>::new(geometry, 1 << 16);
+        let builder = new_builder::<_, F>(builder_impl);
+
+        let builder = builder.allow_lookup(
+            crate::cs::LookupParameters::UseSpecializedColumnsWithTableIdAsConstant {
+                width: 3,
+                num_repetitions: 5,
+                share_table_id: true,
+            },
+        );
+        let builder = ConstantsAllocatorGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = FmaGateInBaseFieldWithoutConstant::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = ReductionGate::<F, 4>::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = UIntXAddGate::<32>::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = U8x4FMAGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = ZeroCheckGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+            false,
+        );
+        let builder = BooleanConstraintGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder = SelectionGate::configure_builder(
+            builder,
+            GatePlacementStrategy::UseGeneralPurposeColumns,
+        );
+        let builder =
+            NopGate::configure_builder(builder, GatePlacementStrategy::UseGeneralPurposeColumns);
+
+        let mut owned_cs = builder.build(CircuitResolverOpts::new(1 << 20));
+
+        let table = create_xor8_table();
+        owned_cs.add_lookup_table::<Xor8Table, 3>(table);
+        let table = create_byte_split_table::<F, 1>();
+        owned_cs.add_lookup_table::<ByteSplitTable<1>, 3>(table);
+
+        owned_cs
+    }
+
+    fn test_values() -> Vec<(U256, U256)> {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut values = vec![
+            (U256::from(7u64), U256::zero()),
+            (U256::zero(), U256::from(7u64)),
+            (U256::from(7u64), U256::from(7u64)),
+            (U256::MAX, U256::one()),
+            (U256::MAX, U256::MAX - 1),
+            (U256::from(1u64) << 200, (U256::from(1u64) << 100) + 1),
+        ];
+        for _ in 0..8 {
+            let a = U256(rng.gen());
+            // divisors of all the widths
+            let width: usize = rng.gen_range(1..=256);
+            let b = U256(rng.gen()) >> (256 - width);
+            values.push((a, b));
+        }
+
+        values
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let mut owned_cs = create_test_cs();
+        let cs = &mut owned_cs;
+
+        for (a_value, b_value) in test_values() {
+            let a = UInt256::allocate(cs, a_value);
+            let b = UInt256::allocate(cs, b_value);
+
+            let (quotient, remainder) = a.div_rem(cs, &b);
+            let expected = if b_value.is_zero() {
+                (U256::zero(), a_value)
+            } else {
+                a_value.div_mod(b_value)
+            };
+            let quotient = quotient.witness_hook(&*cs)().unwrap();
+            let remainder = remainder.witness_hook(&*cs)().unwrap();
+            assert_eq!((quotient, remainder), expected);
+        }
+
+        drop(cs);
+        owned_cs.pad_and_shrink();
+        let mut owned_cs = owned_cs.into_assembly::<Global>();
+        let worker = Worker::new_with_num_threads(8);
+        assert!(owned_cs.check_if_satisfied(&worker));
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let mut owned_cs = create_test_cs();
+        let cs = &mut owned_cs;
+
+        for (a_value, b_value) in test_values() {
+            let a = UInt256::allocate(cs, a_value);
+            let b = UInt256::allocate(cs, b_value);
+
+            let lt = a.lt(cs, &b).witness_hook(&*cs)().unwrap();
+            let le = a.le(cs, &b).witness_hook(&*cs)().unwrap();
+            let gt = a.gt(cs, &b).witness_hook(&*cs)().unwrap();
+            let ge = a.ge(cs, &b).witness_hook(&*cs)().unwrap();
+            assert_eq!(
+                [lt, le, gt, ge],
+                [
+                    a_value < b_value,
+                    a_value <= b_value,
+                    a_value > b_value,
+                    a_value >= b_value
+                ]
+            );
+
+            let min = a.min(cs, &b).witness_hook(&*cs)().unwrap();
+            let max = a.max(cs, &b).witness_hook(&*cs)().unwrap();
+            assert_eq!((min, max), (a_value.min(b_value), a_value.max(b_value)));
+        }
+
+        drop(cs);
+        owned_cs.pad_and_shrink();
+        let mut owned_cs = owned_cs.into_assembly::<Global>();
+        let worker = Worker::new_with_num_threads(8);
+        assert!(owned_cs.check_if_satisfied(&worker));
+    }
+
+    #[test]
+    fn test_bits() {
+        let mut owned_cs = create_test_cs();
+        owned_cs.enable_cost_accounting();
+        let cs = &mut owned_cs;
+
+        for (a_value, _) in test_values() {
+            let a = UInt256::allocate(cs, a_value);
+            let expected: Vec<_> = (0..256).map(|idx| a_value.bit(idx)).collect();
+
+            cs.push_namespace("to_le_bits");
+            let bits = a.to_le_bits(cs);
+            cs.pop_namespace();
+            cs.push_namespace("to_le_bits_using_table");
+            let bits_using_table = a.to_le_bits_using_table(cs);
+            cs.pop_namespace();
+
+            for bits in [&bits, &bits_using_table] {
+                let bits: Vec<_> = bits
+                    .iter()
+                    .map(|el| el.witness_hook(&*cs)().unwrap())
+                    .collect();
+                assert_eq!(bits, expected);
+            }
+
+            cs.push_namespace("from_le_bits");
+            let recomposed = UInt256::from_le_bits(cs, &bits_using_table);
+            cs.pop_namespace();
+            assert_eq!(recomposed.witness_hook(&*cs)().unwrap(), a_value);
+
+            let low = UInt160 {
+                inner: a.inner[..5].try_into().unwrap(),
+            };
+            let low_bits = low.to_le_bits(cs);
+            let recomposed = UInt160::from_le_bits(cs, &low_bits).to_u256(cs);
+            let low_mask = (U256::one() << 160) - 1;
+            assert_eq!(recomposed.witness_hook(&*cs)().unwrap(), a_value & low_mask);
+        }
+
+        let report = cs.cost_report().unwrap();
+        let num_values = test_values().len();
+        // the allocated values are range checked by the byte decomposition, so the table variant
+        // only takes the lookups to split the bytes
+        let bits = report.scope("to_le_bits").unwrap();
+        let bits_using_table = report.scope("to_le_bits_using_table").unwrap();
+        assert!(bits.lookups.is_empty());
+        assert!(bits
+            .general_purpose_rows
+            .contains_key(&short_type_name::<BooleanConstraintGate>()));
+        assert_eq!(
+            bits_using_table.lookups["Byte split at 1"],
+            num_values * 32 * 7
+        );
+        assert_eq!(bits_using_table.total_general_purpose_rows(), 0);
+        assert!(bits_using_table.variables > bits.variables);
+
+        drop(cs);
+        owned_cs.pad_and_shrink();
+        let mut owned_cs = owned_cs.into_assembly::<Global>();
+        let worker = Worker::new_with_num_threads(8);
+        assert!(owned_cs.check_if_satisfied(&worker));
+    }
+}
@@ -852,6 +852,37 @@ impl<F: SmallField> UInt32<F> {
         bytes
     }
 
+    #[must_use]
+    pub fn to_le_bits<CS: ConstraintSystem<F>>(self, cs: &mut CS) -> Vec<Boolean<F>> {
+        self.into_num().spread_into_bits::<CS, 32>(cs).to_vec()
+    }
+
+    /// Same as `to_le_bits`, but through 28 lookups into `ByteSplitTable<1>` instead of
+    /// the booleanity constraints and the recomposition in the general purpose columns. The byte
+    /// decomposition is reused if the value was already range checked by it
+    #[must_use]
+    pub fn to_le_bits_using_table<CS: ConstraintSystem<F>>(self, cs: &mut CS) -> Vec<Boolean<F>> {
+        self.to_le_bytes(cs)
+            .iter()
+            .flat_map(|el| el.to_le_bits_using_table(cs))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn from_le_bits<CS: ConstraintSystem<F>>(cs: &mut CS, bits: &[Boolean<F>]) -> Self {
+        assert_eq!(bits.len(), 32);
+
+        let terms: Vec<_> = bits
+            .iter()
+            .zip(F::SHIFTS.iter())
+            .map(|(bit, shift)| (bit.get_variable(), *shift))
+            .collect();
+        let result = Num::linear_combination(cs, &terms);
+
+        // booleans are enough for the range check
+        unsafe { Self::from_variable_unchecked(result.get_variable()) }
+    }
+
     #[must_use]
     pub fn div2<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> Self {
         let byte_split_id = cs
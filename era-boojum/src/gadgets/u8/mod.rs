@@ -565,6 +565,26 @@ impl<F: SmallField> UInt8<F> {
             _marker: std::marker::PhantomData,
         }
     }
+
+    /// Splits off one bit per lookup into `ByteSplitTable<1>`, so the 7 lookups replace
+    /// the booleanity constraints and the recomposition
+    #[must_use]
+    pub fn to_le_bits_using_table<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> [Boolean<F>; 8] {
+        let byte_split_id = cs
+            .get_table_id_for_marker::<ByteSplitTable<1>>()
+            .expect("table should exist");
+        let mut bits = [Variable::placeholder(); 8];
+        let mut rest = self.get_variable();
+        for dst in bits[..7].iter_mut() {
+            let [bit, high] = cs.perform_lookup::<1, 2>(byte_split_id, &[rest]);
+            *dst = bit;
+            rest = high;
+        }
+        // what is left of the byte after 7 shifts is a single bit
+        bits[7] = rest;
+
+        bits.map(|el| unsafe { Boolean::from_variable_unchecked(el) })
+    }
 }
 
 use crate::gadgets::traits::encodable::CircuitVarLengthEncodable;
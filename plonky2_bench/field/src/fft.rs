@@ -1,6 +1,7 @@
 use alloc::vec::Vec;
 use core::cmp::{max, min};
 
+use plonky2_util::transpose_util::transpose_in_place_square;
 use plonky2_util::{log2_strict, reverse_index_bits_in_place};
 use unroll::unroll_for_loops;
 
@@ -32,16 +33,44 @@ pub fn fft_root_table<F: Field>(n: usize) -> FftRootTable<F> {
     root_table
 }
 
+/// The algorithm used to compute an FFT.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum FftStrategy {
+    /// The radix-2 FFT of `fft_classic`.
+    #[default]
+    Classic,
+    /// The six-step FFT of `fft_six_step`, which works on rows of about `sqrt(n)` elements and so
+    /// stays in cache for large domains.
+    SixStep,
+}
+
+impl FftStrategy {
+    /// The strategy for an FFT of `2^lg_n` points, when domains of at least `2^six_step_min_lg_n`
+    /// points are transformed with the six-step FFT. The `ffts` benchmark of `plonky2` locates
+    /// the size from which it is faster on a given machine.
+    pub const fn for_lg_n(lg_n: usize, six_step_min_lg_n: Option<usize>) -> Self {
+        match six_step_min_lg_n {
+            Some(min_lg_n) if lg_n >= min_lg_n => Self::SixStep,
+            _ => Self::Classic,
+        }
+    }
+}
+
 #[inline]
 fn fft_dispatch<F: Field>(
     input: &mut [F],
     zero_factor: Option<usize>,
     root_table: Option<&FftRootTable<F>>,
+    strategy: FftStrategy,
 ) {
     let computed_root_table = root_table.is_none().then(|| fft_root_table(input.len()));
     let used_root_table = root_table.or(computed_root_table.as_ref()).unwrap();
+    let zero_factor = zero_factor.unwrap_or(0);
 
-    fft_classic(input, zero_factor.unwrap_or(0), used_root_table);
+    match strategy {
+        FftStrategy::Classic => fft_classic(input, zero_factor, used_root_table),
+        FftStrategy::SixStep => fft_six_step(input, zero_factor, used_root_table),
+    }
 }
 
 #[inline]
@@ -54,9 +83,18 @@ pub fn fft_with_options<F: Field>(
     poly: PolynomialCoeffs<F>,
     zero_factor: Option<usize>,
     root_table: Option<&FftRootTable<F>>,
+) -> PolynomialValues<F> {
+    fft_with_strategy(poly, zero_factor, root_table, FftStrategy::Classic)
+}
+
+pub fn fft_with_strategy<F: Field>(
+    poly: PolynomialCoeffs<F>,
+    zero_factor: Option<usize>,
+    root_table: Option<&FftRootTable<F>>,
+    strategy: FftStrategy,
 ) -> PolynomialValues<F> {
     let PolynomialCoeffs { coeffs: mut buffer } = poly;
-    fft_dispatch(&mut buffer, zero_factor, root_table);
+    fft_dispatch(&mut buffer, zero_factor, root_table, strategy);
     PolynomialValues::new(buffer)
 }
 
@@ -69,13 +107,22 @@ pub fn ifft_with_options<F: Field>(
     poly: PolynomialValues<F>,
     zero_factor: Option<usize>,
     root_table: Option<&FftRootTable<F>>,
+) -> PolynomialCoeffs<F> {
+    ifft_with_strategy(poly, zero_factor, root_table, FftStrategy::Classic)
+}
+
+pub fn ifft_with_strategy<F: Field>(
+    poly: PolynomialValues<F>,
+    zero_factor: Option<usize>,
+    root_table: Option<&FftRootTable<F>>,
+    strategy: FftStrategy,
 ) -> PolynomialCoeffs<F> {
     let n = poly.len();
     let lg_n = log2_strict(n);
     let n_inv = F::inverse_2exp(lg_n);
 
     let PolynomialValues { values: mut buffer } = poly;
-    fft_dispatch(&mut buffer, zero_factor, root_table);
+    fft_dispatch(&mut buffer, zero_factor, root_table, strategy);
 
     // We reverse all values except the first, and divide each by n.
     buffer[0] *= n_inv;
@@ -96,7 +143,7 @@ fn fft_classic_simd<P: PackedField>(
     values: &mut [P::Scalar],
     r: usize,
     lg_n: usize,
-    root_table: &FftRootTable<P::Scalar>,
+    root_table: &[Vec<P::Scalar>],
 ) {
     let lg_packed_width = log2_strict(P::WIDTH); // 0 when P is a scalar.
     let packed_values = P::pack_slice_mut(values);
@@ -162,7 +209,7 @@ fn fft_classic_simd<P: PackedField>(
 /// The parameter r signifies that the first 1/2^r of the entries of
 /// input may be non-zero, but the last 1 - 1/2^r entries are
 /// definitely zero.
-pub(crate) fn fft_classic<F: Field>(values: &mut [F], r: usize, root_table: &FftRootTable<F>) {
+pub(crate) fn fft_classic<F: Field>(values: &mut [F], r: usize, root_table: &[Vec<F>]) {
     reverse_index_bits_in_place(values);
 
     let n = values.len();
@@ -201,16 +248,115 @@ pub(crate) fn fft_classic<F: Field>(values: &mut [F], r: usize, root_table: &Fft
     }
 }
 
+/// Six-step FFT, also known as Bailey's FFT, with natural order input and output like
+/// `fft_classic`.
+///
+/// The `n = n_1 * n_2` values are seen as an `n_2 x n_1` matrix, whose columns are transformed,
+/// multiplied by twiddle factors, and whose rows are then transformed. With the blocked
+/// transposes in between, all the FFTs work on contiguous rows of about `sqrt(n)` elements, which
+/// fit in cache even when the whole domain doesn't.
+///
+/// The transposes need a square matrix, so for odd `lg_n` a first radix-2 round splits the
+/// values into the even and odd outputs, each of which has an even `lg_n`.
+///
+/// As for `fft_classic`, only the first 1/2^r of the entries of input may be non-zero.
+pub(crate) fn fft_six_step<F: Field>(values: &mut [F], r: usize, root_table: &[Vec<F>]) {
+    let n = values.len();
+    let lg_n = log2_strict(n);
+
+    if root_table.len() != lg_n {
+        panic!(
+            "Expected root table of length {}, but it was {}.",
+            lg_n,
+            root_table.len()
+        );
+    }
+
+    if lg_n.is_multiple_of(2) {
+        fft_six_step_square(values, r, root_table);
+        return;
+    }
+
+    // Decimation in frequency: with `u = values[j]`, `v = values[j + n / 2]`, the even outputs are
+    // the FFT of `u + v` and the odd outputs the FFT of `(u - v) * omega^j`. If `r > 0`, `v` is
+    // zero and both halves keep all but the first 1/2^(r - 1) of their entries zero.
+    let half_n = n / 2;
+    let omegas = &root_table[lg_n - 1];
+    let (evens, odds) = values.split_at_mut(half_n);
+    for j in 0..half_n {
+        let (u, v) = (evens[j], odds[j]);
+        evens[j] = u + v;
+        odds[j] = (u - v) * omegas[j];
+    }
+    let half_r = r.saturating_sub(1);
+    fft_six_step_square(evens, half_r, &root_table[..lg_n - 1]);
+    fft_six_step_square(odds, half_r, &root_table[..lg_n - 1]);
+
+    // Interleave the even and odd outputs. Going backwards, the even outputs are read before
+    // being overwritten.
+    let odds = odds.to_vec();
+    for k in (0..half_n).rev() {
+        values[2 * k] = values[k];
+        values[2 * k + 1] = odds[k];
+    }
+}
+
+/// The six-step FFT for even `lg_n`, i.e. for a square matrix of size `sqrt(n)`.
+fn fft_six_step_square<F: Field>(values: &mut [F], r: usize, root_table: &[Vec<F>]) {
+    let lg_n = root_table.len();
+    if lg_n == 0 {
+        return;
+    }
+    let lg_size = lg_n / 2;
+    let size = 1 << lg_size;
+    let row_root_table = &root_table[..lg_size];
+
+    // `omega^i` for `i < sqrt(n)`, from the powers of `omega` up to `n / 2`.
+    let omegas = &root_table[lg_n - 1];
+
+    // SAFETY: `values` holds a `size x size` matrix, stored with a stride of `size`.
+    let transpose = |values: &mut [F]| unsafe {
+        transpose_in_place_square(values, lg_size, lg_size, 0);
+    };
+
+    // Transform the columns, made contiguous by transposing, and multiply by the twiddle factors
+    // while they are in cache. The zero tail of the input is made of its last rows, so it is
+    // the tail of every column.
+    let column_r = r.min(lg_size);
+    transpose(values);
+    for (i, row) in values.chunks_exact_mut(size).enumerate() {
+        fft_classic(row, column_r, row_root_table);
+        // Multiply by `omega^(i * j)`, computing the powers of `omega^i` along the row rather than
+        // looking them up all over the root table.
+        let omega_i = omegas[i];
+        let mut twiddle = omega_i;
+        for x in row.iter_mut().skip(1) {
+            *x *= twiddle;
+            twiddle *= omega_i;
+        }
+    }
+    transpose(values);
+
+    // Transform the rows, and transpose to put the outputs in natural order.
+    for row in values.chunks_exact_mut(size) {
+        fft_classic(row, 0, row_root_table);
+    }
+    transpose(values);
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::vec::Vec;
 
     use plonky2_util::{log2_ceil, log2_strict};
 
-    use crate::fft::{fft, fft_with_options, ifft};
+    use crate::fft::{
+        fft, fft_classic, fft_root_table, fft_six_step, fft_with_options, fft_with_strategy, ifft,
+        ifft_with_strategy, FftStrategy,
+    };
     use crate::goldilocks_field::GoldilocksField;
     use crate::polynomial::{PolynomialCoeffs, PolynomialValues};
-    use crate::types::Field;
+    use crate::types::{Field, Sample};
 
     #[test]
     fn fft_and_ifft() {
@@ -248,6 +394,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn fft_six_step_matches_classic() {
+        type F = GoldilocksField;
+
+        for lg_n in 0..12 {
+            let n = 1 << lg_n;
+            let root_table = fft_root_table(n);
+            for r in 0..=lg_n {
+                // Only the first 1/2^r of the values are non-zero.
+                let mut values = F::rand_vec(n >> r);
+                values.resize(n, F::ZERO);
+
+                let mut classic = values.clone();
+                fft_classic(&mut classic, r, &root_table);
+                let mut six_step = values;
+                fft_six_step(&mut six_step, r, &root_table);
+                assert_eq!(classic, six_step, "lg_n = {}, r = {}", lg_n, r);
+            }
+        }
+    }
+
+    #[test]
+    fn fft_and_ifft_six_step() {
+        type F = GoldilocksField;
+        let lg_n = 9;
+        let coeffs = PolynomialCoeffs::new(F::rand_vec(1 << lg_n));
+
+        let values = fft_with_strategy(coeffs.clone(), None, None, FftStrategy::SixStep);
+        assert_eq!(values, fft(coeffs.clone()));
+        let interpolated = ifft_with_strategy(values, None, None, FftStrategy::SixStep);
+        assert_eq!(interpolated, coeffs);
+
+        for r in 0..4 {
+            let zero_tail = coeffs.lde(r);
+            assert_eq!(
+                fft_with_strategy(zero_tail.clone(), Some(r), None, FftStrategy::SixStep),
+                fft(zero_tail)
+            );
+        }
+    }
+
+    #[test]
+    fn fft_strategy_for_lg_n() {
+        assert_eq!(FftStrategy::for_lg_n(20, None), FftStrategy::Classic);
+        assert_eq!(FftStrategy::for_lg_n(19, Some(20)), FftStrategy::Classic);
+        assert_eq!(FftStrategy::for_lg_n(20, Some(20)), FftStrategy::SixStep);
+    }
+
     fn evaluate_naive<F: Field>(coefficients: &PolynomialCoeffs<F>) -> PolynomialValues<F> {
         let degree = coefficients.len();
         let degree_padded = 1 << log2_ceil(degree);
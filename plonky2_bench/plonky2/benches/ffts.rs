@@ -0,0 +1,35 @@
+mod allocator;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use plonky2::field::fft::{fft_with_strategy, FftStrategy};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::polynomial::PolynomialCoeffs;
+use plonky2::field::types::Field;
+use tynm::type_name;
+
+/// Compares the classic and six-step FFTs, to locate the size from which the six-step FFT is
+/// faster, i.e. the best `six_step_min_lg_n` for `FftStrategy::for_lg_n`.
+pub(crate) fn bench_ffts<F: Field>(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("fft<{}>", type_name::<F>()));
+    group.sample_size(10);
+
+    for size_log in 14..=24 {
+        let size = 1 << size_log;
+        let coeffs = PolynomialCoeffs::new(F::rand_vec(size));
+        for (name, strategy) in [
+            ("classic", FftStrategy::Classic),
+            ("six-step", FftStrategy::SixStep),
+        ] {
+            group.bench_with_input(BenchmarkId::new(name, size), &size, |b, _| {
+                b.iter(|| fft_with_strategy(coeffs.clone(), None, None, strategy));
+            });
+        }
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    bench_ffts::<GoldilocksField>(c);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -32,8 +32,27 @@ pub(crate) fn bench_poseidon<F: Poseidon>(c: &mut Criterion) {
     );
 }
 
+pub(crate) fn bench_poseidon_x2<F: Poseidon>(c: &mut Criterion) {
+    c.bench_function(
+        &format!("poseidon_x2<{}, {SPONGE_WIDTH}>", type_name::<F>()),
+        |b| {
+            b.iter_batched(
+                || {
+                    [
+                        F::rand_array::<SPONGE_WIDTH>(),
+                        F::rand_array::<SPONGE_WIDTH>(),
+                    ]
+                },
+                |states| F::poseidon_x2(states),
+                BatchSize::SmallInput,
+            )
+        },
+    );
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     bench_poseidon::<GoldilocksField>(c);
+    bench_poseidon_x2::<GoldilocksField>(c);
     bench_keccak::<GoldilocksField>(c);
 }
 
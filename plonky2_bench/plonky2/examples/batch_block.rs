@@ -0,0 +1,109 @@
+use std::time::{Duration, Instant};
+
+use bench_cli::BenchOptions;
+use block_circuits::{C, D, F};
+use jemallocator::Jemalloc;
+use plonky2::iop::witness::PartialWitness;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{ProverCircuitData, VerifierCircuitData};
+use plonky2::plonk::prover::{prove, prove_batch};
+use plonky2::plonk::verifier::verify_batch;
+use plonky2::util::timing::TimingTree;
+use structopt::StructOpt;
+
+mod bench_cli;
+mod block_circuits;
+
+#[global_allocator]
+static GLOBAL: Jemalloc = Jemalloc;
+
+type AddCircuit = fn(&mut CircuitBuilder<F, D>, &mut PartialWitness<F>);
+
+/// Proves the pre_block, tx_loop and verify_block circuits once with a proof each, and once with
+/// a single batch proof, and compares the time and size of both.
+fn main() {
+    // Parse command line arguments, see `--help` for details.
+    let options = BenchOptions::from_args();
+    assert!(
+        !options.sweep,
+        "Sweeps are run by the examples of each circuit"
+    );
+    let config = options.config();
+
+    let circuits: [(&str, AddCircuit); 3] = [
+        ("pre_block", block_circuits::pre_block),
+        ("tx_loop", block_circuits::tx_loop),
+        ("verify_block", block_circuits::verify_block),
+    ];
+
+    let mut prover_data: Vec<ProverCircuitData<F, C, D>> = Vec::new();
+    let mut verifier_data: Vec<VerifierCircuitData<F, C, D>> = Vec::new();
+    let mut witnesses = Vec::new();
+    for (name, add_circuit) in circuits {
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        add_circuit(&mut builder, &mut pw);
+        let start = Instant::now();
+        let data = builder.build::<C>();
+        println!(
+            "Built {} (degree 2^{}) in: {:?}",
+            name,
+            data.common.degree_bits(),
+            start.elapsed()
+        );
+        verifier_data.push(data.verifier_data());
+        prover_data.push(data.prover_data());
+        witnesses.push(pw);
+    }
+
+    // One proof per circuit
+    let mut separate_prove_time = Duration::ZERO;
+    let mut separate_verify_time = Duration::ZERO;
+    let mut separate_size = 0;
+    for ((prover_data, verifier_data), pw) in prover_data.iter().zip(&verifier_data).zip(&witnesses)
+    {
+        let start = Instant::now();
+        let proof = prove(
+            &prover_data.prover_only,
+            &prover_data.common,
+            pw.clone(),
+            &mut TimingTree::default(),
+        )
+        .unwrap();
+        separate_prove_time += start.elapsed();
+        separate_size += proof.to_bytes().len();
+
+        let start = Instant::now();
+        verifier_data.verify(proof).unwrap();
+        separate_verify_time += start.elapsed();
+    }
+
+    // One proof for all circuits
+    let start = Instant::now();
+    let proof = prove_batch(
+        &prover_data.iter().collect::<Vec<_>>(),
+        witnesses,
+        &mut TimingTree::default(),
+    )
+    .unwrap();
+    let batch_prove_time = start.elapsed();
+    let batch_size = proof.to_bytes().len();
+
+    let start = Instant::now();
+    verify_batch(proof, &verifier_data.iter().collect::<Vec<_>>()).unwrap();
+    let batch_verify_time = start.elapsed();
+
+    println!();
+    println!(
+        "{:<10} {:>12} {:>12} {:>12}",
+        "", "prove", "verify", "bytes"
+    );
+    println!(
+        "{:<10} {:>12.2?} {:>12.2?} {:>12}",
+        "separate", separate_prove_time, separate_verify_time, separate_size
+    );
+    println!(
+        "{:<10} {:>12.2?} {:>12.2?} {:>12}",
+        "batch", batch_prove_time, batch_verify_time, batch_size
+    );
+}
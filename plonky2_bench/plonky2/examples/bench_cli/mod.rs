@@ -0,0 +1,464 @@
+//! Command line options shared by the block examples to choose the circuit configuration, either
+//! from a named preset or field by field, and a sweep mode which proves the same circuit under
+//! several configurations to compare them.
+#![allow(dead_code)]
+
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use std::{fmt, fs};
+
+use anyhow::{anyhow, ensure, Result};
+use plonky2::field::extension::Extendable;
+use plonky2::fri::reduction_strategies::FriReductionStrategy;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::witness::PartialWitness;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{CircuitConfig, ProverCircuitData, VerifierCircuitData};
+use plonky2::plonk::config::GenericConfig;
+use plonky2::plonk::prover::prove;
+use plonky2::util::log2_ceil;
+use plonky2::util::serialization::{GateSerializer, WitnessGeneratorSerializer};
+use plonky2::util::timing::TimingTree;
+use structopt::StructOpt;
+
+/// A named starting point for the circuit configuration.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Preset {
+    /// `CircuitConfig::standard_recursion_config`, which the block examples have always used.
+    StandardRecursion,
+    /// `CircuitConfig::standard_recursion_zk_config`.
+    StandardRecursionZk,
+    /// More proof of work for fewer queries, so less Merkle openings to prove. The rate stays at
+    /// 1/8, the lowest the degree 7 of `PoseidonGate` allows, since the quotient degree factor can
+    /// be at most the inverse rate.
+    FastProve,
+    /// A rate of 1/16 and more proof of work, for fewer queries at the cost of slower commitments.
+    SmallProof,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [
+        Preset::StandardRecursion,
+        Preset::StandardRecursionZk,
+        Preset::FastProve,
+        Preset::SmallProof,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Preset::StandardRecursion => "standard-recursion",
+            Preset::StandardRecursionZk => "standard-recursion-zk",
+            Preset::FastProve => "fast-prove",
+            Preset::SmallProof => "small-proof",
+        }
+    }
+
+    pub fn config(self) -> CircuitConfig {
+        let standard = CircuitConfig::standard_recursion_config();
+        match self {
+            Preset::StandardRecursion => standard,
+            Preset::StandardRecursionZk => CircuitConfig::standard_recursion_zk_config(),
+            Preset::FastProve => {
+                let mut config = standard;
+                config.max_quotient_degree_factor = 8;
+                config.fri_config.rate_bits = 3;
+                config.fri_config.proof_of_work_bits = 20;
+                config.fri_config.num_query_rounds = 27;
+                config
+            }
+            Preset::SmallProof => {
+                let mut config = standard;
+                config.fri_config.rate_bits = 4;
+                config.fri_config.proof_of_work_bits = 20;
+                config.fri_config.num_query_rounds = 20;
+                config
+            }
+        }
+    }
+}
+
+impl FromStr for Preset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Preset::ALL
+            .into_iter()
+            .find(|preset| preset.name() == s.replace('_', "-"))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown preset {}, expected one of: {}",
+                    s,
+                    Preset::ALL.map(Preset::name).join(", ")
+                )
+            })
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A [`FriReductionStrategy`] parsed from `constant:<arity_bits>:<final_poly_bits>`,
+/// `fixed:<arity_bits>:...` or `min-size[:<max_arity_bits>]`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReductionStrategy(pub FriReductionStrategy);
+
+impl FromStr for ReductionStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.split(':');
+        let kind = parts.next().unwrap_or_default();
+        let bits = parts.map(usize::from_str).collect::<Result<Vec<_>, _>>()?;
+        let strategy = match (kind, bits.as_slice()) {
+            ("constant", &[arity_bits, final_poly_bits]) => {
+                FriReductionStrategy::ConstantArityBits(arity_bits, final_poly_bits)
+            }
+            ("fixed", _) if !bits.is_empty() => FriReductionStrategy::Fixed(bits),
+            ("min-size", &[]) => FriReductionStrategy::MinSize(None),
+            ("min-size", &[max_arity_bits]) => FriReductionStrategy::MinSize(Some(max_arity_bits)),
+            _ => return Err(anyhow!("Invalid reduction strategy {}", s)),
+        };
+        Ok(Self(strategy))
+    }
+}
+
+/// Options of the circuit configuration. Each field may be given as a comma-separated list of
+/// values with `--sweep`.
+#[derive(Clone, StructOpt, Debug)]
+pub struct BenchOptions {
+    /// Presets to start from: standard-recursion, standard-recursion-zk, fast-prove or
+    /// small-proof. The fields below override those of the presets.
+    #[structopt(long, default_value = "standard-recursion", use_delimiter = true)]
+    pub preset: Vec<Preset>,
+
+    /// Number of wires per row.
+    #[structopt(long, use_delimiter = true)]
+    pub num_wires: Vec<usize>,
+
+    /// Number of wires taking part in copy constraints.
+    #[structopt(long, use_delimiter = true)]
+    pub num_routed_wires: Vec<usize>,
+
+    /// Number of constant columns.
+    #[structopt(long, use_delimiter = true)]
+    pub num_constants: Vec<usize>,
+
+    /// Whether to use `ArithmeticGate` rather than its extension-field counterpart for base field
+    /// arithmetic.
+    #[structopt(long, use_delimiter = true)]
+    pub use_base_arithmetic_gate: Vec<bool>,
+
+    /// Target security level, which the FRI parameters must reach.
+    #[structopt(long, use_delimiter = true)]
+    pub security_bits: Vec<usize>,
+
+    /// Number of repetitions of the permutation and lookup arguments.
+    #[structopt(long, use_delimiter = true)]
+    pub num_challenges: Vec<usize>,
+
+    /// Whether to blind the witness polynomials.
+    #[structopt(long, use_delimiter = true)]
+    pub zero_knowledge: Vec<bool>,
+
+    /// Maximum degree of the constraints, relative to the degree of the circuit.
+    #[structopt(long, use_delimiter = true)]
+    pub max_quotient_degree_factor: Vec<usize>,
+
+    /// Log2 of the inverse rate of the Reed-Solomon code.
+    #[structopt(long, use_delimiter = true)]
+    pub rate_bits: Vec<usize>,
+
+    /// Height of the Merkle caps sent instead of roots.
+    #[structopt(long, use_delimiter = true)]
+    pub cap_height: Vec<usize>,
+
+    /// Number of leading zeros of the grinding challenge.
+    #[structopt(long, use_delimiter = true)]
+    pub proof_of_work_bits: Vec<u32>,
+
+    /// `constant:<arity_bits>:<final_poly_bits>`, `fixed:<arity_bits>:...` or
+    /// `min-size[:<max_arity_bits>]`.
+    #[structopt(long, use_delimiter = true)]
+    pub reduction_strategy: Vec<ReductionStrategy>,
+
+    /// Number of FRI queries.
+    #[structopt(long, use_delimiter = true)]
+    pub num_query_rounds: Vec<usize>,
+
+    /// Prove the circuit under every combination of the given presets and field values, and print
+    /// a table of the results.
+    #[structopt(long)]
+    pub sweep: bool,
+}
+
+/// Returns a copy of each configuration for each of `values`, set with `set`. Returns the
+/// configurations unchanged if there are no values.
+fn expand<T: Clone>(
+    configs: Vec<(Preset, CircuitConfig)>,
+    values: &[T],
+    set: impl Fn(&mut CircuitConfig, T),
+) -> Vec<(Preset, CircuitConfig)> {
+    if values.is_empty() {
+        return configs;
+    }
+    let set = &set;
+    configs
+        .into_iter()
+        .flat_map(|(preset, config)| {
+            values.iter().map(move |value| {
+                let mut config = config.clone();
+                set(&mut config, value.clone());
+                (preset, config)
+            })
+        })
+        .collect()
+}
+
+impl BenchOptions {
+    /// Returns every combination of the given presets and field values.
+    pub fn configs(&self) -> Vec<(Preset, CircuitConfig)> {
+        let mut configs = self
+            .preset
+            .iter()
+            .map(|&preset| (preset, preset.config()))
+            .collect();
+        configs = expand(configs, &self.num_wires, |c, v| c.num_wires = v);
+        configs = expand(configs, &self.num_routed_wires, |c, v| {
+            c.num_routed_wires = v
+        });
+        configs = expand(configs, &self.num_constants, |c, v| c.num_constants = v);
+        configs = expand(configs, &self.use_base_arithmetic_gate, |c, v| {
+            c.use_base_arithmetic_gate = v
+        });
+        configs = expand(configs, &self.security_bits, |c, v| c.security_bits = v);
+        configs = expand(configs, &self.num_challenges, |c, v| c.num_challenges = v);
+        configs = expand(configs, &self.zero_knowledge, |c, v| c.zero_knowledge = v);
+        configs = expand(configs, &self.max_quotient_degree_factor, |c, v| {
+            c.max_quotient_degree_factor = v
+        });
+        configs = expand(configs, &self.rate_bits, |c, v| c.fri_config.rate_bits = v);
+        configs = expand(configs, &self.cap_height, |c, v| {
+            c.fri_config.cap_height = v
+        });
+        configs = expand(configs, &self.proof_of_work_bits, |c, v| {
+            c.fri_config.proof_of_work_bits = v
+        });
+        configs = expand(configs, &self.reduction_strategy, |c, v| {
+            c.fri_config.reduction_strategy = v.0
+        });
+        configs = expand(configs, &self.num_query_rounds, |c, v| {
+            c.fri_config.num_query_rounds = v
+        });
+        configs
+    }
+
+    /// Returns the configuration to use outside of sweep mode.
+    pub fn config(&self) -> CircuitConfig {
+        let mut configs = self.configs();
+        assert_eq!(
+            configs.len(),
+            1,
+            "Several configurations were given, which is only supported with --sweep"
+        );
+        let config = configs.remove(0).1;
+        check_config(&config).expect("Invalid configuration");
+        config
+    }
+
+    /// Builds and proves the circuit added by `add_circuit` under each configuration, and prints
+    /// a table of the degree, proving and verification times and proof size of each.
+    pub fn sweep<F, C, const D: usize>(
+        &self,
+        add_circuit: impl Fn(&mut CircuitBuilder<F, D>, &mut PartialWitness<F>),
+    ) -> Result<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        println!(
+            "{:<22} {:>5} {:>6} {:>4} {:>7} {:>3} {:>16} {:>5} {:>6} {:>12} {:>12} {:>10}",
+            "preset",
+            "wires",
+            "routed",
+            "rate",
+            "queries",
+            "pow",
+            "reduction",
+            "zk",
+            "degree",
+            "prove",
+            "verify",
+            "bytes"
+        );
+        for (preset, config) in self.configs() {
+            let fri_config = &config.fri_config;
+            print!(
+                "{:<22} {:>5} {:>6} {:>4} {:>7} {:>3} ",
+                preset.name(),
+                config.num_wires,
+                config.num_routed_wires,
+                fri_config.rate_bits,
+                fri_config.num_query_rounds,
+                fri_config.proof_of_work_bits,
+            );
+            // Report the failures of a configuration in its row rather than stopping the sweep.
+            // The panic hook still prints where a panic came from.
+            let result = check_config(&config).and_then(|()| {
+                panic::catch_unwind(AssertUnwindSafe(|| {
+                    prove_and_verify::<F, C, D>(&config, &add_circuit)
+                }))
+                .unwrap_or_else(|payload| {
+                    let message = payload
+                        .downcast_ref::<String>()
+                        .cloned()
+                        .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+                        .unwrap_or_default();
+                    Err(anyhow!(message))
+                })
+            });
+            match result {
+                Ok(row) => println!(
+                    "{:>16} {:>5} {:>6} {:>12.2?} {:>12.2?} {:>10}",
+                    format!("{:?}", row.reduction_arity_bits),
+                    config.zero_knowledge,
+                    row.degree_bits,
+                    row.prove_time,
+                    row.verify_time,
+                    row.proof_size
+                ),
+                Err(e) => println!("failed: {}", e),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The results of proving a circuit under one configuration of a sweep.
+struct SweepRow {
+    reduction_arity_bits: Vec<usize>,
+    degree_bits: usize,
+    prove_time: Duration,
+    verify_time: Duration,
+    proof_size: usize,
+}
+
+fn prove_and_verify<F, C, const D: usize>(
+    config: &CircuitConfig,
+    add_circuit: &impl Fn(&mut CircuitBuilder<F, D>, &mut PartialWitness<F>),
+) -> Result<SweepRow>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+    let mut pw = PartialWitness::new();
+    add_circuit(&mut builder, &mut pw);
+    let data = builder.build::<C>();
+
+    let start = Instant::now();
+    let proof = prove(
+        &data.prover_only,
+        &data.common,
+        pw,
+        &mut TimingTree::default(),
+    )?;
+    let prove_time = start.elapsed();
+    let proof_size = proof.to_bytes().len();
+
+    let start = Instant::now();
+    data.verify(proof)?;
+    let verify_time = start.elapsed();
+
+    Ok(SweepRow {
+        reduction_arity_bits: data.common.fri_params.reduction_arity_bits.clone(),
+        degree_bits: data.common.degree_bits(),
+        prove_time,
+        verify_time,
+        proof_size,
+    })
+}
+
+/// Checks the constraints between fields of a configuration which the prover relies on.
+fn check_config(config: &CircuitConfig) -> Result<()> {
+    ensure!(
+        config.fri_config.rate_bits >= log2_ceil(config.max_quotient_degree_factor),
+        "the quotient is evaluated on the LDE, so rate_bits must be at least \
+         log2(max_quotient_degree_factor)"
+    );
+    Ok(())
+}
+
+/// Logs at the debug level for plonky2 unless `RUST_LOG` says otherwise, so that printing a
+/// `TimingTree` shows the phases of the prover and not only its total.
+pub fn init_logger() {
+    let _ = env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or("plonky2=debug"),
+    )
+    .try_init();
+}
+
+/// Returns the name of the cached circuit data of `example`, keyed by the structure of the circuit
+/// laid out in `builder`, so that a cache is never used for another circuit or configuration.
+pub fn cache_name<F: RichField + Extendable<D>, const D: usize>(
+    example: &str,
+    builder: &CircuitBuilder<F, D>,
+) -> String {
+    let digest = builder.structure_digest();
+    let key: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}_{}", example, key)
+}
+
+/// Loads the prover and verifier data of the circuit laid out in `builder` from the cache of a
+/// previous run, or builds the circuit and caches its data.
+pub fn load_or_build<F, C, const D: usize>(
+    example: &str,
+    builder: CircuitBuilder<F, D>,
+    gate_serializer: &dyn GateSerializer<F, D>,
+    generator_serializer: &dyn WitnessGeneratorSerializer<F, D>,
+) -> (ProverCircuitData<F, C, D>, VerifierCircuitData<F, C, D>)
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let cache_name = cache_name(example, &builder);
+    let prover_path = format!("{cache_name}_prover.bin");
+    let verifier_path = format!("{cache_name}_verifier.bin");
+    let start = Instant::now();
+    if let (Ok(prover_bytes), Ok(verifier_bytes)) =
+        (fs::read(&prover_path), fs::read(&verifier_path))
+    {
+        let prover_data = ProverCircuitData::<F, C, D>::from_bytes(
+            &prover_bytes,
+            gate_serializer,
+            generator_serializer,
+        )
+        .expect("Unable to deserialize prover data");
+        let verifier_data =
+            VerifierCircuitData::<F, C, D>::from_bytes(verifier_bytes, gate_serializer)
+                .expect("Unable to deserialize verifier data");
+        // Both files are written together, so they only differ if one of them was replaced.
+        if prover_data.prover_only.circuit_digest == verifier_data.verifier_only.circuit_digest {
+            println!("Loaded circuit in: {:?}", start.elapsed());
+            return (prover_data, verifier_data);
+        }
+        println!("Cached prover and verifier data are for different circuits, rebuilding");
+    }
+
+    let data = builder.build::<C>();
+    println!("Built circuit in: {:?}", start.elapsed());
+    let verifier_data = data.verifier_data();
+    let prover_data = data.prover_data();
+    let prover_bytes = prover_data
+        .to_bytes(gate_serializer, generator_serializer)
+        .expect("Unable to serialize prover data");
+    fs::write(prover_path, prover_bytes).expect("Unable to write prover data");
+    let verifier_bytes = verifier_data
+        .to_bytes(gate_serializer)
+        .expect("Unable to serialize verifier data");
+    fs::write(verifier_path, verifier_bytes).expect("Unable to write verifier data");
+    (prover_data, verifier_data)
+}
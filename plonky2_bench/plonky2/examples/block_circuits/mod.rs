@@ -0,0 +1,418 @@
+//! The circuits of the block examples, shared by the examples which prove them on their own and
+//! the one which proves them in a batch.
+#![allow(dead_code)]
+
+use log::Level;
+use num::{BigUint, Num};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::secp256k1_scalar::Secp256K1Scalar;
+use plonky2::field::types::{Field, PrimeField64, Sample};
+use plonky2::hash::hash_types::HashOutTarget;
+use plonky2::hash::poseidon::PoseidonHash;
+use plonky2::hash::sha256::circuit::{array_to_bits, make_circuits};
+use plonky2::hash::sparse_merkle_tree::SparseMerkleTree;
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::nonnative::biguint::biguint::{BigUintTarget, CircuitBuilderBiguint, WitnessBigUint};
+use plonky2::nonnative::biguint::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+use plonky2::nonnative::biguint::split_nonnative::CircuitBuilderSplit;
+use plonky2::nonnative::curve::ecdsa::{sign_message, ECDSASecretKey};
+use plonky2::nonnative::curve::gadgets::ecdsa::{CircuitBuilderEcdsa, WitnessEcdsa};
+use plonky2::nonnative::curve::secp256k1::Secp256K1;
+use plonky2::nonnative::u32::gadgets::arithmetic_u32::U32Target;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::config::{GenericConfig, Hasher, PoseidonGoldilocksConfig};
+use sha2::{Digest, Sha256};
+
+pub const D: usize = 2;
+pub type C = PoseidonGoldilocksConfig;
+pub type F = GoldilocksField;
+
+/// Adds the checks done before processing the transactions of a block.
+pub fn pre_block(builder: &mut CircuitBuilder<F, D>, pw: &mut PartialWitness<F>) {
+    let x_value =
+        BigUint::from_str_radix("91343852333181432387730302044767688728495783935", 10).unwrap();
+    let y_value = BigUint::from_str_radix("1152921504606846975", 10).unwrap();
+
+    // Fill targets & connect expected values
+    let x = builder.add_virtual_biguint_target(x_value.to_u32_digits().len());
+    let y = builder.add_virtual_biguint_target(y_value.to_u32_digits().len());
+
+    pw.set_biguint_target(&x, &x_value);
+    pw.set_biguint_target(&y, &y_value);
+
+    // Comparison
+    builder.push_context(Level::Info, "Comparison");
+    for _ in 0..1280 {
+        let lte = builder.cmp_biguint(&y, &x);
+        let expected_lte = builder.constant_bool(y_value <= x_value);
+        builder.connect(lte.target, expected_lte.target);
+    }
+    builder.pop_context();
+
+    // Asserted Comparison
+    builder.push_context(Level::Info, "Asserted Comparison");
+    for _ in 0..1024 {
+        let lte = builder.cmp_biguint(&y, &x);
+        let expected_lte = builder.constant_bool(y_value <= x_value);
+        builder.connect(lte.target, expected_lte.target);
+    }
+    builder.pop_context();
+
+    // Integer division
+    builder.push_context(Level::Info, "Integer division");
+    for _ in 0..256 {
+        let div_result = builder.div_biguint(&x, &y);
+        let expected_div = builder.constant_biguint(&(&x_value / &y_value));
+        builder.connect_biguint(&div_result, &expected_div);
+    }
+    builder.pop_context();
+
+    let constant_2_to_160_value =
+        BigUint::from_str_radix("91343852333181432387730302044767688728495783935", 10).unwrap();
+    let constant_2_to_160 = builder.constant_biguint(&constant_2_to_160_value); // 2^160 - 1
+
+    let _true = builder.constant_bool(x_value <= constant_2_to_160_value);
+
+    // IsNegative, which is <160 bits in our case
+    builder.push_context(Level::Info, "IsNegative");
+    for _ in 0..256 {
+        let lte = builder.cmp_biguint(&constant_2_to_160, &x);
+        builder.connect(lte.target, _true.target);
+    }
+    builder.pop_context();
+}
+
+/// The targets of the balance transfer of a transaction.
+struct Transfer {
+    sender: Target,
+    receiver: Target,
+    amount: Target,
+    new_state_root: HashOutTarget,
+}
+
+/// Adds the processing of the transactions of a block.
+pub fn tx_loop(builder: &mut CircuitBuilder<F, D>, pw: &mut PartialWitness<F>) {
+    add_tx_loop(builder, pw);
+}
+
+/// Adds the processing of the transactions of a block, and returns the state root after them.
+pub fn tx_loop_with_new_state_root(
+    builder: &mut CircuitBuilder<F, D>,
+    pw: &mut PartialWitness<F>,
+) -> HashOutTarget {
+    add_tx_loop(builder, pw).new_state_root
+}
+
+/// Adds the processing of the transactions of a block, along with an ECDSA signature check over
+/// the transfer of each transaction. Needs at least the wires of
+/// `CircuitConfig::standard_ecc_config`.
+pub fn tx_loop_ecdsa(builder: &mut CircuitBuilder<F, D>, pw: &mut PartialWitness<F>) {
+    let transfer = add_tx_loop(builder, pw);
+
+    builder.push_context(Level::Info, "Signature");
+    let sender_key = ECDSASecretKey::<Secp256K1>(Secp256K1Scalar::rand());
+    let tx_hash = PoseidonHash::hash_no_pad(&[F::ONE, F::TWO, F::from_canonical_u64(10)]);
+    let msg_value = tx_hash
+        .elements
+        .iter()
+        .rev()
+        .fold(BigUint::from(0u64), |acc, h| {
+            (acc << 64) + h.to_canonical_u64()
+        });
+    let msg_value = msg_value % Secp256K1Scalar::order();
+    let signature = sign_message(
+        Secp256K1Scalar::from_noncanonical_biguint(msg_value),
+        sender_key,
+    );
+
+    let tx_hash_target = builder.hash_n_to_hash_no_pad::<PoseidonHash>(vec![
+        transfer.sender,
+        transfer.receiver,
+        transfer.amount,
+    ]);
+    let msg_limbs = tx_hash_target
+        .elements
+        .iter()
+        .flat_map(|&h| {
+            let bits = builder.split_le(h, 64);
+            [
+                U32Target(builder.le_sum(bits[..32].iter())),
+                U32Target(builder.le_sum(bits[32..].iter())),
+            ]
+        })
+        .collect();
+    let msg = builder.reduce::<Secp256K1Scalar>(&BigUintTarget { limbs: msg_limbs });
+    let signature_target = builder.add_virtual_ecdsa_signature_target::<Secp256K1>();
+    let public_key = builder.add_virtual_ecdsa_public_key_target::<Secp256K1>();
+    builder.verify_ecdsa_message(&msg, &signature_target, &public_key);
+    pw.set_ecdsa_signature_target(&signature_target, &signature);
+    pw.set_ecdsa_public_key_target(&public_key, &sender_key.to_public());
+    builder.pop_context();
+}
+
+fn add_tx_loop(builder: &mut CircuitBuilder<F, D>, pw: &mut PartialWitness<F>) -> Transfer {
+    let negative_example_value =
+        BigUint::from_str_radix("91343852333181432387730302044767688728495783945", 10).unwrap(); // 2^160+16
+    let upper_limit_value =
+        BigUint::from_str_radix("91343852333181432387730302044767688728495783935", 10).unwrap(); // 2^160
+    let x_value =
+        BigUint::from_str_radix("91343852333181432387730302044767688728495783934", 10).unwrap(); // 2^160-1
+    let y_value = BigUint::from_str_radix("1152921504606846975", 10).unwrap();
+
+    // Fill targets & connect expected values
+    let x = builder.add_virtual_biguint_target(x_value.to_u32_digits().len());
+    let y = builder.add_virtual_biguint_target(y_value.to_u32_digits().len());
+    let upper_limit = builder.add_virtual_biguint_target(upper_limit_value.to_u32_digits().len());
+    let negative_example =
+        builder.add_virtual_biguint_target(negative_example_value.to_u32_digits().len());
+
+    pw.set_biguint_target(&x, &x_value);
+    pw.set_biguint_target(&y, &y_value);
+    pw.set_biguint_target(&upper_limit, &upper_limit_value);
+    pw.set_biguint_target(&negative_example, &negative_example_value);
+
+    // Poseidon (Instead of MiMC)
+    builder.push_context(Level::Info, "Poseidon");
+    let x_limbs: Vec<F> = x_value
+        .to_u32_digits()
+        .iter()
+        .map(|u32_val| F::from_canonical_u32(*u32_val))
+        .collect();
+    let expected_hash_out = PoseidonHash::hash_no_pad(x_limbs.as_slice());
+    for _ in 0..5648 {
+        let public_inputs_hash = builder
+            .hash_n_to_hash_no_pad::<<C as GenericConfig<D>>::InnerHasher>(
+                x.limbs.iter().map(|u32_target| u32_target.0).collect(),
+            );
+        pw.set_hash_target(public_inputs_hash, expected_hash_out);
+    }
+    builder.pop_context();
+
+    // To binary
+    builder.push_context(Level::Info, "To binary");
+    let nonnative_x = builder.biguint_to_nonnative::<F>(&x);
+    let split = builder.split_nonnative_to_1_bit_limbs(&nonnative_x);
+    let combined: NonNativeTarget<F> = builder.recombine_nonnative_bits(&split);
+    builder.connect_nonnative(&nonnative_x, &combined);
+    for _ in 0..64 {
+        let nonnative_x = builder.biguint_to_nonnative::<F>(&x);
+        let split = builder.split_nonnative_to_1_bit_limbs(&nonnative_x);
+        let combined = builder.recombine_nonnative_bits(&split);
+        builder.connect_nonnative(&nonnative_x, &combined);
+    }
+    builder.pop_context();
+
+    // From binary
+    builder.push_context(Level::Info, "From binary");
+    for _ in 0..(236 - 65) {
+        let combined = builder.recombine_nonnative_bits(&split);
+        builder.connect_nonnative(&nonnative_x, &combined);
+    }
+    builder.pop_context();
+
+    // Comparison
+    builder.push_context(Level::Info, "Comparison");
+    for _ in 0..49 {
+        let lte = builder.cmp_biguint(&y, &x);
+        let expected_lte = builder.constant_bool(y_value <= x_value);
+        builder.connect(lte.target, expected_lte.target);
+    }
+    builder.pop_context();
+
+    // Asserted Comparison
+    builder.push_context(Level::Info, "Asserted Comparison");
+    for _ in 0..19 {
+        let lte = builder.cmp_biguint(&y, &x);
+        let expected_lte = builder.constant_bool(y_value <= x_value);
+        builder.connect(lte.target, expected_lte.target);
+    }
+    builder.pop_context();
+
+    // Integer division
+    builder.push_context(Level::Info, "Integer division");
+    for _ in 0..13 {
+        let div_result = builder.div_biguint(&x, &y);
+        let expected_div = builder.constant_biguint(&(&x_value / &y_value));
+        builder.connect_biguint(&div_result, &expected_div);
+    }
+    builder.pop_context();
+
+    let _true = builder.constant_bool(x_value <= upper_limit_value);
+
+    // IsNegative, which is <160 bits in our case
+    builder.push_context(Level::Info, "IsNegative");
+    for _ in 0..6 {
+        let lte = builder.cmp_biguint(&x, &upper_limit);
+        builder.connect(lte.target, _true.target);
+    }
+    builder.pop_context();
+
+    // Abs
+    builder.push_context(Level::Info, "Abs");
+    for _ in 0..4 {
+        let lte = builder.cmp_biguint(&upper_limit, &negative_example);
+        builder.connect(lte.target, _true.target);
+        builder.sub_biguint(&negative_example, &upper_limit);
+    }
+    builder.pop_context();
+
+    // Balance transfer against the state root
+    builder.push_context(Level::Info, "Balance transfer");
+    let mut state_tree = SparseMerkleTree::<F, PoseidonHash>::new(32);
+    state_tree.insert(1, F::from_canonical_u64(1000)).unwrap();
+    state_tree.insert(2, F::from_canonical_u64(500)).unwrap();
+    let old_state_root = builder.add_virtual_hash();
+    pw.set_hash_target(old_state_root, state_tree.root());
+    builder.register_public_inputs(&old_state_root.elements);
+    let amount = builder.constant(F::from_canonical_u64(10));
+    let debit = builder.add_virtual_smt_update_target(state_tree.depth());
+    debit.set_witness(
+        pw,
+        &state_tree.update(1, F::from_canonical_u64(990)).unwrap(),
+    );
+    let credit = builder.add_virtual_smt_update_target(state_tree.depth());
+    credit.set_witness(
+        pw,
+        &state_tree.update(2, F::from_canonical_u64(510)).unwrap(),
+    );
+    // balances are 32-bit, so a debit larger than the balance wraps around the field and fails
+    // the range check, and so does a credit overflowing the balance
+    let debited = builder.sub(debit.old_value, amount);
+    builder.range_check(debited, 32);
+    builder.connect(debit.new_value, debited);
+    let credited = builder.add(credit.old_value, amount);
+    builder.range_check(credited, 32);
+    builder.connect(credit.new_value, credited);
+    let (sender, receiver) = (debit.key, credit.key);
+    let new_state_root =
+        builder.verify_smt_updates::<PoseidonHash>(old_state_root, &[debit, credit]);
+    builder.register_public_inputs(&new_state_root.elements);
+    builder.pop_context();
+
+    Transfer {
+        sender,
+        receiver,
+        amount,
+        new_state_root,
+    }
+}
+
+/// Adds the checks of a processed block.
+pub fn verify_block(builder: &mut CircuitBuilder<F, D>, pw: &mut PartialWitness<F>) {
+    let negative_example_value =
+        BigUint::from_str_radix("91343852333181432387730302044767688728495783945", 10).unwrap(); // 2^160+16
+    let upper_limit_value =
+        BigUint::from_str_radix("91343852333181432387730302044767688728495783935", 10).unwrap(); // 2^160
+    let x_value =
+        BigUint::from_str_radix("91343852333181432387730302044767688728495783934", 10).unwrap(); // 2^160-1
+    let y_value = BigUint::from_str_radix("1152921504606846975", 10).unwrap();
+
+    // Fill targets & connect expected values
+    let x = builder.add_virtual_biguint_target(x_value.to_u32_digits().len());
+    let y = builder.add_virtual_biguint_target(y_value.to_u32_digits().len());
+    let upper_limit = builder.add_virtual_biguint_target(upper_limit_value.to_u32_digits().len());
+    let negative_example =
+        builder.add_virtual_biguint_target(negative_example_value.to_u32_digits().len());
+
+    pw.set_biguint_target(&x, &x_value);
+    pw.set_biguint_target(&y, &y_value);
+    pw.set_biguint_target(&upper_limit, &upper_limit_value);
+    pw.set_biguint_target(&negative_example, &negative_example_value);
+
+    // Sha256
+    builder.push_context(Level::Info, "Sha256");
+    const MSG_SIZE_BYTES: usize = 242;
+    let mut msg = [0; MSG_SIZE_BYTES as usize];
+    let x_bytes = x_value.to_bytes_le();
+    for i in 0..MSG_SIZE_BYTES - 1 {
+        msg[i] = x_bytes[i % x_bytes.len()];
+    }
+    let msg_bits = array_to_bits(&msg);
+    for _ in 0..2 {
+        let sha_256_bit_targets = make_circuits(builder, msg_bits.len() as u64);
+        for i in 0..msg_bits.len() {
+            pw.set_bool_target(sha_256_bit_targets.message[i], msg_bits[i]);
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(&msg);
+        let hash = hasher.finalize();
+
+        let expected_res = array_to_bits(hash.as_slice());
+        for i in 0..expected_res.len() {
+            if expected_res[i] {
+                builder.assert_one(sha_256_bit_targets.digest[i].target);
+            } else {
+                builder.assert_zero(sha_256_bit_targets.digest[i].target);
+            }
+        }
+    }
+    builder.pop_context();
+
+    // To binary
+    builder.push_context(Level::Info, "To binary");
+    let nonnative_x = builder.biguint_to_nonnative::<F>(&x);
+    let split = builder.split_nonnative_to_1_bit_limbs(&nonnative_x);
+    let combined: NonNativeTarget<F> = builder.recombine_nonnative_bits(&split);
+    builder.connect_nonnative(&nonnative_x, &combined);
+    for _ in 0..83 {
+        let nonnative_x = builder.biguint_to_nonnative::<F>(&x);
+        let split = builder.split_nonnative_to_1_bit_limbs(&nonnative_x);
+        let combined = builder.recombine_nonnative_bits(&split);
+        builder.connect_nonnative(&nonnative_x, &combined);
+    }
+    builder.pop_context();
+
+    // From binary
+    builder.push_context(Level::Info, "From binary");
+    for _ in 0..484 {
+        let combined = builder.recombine_nonnative_bits(&split);
+        builder.connect_nonnative(&nonnative_x, &combined);
+    }
+    builder.pop_context();
+
+    // Comparison
+    builder.push_context(Level::Info, "Comparison");
+    for _ in 0..3 {
+        let lte = builder.cmp_biguint(&y, &x);
+        let expected_lte = builder.constant_bool(y_value <= x_value);
+        builder.connect(lte.target, expected_lte.target);
+    }
+    builder.pop_context();
+
+    // Asserted Comparison
+    builder.push_context(Level::Info, "Asserted Comparison");
+    for _ in 0..1 {
+        let lte = builder.cmp_biguint(&y, &x);
+        let expected_lte = builder.constant_bool(y_value <= x_value);
+        builder.connect(lte.target, expected_lte.target);
+    }
+    builder.pop_context();
+
+    // Integer division
+    builder.push_context(Level::Info, "Integer division");
+    for _ in 0..1 {
+        let div_result = builder.div_biguint(&x, &y);
+        let expected_div = builder.constant_biguint(&(&x_value / &y_value));
+        builder.connect_biguint(&div_result, &expected_div);
+    }
+    builder.pop_context();
+
+    // Poseidon (Instead of MiMC)
+    builder.push_context(Level::Info, "Poseidon");
+    let x_limbs: Vec<F> = x_value
+        .to_u32_digits()
+        .iter()
+        .map(|u32_val| F::from_canonical_u32(*u32_val))
+        .collect();
+    let expected_hash_out = PoseidonHash::hash_no_pad(x_limbs.as_slice());
+    for _ in 0..1 {
+        let public_inputs_hash = builder
+            .hash_n_to_hash_no_pad::<<C as GenericConfig<D>>::InnerHasher>(
+                x.limbs.iter().map(|u32_target| u32_target.0).collect(),
+            );
+        pw.set_hash_target(public_inputs_hash, expected_hash_out);
+    }
+    builder.pop_context();
+}
@@ -0,0 +1,82 @@
+//! Proves the transactions of a block one at a time with IVC: each step proves the tx_loop
+//! workload of `block_circuits`, absorbs the resulting state root into a running state hash, and
+//! verifies the proof of the previous step.
+//!
+//! `block_circuits` has a single transaction, so every step proves the same one.
+
+use std::str::FromStr;
+use std::time::Instant;
+
+use block_circuits::{C, D, F};
+use jemallocator::Jemalloc;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::HashOut;
+use plonky2::hash::poseidon::PoseidonHash;
+use plonky2::iop::witness::PartialWitness;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::recursion::ivc::IvcCircuit;
+use structopt::StructOpt;
+
+mod block_circuits;
+
+#[global_allocator]
+static GLOBAL: Jemalloc = Jemalloc;
+
+#[derive(Clone, StructOpt, Debug)]
+#[structopt(name = "tx_ivc")]
+struct Options {
+    /// Number of transactions, i.e. IVC steps, to prove.
+    #[structopt(long, default_value = "4", parse(try_from_str = parse_steps))]
+    steps: usize,
+}
+
+fn parse_steps(src: &str) -> anyhow::Result<usize> {
+    let steps = usize::from_str(src)?;
+    anyhow::ensure!(steps >= 1, "at least one IVC step must be proven");
+    Ok(steps)
+}
+
+fn main() {
+    let options = Options::from_args();
+
+    // Build the circuit. The witness of the transaction is that of the last synthesis of the step
+    // circuit, the one the IVC circuit is built from.
+    let start = Instant::now();
+    let ivc = IvcCircuit::<F, C, D, _>::new(
+        CircuitConfig::standard_recursion_config(),
+        |builder, state_in| {
+            let mut pw = PartialWitness::new();
+            let new_state_root = block_circuits::tx_loop_with_new_state_root(builder, &mut pw);
+            let mut inputs = state_in.elements.to_vec();
+            inputs.extend(new_state_root.elements);
+            (builder.hash_n_to_hash_no_pad::<PoseidonHash>(inputs), pw)
+        },
+    )
+    .expect("Unable to build the IVC circuit");
+    println!(
+        "Built IVC circuit (degree_bits = {}) in: {:?}",
+        ivc.data.common.degree_bits(),
+        start.elapsed()
+    );
+
+    // Prove
+    let initial_state = HashOut::from_partial(&[F::ZERO]);
+    let mut proof = None;
+    for step in 0..options.steps {
+        let pw = ivc.step_targets.clone();
+        let start = Instant::now();
+        proof = Some(match proof {
+            None => ivc.prove_first_step(initial_state, pw).unwrap(),
+            Some(previous) => ivc.prove_step(&previous, pw).unwrap(),
+        });
+        println!("Proved step {} in: {:?}", step, start.elapsed());
+    }
+    let proof = proof.expect("The step count is validated to be nonzero");
+    println!("Final proof size: {} bytes", proof.to_bytes().len());
+
+    // Verify
+    let start = Instant::now();
+    ivc.verify(proof).unwrap();
+    let duration = start.elapsed();
+    println!("Verified in: {:?}", duration);
+}
@@ -1,156 +1,110 @@
-use jemallocator::Jemalloc;
-use num::{BigUint, Num};
-use plonky2::field::types::Field;
-use plonky2::hash::poseidon::PoseidonHash;
-use plonky2::nonnative::biguint::nonnative::CircuitBuilderNonNative;
-use plonky2::nonnative::biguint::nonnative::NonNativeTarget;
-use plonky2::nonnative::biguint::split_nonnative::CircuitBuilderSplit;
-use plonky2::plonk::config::Hasher;
-use plonky2::{
-    fri::{reduction_strategies::FriReductionStrategy, FriConfig},
-    iop::witness::{PartialWitness, WitnessWrite},
-    nonnative::biguint::biguint::{CircuitBuilderBiguint, WitnessBigUint},
-    plonk::{
-        circuit_builder::CircuitBuilder,
-        circuit_data::CircuitConfig,
-        config::{GenericConfig, PoseidonGoldilocksConfig},
-    },
-};
 use std::fs;
 use std::time::Instant;
 
+use bench_cli::{init_logger, load_or_build, BenchOptions};
+use block_circuits::{C, D, F};
+use jemallocator::Jemalloc;
+use log::Level;
+use plonky2::iop::generator::generate_partial_witness;
+use plonky2::iop::witness::PartialWitness;
+use plonky2::nonnative::serialization::{NonnativeGateSerializer, NonnativeGeneratorSerializer};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::plonk::prover::prove_with_partition_witness;
+use plonky2::timed;
+use plonky2::util::timing::TimingTree;
+use structopt::StructOpt;
+
+mod bench_cli;
+mod block_circuits;
+
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
 
-fn main() {
-    const D: usize = 2;
-    type C = PoseidonGoldilocksConfig;
-    type F = <C as GenericConfig<D>>::F;
-    // let mut rng = OsRng;
-
-    let negative_example_value =
-        BigUint::from_str_radix("91343852333181432387730302044767688728495783945", 10).unwrap(); // 2^160+16
-    let upper_limit_value =
-        BigUint::from_str_radix("91343852333181432387730302044767688728495783935", 10).unwrap(); // 2^160
-    let x_value =
-        BigUint::from_str_radix("91343852333181432387730302044767688728495783934", 10).unwrap(); // 2^160-1
-    let y_value = BigUint::from_str_radix("1152921504606846975", 10).unwrap();
-
-    // Init circuit
-    let config = CircuitConfig {
-        num_wires: 135,
-        num_routed_wires: 80,
-        num_constants: 2,
-        use_base_arithmetic_gate: true,
-        security_bits: 100,
-        num_challenges: 2,
-        zero_knowledge: false,
-        max_quotient_degree_factor: 8,
-        fri_config: FriConfig {
-            rate_bits: 3,
-            cap_height: 4,
-            proof_of_work_bits: 16,
-            reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
-            num_query_rounds: 28,
-        },
-    };
-    let mut pw = PartialWitness::new();
-    let mut builder = CircuitBuilder::<F, D>::new(config);
-
-    // Fill targets & connect expected values
-    let x = builder.add_virtual_biguint_target(x_value.to_u32_digits().len());
-    let y = builder.add_virtual_biguint_target(y_value.to_u32_digits().len());
-    let upper_limit = builder.add_virtual_biguint_target(upper_limit_value.to_u32_digits().len());
-    let negative_example =
-        builder.add_virtual_biguint_target(negative_example_value.to_u32_digits().len());
-
-    pw.set_biguint_target(&x, &x_value);
-    pw.set_biguint_target(&y, &y_value);
-    pw.set_biguint_target(&upper_limit, &upper_limit_value);
-    pw.set_biguint_target(&negative_example, &negative_example_value);
-
-    // Poseidon (Instead of MiMC)
-    let x_limbs: Vec<F> = x_value
-        .to_u32_digits()
-        .iter()
-        .map(|u32_val| F::from_canonical_u32(*u32_val))
-        .collect();
-    let expected_hash_out = PoseidonHash::hash_no_pad(x_limbs.as_slice());
-    for _ in 0..5648 {
-        let public_inputs_hash = builder
-            .hash_n_to_hash_no_pad::<<C as GenericConfig<D>>::InnerHasher>(
-                x.limbs.iter().map(|u32_target| u32_target.0).collect(),
-            );
-        pw.set_hash_target(public_inputs_hash, expected_hash_out);
-    }
-
-    // To binary
-    let nonnative_x = builder.biguint_to_nonnative::<F>(&x);
-    let split = builder.split_nonnative_to_1_bit_limbs(&nonnative_x);
-    let combined: NonNativeTarget<F> = builder.recombine_nonnative_bits(&split);
-    builder.connect_nonnative(&nonnative_x, &combined);
-    for _ in 0..64 {
-        let nonnative_x = builder.biguint_to_nonnative::<F>(&x);
-        let split = builder.split_nonnative_to_1_bit_limbs(&nonnative_x);
-        let combined = builder.recombine_nonnative_bits(&split);
-        builder.connect_nonnative(&nonnative_x, &combined);
-    }
-
-    // From binary
-    for _ in 0..(236 - 65) {
-        let combined = builder.recombine_nonnative_bits(&split);
-        builder.connect_nonnative(&nonnative_x, &combined);
-    }
-
-    // Comparison
-    for _ in 0..49 {
-        let lte = builder.cmp_biguint(&y, &x);
-        let expected_lte = builder.constant_bool(y_value <= x_value);
-        builder.connect(lte.target, expected_lte.target);
-    }
-
-    // Asserted Comparison
-    for _ in 0..19 {
-        let lte = builder.cmp_biguint(&y, &x);
-        let expected_lte = builder.constant_bool(y_value <= x_value);
-        builder.connect(lte.target, expected_lte.target);
-    }
+#[derive(Clone, StructOpt, Debug)]
+#[structopt(name = "tx_loop")]
+struct Options {
+    #[structopt(flatten)]
+    bench: BenchOptions,
 
-    // Integer division
-    for _ in 0..13 {
-        let div_result = builder.div_biguint(&x, &y);
-        let expected_div = builder.constant_biguint(&(&x_value / &y_value));
-        builder.connect_biguint(&div_result, &expected_div);
-    }
-
-    let _true = builder.constant_bool(x_value <= upper_limit_value);
+    /// Also check an ECDSA signature over the transfer of each transaction. Defaults the number
+    /// of wires to that of `CircuitConfig::standard_ecc_config`.
+    #[structopt(long)]
+    ecdsa: bool,
+}
 
-    // IsNegative, which is <160 bits in our case
-    for _ in 0..6 {
-        let lte = builder.cmp_biguint(&x, &upper_limit);
-        builder.connect(lte.target, _true.target);
+fn main() {
+    // Parse command line arguments, see `--help` for details.
+    let mut options = Options::from_args();
+    init_logger();
+    if options.ecdsa && options.bench.num_wires.is_empty() {
+        options.bench.num_wires = vec![CircuitConfig::standard_ecc_config().num_wires];
     }
-
-    // Abs
-    for _ in 0..4 {
-        let lte = builder.cmp_biguint(&upper_limit, &negative_example);
-        builder.connect(lte.target, _true.target);
-        builder.sub_biguint(&negative_example, &upper_limit);
+    let example = if options.ecdsa {
+        "tx_loop_ecdsa"
+    } else {
+        "tx_loop"
+    };
+    let add_circuit = if options.ecdsa {
+        block_circuits::tx_loop_ecdsa
+    } else {
+        block_circuits::tx_loop
+    };
+    let options = options.bench;
+    if options.sweep {
+        options.sweep::<F, C, D>(add_circuit).expect("Sweep failed");
+        return;
     }
 
-    // Build the circuit
-    let data = builder.build::<C>();
+    // Init circuit
+    let config = options.config();
+    let mut pw = PartialWitness::new();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+    add_circuit(&mut builder, &mut pw);
+
+    // Export the cost of each section
+    let report = builder.cost_report();
+    print!("{}", report);
+    fs::write(
+        format!("{example}_cost.json"),
+        serde_json::to_string_pretty(&report).unwrap(),
+    )
+    .expect("Unable to write cost report");
+    fs::write(format!("{example}_cost.folded"), report.to_folded())
+        .expect("Unable to write cost report");
+
+    // Build the circuit, or load it from a previous run
+    let (prover_data, verifier_data) = load_or_build::<F, C, D>(
+        example,
+        builder,
+        &NonnativeGateSerializer,
+        &NonnativeGeneratorSerializer::<C, D>::default(),
+    );
+
+    // Generate the witness in the same timing tree as the proof, so that its share of the proving
+    // time is visible
+    let mut timing = TimingTree::new("prove", Level::Info);
+    let partition_witness = timed!(
+        timing,
+        Level::Info,
+        "generate witness",
+        generate_partial_witness(pw, &prover_data.prover_only, &prover_data.common)
+    );
 
     // Prove
-    let start = Instant::now();
-    let proof = data.prove(pw).unwrap();
-    let duration = start.elapsed();
-    println!("Proved in: {:?}", duration);
+    let proof = prove_with_partition_witness(
+        &prover_data.prover_only,
+        &prover_data.common,
+        partition_witness,
+        &mut timing,
+    )
+    .unwrap();
+    timing.print();
     fs::write("proof.bin", proof.to_bytes()).expect("Unable to write proof to file");
 
     // Verify
     let start = Instant::now();
-    data.verify(proof).unwrap();
+    verifier_data.verify(proof).unwrap();
     let duration = start.elapsed();
     println!("Verified in: {:?}", duration);
 }
@@ -0,0 +1,123 @@
+use plonky2::field::types::Field;
+use plonky2::iop::target::{BoolTarget, Target};
+use plonky2::nonnative::u32::gadgets::arithmetic_u32::{CircuitBuilderU32, U32Target};
+use plonky2::nonnative::u32::gadgets::bitwise_u32::CircuitBuilderBitwiseU32;
+use plonky2::nonnative::u32::gadgets::range_check::range_check_u32_circuit;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = <C as GenericConfig<D>>::F;
+
+/// Numbers of times each operation is repeated, as lookup tables are amortized over all the
+/// operations using them.
+const NUM_OPS: [usize; 3] = [256, 1024, 4096];
+
+type Op = fn(&mut CircuitBuilder<F, D>, U32Target, U32Target);
+
+fn bits_binary_op(
+    builder: &mut CircuitBuilder<F, D>,
+    a: U32Target,
+    b: U32Target,
+    f: fn(&mut CircuitBuilder<F, D>, Target, Target) -> Target,
+) {
+    let a_bits = builder.split_le(a.0, 32);
+    let b_bits = builder.split_le(b.0, 32);
+    let bits = a_bits
+        .iter()
+        .zip(&b_bits)
+        .map(|(a, b)| BoolTarget::new_unsafe(f(builder, a.target, b.target)))
+        .collect::<Vec<_>>();
+    builder.le_sum(bits.into_iter());
+}
+
+fn bits_permutation(builder: &mut CircuitBuilder<F, D>, x: U32Target, n: usize, rotate: bool) {
+    let mut bits = builder.split_le(x.0, 32);
+    bits.rotate_left(n);
+    if !rotate {
+        bits.truncate(32 - n);
+    }
+    builder.le_sum(bits.into_iter());
+}
+
+fn count_gates(op: Op, num_ops: usize) -> usize {
+    let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+    for _ in 0..num_ops {
+        let a = builder.add_virtual_u32_target();
+        let b = builder.add_virtual_u32_target();
+        op(&mut builder, a, b);
+    }
+    builder.num_gates() + builder.num_lookup_gates()
+}
+
+/// Compares the number of gates used by the lookup-backed `u32` gadgets with the bit
+/// decomposition they replace.
+fn main() {
+    let ops: [(&str, Op, Op); 7] = [
+        (
+            "and_u32",
+            |builder, a, b| bits_binary_op(builder, a, b, |builder, x, y| builder.mul(x, y)),
+            |builder, a, b| {
+                builder.and_u32(a, b);
+            },
+        ),
+        (
+            "xor_u32",
+            |builder, a, b| {
+                bits_binary_op(builder, a, b, |builder, x, y| {
+                    let sum = builder.add(x, y);
+                    let product = builder.mul(x, y);
+                    builder.mul_const_add(-F::TWO, product, sum)
+                })
+            },
+            |builder, a, b| {
+                builder.xor_u32(a, b);
+            },
+        ),
+        (
+            "rotr_u32(7)",
+            |builder, a, _| bits_permutation(builder, a, 7, true),
+            |builder, a, _| {
+                builder.rotr_u32(a, 7);
+            },
+        ),
+        (
+            "shr_u32(3)",
+            |builder, a, _| bits_permutation(builder, a, 3, false),
+            |builder, a, _| {
+                builder.shr_u32(a, 3);
+            },
+        ),
+        (
+            "range_check_u16",
+            |builder, a, _| builder.range_check(a.0, 16),
+            |builder, a, _| builder.range_check_u16(a.0),
+        ),
+        (
+            "range_check_u32",
+            |builder, a, _| builder.range_check(a.0, 32),
+            |builder, a, _| builder.range_check_u32(a),
+        ),
+        (
+            "U32RangeCheckGate",
+            |builder, a, _| range_check_u32_circuit(builder, vec![a]),
+            |builder, a, _| builder.range_check_u32(a),
+        ),
+    ];
+
+    for num_ops in NUM_OPS {
+        println!("Gates for {} operations:", num_ops);
+        println!("{:<20} {:>12} {:>12}", "operation", "bits", "lookups");
+        for (name, bits_op, lookup_op) in ops {
+            println!(
+                "{:<20} {:>12} {:>12}",
+                name,
+                count_gates(bits_op, num_ops),
+                count_gates(lookup_op, num_ops)
+            );
+        }
+        println!();
+    }
+}
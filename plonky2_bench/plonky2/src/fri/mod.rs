@@ -16,6 +16,7 @@ pub mod proof;
 pub mod prover;
 pub mod recursive_verifier;
 pub mod reduction_strategies;
+pub mod security;
 pub mod structure;
 mod validate_shape;
 pub mod verifier;
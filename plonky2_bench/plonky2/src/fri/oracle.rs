@@ -1,16 +1,16 @@
 #[cfg(not(feature = "std"))]
-use alloc::{format, vec::Vec};
+use alloc::{format, vec, vec::Vec};
 
 use itertools::Itertools;
 use plonky2_field::types::Field;
 use plonky2_maybe_rayon::*;
 
-use crate::field::extension::Extendable;
+use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::fft::FftRootTable;
 use crate::field::packed::PackedField;
 use crate::field::polynomial::{PolynomialCoeffs, PolynomialValues};
 use crate::fri::proof::FriProof;
-use crate::fri::prover::fri_proof;
+use crate::fri::prover::{fri_proof, lifted_fri_proof};
 use crate::fri::structure::{FriBatchInfo, FriInstanceInfo};
 use crate::fri::FriParams;
 use crate::hash::hash_types::RichField;
@@ -182,9 +182,101 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
     ) -> FriProof<F, C::Hasher, D> {
         assert!(D > 1, "Not implemented for D=1.");
         let alpha = challenger.get_extension_challenge::<D>();
-        let mut alpha = ReducingFactor::new(alpha);
 
         // Final low-degree polynomial that goes into FRI.
+        let final_poly = Self::combine_openings(instance, oracles, alpha, timing);
+
+        let lde_final_poly = final_poly.lde(fri_params.config.rate_bits);
+        let lde_final_values = timed!(
+            timing,
+            &format!("perform final FFT {}", lde_final_poly.len()),
+            lde_final_poly.coset_fft(F::coset_shift().into())
+        );
+
+        let fri_proof = fri_proof::<F, C, D>(
+            &oracles
+                .par_iter()
+                .map(|c| &c.merkle_tree)
+                .collect::<Vec<_>>(),
+            lde_final_poly,
+            lde_final_values,
+            challenger,
+            fri_params,
+            timing,
+        );
+
+        fri_proof
+    }
+
+    /// Produces a single opening proof for several instances, each with its own oracles, whose
+    /// degrees may differ. `fri_params` must be those of the largest degree.
+    ///
+    /// The combined polynomial `Q_i` of an instance of degree `2^k` is lifted to the largest degree
+    /// `2^K` as `Q_i(g * (X / g)^(2^(K - k)))`, where `g` is the coset shift. On the LDE domain of
+    /// degree `2^K`, it takes the values of `Q_i` on the LDE domain of degree `2^k`, so queries
+    /// open the oracles of the instance at `x_index >> (K - k)`. The lifted polynomial has degree
+    /// less than `2^K` if and only if `Q_i` has degree less than `2^k`.
+    pub fn prove_batch_openings(
+        instances: &[FriInstanceInfo<F, D>],
+        oracles: &[&[&Self]],
+        challenger: &mut Challenger<F, C::Hasher>,
+        fri_params: &FriParams,
+        timing: &mut TimingTree,
+    ) -> FriProof<F, C::Hasher, D> {
+        assert!(D > 1, "Not implemented for D=1.");
+        assert_eq!(instances.len(), oracles.len());
+        let alpha = challenger.get_extension_challenge::<D>();
+
+        // Polynomials of each instance get the next powers of `alpha`, as if all batches of all
+        // instances were reduced in sequence.
+        let mut final_poly = PolynomialCoeffs::empty();
+        let mut initial_lift_bits = Vec::new();
+        for (instance, &instance_oracles) in instances.iter().zip(oracles) {
+            let degree_log = instance_oracles[0].degree_log;
+            assert!(degree_log <= fri_params.degree_bits);
+            let lift_bits = fri_params.degree_bits - degree_log;
+            initial_lift_bits.extend(instance_oracles.iter().map(|_| lift_bits));
+
+            let instance_poly = Self::combine_openings(instance, instance_oracles, alpha, timing);
+            let num_polys = instance
+                .batches
+                .iter()
+                .map(|batch| batch.polynomials.len())
+                .sum::<usize>();
+            final_poly *= alpha.exp_u64(num_polys as u64);
+            final_poly += lift_poly::<F, D>(&instance_poly, lift_bits);
+        }
+
+        let lde_final_poly = final_poly.lde(fri_params.config.rate_bits);
+        let lde_final_values = timed!(
+            timing,
+            &format!("perform final FFT {}", lde_final_poly.len()),
+            lde_final_poly.coset_fft(F::coset_shift().into())
+        );
+
+        lifted_fri_proof::<F, C, D>(
+            &oracles
+                .iter()
+                .flat_map(|instance_oracles| instance_oracles.iter().map(|c| &c.merkle_tree))
+                .collect::<Vec<_>>(),
+            &initial_lift_bits,
+            lde_final_poly,
+            lde_final_values,
+            challenger,
+            fri_params,
+            timing,
+        )
+    }
+
+    /// Computes the polynomial `sum_i alpha^(k_i) (F_i(X) - F_i(z_i))/(X-z_i)` of an instance,
+    /// which is low-degree if the openings are correct.
+    fn combine_openings(
+        instance: &FriInstanceInfo<F, D>,
+        oracles: &[&Self],
+        alpha: F::Extension,
+        timing: &mut TimingTree,
+    ) -> PolynomialCoeffs<F::Extension> {
+        let mut alpha = ReducingFactor::new(alpha);
         let mut final_poly = PolynomialCoeffs::empty();
 
         // Each batch `i` consists of an opening point `z_i` and polynomials `{f_ij}_j` to be opened at that point.
@@ -210,25 +302,27 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
             final_poly += quotient;
         }
 
-        let lde_final_poly = final_poly.lde(fri_params.config.rate_bits);
-        let lde_final_values = timed!(
-            timing,
-            &format!("perform final FFT {}", lde_final_poly.len()),
-            lde_final_poly.coset_fft(F::coset_shift().into())
-        );
-
-        let fri_proof = fri_proof::<F, C, D>(
-            &oracles
-                .par_iter()
-                .map(|c| &c.merkle_tree)
-                .collect::<Vec<_>>(),
-            lde_final_poly,
-            lde_final_values,
-            challenger,
-            fri_params,
-            timing,
-        );
+        final_poly
+    }
+}
 
-        fri_proof
+/// Returns `p(g * (X / g)^(2^lift_bits))`, where `g` is the coset shift, which maps the LDE domain
+/// of a degree `2^lift_bits` times larger onto that of `p`.
+fn lift_poly<F: RichField + Extendable<D>, const D: usize>(
+    p: &PolynomialCoeffs<F::Extension>,
+    lift_bits: usize,
+) -> PolynomialCoeffs<F::Extension> {
+    // p(g * (X / g)^m) = sum_j c_j g^(j (1 - m)) X^(j m)
+    let m = 1 << lift_bits;
+    let factor = F::coset_shift().exp_u64(m as u64 - 1).inverse();
+    let mut coeffs = vec![F::Extension::ZERO; p.len() << lift_bits];
+    for ((c, &p_c), power) in coeffs
+        .iter_mut()
+        .step_by(m)
+        .zip(&p.coeffs)
+        .zip(factor.powers())
+    {
+        *c = p_c.scalar_mul(power);
     }
+    PolynomialCoeffs::new(coeffs)
 }
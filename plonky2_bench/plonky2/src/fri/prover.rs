@@ -1,5 +1,5 @@
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
 
 use plonky2_maybe_rayon::*;
 
@@ -28,6 +28,35 @@ pub fn fri_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const
     fri_params: &FriParams,
     timing: &mut TimingTree,
 ) -> FriProof<F, C::Hasher, D> {
+    lifted_fri_proof::<F, C, D>(
+        initial_merkle_trees,
+        &vec![0; initial_merkle_trees.len()],
+        lde_polynomial_coeffs,
+        lde_polynomial_values,
+        challenger,
+        fri_params,
+        timing,
+    )
+}
+
+/// Builds a FRI proof whose initial Merkle trees may be over smaller LDE domains than the
+/// polynomial on which the LDT is performed. The `i`-th tree, with an LDE domain
+/// `2^initial_lift_bits[i]` times smaller, is opened at `x_index >> initial_lift_bits[i]` when
+/// querying `x_index`.
+pub fn lifted_fri_proof<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    initial_merkle_trees: &[&MerkleTree<F, C::Hasher>],
+    initial_lift_bits: &[usize],
+    lde_polynomial_coeffs: PolynomialCoeffs<F::Extension>,
+    lde_polynomial_values: PolynomialValues<F::Extension>,
+    challenger: &mut Challenger<F, C::Hasher>,
+    fri_params: &FriParams,
+    timing: &mut TimingTree,
+) -> FriProof<F, C::Hasher, D> {
+    assert_eq!(initial_merkle_trees.len(), initial_lift_bits.len());
     let n = lde_polynomial_values.len();
     assert_eq!(lde_polynomial_coeffs.len(), n);
 
@@ -51,8 +80,14 @@ pub fn fri_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const
     );
 
     // Query phase
-    let query_round_proofs =
-        fri_prover_query_rounds::<F, C, D>(initial_merkle_trees, &trees, challenger, n, fri_params);
+    let query_round_proofs = fri_prover_query_rounds::<F, C, D>(
+        initial_merkle_trees,
+        initial_lift_bits,
+        &trees,
+        challenger,
+        n,
+        fri_params,
+    );
 
     FriProof {
         commit_phase_merkle_caps: trees.iter().map(|t| t.cap.clone()).collect(),
@@ -166,6 +201,7 @@ fn fri_prover_query_rounds<
     const D: usize,
 >(
     initial_merkle_trees: &[&MerkleTree<F, C::Hasher>],
+    initial_lift_bits: &[usize],
     trees: &[MerkleTree<F, C::Hasher>],
     challenger: &mut Challenger<F, C::Hasher>,
     n: usize,
@@ -176,7 +212,13 @@ fn fri_prover_query_rounds<
         .into_par_iter()
         .map(|rand| {
             let x_index = rand.to_canonical_u64() as usize % n;
-            fri_prover_query_round::<F, C, D>(initial_merkle_trees, trees, x_index, fri_params)
+            fri_prover_query_round::<F, C, D>(
+                initial_merkle_trees,
+                initial_lift_bits,
+                trees,
+                x_index,
+                fri_params,
+            )
         })
         .collect()
 }
@@ -187,6 +229,7 @@ fn fri_prover_query_round<
     const D: usize,
 >(
     initial_merkle_trees: &[&MerkleTree<F, C::Hasher>],
+    initial_lift_bits: &[usize],
     trees: &[MerkleTree<F, C::Hasher>],
     mut x_index: usize,
     fri_params: &FriParams,
@@ -194,7 +237,11 @@ fn fri_prover_query_round<
     let mut query_steps = Vec::new();
     let initial_proof = initial_merkle_trees
         .iter()
-        .map(|t| (t.get(x_index).to_vec(), t.prove(x_index)))
+        .zip(initial_lift_bits)
+        .map(|(t, &lift_bits)| {
+            let index = x_index >> lift_bits;
+            (t.get(index).to_vec(), t.prove(index))
+        })
         .collect::<Vec<_>>();
     for (i, tree) in trees.iter().enumerate() {
         let arity_bits = fri_params.reduction_arity_bits[i];
@@ -1,5 +1,5 @@
 #[cfg(not(feature = "std"))]
-use alloc::{format, vec::Vec};
+use alloc::{format, vec, vec::Vec};
 
 use itertools::Itertools;
 
@@ -265,7 +265,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         // Note that this `low_bits` decomposition permits non-canonical binary encodings. Here we
         // verify that this has a negligible impact on soundness error.
         Self::assert_noncanonical_indices_ok(&params.config);
-        let mut x_index_bits = self.low_bits(x_index, n_log, F::BITS);
+        let x_index_bits = self.low_bits(x_index, n_log, F::BITS);
 
         let cap_index =
             self.le_sum(x_index_bits[x_index_bits.len() - params.config.cap_height..].iter());
@@ -281,7 +281,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         );
 
         // `subgroup_x` is `subgroup[x_index]`, i.e., the actual field element in the domain.
-        let mut subgroup_x = with_context!(self, "compute x from its index", {
+        let subgroup_x = with_context!(self, "compute x from its index", {
             let g = self.constant(F::coset_shift());
             let phi = F::primitive_root_of_unity(n_log);
             let phi = self.exp_from_bits_const_base(phi, x_index_bits.iter().rev());
@@ -291,7 +291,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
 
         // old_eval is the last derived evaluation; it will be checked for consistency with its
         // committed "parent" value in the next iteration.
-        let mut old_eval = with_context!(
+        let old_eval = with_context!(
             self,
             "combine initial oracles",
             self.fri_combine_initial(
@@ -304,6 +304,33 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             )
         );
 
+        self.fri_verify_reduction_steps::<C>(
+            challenges,
+            proof,
+            x_index_bits,
+            cap_index,
+            subgroup_x,
+            old_eval,
+            round_proof,
+            params,
+        );
+    }
+
+    /// Checks the reductions of a query round, starting from the evaluation `old_eval` of the
+    /// combined polynomial at `subgroup_x`, the point of index `x_index_bits` in the LDE domain.
+    fn fri_verify_reduction_steps<C: GenericConfig<D, F = F>>(
+        &mut self,
+        challenges: &FriChallengesTarget<D>,
+        proof: &FriProofTarget<D>,
+        mut x_index_bits: Vec<BoolTarget>,
+        cap_index: Target,
+        mut subgroup_x: Target,
+        mut old_eval: ExtensionTarget<D>,
+        round_proof: &FriQueryRoundTarget<D>,
+        params: &FriParams,
+    ) where
+        C::Hasher: AlgebraicHasher<F>,
+    {
         for (i, &arity_bits) in params.reduction_arity_bits.iter().enumerate() {
             let evals = &round_proof.steps[i].evals;
 
@@ -360,6 +387,201 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.connect_extension(eval, old_eval);
     }
 
+    /// Recursively verifies an opening proof of several instances, as
+    /// [`verify_batch_fri_proof`](crate::fri::verifier::verify_batch_fri_proof) does.
+    pub fn verify_batch_fri_proof<C: GenericConfig<D, F = F>>(
+        &mut self,
+        instances: &[FriInstanceInfoTarget<D>],
+        openings: &[FriOpeningsTarget<D>],
+        degree_bits: &[usize],
+        challenges: &FriChallengesTarget<D>,
+        initial_merkle_caps: &[Vec<MerkleCapTarget>],
+        proof: &FriProofTarget<D>,
+        params: &FriParams,
+    ) where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        assert_eq!(instances.len(), openings.len());
+        assert_eq!(instances.len(), degree_bits.len());
+        assert_eq!(instances.len(), initial_merkle_caps.len());
+        assert_eq!(degree_bits.iter().max(), Some(&params.degree_bits));
+        if let Some(max_arity_bits) = params.max_arity_bits() {
+            self.check_recursion_config(max_arity_bits);
+        }
+
+        debug_assert_eq!(
+            params.final_poly_len(),
+            proof.final_poly.len(),
+            "Final polynomial has wrong degree."
+        );
+
+        with_context!(
+            self,
+            "check PoW",
+            self.fri_verify_proof_of_work(challenges.fri_pow_response, &params.config)
+        );
+
+        // Check that parameters are coherent.
+        debug_assert_eq!(
+            params.config.num_query_rounds,
+            proof.query_round_proofs.len(),
+            "Number of query rounds does not match config."
+        );
+
+        let instance_params = degree_bits
+            .iter()
+            .map(|&bits| params.config.fri_params(bits, params.hiding))
+            .collect::<Vec<_>>();
+        let precomputed_reduced_evals = with_context!(
+            self,
+            "precompute reduced evaluations",
+            openings
+                .iter()
+                .map(|os| PrecomputedReducedOpeningsTarget::from_os_and_alpha(
+                    os,
+                    challenges.fri_alpha,
+                    self
+                ))
+                .collect::<Vec<_>>()
+        );
+        let alpha_powers = instances
+            .iter()
+            .map(|instance| {
+                let num_polys = instance
+                    .batches
+                    .iter()
+                    .map(|batch| batch.polynomials.len())
+                    .sum::<usize>();
+                self.exp_u64_extension(challenges.fri_alpha, num_polys as u64)
+            })
+            .collect::<Vec<_>>();
+        let initial_merkle_caps = initial_merkle_caps.concat();
+
+        for (i, round_proof) in proof.query_round_proofs.iter().enumerate() {
+            // As in `verify_fri_proof`, only record a context for the second query.
+            let level = if i == 1 {
+                log::Level::Debug
+            } else {
+                log::Level::Trace
+            };
+
+            let num_queries = proof.query_round_proofs.len();
+            with_context!(
+                self,
+                level,
+                &format!("verify one (of {num_queries}) query rounds"),
+                self.batch_fri_verifier_query_round::<C>(
+                    instances,
+                    &instance_params,
+                    &alpha_powers,
+                    challenges,
+                    &precomputed_reduced_evals,
+                    &initial_merkle_caps,
+                    proof,
+                    challenges.fri_query_indices[i],
+                    round_proof,
+                    params,
+                )
+            );
+        }
+    }
+
+    fn batch_fri_verifier_query_round<C: GenericConfig<D, F = F>>(
+        &mut self,
+        instances: &[FriInstanceInfoTarget<D>],
+        instance_params: &[FriParams],
+        alpha_powers: &[ExtensionTarget<D>],
+        challenges: &FriChallengesTarget<D>,
+        precomputed_reduced_evals: &[PrecomputedReducedOpeningsTarget<D>],
+        initial_merkle_caps: &[MerkleCapTarget],
+        proof: &FriProofTarget<D>,
+        x_index: Target,
+        round_proof: &FriQueryRoundTarget<D>,
+        params: &FriParams,
+    ) where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let n_log = params.lde_bits();
+
+        Self::assert_noncanonical_indices_ok(&params.config);
+        let x_index_bits = self.low_bits(x_index, n_log, F::BITS);
+
+        // The oracles of an instance with `lift_bits` fewer degree bits are opened at
+        // `x_index >> lift_bits`. All indices have the same top bits, hence the same cap index.
+        let cap_index =
+            self.le_sum(x_index_bits[x_index_bits.len() - params.config.cap_height..].iter());
+        let initial_trees_proof = &round_proof.initial_trees_proof;
+        let mut old_eval = self.zero_extension();
+        let mut oracle_start = 0;
+        for (((instance, instance_params), &alpha_power), instance_precomputed_reduced_evals) in
+            instances
+                .iter()
+                .zip(instance_params)
+                .zip(alpha_powers)
+                .zip(precomputed_reduced_evals)
+        {
+            let num_oracles = instance.oracles.len();
+            let instance_proof = FriInitialTreeProofTarget {
+                evals_proofs: initial_trees_proof.evals_proofs
+                    [oracle_start..oracle_start + num_oracles]
+                    .to_vec(),
+            };
+            let lift_bits = params.degree_bits - instance_params.degree_bits;
+            let instance_x_index_bits = &x_index_bits[lift_bits..];
+            with_context!(
+                self,
+                "check FRI initial proof",
+                self.fri_verify_initial_proof::<C::Hasher>(
+                    instance_x_index_bits,
+                    &instance_proof,
+                    &initial_merkle_caps[oracle_start..oracle_start + num_oracles],
+                    cap_index
+                )
+            );
+            oracle_start += num_oracles;
+
+            let instance_subgroup_x = with_context!(self, "compute x from its index", {
+                let g = self.constant(F::coset_shift());
+                let phi = F::primitive_root_of_unity(n_log - lift_bits);
+                let phi = self.exp_from_bits_const_base(phi, instance_x_index_bits.iter().rev());
+                self.mul(g, phi)
+            });
+            let instance_eval = with_context!(
+                self,
+                "combine initial oracles",
+                self.fri_combine_initial(
+                    instance,
+                    &instance_proof,
+                    challenges.fri_alpha,
+                    instance_subgroup_x,
+                    instance_precomputed_reduced_evals,
+                    instance_params,
+                )
+            );
+            old_eval = self.mul_add_extension(old_eval, alpha_power, instance_eval);
+        }
+
+        // `subgroup_x` is `subgroup[x_index]`, i.e., the actual field element in the domain.
+        let subgroup_x = with_context!(self, "compute x from its index", {
+            let g = self.constant(F::coset_shift());
+            let phi = F::primitive_root_of_unity(n_log);
+            let phi = self.exp_from_bits_const_base(phi, x_index_bits.iter().rev());
+            // subgroup_x = g * phi
+            self.mul(g, phi)
+        });
+
+        self.fri_verify_reduction_steps::<C>(
+            challenges,
+            proof,
+            x_index_bits,
+            cap_index,
+            subgroup_x,
+            old_eval,
+            round_proof,
+            params,
+        );
+    }
+
     /// We decompose FRI query indices into bits without verifying that the decomposition given by
     /// the prover is the canonical one. In particular, if `x_index < 2^field_bits - p`, then the
     /// prover could supply the binary encoding of either `x_index` or `x_index + p`, since the are
@@ -384,6 +606,21 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         &mut self,
         num_leaves_per_oracle: &[usize],
         params: &FriParams,
+    ) -> FriProofTarget<D> {
+        self.add_virtual_lifted_fri_proof(
+            num_leaves_per_oracle,
+            &vec![0; num_leaves_per_oracle.len()],
+            params,
+        )
+    }
+
+    /// Adds a virtual FRI proof whose initial oracles may be over smaller LDE domains, as produced
+    /// by [`lifted_fri_proof`](crate::fri::prover::lifted_fri_proof).
+    pub fn add_virtual_lifted_fri_proof(
+        &mut self,
+        num_leaves_per_oracle: &[usize],
+        initial_lift_bits: &[usize],
+        params: &FriParams,
     ) -> FriProofTarget<D> {
         let cap_height = params.config.cap_height;
         let num_queries = params.config.num_query_rounds;
@@ -391,7 +628,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             .map(|_| self.add_virtual_cap(cap_height))
             .collect();
         let query_round_proofs = (0..num_queries)
-            .map(|_| self.add_virtual_fri_query(num_leaves_per_oracle, params))
+            .map(|_| self.add_virtual_fri_query(num_leaves_per_oracle, initial_lift_bits, params))
             .collect();
         let final_poly = self.add_virtual_poly_coeff_ext(params.final_poly_len());
         let pow_witness = self.add_virtual_target();
@@ -406,14 +643,18 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     fn add_virtual_fri_query(
         &mut self,
         num_leaves_per_oracle: &[usize],
+        initial_lift_bits: &[usize],
         params: &FriParams,
     ) -> FriQueryRoundTarget<D> {
         let cap_height = params.config.cap_height;
         assert!(params.lde_bits() >= cap_height);
         let mut merkle_proof_len = params.lde_bits() - cap_height;
 
-        let initial_trees_proof =
-            self.add_virtual_fri_initial_trees_proof(num_leaves_per_oracle, merkle_proof_len);
+        let initial_trees_proof = self.add_virtual_fri_initial_trees_proof(
+            num_leaves_per_oracle,
+            initial_lift_bits,
+            merkle_proof_len,
+        );
 
         let mut steps = Vec::with_capacity(params.reduction_arity_bits.len());
         for &arity_bits in &params.reduction_arity_bits {
@@ -431,13 +672,16 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     fn add_virtual_fri_initial_trees_proof(
         &mut self,
         num_leaves_per_oracle: &[usize],
+        initial_lift_bits: &[usize],
         initial_merkle_proof_len: usize,
     ) -> FriInitialTreeProofTarget {
         let evals_proofs = num_leaves_per_oracle
             .iter()
-            .map(|&num_oracle_leaves| {
+            .zip(initial_lift_bits)
+            .map(|(&num_oracle_leaves, &lift_bits)| {
                 let leaves = self.add_virtual_targets(num_oracle_leaves);
-                let merkle_proof = self.add_virtual_merkle_proof(initial_merkle_proof_len);
+                let merkle_proof =
+                    self.add_virtual_merkle_proof(initial_merkle_proof_len - lift_bits);
                 (leaves, merkle_proof)
             })
             .collect();
@@ -0,0 +1,211 @@
+//! Bits of security reached by a FRI configuration.
+//!
+//! Two regimes are supported:
+//!
+//! - The *conjectured* regime of the ethSTARK paper, where each query is assumed to reduce the
+//!   soundness error by a factor `rate`, i.e. `rate_bits` bits of security per query, on top of
+//!   the grinding bits. This is the regime `security_bits` usually refers to.
+//! - The *provable* regime, which relies on the proximity gaps of Reed-Solomon codes up to the
+//!   Johnson bound (BCIKS20, Theorem 8.3, as instantiated in the ethSTARK paper). Each query then
+//!   only gives about `rate_bits / 2` bits, and the commit phase adds an error which grows with the
+//!   domain size and the number of batched polynomials.
+//!
+//! Only the FRI low-degree test is accounted for; the soundness errors of the DEEP and
+//! constraint-combination steps are bounded separately, through `num_challenges`.
+
+use crate::field::extension::Extendable;
+use crate::field::types::Field;
+#[cfg(feature = "std")]
+use crate::fri::FriParams;
+use crate::hash::hash_types::RichField;
+
+/// The largest Johnson proximity parameter `m` tried when maximizing the provable security.
+#[cfg(feature = "std")]
+const MAX_JOHNSON_M: usize = 1 << 10;
+
+/// The number of bits of the field from which FRI challenges are drawn, i.e. the extension field.
+pub fn fri_field_bits<F: RichField + Extendable<D>, const D: usize>() -> usize {
+    F::Extension::order().bits() as usize
+}
+
+/// Conjectured bits of security of FRI with the given parameters, as per the ethSTARK paper.
+pub const fn conjectured_security_bits(
+    field_bits: usize,
+    rate_bits: usize,
+    num_query_rounds: usize,
+    proof_of_work_bits: u32,
+) -> usize {
+    let query_bits = num_query_rounds * rate_bits + proof_of_work_bits as usize;
+    if query_bits < field_bits {
+        query_bits
+    } else {
+        field_bits
+    }
+}
+
+/// The minimum number of query rounds for FRI to reach `target_bits` bits of conjectured
+/// security, or `None` if the target exceeds the field size. FRI always makes at least one query,
+/// however many bits the proof of work gives.
+pub fn min_conjectured_query_rounds(
+    target_bits: usize,
+    field_bits: usize,
+    rate_bits: usize,
+    proof_of_work_bits: u32,
+) -> Option<usize> {
+    if target_bits > field_bits || rate_bits == 0 {
+        return None;
+    }
+    let num_query_rounds = target_bits
+        .saturating_sub(proof_of_work_bits as usize)
+        .div_ceil(rate_bits);
+
+    Some(num_query_rounds.max(1))
+}
+
+/// Provable bits of security of FRI with the given parameters, when `num_polys` polynomials are
+/// batched into a single low-degree test. The Johnson proximity parameter `m` is chosen to
+/// balance the commit and query phase errors.
+#[cfg(feature = "std")]
+pub fn provable_security_bits(field_bits: usize, params: &FriParams, num_polys: usize) -> f64 {
+    (3..=MAX_JOHNSON_M)
+        .map(|m| {
+            let m = m as f64;
+            commit_phase_security_bits(field_bits, params, num_polys, m).min(
+                query_phase_security_bits(
+                    params.config.rate_bits,
+                    params.config.num_query_rounds,
+                    params.config.proof_of_work_bits,
+                    m,
+                ),
+            )
+        })
+        .fold(f64::NEG_INFINITY, f64::max)
+}
+
+/// The minimum number of query rounds for FRI to reach `target_bits` bits of provable security,
+/// or `None` if the commit phase error alone exceeds the target.
+#[cfg(feature = "std")]
+pub fn min_provable_query_rounds(
+    target_bits: usize,
+    field_bits: usize,
+    params: &FriParams,
+    num_polys: usize,
+) -> Option<usize> {
+    // The commit phase error is smallest for the smallest `m`, and the query phase error can be
+    // made arbitrarily small, so this is the best achievable level.
+    if commit_phase_security_bits(field_bits, params, num_polys, 3.0) < target_bits as f64 {
+        return None;
+    }
+    let mut params = params.clone();
+    params.config.num_query_rounds = 1;
+    while provable_security_bits(field_bits, &params, num_polys) < target_bits as f64 {
+        params.config.num_query_rounds += 1;
+    }
+    Some(params.config.num_query_rounds)
+}
+
+/// Bits of security of the commit phase: the batching of `num_polys` polynomials with powers of
+/// a random challenge, then one folding per reduction, each losing to the proximity gap error.
+#[cfg(feature = "std")]
+fn commit_phase_security_bits(
+    field_bits: usize,
+    params: &FriParams,
+    num_polys: usize,
+    m: f64,
+) -> f64 {
+    let rate = params.config.rate();
+    let lde_size = params.lde_size() as f64;
+    let batching_error = (m + 0.5).powi(7) / (3.0 * rate.powf(1.5)) * lde_size * lde_size;
+    let folding_error = (2.0 * m + 1.0) * (lde_size + 1.0) / rate.sqrt();
+    let num_batched = num_polys.max(2) - 1;
+    let total_arity: usize = params.reduction_arity_bits.iter().map(|&b| 1 << b).sum();
+    let error = batching_error * num_batched as f64 + folding_error * total_arity as f64;
+    field_bits as f64 - error.log2()
+}
+
+/// Bits of security of the query phase, where each query is rejected with probability at least
+/// `1 - sqrt(rate) * (1 + 1/2m)`.
+#[cfg(feature = "std")]
+fn query_phase_security_bits(
+    rate_bits: usize,
+    num_query_rounds: usize,
+    proof_of_work_bits: u32,
+    m: f64,
+) -> f64 {
+    let bits_per_query = rate_bits as f64 / 2.0 - (1.0 + 0.5 / m).log2();
+    bits_per_query * num_query_rounds as f64 + proof_of_work_bits as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::plonk::circuit_data::CircuitConfig;
+
+    const D: usize = 2;
+    type F = GoldilocksField;
+
+    #[test]
+    fn test_conjectured_security() {
+        let field_bits = fri_field_bits::<F, D>();
+        assert_eq!(field_bits, 128);
+
+        let fri_config = CircuitConfig::standard_recursion_config().fri_config;
+        assert_eq!(
+            conjectured_security_bits(
+                field_bits,
+                fri_config.rate_bits,
+                fri_config.num_query_rounds,
+                fri_config.proof_of_work_bits
+            ),
+            100
+        );
+        // Capped by the field size.
+        assert_eq!(conjectured_security_bits(field_bits, 3, 100, 16), 128);
+
+        assert_eq!(
+            min_conjectured_query_rounds(100, field_bits, 3, 16),
+            Some(28)
+        );
+        assert_eq!(
+            min_conjectured_query_rounds(100, field_bits, 4, 20),
+            Some(20)
+        );
+        assert_eq!(min_conjectured_query_rounds(10, field_bits, 3, 16), Some(1));
+        assert_eq!(min_conjectured_query_rounds(129, field_bits, 3, 16), None);
+    }
+
+    #[test]
+    fn test_provable_security() {
+        let field_bits = fri_field_bits::<F, D>();
+        let fri_config = CircuitConfig::standard_recursion_config().fri_config;
+        let params = fri_config.fri_params(16, false);
+        let num_polys = 200;
+
+        // The Johnson bound regime is much more conservative than the conjectured one.
+        let provable = provable_security_bits(field_bits, &params, num_polys);
+        assert!(provable > 40.0 && provable < 100.0, "{provable}");
+
+        // More queries, grinding or batched polynomials move the level in the expected direction.
+        let mut more_queries = params.clone();
+        more_queries.config.num_query_rounds *= 2;
+        assert!(provable_security_bits(field_bits, &more_queries, num_polys) > provable);
+        let mut more_pow = params.clone();
+        more_pow.config.proof_of_work_bits += 4;
+        assert!(provable_security_bits(field_bits, &more_pow, num_polys) > provable);
+        assert!(provable_security_bits(field_bits, &params, 4 * num_polys) < provable);
+
+        let min_queries = min_provable_query_rounds(60, field_bits, &params, num_polys).unwrap();
+        let mut enough = params.clone();
+        enough.config.num_query_rounds = min_queries;
+        assert!(provable_security_bits(field_bits, &enough, num_polys) >= 60.0);
+        enough.config.num_query_rounds -= 1;
+        assert!(provable_security_bits(field_bits, &enough, num_polys) < 60.0);
+
+        // The commit phase error alone caps the level at ~67 bits, whatever the number of queries.
+        assert_eq!(
+            min_provable_query_rounds(80, field_bits, &params, num_polys),
+            None
+        );
+    }
+}
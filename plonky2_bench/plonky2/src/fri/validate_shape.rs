@@ -1,8 +1,11 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
 use anyhow::ensure;
 
 use crate::field::extension::Extendable;
 use crate::fri::proof::{FriProof, FriQueryRound, FriQueryStep};
-use crate::fri::structure::FriInstanceInfo;
+use crate::fri::structure::{FriInstanceInfo, FriOracleInfo};
 use crate::fri::FriParams;
 use crate::hash::hash_types::RichField;
 use crate::plonk::config::GenericConfig;
@@ -13,6 +16,51 @@ pub(crate) fn validate_fri_proof_shape<F, C, const D: usize>(
     instance: &FriInstanceInfo<F, D>,
     params: &FriParams,
 ) -> anyhow::Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let oracles = instance
+        .oracles
+        .iter()
+        .map(|&oracle| (oracle, params.lde_bits()))
+        .collect::<Vec<_>>();
+    validate_oracles_fri_proof_shape::<F, C, D>(proof, &oracles, params)
+}
+
+/// Validates the shape of a proof of several instances, where the oracles of the `i`-th instance
+/// have degree `2^degree_bits[i]`.
+pub(crate) fn validate_batch_fri_proof_shape<F, C, const D: usize>(
+    proof: &FriProof<F, C::Hasher, D>,
+    instances: &[FriInstanceInfo<F, D>],
+    degree_bits: &[usize],
+    params: &FriParams,
+) -> anyhow::Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let oracles = instances
+        .iter()
+        .zip(degree_bits)
+        .flat_map(|(instance, &bits)| {
+            let lde_bits = bits + params.config.rate_bits;
+            instance
+                .oracles
+                .iter()
+                .map(move |&oracle| (oracle, lde_bits))
+        })
+        .collect::<Vec<_>>();
+    validate_oracles_fri_proof_shape::<F, C, D>(proof, &oracles, params)
+}
+
+/// Validates the shape of a proof whose initial oracles are given with the size of their LDE
+/// domain, in bits.
+fn validate_oracles_fri_proof_shape<F, C, const D: usize>(
+    proof: &FriProof<F, C::Hasher, D>,
+    oracles: &[(FriOracleInfo, usize)],
+    params: &FriParams,
+) -> anyhow::Result<()>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -35,14 +83,12 @@ where
             steps,
         } = query_round;
 
-        ensure!(initial_trees_proof.evals_proofs.len() == instance.oracles.len());
-        for ((leaf, merkle_proof), oracle) in initial_trees_proof
-            .evals_proofs
-            .iter()
-            .zip(&instance.oracles)
+        ensure!(initial_trees_proof.evals_proofs.len() == oracles.len());
+        for ((leaf, merkle_proof), (oracle, lde_bits)) in
+            initial_trees_proof.evals_proofs.iter().zip(oracles)
         {
             ensure!(leaf.len() == oracle.num_polys + salt_size(oracle.blinding && params.hiding));
-            ensure!(merkle_proof.len() + cap_height == params.lde_bits());
+            ensure!(merkle_proof.len() + cap_height == *lde_bits);
         }
 
         ensure!(steps.len() == params.reduction_arity_bits.len());
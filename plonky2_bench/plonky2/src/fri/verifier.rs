@@ -8,7 +8,7 @@ use crate::field::interpolation::{barycentric_weights, interpolate};
 use crate::field::types::Field;
 use crate::fri::proof::{FriChallenges, FriInitialTreeProof, FriProof, FriQueryRound};
 use crate::fri::structure::{FriBatchInfo, FriInstanceInfo, FriOpenings};
-use crate::fri::validate_shape::validate_fri_proof_shape;
+use crate::fri::validate_shape::{validate_batch_fri_proof_shape, validate_fri_proof_shape};
 use crate::fri::{FriConfig, FriParams};
 use crate::hash::hash_types::RichField;
 use crate::hash::merkle_proofs::verify_merkle_proof_to_cap;
@@ -171,7 +171,7 @@ fn fri_verifier_query_round<
     precomputed_reduced_evals: &PrecomputedReducedOpenings<F, D>,
     initial_merkle_caps: &[MerkleCap<F, C::Hasher>],
     proof: &FriProof<F, C::Hasher, D>,
-    mut x_index: usize,
+    x_index: usize,
     n: usize,
     round_proof: &FriQueryRound<F, C::Hasher, D>,
     params: &FriParams,
@@ -183,12 +183,12 @@ fn fri_verifier_query_round<
     )?;
     // `subgroup_x` is `subgroup[x_index]`, i.e., the actual field element in the domain.
     let log_n = log2_strict(n);
-    let mut subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
+    let subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
         * F::primitive_root_of_unity(log_n).exp_u64(reverse_bits(x_index, log_n) as u64);
 
     // old_eval is the last derived evaluation; it will be checked for consistency with its
     // committed "parent" value in the next iteration.
-    let mut old_eval = fri_combine_initial::<F, C, D>(
+    let old_eval = fri_combine_initial::<F, C, D>(
         instance,
         &round_proof.initial_trees_proof,
         challenges.fri_alpha,
@@ -197,6 +197,32 @@ fn fri_verifier_query_round<
         params,
     );
 
+    fri_verify_reduction_steps::<F, C, D>(
+        challenges,
+        proof,
+        x_index,
+        subgroup_x,
+        old_eval,
+        round_proof,
+        params,
+    )
+}
+
+/// Checks the reductions of a query round, starting from the evaluation `old_eval` of the
+/// combined polynomial at `subgroup_x`, the point of index `x_index` in the LDE domain.
+fn fri_verify_reduction_steps<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    challenges: &FriChallenges<F, D>,
+    proof: &FriProof<F, C::Hasher, D>,
+    mut x_index: usize,
+    mut subgroup_x: F,
+    mut old_eval: F::Extension,
+    round_proof: &FriQueryRound<F, C::Hasher, D>,
+    params: &FriParams,
+) -> Result<()> {
     for (i, &arity_bits) in params.reduction_arity_bits.iter().enumerate() {
         let arity = 1 << arity_bits;
         let evals = &round_proof.steps[i].evals;
@@ -240,6 +266,142 @@ fn fri_verifier_query_round<
     Ok(())
 }
 
+/// Verifies an opening proof of several instances produced by
+/// [`PolynomialBatch::prove_batch_openings`](crate::fri::oracle::PolynomialBatch::prove_batch_openings).
+/// `degree_bits[i]` is the degree of the oracles of the `i`-th instance, and `params` are the FRI
+/// parameters of the largest degree.
+pub fn verify_batch_fri_proof<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    instances: &[FriInstanceInfo<F, D>],
+    openings: &[FriOpenings<F, D>],
+    degree_bits: &[usize],
+    challenges: &FriChallenges<F, D>,
+    initial_merkle_caps: &[Vec<MerkleCap<F, C::Hasher>>],
+    proof: &FriProof<F, C::Hasher, D>,
+    params: &FriParams,
+) -> Result<()> {
+    ensure!(
+        instances.len() == openings.len()
+            && instances.len() == degree_bits.len()
+            && instances.len() == initial_merkle_caps.len(),
+        "Inconsistent number of instances."
+    );
+    ensure!(
+        degree_bits.iter().max() == Some(&params.degree_bits),
+        "FRI parameters do not match the largest instance."
+    );
+    validate_batch_fri_proof_shape::<F, C, D>(proof, instances, degree_bits, params)?;
+
+    // Size of the LDE domain.
+    let n = params.lde_size();
+
+    // Check PoW.
+    fri_verify_proof_of_work(challenges.fri_pow_response, &params.config)?;
+
+    // Check that parameters are coherent.
+    ensure!(
+        params.config.num_query_rounds == proof.query_round_proofs.len(),
+        "Number of query rounds does not match config."
+    );
+
+    let instance_params = degree_bits
+        .iter()
+        .map(|&bits| params.config.fri_params(bits, params.hiding))
+        .collect::<Vec<_>>();
+    let precomputed_reduced_evals = openings
+        .iter()
+        .map(|os| PrecomputedReducedOpenings::from_os_and_alpha(os, challenges.fri_alpha))
+        .collect::<Vec<_>>();
+    let initial_merkle_caps = initial_merkle_caps.concat();
+    let initial_lift_bits = instances
+        .iter()
+        .zip(degree_bits)
+        .flat_map(|(instance, &bits)| {
+            instance
+                .oracles
+                .iter()
+                .map(move |_| params.degree_bits - bits)
+        })
+        .collect::<Vec<_>>();
+
+    for (&x_index, round_proof) in challenges
+        .fri_query_indices
+        .iter()
+        .zip(&proof.query_round_proofs)
+    {
+        let initial_trees_proof = &round_proof.initial_trees_proof;
+        for (((evals, merkle_proof), cap), &lift_bits) in initial_trees_proof
+            .evals_proofs
+            .iter()
+            .zip(&initial_merkle_caps)
+            .zip(&initial_lift_bits)
+        {
+            verify_merkle_proof_to_cap::<F, C::Hasher>(
+                evals.clone(),
+                x_index >> lift_bits,
+                cap,
+                merkle_proof,
+            )?;
+        }
+
+        // Combine the instances as in `PolynomialBatch::prove_batch_openings`, each at the point
+        // of its own LDE domain that `subgroup_x` is lifted from.
+        let log_n = log2_strict(n);
+        let subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
+            * F::primitive_root_of_unity(log_n).exp_u64(reverse_bits(x_index, log_n) as u64);
+        let mut old_eval = F::Extension::ZERO;
+        let mut oracle_start = 0;
+        for ((instance, instance_params), instance_precomputed_reduced_evals) in instances
+            .iter()
+            .zip(&instance_params)
+            .zip(&precomputed_reduced_evals)
+        {
+            let num_oracles = instance.oracles.len();
+            let instance_proof = FriInitialTreeProof {
+                evals_proofs: initial_trees_proof.evals_proofs
+                    [oracle_start..oracle_start + num_oracles]
+                    .to_vec(),
+            };
+            oracle_start += num_oracles;
+
+            let lift_bits = params.degree_bits - instance_params.degree_bits;
+            let instance_log_n = log_n - lift_bits;
+            let instance_subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
+                * F::primitive_root_of_unity(instance_log_n)
+                    .exp_u64(reverse_bits(x_index >> lift_bits, instance_log_n) as u64);
+            let num_polys = instance
+                .batches
+                .iter()
+                .map(|batch| batch.polynomials.len())
+                .sum::<usize>();
+            old_eval = old_eval * challenges.fri_alpha.exp_u64(num_polys as u64)
+                + fri_combine_initial::<F, C, D>(
+                    instance,
+                    &instance_proof,
+                    challenges.fri_alpha,
+                    instance_subgroup_x,
+                    instance_precomputed_reduced_evals,
+                    instance_params,
+                );
+        }
+
+        fri_verify_reduction_steps::<F, C, D>(
+            challenges,
+            proof,
+            x_index,
+            subgroup_x,
+            old_eval,
+            round_proof,
+            params,
+        )?;
+    }
+
+    Ok(())
+}
+
 /// For each opening point, holds the reduced (by `alpha`) evaluations of each polynomial that's
 /// opened at that point.
 #[derive(Clone, Debug)]
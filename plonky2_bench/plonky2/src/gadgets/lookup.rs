@@ -153,4 +153,21 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             }
         }
     }
+
+    /// Returns the number of gates that `add_all_lookups` will add for the lookups registered so
+    /// far. This allows measuring the full size of a circuit using lookups before building it.
+    pub fn num_lookup_gates(&self) -> usize {
+        let num_slots = LookupGate::num_slots(&self.config);
+        let num_lut_entries = LookupTableGate::num_slots(&self.config);
+        (0..self.num_luts())
+            .map(|lut_index| {
+                let num_lu_rows = self.get_lut_lookups(lut_index).len().div_ceil(num_slots);
+                let num_lut_rows = self
+                    .get_luts_idx_length(lut_index)
+                    .div_ceil(num_lut_entries);
+                // One extra `NoopGate` follows the LUT gates.
+                num_lu_rows + num_lut_rows + 1
+            })
+            .sum()
+    }
 }
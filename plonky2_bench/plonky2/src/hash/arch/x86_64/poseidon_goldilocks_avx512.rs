@@ -0,0 +1,394 @@
+//! AVX-512 implementation of the Poseidon permutation over the Goldilocks field, for one or two
+//! states at once.
+//!
+//! The 12 elements of a state are spread over vectors of 8 lanes, in one of two layouts:
+//! - `ONE_STATE` uses two vectors, holding `state[0..8]`, and `state[8..12]` followed by zeros.
+//! - `TWO_STATES` uses three vectors, the `k`-th of which holds `a[4 * k..4 * k + 4]` in its lower
+//!   half and `b[4 * k..4 * k + 4]` in its upper half.
+//!
+//! Field arithmetic is that of `Avx512GoldilocksField`, except for the MDS layer, whose
+//! coefficients are small enough to accumulate the products without reduction.
+
+use core::arch::x86_64::*;
+use core::array;
+use core::mem::transmute;
+
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::packable::Packable;
+use crate::field::packed::PackedField;
+use crate::hash::poseidon::{
+    Poseidon, ALL_ROUND_CONSTANTS, HALF_N_FULL_ROUNDS, N_PARTIAL_ROUNDS, N_ROUNDS, SPONGE_WIDTH,
+};
+
+/// `Avx512GoldilocksField`, the packing of `GoldilocksField` when AVX-512 is enabled.
+type Packed = <GoldilocksField as Packable>::Packing;
+
+/// A vector given by the canonical values of its lanes.
+type Lanes = [u64; 8];
+
+const EPSILON: u64 = 0xffffffff;
+
+/// The layout of states in `N` vectors, and the constants of the permutation in that layout.
+/// Constants are the same for all states.
+struct Layout<const N: usize> {
+    /// The number of consecutive elements of a state in a vector.
+    elements_per_vector: usize,
+    /// The lanes holding `state[0]` of each state.
+    first_element_mask: __mmask8,
+    /// Permutations of a vector, selecting its `i`-th element of each state in all the lanes of
+    /// that state, for `i < elements_per_vector`.
+    broadcast_indices: [Lanes; 8],
+    /// Permutations exchanging lanes within each state, whose sums add up all of its lanes.
+    sum_indices: [Lanes; 3],
+    num_sum_indices: usize,
+    round_constants: [[Lanes; N]; N_ROUNDS],
+    /// `mds_columns[j]` is the `j`-th column of the MDS matrix, i.e. the coefficients of
+    /// `state[j]` in the rows of the output.
+    mds_columns: [[Lanes; N]; SPONGE_WIDTH],
+    fast_partial_first_round_constant: [Lanes; N],
+    /// `fast_partial_round_initial_rows[r - 1]` is the `r`-th row of the initial matrix, whose
+    /// first row and column are `[1, 0, ..., 0]`.
+    fast_partial_round_initial_rows: [[Lanes; N]; SPONGE_WIDTH - 1],
+    /// The `v` vectors of the fast partial rounds, after a zero so that `state[0]` is left as is.
+    fast_partial_round_vs: [[Lanes; N]; N_PARTIAL_ROUNDS],
+    /// The `[M_00 | w_hat]` rows of the fast partial rounds.
+    fast_partial_round_w_hats: [[Lanes; N]; N_PARTIAL_ROUNDS],
+}
+
+/// Prepends `first` to an array indexed by state elements `1..12`.
+const fn with_first(first: u64, c: [u64; SPONGE_WIDTH - 1]) -> [u64; SPONGE_WIDTH] {
+    let mut v = [first; SPONGE_WIDTH];
+    let mut i = 1;
+    while i < SPONGE_WIDTH {
+        v[i] = c[i - 1];
+        i += 1;
+    }
+    v
+}
+
+impl<const N: usize> Layout<N> {
+    const fn new() -> Self {
+        let elements_per_vector = if N == 2 { 8 } else { 4 };
+
+        let mut first_element_mask = 0;
+        let mut broadcast_indices = [[0; 8]; 8];
+        let mut lane = 0;
+        while lane < 8 {
+            let state_offset = lane / elements_per_vector * elements_per_vector;
+            if lane == state_offset {
+                first_element_mask |= 1 << lane;
+            }
+            let mut i = 0;
+            while i < elements_per_vector {
+                broadcast_indices[i][lane] = (state_offset + i) as u64;
+                i += 1;
+            }
+            lane += 1;
+        }
+
+        let mut sum_indices = [[0; 8]; 3];
+        let mut num_sum_indices = 0;
+        while 1 << num_sum_indices < elements_per_vector {
+            let mut lane = 0;
+            while lane < 8 {
+                sum_indices[num_sum_indices][lane] = (lane ^ (1 << num_sum_indices)) as u64;
+                lane += 1;
+            }
+            num_sum_indices += 1;
+        }
+
+        let mut round_constants = [[[0; 8]; N]; N_ROUNDS];
+        let mut r = 0;
+        while r < N_ROUNDS {
+            let mut c = [0; SPONGE_WIDTH];
+            let mut i = 0;
+            while i < SPONGE_WIDTH {
+                c[i] = ALL_ROUND_CONSTANTS[i + SPONGE_WIDTH * r];
+                i += 1;
+            }
+            round_constants[r] = Self::lay_out(c, elements_per_vector);
+            r += 1;
+        }
+
+        let circ = <GoldilocksField as Poseidon>::MDS_MATRIX_CIRC;
+        let diag = <GoldilocksField as Poseidon>::MDS_MATRIX_DIAG;
+        let mut mds_columns = [[[0; 8]; N]; SPONGE_WIDTH];
+        let mut j = 0;
+        while j < SPONGE_WIDTH {
+            let mut column = [0; SPONGE_WIDTH];
+            let mut r = 0;
+            while r < SPONGE_WIDTH {
+                column[r] = circ[(j + SPONGE_WIDTH - r) % SPONGE_WIDTH];
+                if r == j {
+                    column[r] += diag[r];
+                }
+                r += 1;
+            }
+            mds_columns[j] = Self::lay_out(column, elements_per_vector);
+            j += 1;
+        }
+
+        let fast_partial_first_round_constant = Self::lay_out(
+            <GoldilocksField as Poseidon>::FAST_PARTIAL_FIRST_ROUND_CONSTANT,
+            elements_per_vector,
+        );
+
+        let matrix = <GoldilocksField as Poseidon>::FAST_PARTIAL_ROUND_INITIAL_MATRIX;
+        let mut fast_partial_round_initial_rows = [[[0; 8]; N]; SPONGE_WIDTH - 1];
+        let mut r = 0;
+        while r < SPONGE_WIDTH - 1 {
+            fast_partial_round_initial_rows[r] =
+                Self::lay_out(with_first(0, matrix[r]), elements_per_vector);
+            r += 1;
+        }
+
+        let vs = <GoldilocksField as Poseidon>::FAST_PARTIAL_ROUND_VS;
+        let w_hats = <GoldilocksField as Poseidon>::FAST_PARTIAL_ROUND_W_HATS;
+        let mut fast_partial_round_vs = [[[0; 8]; N]; N_PARTIAL_ROUNDS];
+        let mut fast_partial_round_w_hats = [[[0; 8]; N]; N_PARTIAL_ROUNDS];
+        let mut r = 0;
+        while r < N_PARTIAL_ROUNDS {
+            fast_partial_round_vs[r] = Self::lay_out(with_first(0, vs[r]), elements_per_vector);
+            fast_partial_round_w_hats[r] = Self::lay_out(
+                with_first(circ[0] + diag[0], w_hats[r]),
+                elements_per_vector,
+            );
+            r += 1;
+        }
+
+        Self {
+            elements_per_vector,
+            first_element_mask,
+            broadcast_indices,
+            sum_indices,
+            num_sum_indices,
+            round_constants,
+            mds_columns,
+            fast_partial_first_round_constant,
+            fast_partial_round_initial_rows,
+            fast_partial_round_vs,
+            fast_partial_round_w_hats,
+        }
+    }
+
+    /// Lays out an array indexed by state elements, for each state. Unused lanes are zero.
+    const fn lay_out(c: [u64; SPONGE_WIDTH], elements_per_vector: usize) -> [Lanes; N] {
+        let mut v = [[0; 8]; N];
+        let mut state_offset = 0;
+        while state_offset < 8 {
+            let mut i = 0;
+            while i < SPONGE_WIDTH {
+                v[i / elements_per_vector][i % elements_per_vector + state_offset] = c[i];
+                i += 1;
+            }
+            state_offset += elements_per_vector;
+        }
+        v
+    }
+
+    /// Returns a vector holding `state[i]` of each state in all the lanes of that state.
+    #[inline(always)]
+    unsafe fn broadcast(&self, state: &[__m512i; N], i: usize) -> __m512i {
+        let idx = lanes_to_m512i(self.broadcast_indices[i % self.elements_per_vector]);
+        _mm512_permutexvar_epi64(idx, state[i / self.elements_per_vector])
+    }
+
+    /// Sums the lanes of each state, returning the sum in all the lanes of that state.
+    #[inline(always)]
+    unsafe fn sum_lanes(&self, mut x: Packed) -> Packed {
+        for &idx in &self.sum_indices[..self.num_sum_indices] {
+            x += from_m512i(_mm512_permutexvar_epi64(lanes_to_m512i(idx), to_m512i(x)));
+        }
+        x
+    }
+
+    /// Replaces `state[0]` of each state in `x` by that in `first`.
+    #[inline(always)]
+    unsafe fn set_first_element(&self, x: Packed, first: Packed) -> Packed {
+        from_m512i(_mm512_mask_mov_epi64(
+            to_m512i(x),
+            self.first_element_mask,
+            to_m512i(first),
+        ))
+    }
+
+    #[inline(always)]
+    fn constant_layer(&self, state: &mut [Packed; N], c: &[Lanes; N]) {
+        for k in 0..N {
+            state[k] += packed(c[k]);
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn mds_layer(&self, state: &[Packed; N]) -> [Packed; N] {
+        // The coefficients of each row sum to less than 2^10, so the products of the coefficients
+        // with the 32-bit halves of the state are accumulated without overflow.
+        let lo_mask = _mm512_set1_epi64(0xffffffff);
+        let state = state.map(to_m512i);
+        let state_lo = state.map(|x| _mm512_and_si512(x, lo_mask));
+        let state_hi = state.map(|x| _mm512_srli_epi64::<32>(x));
+
+        let mut acc_lo = [_mm512_setzero_si512(); N];
+        let mut acc_hi = [_mm512_setzero_si512(); N];
+        for j in 0..SPONGE_WIDTH {
+            let x_lo = self.broadcast(&state_lo, j);
+            let x_hi = self.broadcast(&state_hi, j);
+            for k in 0..N {
+                let c = lanes_to_m512i(self.mds_columns[j][k]);
+                acc_lo[k] = _mm512_add_epi64(acc_lo[k], _mm512_mul_epu32(x_lo, c));
+                acc_hi[k] = _mm512_add_epi64(acc_hi[k], _mm512_mul_epu32(x_hi, c));
+            }
+        }
+
+        array::from_fn(|k| from_m512i(reduce_lo_hi(acc_lo[k], acc_hi[k])))
+    }
+
+    #[inline(always)]
+    unsafe fn full_rounds(&self, state: &mut [Packed; N], round_ctr: &mut usize) {
+        for _ in 0..HALF_N_FULL_ROUNDS {
+            self.constant_layer(state, &self.round_constants[*round_ctr]);
+            for k in 0..N {
+                state[k] = sbox(state[k]);
+            }
+            *state = self.mds_layer(state);
+            *round_ctr += 1;
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn partial_rounds(&self, state: &mut [Packed; N], round_ctr: &mut usize) {
+        self.constant_layer(state, &self.fast_partial_first_round_constant);
+
+        // mds_partial_layer_init
+        let lanes = state.map(to_m512i);
+        let mut result = [Packed::ZEROS; N];
+        result[0] = from_m512i(_mm512_maskz_mov_epi64(self.first_element_mask, lanes[0]));
+        for r in 1..SPONGE_WIDTH {
+            let x = from_m512i(self.broadcast(&lanes, r));
+            for k in 0..N {
+                result[k] += x * packed(self.fast_partial_round_initial_rows[r - 1][k]);
+            }
+        }
+        *state = result;
+
+        for i in 0..N_PARTIAL_ROUNDS {
+            // Only `state[0]` goes through the S-box, but it is computed for all lanes.
+            let c = <GoldilocksField as Poseidon>::FAST_PARTIAL_ROUND_CONSTANTS[i];
+            let first = sbox(state[0]) + packed([c; 8]);
+            state[0] = self.set_first_element(state[0], first);
+
+            // mds_partial_layer_fast
+            let mut d = Packed::ZEROS;
+            for k in 0..N {
+                d += state[k] * packed(self.fast_partial_round_w_hats[i][k]);
+            }
+            let d = self.sum_lanes(d);
+            let s0 = from_m512i(self.broadcast(&state.map(to_m512i), 0));
+            for k in 0..N {
+                state[k] += s0 * packed(self.fast_partial_round_vs[i][k]);
+            }
+            state[0] = self.set_first_element(state[0], d);
+        }
+        *round_ctr += N_PARTIAL_ROUNDS;
+    }
+
+    /// Applies the permutation to the given states, of which there are `8 / elements_per_vector`.
+    #[inline(always)]
+    fn permute(&self, inputs: &[[GoldilocksField; SPONGE_WIDTH]]) -> [Packed; N] {
+        let mut lanes = [[GoldilocksField(0); 8]; N];
+        for (s, input) in inputs.iter().enumerate() {
+            for (i, &x) in input.iter().enumerate() {
+                lanes[i / self.elements_per_vector]
+                    [i % self.elements_per_vector + s * self.elements_per_vector] = x;
+            }
+        }
+        let mut state = lanes.map(|v| *Packed::from_slice(&v));
+
+        let mut round_ctr = 0;
+        // SAFETY: This module is only compiled with the required AVX-512 features.
+        unsafe {
+            self.full_rounds(&mut state, &mut round_ctr);
+            self.partial_rounds(&mut state, &mut round_ctr);
+            self.full_rounds(&mut state, &mut round_ctr);
+        }
+        debug_assert_eq!(round_ctr, N_ROUNDS);
+
+        state
+    }
+
+    /// Returns element `i` of state `s`.
+    #[inline(always)]
+    fn get(&self, state: &[Packed; N], s: usize, i: usize) -> GoldilocksField {
+        state[i / self.elements_per_vector].as_slice()
+            [i % self.elements_per_vector + s * self.elements_per_vector]
+    }
+}
+
+const ONE_STATE: Layout<2> = Layout::new();
+const TWO_STATES: Layout<3> = Layout::new();
+
+#[inline(always)]
+fn packed(c: Lanes) -> Packed {
+    // SAFETY: `Packed` wraps `[GoldilocksField; 8]`, and `GoldilocksField` wraps `u64`.
+    unsafe { transmute(c) }
+}
+
+#[inline(always)]
+fn lanes_to_m512i(c: Lanes) -> __m512i {
+    // SAFETY: Both are 512-bit plain data.
+    unsafe { transmute(c) }
+}
+
+#[inline(always)]
+fn to_m512i(x: Packed) -> __m512i {
+    // SAFETY: Both are 512-bit plain data.
+    unsafe { transmute(x) }
+}
+
+#[inline(always)]
+fn from_m512i(x: __m512i) -> Packed {
+    // SAFETY: Both are 512-bit plain data.
+    unsafe { transmute(x) }
+}
+
+#[inline(always)]
+fn sbox(x: Packed) -> Packed {
+    // x |--> x^7
+    let x2 = x * x;
+    let x4 = x2 * x2;
+    let x3 = x * x2;
+    x3 * x4
+}
+
+/// Reduces `lo + 2^32 * hi` for `lo, hi < 2^42` to a 64-bit representative.
+#[inline(always)]
+unsafe fn reduce_lo_hi(lo: __m512i, hi: __m512i) -> __m512i {
+    // lo + 2^32 * hi = sum + 2^64 * high, and 2^64 = EPSILON mod p.
+    let sum = _mm512_add_epi64(lo, _mm512_slli_epi64::<32>(hi));
+    let carry = _mm512_cmplt_epu64_mask(sum, lo);
+    let high = _mm512_srli_epi64::<32>(hi);
+    let high = _mm512_mask_add_epi64(high, carry, high, _mm512_set1_epi64(1));
+
+    // high * EPSILON < 2^42, so the sum overflows at most once, and adding EPSILON when it does
+    // can't overflow again.
+    let epsilon = _mm512_set1_epi64(EPSILON as i64);
+    let res = _mm512_add_epi64(sum, _mm512_mul_epu32(high, epsilon));
+    let overflow = _mm512_cmplt_epu64_mask(res, sum);
+    _mm512_mask_add_epi64(res, overflow, res, epsilon)
+}
+
+/// Applies the Poseidon permutation to one state.
+#[inline]
+pub(crate) fn poseidon(input: &[GoldilocksField; SPONGE_WIDTH]) -> [GoldilocksField; SPONGE_WIDTH] {
+    let state = ONE_STATE.permute(&[*input]);
+    array::from_fn(|i| ONE_STATE.get(&state, 0, i))
+}
+
+/// Applies the Poseidon permutation to two states.
+#[inline]
+pub(crate) fn poseidon_x2(
+    inputs: [[GoldilocksField; SPONGE_WIDTH]; 2],
+) -> [[GoldilocksField; SPONGE_WIDTH]; 2] {
+    let state = TWO_STATES.permute(&inputs);
+    array::from_fn(|s| array::from_fn(|i| TWO_STATES.get(&state, s, i)))
+}
@@ -90,6 +90,13 @@ fn fill_subtree<F: RichField, H: Hasher<F>>(
     assert_eq!(leaves.len(), digests_buf.len() / 2 + 1);
     if digests_buf.is_empty() {
         H::hash_or_noop(&leaves[0])
+    } else if digests_buf.len() == 2 {
+        // Two leaves, whose digests are the children's. Hash them together, which is faster with a
+        // vectorized permutation.
+        let [left_digest, right_digest] = H::hash_or_noop_x2([&leaves[0], &leaves[1]]);
+        digests_buf[0].write(left_digest);
+        digests_buf[1].write(right_digest);
+        H::two_to_one(left_digest, right_digest)
     } else {
         // Layout is: left recursive output || left child digest
         //             || right child digest || right recursive output.
@@ -124,10 +131,17 @@ fn fill_digests_buf<F: RichField, H: Hasher<F>>(
     if digests_buf.is_empty() {
         debug_assert_eq!(cap_buf.len(), leaves.len());
         cap_buf
-            .par_iter_mut()
-            .zip(leaves)
-            .for_each(|(cap_buf, leaf)| {
-                cap_buf.write(H::hash_or_noop(leaf));
+            .par_chunks_mut(2)
+            .zip(leaves.par_chunks(2))
+            .for_each(|(cap_buf, leaves)| match (cap_buf, leaves) {
+                ([left_buf, right_buf], [left_leaf, right_leaf]) => {
+                    let [left, right] = H::hash_or_noop_x2([left_leaf, right_leaf]);
+                    left_buf.write(left);
+                    right_buf.write(right);
+                }
+                (cap_buf, leaves) => {
+                    cap_buf[0].write(H::hash_or_noop(&leaves[0]));
+                }
             });
         return;
     }
@@ -11,3 +11,4 @@ pub mod path_compression;
 pub mod poseidon;
 pub mod poseidon_goldilocks;
 pub mod sha256;
+pub mod sparse_merkle_tree;
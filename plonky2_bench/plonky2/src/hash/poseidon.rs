@@ -13,7 +13,7 @@ use crate::field::types::{Field, PrimeField64};
 use crate::gates::gate::Gate;
 use crate::gates::poseidon::PoseidonGate;
 use crate::gates::poseidon_mds::PoseidonMdsGate;
-use crate::hash::hash_types::{HashOut, RichField};
+use crate::hash::hash_types::{HashOut, RichField, NUM_HASH_OUT_ELTS};
 use crate::hash::hashing::{compress, hash_n_to_hash_no_pad, PlonkyPermutation};
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::{BoolTarget, Target};
@@ -776,6 +776,13 @@ pub trait Poseidon: PrimeField64 {
         state
     }
 
+    /// Applies the permutation to two states. Vectorized implementations override it to permute
+    /// both states together.
+    #[inline]
+    fn poseidon_x2(inputs: [[Self; SPONGE_WIDTH]; 2]) -> [[Self; SPONGE_WIDTH]; 2] {
+        inputs.map(Self::poseidon)
+    }
+
     // For testing only, to ensure that various tricks are correct.
     #[inline]
     fn partial_rounds_naive(state: &mut [Self; SPONGE_WIDTH], round_ctr: &mut usize) {
@@ -881,6 +888,26 @@ impl<F: RichField> Hasher<F> for PoseidonHash {
         hash_n_to_hash_no_pad::<F, Self::Permutation>(input)
     }
 
+    fn hash_or_noop_x2(inputs: [&[F]; 2]) -> [Self::Hash; 2] {
+        let [a, b] = inputs;
+        // Only inputs which are hashed, and go through the same number of permutations, can share
+        // them.
+        if a.len() != b.len() || a.len() * 8 <= <Self as Hasher<F>>::HASH_SIZE {
+            return inputs.map(Self::hash_or_noop);
+        }
+
+        // Same as `hash_n_to_hash_no_pad`, whose output fits in a single squeeze.
+        let mut states = [[F::ZERO; SPONGE_WIDTH]; 2];
+        for (a_chunk, b_chunk) in a.chunks(SPONGE_RATE).zip(b.chunks(SPONGE_RATE)) {
+            states[0][..a_chunk.len()].copy_from_slice(a_chunk);
+            states[1][..b_chunk.len()].copy_from_slice(b_chunk);
+            states = F::poseidon_x2(states);
+        }
+        states.map(|state| HashOut {
+            elements: state[..NUM_HASH_OUT_ELTS].try_into().unwrap(),
+        })
+    }
+
     fn two_to_one(left: Self::Hash, right: Self::Hash) -> Self::Hash {
         compress::<F, Self::Permutation>(left, right)
     }
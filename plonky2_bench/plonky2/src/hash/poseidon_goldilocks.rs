@@ -247,6 +247,20 @@ impl Poseidon for GoldilocksField {
         result
     }
 
+    #[cfg(all(target_arch="x86_64", target_feature="avx512bw", target_feature="avx512cd",
+              target_feature="avx512dq", target_feature="avx512f", target_feature="avx512vl"))]
+    #[inline]
+    fn poseidon(input: [Self; 12]) -> [Self; 12] {
+        crate::hash::arch::x86_64::poseidon_goldilocks_avx512::poseidon(&input)
+    }
+
+    #[cfg(all(target_arch="x86_64", target_feature="avx512bw", target_feature="avx512cd",
+              target_feature="avx512dq", target_feature="avx512f", target_feature="avx512vl"))]
+    #[inline]
+    fn poseidon_x2(inputs: [[Self; 12]; 2]) -> [[Self; 12]; 2] {
+        crate::hash::arch::x86_64::poseidon_goldilocks_avx512::poseidon_x2(inputs)
+    }
+
     // #[cfg(all(target_arch="x86_64", target_feature="avx2", target_feature="bmi2"))]
     // #[inline]
     // fn poseidon(input: [Self; 12]) -> [Self; 12] {
@@ -448,8 +462,10 @@ mod tests {
     use alloc::{vec, vec::Vec};
 
     use crate::field::goldilocks_field::GoldilocksField as F;
-    use crate::field::types::{Field, PrimeField64};
+    use crate::field::types::{Field, PrimeField64, Sample};
     use crate::hash::poseidon::test_helpers::{check_consistency, check_test_vectors};
+    use crate::hash::poseidon::{Poseidon, PoseidonHash, SPONGE_WIDTH};
+    use crate::plonk::config::Hasher;
 
     #[test]
     fn test_vectors() {
@@ -493,4 +509,45 @@ mod tests {
     fn consistency() {
         check_consistency::<F>();
     }
+
+    #[test]
+    fn poseidon_x2_matches_naive() {
+        // Random inputs, and non-canonical ones close to 2^64.
+        let mut inputs = (0..64)
+            .map(|_| F::rand_array::<SPONGE_WIDTH>())
+            .collect::<Vec<_>>();
+        inputs.push(core::array::from_fn(|i| F(u64::MAX - i as u64)));
+        inputs.push([F(u64::MAX); SPONGE_WIDTH]);
+
+        for pair in inputs.chunks_exact(2) {
+            let outputs = F::poseidon_x2([pair[0], pair[1]]);
+            for (input, output) in pair.iter().zip(outputs) {
+                assert_eq!(output, F::poseidon_naive(*input));
+                assert_eq!(output, F::poseidon(*input));
+            }
+        }
+    }
+
+    #[test]
+    fn hash_or_noop_x2() {
+        for len in [0, 3, 4, 5, 8, 9, 16, 135] {
+            let a = F::rand_vec(len);
+            let b = F::rand_vec(len);
+            let c = F::rand_vec(len + 1);
+            assert_eq!(
+                PoseidonHash::hash_or_noop_x2([&a, &b]),
+                [
+                    PoseidonHash::hash_or_noop(&a),
+                    PoseidonHash::hash_or_noop(&b)
+                ]
+            );
+            assert_eq!(
+                PoseidonHash::hash_or_noop_x2([&a, &c]),
+                [
+                    PoseidonHash::hash_or_noop(&a),
+                    PoseidonHash::hash_or_noop(&c)
+                ]
+            );
+        }
+    }
 }
@@ -6,6 +6,8 @@ use crate::iop::generator::{GeneratedValues, SimpleGenerator};
 use crate::iop::target::{BoolTarget, Target};
 use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
 use itertools::Itertools;
 use plonky2_field::extension::Extendable;
 use std::borrow::Borrow;
@@ -86,19 +88,21 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderSplit<F, D>
             self.assert_zero(Target::wire(row, l));
         }
 
-        self.add_simple_generator(BaseSumGenerator::<D> { row, limbs: bits });
+        self.add_simple_generator(BaseSumGenerator::<2> { row, limbs: bits });
 
         Target::wire(row, BaseSumGate::<D>::WIRE_SUM)
     }
 }
 
-#[derive(Debug)]
-struct BaseSumGenerator<const B: usize> {
+#[derive(Debug, Default)]
+pub struct BaseSumGenerator<const B: usize> {
     row: usize,
     limbs: Vec<BoolTarget>,
 }
 
-impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for BaseSumGenerator<D> {
+impl<F: RichField + Extendable<D>, const B: usize, const D: usize> SimpleGenerator<F, D>
+    for BaseSumGenerator<B>
+{
     fn dependencies(&self) -> Vec<Target> {
         self.limbs.iter().map(|b| b.target).collect()
     }
@@ -110,32 +114,25 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Bas
             .map(|&t| witness.get_bool_target(t))
             .rev()
             .fold(F::ZERO, |acc, limb| {
-                acc * F::from_canonical_usize(D) + F::from_bool(limb)
+                acc * F::from_canonical_usize(B) + F::from_bool(limb)
             });
 
-        out_buffer.set_target(Target::wire(self.row, BaseSumGate::<D>::WIRE_SUM), sum);
+        out_buffer.set_target(Target::wire(self.row, BaseSumGate::<B>::WIRE_SUM), sum);
     }
 
     fn id(&self) -> String {
-        todo!()
+        format!("Sha256BaseSumGenerator + Base: {B}")
     }
 
-    fn serialize(
-        &self,
-        _: &mut Vec<u8>,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<()> {
-        todo!()
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_target_bool_vec(&self.limbs)
     }
 
-    fn deserialize(
-        _: &mut crate::util::serialization::Buffer,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!()
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let limbs = src.read_target_bool_vec()?;
+        Ok(Self { row, limbs })
     }
 }
 
@@ -0,0 +1,492 @@
+//! A sparse Merkle tree of fixed depth, mapping `u64` keys to single field element values, with
+//! circuit gadgets to verify updates of its root.
+//!
+//! A value of zero denotes an empty leaf. Leaves are digested with `hash_or_noop`, like the leaves
+//! of [`MerkleTree`](crate::hash::merkle_tree::MerkleTree), so that the digests of empty subtrees
+//! can be precomputed once per level.
+//!
+//! Keys are witnessed as a single field element in the circuit, so they must be below the field
+//! order. This only restricts trees of depth 64: keys out of range are rejected by the tree, and
+//! the circuit only accepts the canonical bits of the key.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use anyhow::{ensure, Result};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::{HashOutTarget, RichField, NUM_HASH_OUT_ELTS};
+use crate::hash::hashing::PlonkyPermutation;
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::WitnessWrite;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::{AlgebraicHasher, Hasher};
+
+/// A sparse Merkle tree. Only the leaves and internal nodes which differ from those of the empty
+/// tree are stored.
+#[derive(Clone, Debug)]
+pub struct SparseMerkleTree<F: RichField, H: Hasher<F>> {
+    depth: usize,
+    /// The non-zero leaf values.
+    leaves: HashMap<u64, F>,
+    /// The non-empty node digests, indexed by height (leaves being at height 0) and index within
+    /// their layer.
+    nodes: HashMap<(usize, u64), H::Hash>,
+    /// The digest of an empty subtree of each height, from the leaves up to the root.
+    empty_digests: Vec<H::Hash>,
+}
+
+/// The siblings of a leaf in a [`SparseMerkleTree`].
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(bound = "")]
+pub struct SparseMerkleProof<F: RichField, H: Hasher<F>> {
+    /// The Merkle digest of each sibling subtree, starting from the bottommost layer.
+    pub siblings: Vec<H::Hash>,
+}
+
+/// A change of the value of a single key, along with the roots before and after it.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(bound = "")]
+pub struct SparseMerkleUpdate<F: RichField, H: Hasher<F>> {
+    pub key: u64,
+    pub old_value: F,
+    pub new_value: F,
+    pub old_root: H::Hash,
+    pub new_root: H::Hash,
+    /// The siblings of the updated leaf, which are identical before and after the update.
+    pub proof: SparseMerkleProof<F, H>,
+}
+
+fn leaf_digest<F: RichField, H: Hasher<F>>(value: F) -> H::Hash {
+    H::hash_or_noop(&[value])
+}
+
+/// Computes the root of a tree with the given leaf value and siblings.
+fn root_from_leaf<F: RichField, H: Hasher<F>>(
+    key: u64,
+    value: F,
+    proof: &SparseMerkleProof<F, H>,
+) -> H::Hash {
+    let mut index = key;
+    let mut current_digest = leaf_digest::<F, H>(value);
+    for &sibling_digest in &proof.siblings {
+        current_digest = if index & 1 == 1 {
+            H::two_to_one(sibling_digest, current_digest)
+        } else {
+            H::two_to_one(current_digest, sibling_digest)
+        };
+        index >>= 1;
+    }
+    current_digest
+}
+
+/// Verifies that `key` maps to `value` in the sparse Merkle tree with the given root. A value of
+/// zero proves that the key is absent.
+pub fn verify_sparse_merkle_proof<F: RichField, H: Hasher<F>>(
+    key: u64,
+    value: F,
+    root: H::Hash,
+    proof: &SparseMerkleProof<F, H>,
+) -> Result<()> {
+    ensure!(
+        proof.siblings.len() >= 64 || key >> proof.siblings.len() == 0,
+        "Key out of range."
+    );
+    ensure!(
+        root_from_leaf(key, value, proof) == root,
+        "Invalid sparse Merkle proof."
+    );
+    Ok(())
+}
+
+/// Verifies that applying `update` to the tree with root `update.old_root` results in the tree
+/// with root `update.new_root`.
+pub fn verify_sparse_merkle_update<F: RichField, H: Hasher<F>>(
+    update: &SparseMerkleUpdate<F, H>,
+) -> Result<()> {
+    verify_sparse_merkle_proof(update.key, update.old_value, update.old_root, &update.proof)?;
+    verify_sparse_merkle_proof(update.key, update.new_value, update.new_root, &update.proof)
+}
+
+impl<F: RichField, H: Hasher<F>> SparseMerkleTree<F, H> {
+    /// Creates an empty tree with `2^depth` leaves.
+    pub fn new(depth: usize) -> Self {
+        assert!(depth <= 64, "Keys are limited to 64 bits.");
+        let mut empty_digests = Vec::with_capacity(depth + 1);
+        empty_digests.push(leaf_digest::<F, H>(F::ZERO));
+        for height in 0..depth {
+            let child = empty_digests[height];
+            empty_digests.push(H::two_to_one(child, child));
+        }
+        Self {
+            depth,
+            leaves: HashMap::new(),
+            nodes: HashMap::new(),
+            empty_digests,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn root(&self) -> H::Hash {
+        self.node(self.depth, 0)
+    }
+
+    /// Returns the value of `key`, which is zero if the key is absent.
+    pub fn get(&self, key: u64) -> F {
+        self.leaves.get(&key).copied().unwrap_or(F::ZERO)
+    }
+
+    pub fn contains_key(&self, key: u64) -> bool {
+        self.leaves.contains_key(&key)
+    }
+
+    /// Number of non-empty leaves.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    fn node(&self, height: usize, index: u64) -> H::Hash {
+        self.nodes
+            .get(&(height, index))
+            .copied()
+            .unwrap_or(self.empty_digests[height])
+    }
+
+    fn check_key(&self, key: u64) -> Result<()> {
+        ensure!(
+            self.depth == 64 || key >> self.depth == 0,
+            "Key {} does not fit in a tree of depth {}.",
+            key,
+            self.depth
+        );
+        ensure!(
+            key < F::ORDER,
+            "Key {} is not below the field order, so it cannot be witnessed in a circuit.",
+            key
+        );
+        Ok(())
+    }
+
+    /// Returns the siblings of the leaf at `key`, proving its current value.
+    pub fn prove(&self, key: u64) -> Result<SparseMerkleProof<F, H>> {
+        self.check_key(key)?;
+        let siblings = (0..self.depth)
+            .map(|height| self.node(height, (key >> height) ^ 1))
+            .collect();
+        Ok(SparseMerkleProof { siblings })
+    }
+
+    /// Inserts a new key. Fails if the key is already present or if `value` is zero.
+    pub fn insert(&mut self, key: u64, value: F) -> Result<SparseMerkleUpdate<F, H>> {
+        ensure!(!self.contains_key(key), "Key {} is already present.", key);
+        ensure!(value != F::ZERO, "Cannot insert a zero value.");
+        self.set(key, value)
+    }
+
+    /// Changes the value of an existing key. Fails if the key is absent or if `value` is zero.
+    pub fn update(&mut self, key: u64, value: F) -> Result<SparseMerkleUpdate<F, H>> {
+        ensure!(self.contains_key(key), "Key {} is absent.", key);
+        ensure!(
+            value != F::ZERO,
+            "Cannot update to a zero value, use delete instead."
+        );
+        self.set(key, value)
+    }
+
+    /// Removes an existing key. Fails if the key is absent.
+    pub fn delete(&mut self, key: u64) -> Result<SparseMerkleUpdate<F, H>> {
+        ensure!(self.contains_key(key), "Key {} is absent.", key);
+        self.set(key, F::ZERO)
+    }
+
+    /// Sets the value of `key`, zero meaning removal, and returns the corresponding update.
+    pub fn set(&mut self, key: u64, value: F) -> Result<SparseMerkleUpdate<F, H>> {
+        let proof = self.prove(key)?;
+        let old_root = self.root();
+        let old_value = if value == F::ZERO {
+            self.leaves.remove(&key)
+        } else {
+            self.leaves.insert(key, value)
+        }
+        .unwrap_or(F::ZERO);
+
+        let mut digest = leaf_digest::<F, H>(value);
+        for height in 0..=self.depth {
+            let index = key.checked_shr(height as u32).unwrap_or(0);
+            if digest == self.empty_digests[height] {
+                self.nodes.remove(&(height, index));
+            } else {
+                self.nodes.insert((height, index), digest);
+            }
+            if height < self.depth {
+                let sibling = proof.siblings[height];
+                digest = if index & 1 == 1 {
+                    H::two_to_one(sibling, digest)
+                } else {
+                    H::two_to_one(digest, sibling)
+                };
+            }
+        }
+
+        Ok(SparseMerkleUpdate {
+            key,
+            old_value,
+            new_value: value,
+            old_root,
+            new_root: self.root(),
+            proof,
+        })
+    }
+}
+
+/// Targets for a [`SparseMerkleUpdate`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SparseMerkleUpdateTarget {
+    pub key: Target,
+    pub old_value: Target,
+    pub new_value: Target,
+    pub old_root: HashOutTarget,
+    pub new_root: HashOutTarget,
+    pub siblings: Vec<HashOutTarget>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn add_virtual_smt_update_target(&mut self, depth: usize) -> SparseMerkleUpdateTarget {
+        SparseMerkleUpdateTarget {
+            key: self.add_virtual_target(),
+            old_value: self.add_virtual_target(),
+            new_value: self.add_virtual_target(),
+            old_root: self.add_virtual_hash(),
+            new_root: self.add_virtual_hash(),
+            siblings: self.add_virtual_hashes(depth),
+        }
+    }
+
+    /// Verifies that setting `key` from `old_value` to `new_value` in the sparse Merkle tree with
+    /// root `old_root` results in the tree with root `new_root`. The depth of the tree is the
+    /// number of siblings, and `key` is range checked accordingly. When the keys span the whole
+    /// field, the bits of `key` are checked to be canonical, so that every key has a single leaf.
+    pub fn verify_smt_update<H: AlgebraicHasher<F>>(
+        &mut self,
+        old_root: HashOutTarget,
+        new_root: HashOutTarget,
+        key: Target,
+        old_value: Target,
+        new_value: Target,
+        siblings: &[HashOutTarget],
+    ) {
+        let key_bits = self.split_le(key, siblings.len());
+        if key_bits.len() >= F::BITS {
+            self.assert_bits_below_order(&key_bits);
+        }
+        let computed_old_root = self.smt_root_from_leaf::<H>(old_value, &key_bits, siblings);
+        self.connect_hashes(computed_old_root, old_root);
+        let computed_new_root = self.smt_root_from_leaf::<H>(new_value, &key_bits, siblings);
+        self.connect_hashes(computed_new_root, new_root);
+    }
+
+    /// Verifies a sequence of updates applied to the tree with root `old_root`, each one starting
+    /// from the root produced by the previous one. Returns the root after the last update.
+    pub fn verify_smt_updates<H: AlgebraicHasher<F>>(
+        &mut self,
+        old_root: HashOutTarget,
+        updates: &[SparseMerkleUpdateTarget],
+    ) -> HashOutTarget {
+        let mut root = old_root;
+        for update in updates {
+            self.connect_hashes(root, update.old_root);
+            self.verify_smt_update::<H>(
+                update.old_root,
+                update.new_root,
+                update.key,
+                update.old_value,
+                update.new_value,
+                &update.siblings,
+            );
+            root = update.new_root;
+        }
+        root
+    }
+
+    /// Checks that the little-endian `bits` encode an integer below the field order, i.e. they
+    /// are the canonical representation of their sum.
+    fn assert_bits_below_order(&mut self, bits: &[BoolTarget]) {
+        let max = F::ORDER - 1;
+        // Whether the higher bits so far equal those of `max`. Once they do, a one bit where
+        // `max` has a zero makes the integer larger.
+        let mut prefix_is_max = self._true();
+        for (i, &bit) in bits.iter().enumerate().rev() {
+            if (max >> i) & 1 == 1 {
+                prefix_is_max = self.and(prefix_is_max, bit);
+            } else {
+                let exceeds = self.mul(prefix_is_max.target, bit.target);
+                self.assert_zero(exceeds);
+            }
+        }
+    }
+
+    fn smt_root_from_leaf<H: AlgebraicHasher<F>>(
+        &mut self,
+        value: Target,
+        key_bits: &[BoolTarget],
+        siblings: &[HashOutTarget],
+    ) -> HashOutTarget {
+        debug_assert!(H::AlgebraicPermutation::RATE >= NUM_HASH_OUT_ELTS);
+
+        let zero = self.zero();
+        let mut state = self.hash_or_noop::<H>(vec![value]);
+        for (&bit, &sibling) in key_bits.iter().zip(siblings) {
+            let mut perm_inputs = H::AlgebraicPermutation::default();
+            perm_inputs.set_from_slice(&state.elements, 0);
+            perm_inputs.set_from_slice(&sibling.elements, NUM_HASH_OUT_ELTS);
+            // Ensure the rest of the state, if any, is zero:
+            perm_inputs.set_from_iter(core::iter::repeat(zero), 2 * NUM_HASH_OUT_ELTS);
+            let perm_outs = self.permute_swapped::<H>(perm_inputs, bit);
+            state = HashOutTarget {
+                elements: perm_outs.squeeze()[0..NUM_HASH_OUT_ELTS]
+                    .try_into()
+                    .unwrap(),
+            };
+        }
+        state
+    }
+}
+
+impl SparseMerkleUpdateTarget {
+    pub fn set_witness<F: RichField, H: AlgebraicHasher<F>, W: WitnessWrite<F>>(
+        &self,
+        witness: &mut W,
+        update: &SparseMerkleUpdate<F, H>,
+    ) {
+        witness.set_target(self.key, F::from_canonical_u64(update.key));
+        witness.set_target(self.old_value, update.old_value);
+        witness.set_target(self.new_value, update.new_value);
+        witness.set_hash_target(self.old_root, update.old_root);
+        witness.set_hash_target(self.new_root, update.new_root);
+        for (&t, &h) in self.siblings.iter().zip(&update.proof.siblings) {
+            witness.set_hash_target(t, h);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::{Field, Field64};
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type H = <C as GenericConfig<D>>::Hasher;
+
+    #[test]
+    fn test_sparse_merkle_tree() -> Result<()> {
+        let mut tree = SparseMerkleTree::<F, H>::new(32);
+        let empty_root = tree.root();
+
+        let insert = tree.insert(7, F::from_canonical_u64(100))?;
+        assert_eq!(insert.old_root, empty_root);
+        verify_sparse_merkle_update(&insert)?;
+        tree.insert(1 << 31, F::from_canonical_u64(5))?;
+        assert!(tree.insert(7, F::ONE).is_err());
+        assert!(tree.update(8, F::ONE).is_err());
+
+        let update = tree.update(7, F::from_canonical_u64(42))?;
+        verify_sparse_merkle_update(&update)?;
+        assert_eq!(update.old_value, F::from_canonical_u64(100));
+        verify_sparse_merkle_proof(7, F::from_canonical_u64(42), tree.root(), &tree.prove(7)?)?;
+        verify_sparse_merkle_proof(8, F::ZERO, tree.root(), &tree.prove(8)?)?;
+        assert!(verify_sparse_merkle_proof(7, F::ONE, tree.root(), &tree.prove(7)?).is_err());
+        assert!(tree.prove(1 << 32).is_err());
+
+        tree.delete(7)?;
+        tree.delete(1 << 31)?;
+        assert!(tree.is_empty());
+        assert_eq!(tree.root(), empty_root);
+
+        let mut tree = SparseMerkleTree::<F, H>::new(64);
+        tree.insert(F::ORDER - 1, F::ONE)?;
+        assert!(tree.insert(F::ORDER, F::ONE).is_err());
+        assert!(tree.prove(u64::MAX).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_smt_update_full_depth() -> Result<()> {
+        // The keys span the whole field, so the largest one has all the high bits set.
+        let mut tree = SparseMerkleTree::<F, H>::new(64);
+        let old_root = tree.root();
+        let update = tree.insert(F::ORDER - 1, F::ONE)?;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let old_root_target = builder.add_virtual_hash();
+        pw.set_hash_target(old_root_target, old_root);
+        let update_target = builder.add_virtual_smt_update_target(tree.depth());
+        update_target.set_witness(&mut pw, &update);
+        let new_root_target = builder.verify_smt_updates::<H>(old_root_target, &[update_target]);
+        let expected_new_root = builder.constant_hash(tree.root());
+        builder.connect_hashes(new_root_target, expected_new_root);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_recursive_smt_updates() -> Result<()> {
+        let depth = 16;
+        let mut tree = SparseMerkleTree::<F, H>::new(depth);
+        tree.insert(3, F::from_canonical_u64(1000))?;
+        let old_root = tree.root();
+
+        // A transfer of 10 from account 3 to the new account 12345, then closing account 3.
+        let updates = [
+            tree.update(3, F::from_canonical_u64(990))?,
+            tree.insert(12345, F::from_canonical_u64(10))?,
+            tree.delete(3)?,
+        ];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let old_root_target = builder.add_virtual_hash();
+        pw.set_hash_target(old_root_target, old_root);
+        let update_targets = updates
+            .iter()
+            .map(|update| {
+                let target = builder.add_virtual_smt_update_target(depth);
+                target.set_witness(&mut pw, update);
+                target
+            })
+            .collect::<Vec<_>>();
+        let new_root_target = builder.verify_smt_updates::<H>(old_root_target, &update_targets);
+        let expected_new_root = builder.constant_hash(tree.root());
+        builder.connect_hashes(new_root_target, expected_new_root);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
@@ -8,6 +8,9 @@ use alloc::{
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
+#[cfg(feature = "parallel")]
+use plonky2_maybe_rayon::*;
+
 use crate::field::extension::Extendable;
 use crate::field::types::Field;
 use crate::hash::hash_types::RichField;
@@ -21,6 +24,9 @@ use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
 /// Given a `PartitionWitness` that has only inputs set, populates the rest of the witness using the
 /// given set of generators.
+///
+/// With the `parallel` feature, generators are run in waves of independent generators, see
+/// `generate_partial_witness_in_waves`. Both strategies produce the same witness.
 pub fn generate_partial_witness<
     'a,
     F: RichField + Extendable<D>,
@@ -31,12 +37,30 @@ pub fn generate_partial_witness<
     prover_data: &'a ProverOnlyCircuitData<F, C, D>,
     common_data: &'a CommonCircuitData<F, D>,
 ) -> PartitionWitness<'a, F> {
-    let config = &common_data.config;
-    let generators = &prover_data.generators;
-    let generator_indices_by_watches = &prover_data.generator_indices_by_watches;
+    #[cfg(feature = "parallel")]
+    {
+        generate_partial_witness_in_waves(inputs, prover_data, common_data)
+    }
 
+    #[cfg(not(feature = "parallel"))]
+    {
+        generate_partial_witness_serial(inputs, prover_data, common_data)
+    }
+}
+
+/// Returns a `PartitionWitness` with only the given inputs set.
+fn initial_partition_witness<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> PartitionWitness<'a, F> {
     let mut witness = PartitionWitness::new(
-        config.num_wires,
+        common_data.config.num_wires,
         common_data.degree(),
         &prover_data.representative_map,
     );
@@ -45,6 +69,27 @@ pub fn generate_partial_witness<
         witness.set_target(t, v);
     }
 
+    witness
+}
+
+/// Runs generators one at a time, merging the values generated by each one before running the
+/// next.
+#[cfg_attr(feature = "parallel", allow(dead_code))]
+fn generate_partial_witness_serial<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> PartitionWitness<'a, F> {
+    let generators = &prover_data.generators;
+    let generator_indices_by_watches = &prover_data.generator_indices_by_watches;
+
+    let mut witness = initial_partition_witness(inputs, prover_data, common_data);
+
     // Build a list of "pending" generators which are queued to be run. Initially, all generators
     // are queued.
     let mut pending_generator_indices: Vec<_> = (0..generators.len()).collect();
@@ -102,6 +147,79 @@ pub fn generate_partial_witness<
     witness
 }
 
+/// Runs generators in waves. All generators of a wave are run in parallel against the same
+/// witness, then their outputs are merged in generator order, so the result doesn't depend on
+/// thread scheduling. The next wave consists of the unfinished generators watching a target
+/// populated by this one.
+#[cfg(feature = "parallel")]
+fn generate_partial_witness_in_waves<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> PartitionWitness<'a, F> {
+    let generators = &prover_data.generators;
+    let generator_indices_by_watches = &prover_data.generator_indices_by_watches;
+
+    let mut witness = initial_partition_witness(inputs, prover_data, common_data);
+
+    // Initially, all generators are run. Most of them will be waiting for their inputs.
+    let mut wave: Vec<_> = (0..generators.len()).collect();
+    let mut generator_is_expired = vec![false; generators.len()];
+    let mut remaining_generators = generators.len();
+    let mut num_waves = 0;
+
+    while !wave.is_empty() {
+        num_waves += 1;
+
+        let outputs: Vec<_> = wave
+            .par_iter()
+            .map(|&generator_idx| {
+                let mut buffer = GeneratedValues::empty();
+                let finished = generators[generator_idx].0.run(&witness, &mut buffer);
+                (finished, buffer)
+            })
+            .collect();
+
+        let mut next_wave = Vec::new();
+        for (&generator_idx, (finished, buffer)) in wave.iter().zip(outputs) {
+            if finished {
+                generator_is_expired[generator_idx] = true;
+                remaining_generators -= 1;
+            }
+
+            let new_target_reps = buffer
+                .target_values
+                .into_iter()
+                .flat_map(|(t, v)| witness.set_target_returning_rep(t, v));
+            for watch in new_target_reps {
+                if let Some(watchers) = generator_indices_by_watches.get(&watch) {
+                    next_wave.extend_from_slice(watchers);
+                }
+            }
+        }
+
+        // A generator must run at most once per wave, and not at all once it has finished.
+        next_wave.sort_unstable();
+        next_wave.dedup();
+        next_wave.retain(|&generator_idx| !generator_is_expired[generator_idx]);
+        wave = next_wave;
+    }
+
+    assert_eq!(
+        remaining_generators, 0,
+        "{} generators weren't run",
+        remaining_generators,
+    );
+    log::debug!("Ran {} generators in {} waves", generators.len(), num_waves);
+
+    witness
+}
+
 /// A generator participates in the generation of the witness.
 pub trait WitnessGenerator<F: RichField + Extendable<D>, const D: usize>:
     'static + Send + Sync + Debug
@@ -418,3 +536,86 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Con
         })
     }
 }
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    #[test]
+    fn test_generate_partial_witness_in_waves() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let inputs = builder.add_virtual_targets(8);
+        let mut state = inputs.clone();
+        for _ in 0..4 {
+            let hash = builder.hash_n_to_hash_no_pad::<<C as GenericConfig<D>>::InnerHasher>(state);
+            state = hash.elements.to_vec();
+            let product = builder.mul(state[0], state[1]);
+            let bits = builder.split_le(state[2], 64);
+            let sum = builder.le_sum(bits.into_iter().take(32));
+            state.push(product);
+            state.push(sum);
+        }
+        let quotient = builder.div(state[0], inputs[0]);
+        builder.register_public_input(quotient);
+        builder.register_public_inputs(&state);
+
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for &input in &inputs {
+            pw.set_target(input, F::rand());
+        }
+
+        // Random values, used for the unused wires of the public input gate, differ between runs.
+        let mut random_values = GeneratedValues::empty();
+        for generator in &data.prover_only.generators {
+            if generator.0.id() == "RandomValueGenerator" {
+                let empty_witness = initial_partition_witness(
+                    PartialWitness::new(),
+                    &data.prover_only,
+                    &data.common,
+                );
+                generator.0.run(&empty_witness, &mut random_values);
+            }
+        }
+        let random_targets = random_values
+            .target_values
+            .iter()
+            .map(|&(t, _)| t)
+            .collect::<Vec<_>>();
+
+        let serial = generate_partial_witness_serial(pw.clone(), &data.prover_only, &data.common);
+        let in_waves =
+            generate_partial_witness_in_waves(pw.clone(), &data.prover_only, &data.common);
+        for row in 0..data.common.degree() {
+            for column in 0..data.common.config.num_wires {
+                let target = Target::wire(row, column);
+                if random_targets.contains(&target) {
+                    continue;
+                }
+                assert_eq!(
+                    serial.try_get_target(target),
+                    in_waves.try_get_target(target),
+                    "witnesses differ at {:?}",
+                    target
+                );
+            }
+        }
+
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
@@ -15,7 +15,10 @@ use crate::iop::target::{BoolTarget, Target};
 use crate::iop::wire::Wire;
 use crate::plonk::circuit_data::{VerifierCircuitTarget, VerifierOnlyCircuitData};
 use crate::plonk::config::{AlgebraicHasher, GenericConfig};
-use crate::plonk::proof::{Proof, ProofTarget, ProofWithPublicInputs, ProofWithPublicInputsTarget};
+use crate::plonk::proof::{
+    BatchProofWithPublicInputs, BatchProofWithPublicInputsTarget, Proof, ProofTarget,
+    ProofWithPublicInputs, ProofWithPublicInputsTarget,
+};
 
 pub trait WitnessWrite<F: Field> {
     fn set_target(&mut self, target: Target, value: F);
@@ -94,6 +97,51 @@ pub trait WitnessWrite<F: Field> {
         self.set_proof_target(pt, proof);
     }
 
+    /// Set the targets in a `BatchProofWithPublicInputsTarget` to their corresponding values in a
+    /// `BatchProofWithPublicInputs`.
+    fn set_batch_proof_with_pis_target<C: GenericConfig<D, F = F>, const D: usize>(
+        &mut self,
+        proof_with_pis_target: &BatchProofWithPublicInputsTarget<D>,
+        proof_with_pis: &BatchProofWithPublicInputs<F, C, D>,
+    ) where
+        F: RichField + Extendable<D>,
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let BatchProofWithPublicInputs {
+            proof,
+            public_inputs,
+        } = proof_with_pis;
+        let BatchProofWithPublicInputsTarget {
+            proof: pt,
+            public_inputs: pi_targets,
+        } = proof_with_pis_target;
+
+        // Set public inputs.
+        for (pi_targets, public_inputs) in pi_targets.iter().zip_eq(public_inputs) {
+            for (&pi_t, &pi) in pi_targets.iter().zip_eq(public_inputs) {
+                self.set_target(pi_t, pi);
+            }
+        }
+
+        for (instance_target, instance) in pt.instances.iter().zip_eq(&proof.instances) {
+            self.set_cap_target(&instance_target.wires_cap, &instance.wires_cap);
+            self.set_cap_target(
+                &instance_target.plonk_zs_partial_products_cap,
+                &instance.plonk_zs_partial_products_cap,
+            );
+            self.set_cap_target(
+                &instance_target.quotient_polys_cap,
+                &instance.quotient_polys_cap,
+            );
+            self.set_fri_openings(
+                &instance_target.openings.to_fri_openings(),
+                &instance.openings.to_fri_openings(),
+            );
+        }
+
+        set_fri_proof_target(self, &pt.opening_proof, &proof.opening_proof);
+    }
+
     /// Set the targets in a `ProofTarget` to their corresponding values in a `Proof`.
     fn set_proof_target<C: GenericConfig<D, F = F>, const D: usize>(
         &mut self,
@@ -1,5 +1,6 @@
 extern crate alloc;
 
+use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
 use core::marker::PhantomData;
@@ -10,13 +11,16 @@ use crate::hash::hash_types::RichField;
 use crate::iop::generator::{GeneratedValues, SimpleGenerator};
 use crate::iop::target::{BoolTarget, Target};
 use crate::iop::witness::{PartitionWitness, Witness};
+use crate::nonnative::biguint::serialization::{ReadBigUint, WriteBigUint};
 use crate::nonnative::u32::gadgets::arithmetic_u32::{CircuitBuilderU32, U32Target};
 use crate::nonnative::u32::gadgets::multiple_comparison::list_le_u32_circuit;
 use crate::nonnative::u32::witness::{GeneratedValuesU32, WitnessU32};
 use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult};
 use num::{BigUint, Integer, Zero};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct BigUintTarget {
     pub limbs: Vec<U32Target>,
 }
@@ -315,8 +319,8 @@ impl<F: PrimeField> GeneratedValuesBigUint<F> for GeneratedValues<F> {
     }
 }
 
-#[derive(Debug)]
-struct BigUintDivRemGenerator<F: RichField + Extendable<D>, const D: usize> {
+#[derive(Debug, Default)]
+pub struct BigUintDivRemGenerator<F: RichField + Extendable<D>, const D: usize> {
     a: BigUintTarget,
     b: BigUintTarget,
     div: BigUintTarget,
@@ -346,24 +350,27 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
     }
 
     fn id(&self) -> String {
-        todo!()
+        "BigUintDivRemGenerator".to_string()
     }
 
-    fn serialize(
-        &self,
-        _: &mut Vec<u8>,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<()> {
-        todo!()
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_biguint(&self.a)?;
+        dst.write_target_biguint(&self.b)?;
+        dst.write_target_biguint(&self.div)?;
+        dst.write_target_biguint(&self.rem)
     }
 
-    fn deserialize(
-        _: &mut crate::util::serialization::Buffer,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!()
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = src.read_target_biguint()?;
+        let b = src.read_target_biguint()?;
+        let div = src.read_target_biguint()?;
+        let rem = src.read_target_biguint()?;
+        Ok(Self {
+            a,
+            b,
+            div,
+            rem,
+            _phantom: PhantomData,
+        })
     }
 }
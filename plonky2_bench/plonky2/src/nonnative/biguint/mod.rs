@@ -1,3 +1,4 @@
 pub mod biguint;
 pub mod nonnative;
+pub mod serialization;
 pub mod split_nonnative;
@@ -1,7 +1,9 @@
 extern crate alloc;
 
-use alloc::vec;
+use alloc::string::String;
 use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::any::type_name;
 use core::marker::PhantomData;
 
 use crate::field::extension::Extendable;
@@ -12,19 +14,23 @@ use crate::iop::target::{BoolTarget, Target};
 use crate::iop::witness::{PartitionWitness, WitnessWrite};
 use crate::nonnative::u32::gadgets::arithmetic_u32::{CircuitBuilderU32, U32Target};
 use crate::nonnative::u32::gadgets::range_check::range_check_u32_circuit;
+use crate::nonnative::u32::serialization::{ReadU32, WriteU32};
 use crate::nonnative::u32::witness::GeneratedValuesU32;
 use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
 use num::{BigUint, Integer, One, Zero};
 
 use crate::nonnative::biguint::biguint::{
     BigUintTarget, CircuitBuilderBiguint, GeneratedValuesBigUint, WitnessBigUint,
 };
+use crate::nonnative::biguint::serialization::{ReadBigUint, WriteBigUint};
 
 pub const fn ceil_div_usize(a: usize, b: usize) -> usize {
     (a + b - 1) / b
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct NonNativeTarget<FF: Field> {
     pub(crate) value: BigUintTarget,
     pub(crate) _phantom: PhantomData<FF>,
@@ -450,8 +456,9 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
     }
 }
 
-#[derive(Debug)]
-struct NonNativeAdditionGenerator<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> {
+#[derive(Debug, Default)]
+pub struct NonNativeAdditionGenerator<F: RichField + Extendable<D>, const D: usize, FF: PrimeField>
+{
     a: NonNativeTarget<FF>,
     b: NonNativeTarget<FF>,
     sum: NonNativeTarget<FF>,
@@ -491,31 +498,37 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     }
 
     fn id(&self) -> String {
-        todo!()
+        format!("NonNativeAdditionGenerator<{}>", type_name::<FF>())
     }
 
-    fn serialize(
-        &self,
-        _: &mut Vec<u8>,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<()> {
-        todo!()
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_nonnative(&self.a)?;
+        dst.write_target_nonnative(&self.b)?;
+        dst.write_target_nonnative(&self.sum)?;
+        dst.write_target_bool(self.overflow)
     }
 
-    fn deserialize(
-        _: &mut crate::util::serialization::Buffer,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!()
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = src.read_target_nonnative()?;
+        let b = src.read_target_nonnative()?;
+        let sum = src.read_target_nonnative()?;
+        let overflow = src.read_target_bool()?;
+        Ok(Self {
+            a,
+            b,
+            sum,
+            overflow,
+            _phantom: PhantomData,
+        })
     }
 }
 
-#[derive(Debug)]
-struct NonNativeMultipleAddsGenerator<F: RichField + Extendable<D>, const D: usize, FF: PrimeField>
-{
+#[derive(Debug, Default)]
+pub struct NonNativeMultipleAddsGenerator<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FF: PrimeField,
+> {
     summands: Vec<NonNativeTarget<FF>>,
     sum: NonNativeTarget<FF>,
     overflow: U32Target,
@@ -551,37 +564,47 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
 
         let modulus = FF::order();
         let (overflow_biguint, sum_reduced) = sum_biguint.div_rem(&modulus);
-        let overflow = overflow_biguint.to_u64_digits()[0] as u32;
+        let overflow = overflow_biguint
+            .to_u32_digits()
+            .first()
+            .copied()
+            .unwrap_or(0);
 
         out_buffer.set_biguint_target(&self.sum.value, &sum_reduced);
         out_buffer.set_u32_target(self.overflow, overflow);
     }
 
     fn id(&self) -> String {
-        todo!()
+        format!("NonNativeMultipleAddsGenerator<{}>", type_name::<FF>())
     }
 
-    fn serialize(
-        &self,
-        _: &mut Vec<u8>,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<()> {
-        todo!()
-    }
-
-    fn deserialize(
-        _: &mut crate::util::serialization::Buffer,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!()
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.summands.len())?;
+        for x in &self.summands {
+            dst.write_target_nonnative(x)?;
+        }
+        dst.write_target_nonnative(&self.sum)?;
+        dst.write_target_u32(self.overflow)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_summands = src.read_usize()?;
+        let summands = (0..num_summands)
+            .map(|_| src.read_target_nonnative())
+            .collect::<IoResult<Vec<_>>>()?;
+        let sum = src.read_target_nonnative()?;
+        let overflow = src.read_target_u32()?;
+        Ok(Self {
+            summands,
+            sum,
+            overflow,
+            _phantom: PhantomData,
+        })
     }
 }
 
-#[derive(Debug)]
-struct NonNativeSubtractionGenerator<F: RichField + Extendable<D>, const D: usize, FF: Field> {
+#[derive(Debug, Default)]
+pub struct NonNativeSubtractionGenerator<F: RichField + Extendable<D>, const D: usize, FF: Field> {
     a: NonNativeTarget<FF>,
     b: NonNativeTarget<FF>,
     diff: NonNativeTarget<FF>,
@@ -621,30 +644,34 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     }
 
     fn id(&self) -> String {
-        todo!()
+        format!("NonNativeSubtractionGenerator<{}>", type_name::<FF>())
     }
 
-    fn serialize(
-        &self,
-        _: &mut Vec<u8>,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<()> {
-        todo!()
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_nonnative(&self.a)?;
+        dst.write_target_nonnative(&self.b)?;
+        dst.write_target_nonnative(&self.diff)?;
+        dst.write_target_bool(self.overflow)
     }
 
-    fn deserialize(
-        _: &mut crate::util::serialization::Buffer,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!()
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = src.read_target_nonnative()?;
+        let b = src.read_target_nonnative()?;
+        let diff = src.read_target_nonnative()?;
+        let overflow = src.read_target_bool()?;
+        Ok(Self {
+            a,
+            b,
+            diff,
+            overflow,
+            _phantom: PhantomData,
+        })
     }
 }
 
-#[derive(Debug)]
-struct NonNativeMultiplicationGenerator<F: RichField + Extendable<D>, const D: usize, FF: Field> {
+#[derive(Debug, Default)]
+pub struct NonNativeMultiplicationGenerator<F: RichField + Extendable<D>, const D: usize, FF: Field>
+{
     a: NonNativeTarget<FF>,
     b: NonNativeTarget<FF>,
     prod: NonNativeTarget<FF>,
@@ -682,30 +709,33 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     }
 
     fn id(&self) -> String {
-        todo!()
+        format!("NonNativeMultiplicationGenerator<{}>", type_name::<FF>())
     }
 
-    fn serialize(
-        &self,
-        _: &mut Vec<u8>,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<()> {
-        todo!()
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_nonnative(&self.a)?;
+        dst.write_target_nonnative(&self.b)?;
+        dst.write_target_nonnative(&self.prod)?;
+        dst.write_target_biguint(&self.overflow)
     }
 
-    fn deserialize(
-        _: &mut crate::util::serialization::Buffer,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!()
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = src.read_target_nonnative()?;
+        let b = src.read_target_nonnative()?;
+        let prod = src.read_target_nonnative()?;
+        let overflow = src.read_target_biguint()?;
+        Ok(Self {
+            a,
+            b,
+            prod,
+            overflow,
+            _phantom: PhantomData,
+        })
     }
 }
 
-#[derive(Debug)]
-struct NonNativeInverseGenerator<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> {
+#[derive(Debug, Default)]
+pub struct NonNativeInverseGenerator<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> {
     x: NonNativeTarget<FF>,
     inv: BigUintTarget,
     div: BigUintTarget,
@@ -734,24 +764,24 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     }
 
     fn id(&self) -> String {
-        todo!()
+        format!("NonNativeInverseGenerator<{}>", type_name::<FF>())
     }
 
-    fn serialize(
-        &self,
-        _: &mut Vec<u8>,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<()> {
-        todo!()
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_nonnative(&self.x)?;
+        dst.write_target_biguint(&self.inv)?;
+        dst.write_target_biguint(&self.div)
     }
 
-    fn deserialize(
-        _: &mut crate::util::serialization::Buffer,
-        _: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> crate::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!()
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let x = src.read_target_nonnative()?;
+        let inv = src.read_target_biguint()?;
+        let div = src.read_target_biguint()?;
+        Ok(Self {
+            x,
+            inv,
+            div,
+            _phantom: PhantomData,
+        })
     }
 }
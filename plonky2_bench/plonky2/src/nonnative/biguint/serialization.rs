@@ -0,0 +1,58 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::field::types::Field;
+use crate::nonnative::biguint::biguint::BigUintTarget;
+use crate::nonnative::biguint::nonnative::NonNativeTarget;
+use crate::nonnative::u32::serialization::{ReadU32, WriteU32};
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+pub trait WriteBigUint {
+    fn write_target_biguint(&mut self, x: &BigUintTarget) -> IoResult<()>;
+
+    fn write_target_nonnative<FF: Field>(&mut self, x: &NonNativeTarget<FF>) -> IoResult<()>;
+}
+
+impl WriteBigUint for Vec<u8> {
+    #[inline]
+    fn write_target_biguint(&mut self, x: &BigUintTarget) -> IoResult<()> {
+        self.write_usize(x.num_limbs())?;
+        for &limb in &x.limbs {
+            self.write_target_u32(limb)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn write_target_nonnative<FF: Field>(&mut self, x: &NonNativeTarget<FF>) -> IoResult<()> {
+        self.write_target_biguint(&x.value)
+    }
+}
+
+pub trait ReadBigUint {
+    fn read_target_biguint(&mut self) -> IoResult<BigUintTarget>;
+
+    fn read_target_nonnative<FF: Field>(&mut self) -> IoResult<NonNativeTarget<FF>>;
+}
+
+impl ReadBigUint for Buffer<'_> {
+    #[inline]
+    fn read_target_biguint(&mut self) -> IoResult<BigUintTarget> {
+        let num_limbs = self.read_usize()?;
+        let limbs = (0..num_limbs)
+            .map(|_| self.read_target_u32())
+            .collect::<IoResult<Vec<_>>>()?;
+        Ok(BigUintTarget { limbs })
+    }
+
+    #[inline]
+    fn read_target_nonnative<FF: Field>(&mut self) -> IoResult<NonNativeTarget<FF>> {
+        let value = self.read_target_biguint()?;
+        Ok(NonNativeTarget {
+            value,
+            _phantom: PhantomData,
+        })
+    }
+}
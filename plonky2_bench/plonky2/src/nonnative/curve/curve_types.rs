@@ -0,0 +1,137 @@
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::ops::{Add, Neg};
+
+use serde::{Deserialize, Serialize};
+
+use crate::field::ops::Square;
+use crate::field::types::{Field, PrimeField};
+
+/// A short Weierstrass curve `y^2 = x^3 + A x + B`.
+pub trait Curve: 'static + Sync + Sized + Copy + Debug {
+    type BaseField: PrimeField;
+    type ScalarField: PrimeField;
+
+    const A: Self::BaseField;
+    const B: Self::BaseField;
+
+    const GENERATOR_AFFINE: AffinePoint<Self>;
+
+    /// Maps a base field element, such as an x-coordinate, to the scalar field.
+    fn base_to_scalar(x: Self::BaseField) -> Self::ScalarField {
+        Self::ScalarField::from_noncanonical_biguint(
+            x.to_canonical_biguint() % Self::ScalarField::order(),
+        )
+    }
+}
+
+/// A point on a short Weierstrass curve, represented in affine coordinates.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+#[serde(bound = "")]
+pub struct AffinePoint<C: Curve> {
+    pub x: C::BaseField,
+    pub y: C::BaseField,
+    pub zero: bool,
+}
+
+impl<C: Curve> AffinePoint<C> {
+    pub const ZERO: Self = Self {
+        x: C::BaseField::ZERO,
+        y: C::BaseField::ZERO,
+        zero: true,
+    };
+
+    pub fn nonzero(x: C::BaseField, y: C::BaseField) -> Self {
+        let point = Self { x, y, zero: false };
+        debug_assert!(point.is_valid());
+        point
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let Self { x, y, zero } = *self;
+        zero || y.square() == x.cube() + C::A * x + C::B
+    }
+
+    pub fn double(&self) -> Self {
+        if self.zero || self.y.is_zero() {
+            return Self::ZERO;
+        }
+        let Self { x, y, .. } = *self;
+        let lambda = (x.square().triple() + C::A) / y.double();
+        let x3 = lambda.square() - x.double();
+        let y3 = lambda * (x - x3) - y;
+        Self::nonzero(x3, y3)
+    }
+
+    /// Double-and-add scalar multiplication.
+    pub fn mul(&self, scalar: C::ScalarField) -> Self {
+        let scalar = scalar.to_canonical_biguint();
+        (0..scalar.bits()).rev().fold(Self::ZERO, |acc, i| {
+            let acc = acc.double();
+            if scalar.bit(i) {
+                acc + *self
+            } else {
+                acc
+            }
+        })
+    }
+}
+
+impl<C: Curve> PartialEq for AffinePoint<C> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.zero, other.zero) {
+            (true, true) => true,
+            (false, false) => self.x == other.x && self.y == other.y,
+            _ => false,
+        }
+    }
+}
+
+impl<C: Curve> Eq for AffinePoint<C> {}
+
+impl<C: Curve> Hash for AffinePoint<C> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        if self.zero {
+            self.zero.hash(state);
+        } else {
+            self.x.hash(state);
+            self.y.hash(state);
+        }
+    }
+}
+
+impl<C: Curve> Add for AffinePoint<C> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if self.zero {
+            return rhs;
+        }
+        if rhs.zero {
+            return self;
+        }
+        if self.x == rhs.x {
+            return if self.y == rhs.y {
+                self.double()
+            } else {
+                Self::ZERO
+            };
+        }
+        let lambda = (rhs.y - self.y) / (rhs.x - self.x);
+        let x3 = lambda.square() - self.x - rhs.x;
+        let y3 = lambda * (self.x - x3) - self.y;
+        Self::nonzero(x3, y3)
+    }
+}
+
+impl<C: Curve> Neg for AffinePoint<C> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: self.x,
+            y: -self.y,
+            zero: self.zero,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::field::types::{Field, Sample};
+use crate::nonnative::curve::curve_types::{AffinePoint, Curve};
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(bound = "")]
+pub struct ECDSASignature<C: Curve> {
+    pub r: C::ScalarField,
+    pub s: C::ScalarField,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(bound = "")]
+pub struct ECDSASecretKey<C: Curve>(pub C::ScalarField);
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(bound = "")]
+pub struct ECDSAPublicKey<C: Curve>(pub AffinePoint<C>);
+
+impl<C: Curve> ECDSASecretKey<C> {
+    pub fn to_public(&self) -> ECDSAPublicKey<C> {
+        ECDSAPublicKey(C::GENERATOR_AFFINE.mul(self.0))
+    }
+}
+
+/// Signs the message hash `msg` with a random nonce.
+pub fn sign_message<C: Curve>(msg: C::ScalarField, sk: ECDSASecretKey<C>) -> ECDSASignature<C> {
+    loop {
+        let k = C::ScalarField::rand();
+        let r = C::base_to_scalar(C::GENERATOR_AFFINE.mul(k).x);
+        if r.is_zero() {
+            continue;
+        }
+        let s = (msg + r * sk.0) / k;
+        if !s.is_zero() {
+            return ECDSASignature { r, s };
+        }
+    }
+}
+
+/// Verifies an ECDSA signature of the message hash `msg`.
+pub fn verify_message<C: Curve>(
+    msg: C::ScalarField,
+    sig: ECDSASignature<C>,
+    pk: ECDSAPublicKey<C>,
+) -> bool {
+    let ECDSASignature { r, s } = sig;
+    if r.is_zero() || s.is_zero() || pk.0.zero || !pk.0.is_valid() {
+        return false;
+    }
+
+    let c = s.inverse();
+    let point = C::GENERATOR_AFFINE.mul(msg * c) + pk.0.mul(r * c);
+    !point.zero && C::base_to_scalar(point.x) == r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::nonnative::curve::secp256k1::Secp256K1;
+
+    #[test]
+    fn test_ecdsa_native() {
+        type C = Secp256K1;
+
+        let msg = Secp256K1Scalar::rand();
+        let sk = ECDSASecretKey::<C>(Secp256K1Scalar::rand());
+        let pk = sk.to_public();
+
+        let sig = sign_message(msg, sk);
+        assert!(verify_message(msg, sig, pk));
+        assert!(!verify_message(msg + Secp256K1Scalar::ONE, sig, pk));
+    }
+}
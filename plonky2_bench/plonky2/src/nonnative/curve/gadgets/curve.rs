@@ -0,0 +1,323 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::field::types::{Field, PrimeField, PrimeField64};
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::Witness;
+use crate::nonnative::biguint::biguint::{BigUintTarget, WitnessBigUint};
+use crate::nonnative::biguint::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+use crate::nonnative::curve::curve_types::{AffinePoint, Curve};
+use crate::nonnative::u32::gadgets::arithmetic_u32::U32Target;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A Target representing an affine point on the curve `C`. We use incomplete arithmetic for
+/// efficiency, so we assume these points are not zero.
+#[derive(Clone, Debug)]
+pub struct AffinePointTarget<C: Curve> {
+    pub x: NonNativeTarget<C::BaseField>,
+    pub y: NonNativeTarget<C::BaseField>,
+}
+
+impl<C: Curve> AffinePointTarget<C> {
+    pub fn to_vec(&self) -> Vec<NonNativeTarget<C::BaseField>> {
+        vec![self.x.clone(), self.y.clone()]
+    }
+}
+
+pub trait CircuitBuilderCurve<F: RichField + Extendable<D>, const D: usize> {
+    fn constant_affine_point<C: Curve>(&mut self, point: AffinePoint<C>) -> AffinePointTarget<C>;
+
+    fn connect_affine_point<C: Curve>(
+        &mut self,
+        lhs: &AffinePointTarget<C>,
+        rhs: &AffinePointTarget<C>,
+    );
+
+    fn add_virtual_affine_point_target<C: Curve>(&mut self) -> AffinePointTarget<C>;
+
+    /// Asserts that `p` satisfies the curve equation.
+    fn curve_assert_valid<C: Curve>(&mut self, p: &AffinePointTarget<C>);
+
+    fn curve_neg<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C>;
+
+    fn curve_conditional_neg<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        b: BoolTarget,
+    ) -> AffinePointTarget<C>;
+
+    fn curve_double<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C>;
+
+    fn curve_repeated_double<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        n: usize,
+    ) -> AffinePointTarget<C>;
+
+    /// Add two points, which are assumed to be non-equal and non-inverse.
+    fn curve_add<C: Curve>(
+        &mut self,
+        p1: &AffinePointTarget<C>,
+        p2: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C>;
+
+    /// Returns `p1 + p2` if `b` is true, `p1` otherwise.
+    fn curve_conditional_add<C: Curve>(
+        &mut self,
+        p1: &AffinePointTarget<C>,
+        p2: &AffinePointTarget<C>,
+        b: BoolTarget,
+    ) -> AffinePointTarget<C>;
+
+    /// Selects `v[access_index]`, where `v` has a power of two length.
+    fn random_access_curve_points<C: Curve>(
+        &mut self,
+        access_index: Target,
+        v: &[AffinePointTarget<C>],
+    ) -> AffinePointTarget<C>;
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderCurve<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn constant_affine_point<C: Curve>(&mut self, point: AffinePoint<C>) -> AffinePointTarget<C> {
+        debug_assert!(!point.zero);
+        AffinePointTarget {
+            x: self.constant_nonnative(point.x),
+            y: self.constant_nonnative(point.y),
+        }
+    }
+
+    fn connect_affine_point<C: Curve>(
+        &mut self,
+        lhs: &AffinePointTarget<C>,
+        rhs: &AffinePointTarget<C>,
+    ) {
+        self.connect_nonnative(&lhs.x, &rhs.x);
+        self.connect_nonnative(&lhs.y, &rhs.y);
+    }
+
+    fn add_virtual_affine_point_target<C: Curve>(&mut self) -> AffinePointTarget<C> {
+        let x = self.add_virtual_nonnative_target();
+        let y = self.add_virtual_nonnative_target();
+
+        AffinePointTarget { x, y }
+    }
+
+    fn curve_assert_valid<C: Curve>(&mut self, p: &AffinePointTarget<C>) {
+        let a = self.constant_nonnative(C::A);
+        let b = self.constant_nonnative(C::B);
+
+        let y_squared = self.mul_nonnative(&p.y, &p.y);
+        let x_squared = self.mul_nonnative(&p.x, &p.x);
+        let x_cubed = self.mul_nonnative(&x_squared, &p.x);
+        let a_x = self.mul_nonnative(&a, &p.x);
+        let rhs = self.add_many_nonnative(&[x_cubed, a_x, b]);
+
+        self.connect_nonnative(&y_squared, &rhs);
+    }
+
+    fn curve_neg<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C> {
+        let neg_y = self.neg_nonnative(&p.y);
+        AffinePointTarget {
+            x: p.x.clone(),
+            y: neg_y,
+        }
+    }
+
+    fn curve_conditional_neg<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        b: BoolTarget,
+    ) -> AffinePointTarget<C> {
+        AffinePointTarget {
+            x: p.x.clone(),
+            y: self.nonnative_conditional_neg(&p.y, b),
+        }
+    }
+
+    fn curve_double<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C> {
+        let AffinePointTarget { x, y } = p;
+
+        // lambda = (3 x^2 + a) / (2 y)
+        let a = self.constant_nonnative(C::A);
+        let x_squared = self.mul_nonnative(x, x);
+        let numerator =
+            self.add_many_nonnative(&[x_squared.clone(), x_squared.clone(), x_squared, a]);
+        let denominator = self.add_nonnative(y, y);
+        let denominator_inv = self.inv_nonnative(&denominator);
+        let lambda = self.mul_nonnative(&numerator, &denominator_inv);
+
+        let lambda_squared = self.mul_nonnative(&lambda, &lambda);
+        let two_x = self.add_nonnative(x, x);
+        let x3 = self.sub_nonnative(&lambda_squared, &two_x);
+        let x_minus_x3 = self.sub_nonnative(x, &x3);
+        let lambda_times_diff = self.mul_nonnative(&lambda, &x_minus_x3);
+        let y3 = self.sub_nonnative(&lambda_times_diff, y);
+
+        AffinePointTarget { x: x3, y: y3 }
+    }
+
+    fn curve_repeated_double<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        n: usize,
+    ) -> AffinePointTarget<C> {
+        let mut result = p.clone();
+
+        for _ in 0..n {
+            result = self.curve_double(&result);
+        }
+
+        result
+    }
+
+    fn curve_add<C: Curve>(
+        &mut self,
+        p1: &AffinePointTarget<C>,
+        p2: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C> {
+        let AffinePointTarget { x: x1, y: y1 } = p1;
+        let AffinePointTarget { x: x2, y: y2 } = p2;
+
+        // lambda = (y2 - y1) / (x2 - x1)
+        let dy = self.sub_nonnative(y2, y1);
+        let dx = self.sub_nonnative(x2, x1);
+        let dx_inv = self.inv_nonnative(&dx);
+        let lambda = self.mul_nonnative(&dy, &dx_inv);
+
+        let lambda_squared = self.mul_nonnative(&lambda, &lambda);
+        let x1_plus_x2 = self.add_nonnative(x1, x2);
+        let x3 = self.sub_nonnative(&lambda_squared, &x1_plus_x2);
+        let x1_minus_x3 = self.sub_nonnative(x1, &x3);
+        let lambda_times_diff = self.mul_nonnative(&lambda, &x1_minus_x3);
+        let y3 = self.sub_nonnative(&lambda_times_diff, y1);
+
+        AffinePointTarget { x: x3, y: y3 }
+    }
+
+    fn curve_conditional_add<C: Curve>(
+        &mut self,
+        p1: &AffinePointTarget<C>,
+        p2: &AffinePointTarget<C>,
+        b: BoolTarget,
+    ) -> AffinePointTarget<C> {
+        let sum = self.curve_add(p1, p2);
+        AffinePointTarget {
+            x: self.if_nonnative(b, &sum.x, &p1.x),
+            y: self.if_nonnative(b, &sum.y, &p1.y),
+        }
+    }
+
+    fn random_access_curve_points<C: Curve>(
+        &mut self,
+        access_index: Target,
+        v: &[AffinePointTarget<C>],
+    ) -> AffinePointTarget<C> {
+        let num_limbs = Self::num_nonnative_limbs::<C::BaseField>();
+        let zero = self.zero();
+        let mut select = |coordinates: Vec<&NonNativeTarget<C::BaseField>>| {
+            let limbs = (0..num_limbs)
+                .map(|i| {
+                    let limb_values = coordinates
+                        .iter()
+                        .map(|c| c.value.limbs.get(i).map_or(zero, |l| l.0))
+                        .collect();
+                    U32Target(self.random_access(access_index, limb_values))
+                })
+                .collect();
+            NonNativeTarget {
+                value: BigUintTarget { limbs },
+                _phantom: Default::default(),
+            }
+        };
+
+        let x = select(v.iter().map(|p| &p.x).collect());
+        let y = select(v.iter().map(|p| &p.y).collect());
+        AffinePointTarget { x, y }
+    }
+}
+
+pub trait WitnessAffinePoint<F: PrimeField64>: Witness<F> {
+    fn get_affine_point_target<C: Curve>(&self, target: &AffinePointTarget<C>) -> AffinePoint<C>;
+    fn set_affine_point_target<C: Curve>(
+        &mut self,
+        target: &AffinePointTarget<C>,
+        value: &AffinePoint<C>,
+    );
+}
+
+impl<T: Witness<F>, F: PrimeField64> WitnessAffinePoint<F> for T {
+    fn get_affine_point_target<C: Curve>(&self, target: &AffinePointTarget<C>) -> AffinePoint<C> {
+        let x = C::BaseField::from_noncanonical_biguint(
+            self.get_biguint_target(target.x.value.clone()),
+        );
+        let y = C::BaseField::from_noncanonical_biguint(
+            self.get_biguint_target(target.y.value.clone()),
+        );
+        AffinePoint::nonzero(x, y)
+    }
+
+    fn set_affine_point_target<C: Curve>(
+        &mut self,
+        target: &AffinePointTarget<C>,
+        value: &AffinePoint<C>,
+    ) {
+        debug_assert!(!value.zero);
+        self.set_biguint_target(&target.x.value, &value.x.to_canonical_biguint());
+        self.set_biguint_target(&target.y.value, &value.y.to_canonical_biguint());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::Sample;
+    use crate::iop::witness::PartialWitness;
+    use crate::nonnative::curve::secp256k1::Secp256K1;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    #[test]
+    fn test_curve_add_double() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let p = g.mul(Secp256K1Scalar::rand());
+
+        let g_target = builder.constant_affine_point(g);
+        let p_target = builder.add_virtual_affine_point_target::<Secp256K1>();
+        pw.set_affine_point_target(&p_target, &p);
+        builder.curve_assert_valid(&p_target);
+
+        let sum = builder.curve_add(&g_target, &p_target);
+        let expected_sum = builder.constant_affine_point(g + p);
+        builder.connect_affine_point(&sum, &expected_sum);
+
+        let double = builder.curve_double(&p_target);
+        let expected_double = builder.constant_affine_point(p.double());
+        builder.connect_affine_point(&double, &expected_double);
+
+        let neg = builder.curve_neg(&p_target);
+        let expected_neg = builder.constant_affine_point(-p);
+        builder.connect_affine_point(&neg, &expected_neg);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
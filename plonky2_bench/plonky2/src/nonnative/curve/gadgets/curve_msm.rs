@@ -0,0 +1,178 @@
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use num::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::Target;
+use crate::nonnative::biguint::nonnative::NonNativeTarget;
+use crate::nonnative::biguint::split_nonnative::CircuitBuilderSplit;
+use crate::nonnative::curve::curve_types::{AffinePoint, Curve};
+use crate::nonnative::curve::gadgets::curve::{AffinePointTarget, CircuitBuilderCurve};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// Number of scalar bits consumed per window, matching
+/// [`CircuitBuilderSplit::split_u32_to_4_bit_limbs`].
+pub const WINDOW_BITS: usize = 4;
+
+const WINDOW_SIZE: usize = 1 << WINDOW_BITS;
+
+/// Returns a fixed offset point, a public multiple of the generator, added to the accumulator so
+/// that the incomplete addition formulas stay away from the point at infinity and from doublings
+/// for honest inputs. It is not meant to have an unknown discrete logarithm.
+fn offset_point<C: Curve>(index: usize) -> AffinePoint<C> {
+    let mut hasher = Sha256::new();
+    hasher.update(b"plonky2 curve msm offset");
+    hasher.update(index.to_le_bytes());
+    let seed = BigUint::from_bytes_le(&hasher.finalize());
+    let scalar = C::ScalarField::from_noncanonical_biguint(seed % C::ScalarField::order());
+    C::GENERATOR_AFFINE.mul(scalar)
+}
+
+pub trait CircuitBuilderCurveMsm<F: RichField + Extendable<D>, const D: usize> {
+    /// Splits `x` into little-endian `WINDOW_BITS`-bit limbs. Only the first `num_limbs` 32-bit
+    /// limbs of `x` are used, the remaining ones are asserted to be zero.
+    fn split_nonnative_to_windows<FF: Field>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+        num_limbs: usize,
+    ) -> Vec<Target>;
+
+    /// Computes `sum_i n_i * p_i`, where each `n_i` is given by its little-endian `WINDOW_BITS`-bit
+    /// limbs, as returned by [`Self::split_nonnative_to_windows`].
+    fn curve_msm_windowed<C: Curve>(
+        &mut self,
+        terms: &[(AffinePointTarget<C>, Vec<Target>)],
+    ) -> AffinePointTarget<C>;
+
+    /// Computes `n * p` with a fixed window of `WINDOW_BITS` bits.
+    fn curve_scalar_mul_windowed<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        n: &NonNativeTarget<C::ScalarField>,
+    ) -> AffinePointTarget<C>;
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderCurveMsm<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn split_nonnative_to_windows<FF: Field>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+        num_limbs: usize,
+    ) -> Vec<Target> {
+        let zero = self.zero();
+        for limb in x.value.limbs.iter().skip(num_limbs) {
+            self.connect(limb.0, zero);
+        }
+
+        x.value
+            .limbs
+            .iter()
+            .take(num_limbs)
+            .flat_map(|&limb| self.split_u32_to_4_bit_limbs(limb))
+            .collect()
+    }
+
+    fn curve_msm_windowed<C: Curve>(
+        &mut self,
+        terms: &[(AffinePointTarget<C>, Vec<Target>)],
+    ) -> AffinePointTarget<C> {
+        let num_windows = terms.iter().map(|(_, w)| w.len()).max().unwrap_or(0);
+        assert!(num_windows > 0, "Empty multi-scalar multiplication");
+
+        // `tables[i][j] = j * p_i + offset_i`.
+        let offsets = (0..terms.len())
+            .map(|i| offset_point::<C>(i + 1))
+            .collect::<Vec<_>>();
+        let tables = terms
+            .iter()
+            .zip(&offsets)
+            .map(|((p, _), &offset)| {
+                let mut table = vec![self.constant_affine_point(offset)];
+                for j in 1..WINDOW_SIZE {
+                    let entry = self.curve_add(&table[j - 1], p);
+                    table.push(entry);
+                }
+                table
+            })
+            .collect::<Vec<_>>();
+
+        // The accumulator starts at another offset, and we track the sum of all offsets natively.
+        let start = offset_point::<C>(0);
+        let offsets_sum = offsets.iter().fold(AffinePoint::ZERO, |acc, &o| acc + o);
+        let mut correction = start;
+        let mut acc = self.constant_affine_point(start);
+        let zero = self.zero();
+        for i in (0..num_windows).rev() {
+            acc = self.curve_repeated_double(&acc, WINDOW_BITS);
+            for _ in 0..WINDOW_BITS {
+                correction = correction.double();
+            }
+            for ((_, windows), table) in terms.iter().zip(&tables) {
+                let window = windows.get(i).copied().unwrap_or(zero);
+                let to_add = self.random_access_curve_points(window, table);
+                acc = self.curve_add(&acc, &to_add);
+            }
+            correction = correction + offsets_sum;
+        }
+
+        let neg_correction = self.constant_affine_point(-correction);
+        self.curve_add(&acc, &neg_correction)
+    }
+
+    fn curve_scalar_mul_windowed<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        n: &NonNativeTarget<C::ScalarField>,
+    ) -> AffinePointTarget<C> {
+        let windows = self.split_nonnative_to_windows(n, n.value.num_limbs());
+        self.curve_msm_windowed(&[(p.clone(), windows)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::Sample;
+    use crate::iop::witness::PartialWitness;
+    use crate::nonnative::biguint::nonnative::CircuitBuilderNonNative;
+    use crate::nonnative::curve::gadgets::curve::WitnessAffinePoint;
+    use crate::nonnative::curve::secp256k1::Secp256K1;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn test_curve_scalar_mul_windowed() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p = Secp256K1::GENERATOR_AFFINE.mul(Secp256K1Scalar::rand());
+        let n = Secp256K1Scalar::rand();
+
+        let p_target = builder.add_virtual_affine_point_target::<Secp256K1>();
+        pw.set_affine_point_target(&p_target, &p);
+        let n_target = builder.constant_nonnative(n);
+
+        let product = builder.curve_scalar_mul_windowed(&p_target, &n_target);
+        let expected = builder.constant_affine_point(p.mul(n));
+        builder.connect_affine_point(&product, &expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
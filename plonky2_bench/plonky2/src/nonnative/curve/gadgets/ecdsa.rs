@@ -0,0 +1,232 @@
+extern crate alloc;
+
+use crate::field::extension::Extendable;
+use crate::field::secp256k1_scalar::Secp256K1Scalar;
+use crate::field::types::{PrimeField, PrimeField64};
+use crate::hash::hash_types::RichField;
+use crate::iop::witness::Witness;
+use crate::nonnative::biguint::biguint::WitnessBigUint;
+use crate::nonnative::biguint::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+use crate::nonnative::curve::curve_types::Curve;
+use crate::nonnative::curve::ecdsa::{ECDSAPublicKey, ECDSASignature};
+use crate::nonnative::curve::gadgets::curve::{
+    AffinePointTarget, CircuitBuilderCurve, WitnessAffinePoint,
+};
+use crate::nonnative::curve::gadgets::curve_msm::CircuitBuilderCurveMsm;
+use crate::nonnative::curve::gadgets::glv::CircuitBuilderGlv;
+use crate::nonnative::curve::secp256k1::Secp256K1;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+#[derive(Clone, Debug)]
+pub struct ECDSAPublicKeyTarget<C: Curve>(pub AffinePointTarget<C>);
+
+#[derive(Clone, Debug)]
+pub struct ECDSASignatureTarget<C: Curve> {
+    pub r: NonNativeTarget<C::ScalarField>,
+    pub s: NonNativeTarget<C::ScalarField>,
+}
+
+pub trait CircuitBuilderEcdsa<F: RichField + Extendable<D>, const D: usize> {
+    fn add_virtual_ecdsa_public_key_target<C: Curve>(&mut self) -> ECDSAPublicKeyTarget<C>;
+
+    fn add_virtual_ecdsa_signature_target<C: Curve>(&mut self) -> ECDSASignatureTarget<C>;
+
+    /// Asserts that `sig` is a valid signature of the message hash `msg` under `pk`.
+    fn verify_ecdsa_message(
+        &mut self,
+        msg: &NonNativeTarget<Secp256K1Scalar>,
+        sig: &ECDSASignatureTarget<Secp256K1>,
+        pk: &ECDSAPublicKeyTarget<Secp256K1>,
+    );
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderEcdsa<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn add_virtual_ecdsa_public_key_target<C: Curve>(&mut self) -> ECDSAPublicKeyTarget<C> {
+        ECDSAPublicKeyTarget(self.add_virtual_affine_point_target())
+    }
+
+    fn add_virtual_ecdsa_signature_target<C: Curve>(&mut self) -> ECDSASignatureTarget<C> {
+        ECDSASignatureTarget {
+            r: self.add_virtual_nonnative_target(),
+            s: self.add_virtual_nonnative_target(),
+        }
+    }
+
+    fn verify_ecdsa_message(
+        &mut self,
+        msg: &NonNativeTarget<Secp256K1Scalar>,
+        sig: &ECDSASignatureTarget<Secp256K1>,
+        pk: &ECDSAPublicKeyTarget<Secp256K1>,
+    ) {
+        let ECDSASignatureTarget { r, s } = sig;
+
+        self.curve_assert_valid(&pk.0);
+
+        // `s` is invertible, hence nonzero.
+        let c = self.inv_nonnative(s);
+        let u1 = self.mul_nonnative(msg, &c);
+        let u2 = self.mul_nonnative(r, &c);
+
+        // `u1 * G + u2 * pk` as a multi-scalar multiplication over four 128-bit scalars.
+        let g = self.constant_affine_point(Secp256K1::GENERATOR_AFFINE);
+        let [g1, g2] = self.glv_msm_terms(&g, &u1);
+        let [pk1, pk2] = self.glv_msm_terms(&pk.0, &u2);
+        let point = self.curve_msm_windowed(&[g1, g2, pk1, pk2]);
+
+        let x = self.reduce::<Secp256K1Scalar>(&point.x.value);
+        self.connect_nonnative(r, &x);
+    }
+}
+
+pub trait WitnessEcdsa<F: PrimeField64>: Witness<F> {
+    fn set_ecdsa_public_key_target<C: Curve>(
+        &mut self,
+        target: &ECDSAPublicKeyTarget<C>,
+        value: &ECDSAPublicKey<C>,
+    );
+
+    fn set_ecdsa_signature_target<C: Curve>(
+        &mut self,
+        target: &ECDSASignatureTarget<C>,
+        value: &ECDSASignature<C>,
+    );
+}
+
+impl<T: Witness<F>, F: PrimeField64> WitnessEcdsa<F> for T {
+    fn set_ecdsa_public_key_target<C: Curve>(
+        &mut self,
+        target: &ECDSAPublicKeyTarget<C>,
+        value: &ECDSAPublicKey<C>,
+    ) {
+        self.set_affine_point_target(&target.0, &value.0);
+    }
+
+    fn set_ecdsa_signature_target<C: Curve>(
+        &mut self,
+        target: &ECDSASignatureTarget<C>,
+        value: &ECDSASignature<C>,
+    ) {
+        self.set_biguint_target(&target.r.value, &value.r.to_canonical_biguint());
+        self.set_biguint_target(&target.s.value, &value.s.to_canonical_biguint());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use k256::ecdsa::signature::hazmat::PrehashSigner;
+    use k256::ecdsa::{Signature, SigningKey};
+    use num::BigUint;
+    use rand::rngs::OsRng;
+    use rand::RngCore;
+
+    use super::*;
+    use crate::field::secp256k1_base::Secp256K1Base;
+    use crate::field::types::Field;
+    use crate::iop::witness::PartialWitness;
+    use crate::nonnative::curve::curve_types::AffinePoint;
+    use crate::nonnative::curve::ecdsa::verify_message;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    fn scalar_from_be_bytes(bytes: &[u8]) -> Secp256K1Scalar {
+        Secp256K1Scalar::from_noncanonical_biguint(
+            BigUint::from_bytes_be(bytes) % Secp256K1Scalar::order(),
+        )
+    }
+
+    /// Signs a random message hash with `k256`, and converts the message hash, signature and
+    /// public key to our types.
+    fn k256_signature() -> (
+        Secp256K1Scalar,
+        ECDSASignature<Secp256K1>,
+        ECDSAPublicKey<Secp256K1>,
+    ) {
+        let signing_key = SigningKey::random(&mut OsRng);
+        let mut prehash = [0u8; 32];
+        OsRng.fill_bytes(&mut prehash);
+        let signature: Signature = signing_key.sign_prehash(&prehash).unwrap();
+
+        let encoded = signing_key.verifying_key().to_encoded_point(false);
+        let x =
+            Secp256K1Base::from_noncanonical_biguint(BigUint::from_bytes_be(encoded.x().unwrap()));
+        let y =
+            Secp256K1Base::from_noncanonical_biguint(BigUint::from_bytes_be(encoded.y().unwrap()));
+        let (r, s) = signature.split_bytes();
+
+        (
+            scalar_from_be_bytes(&prehash),
+            ECDSASignature {
+                r: scalar_from_be_bytes(&r),
+                s: scalar_from_be_bytes(&s),
+            },
+            ECDSAPublicKey(AffinePoint::nonzero(x, y)),
+        )
+    }
+
+    #[test]
+    fn test_k256_signature_native() {
+        for _ in 0..8 {
+            let (msg, sig, pk) = k256_signature();
+            assert!(verify_message(msg, sig, pk));
+            assert!(!verify_message(msg + Secp256K1Scalar::ONE, sig, pk));
+        }
+    }
+
+    #[test]
+    fn test_verify_ecdsa_message() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let (msg, sig, pk) = k256_signature();
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let msg_target = builder.add_virtual_nonnative_target::<Secp256K1Scalar>();
+        let sig_target = builder.add_virtual_ecdsa_signature_target();
+        let pk_target = builder.add_virtual_ecdsa_public_key_target();
+        builder.verify_ecdsa_message(&msg_target, &sig_target, &pk_target);
+
+        pw.set_biguint_target(&msg_target.value, &msg.to_canonical_biguint());
+        pw.set_ecdsa_signature_target(&sig_target, &sig);
+        pw.set_ecdsa_public_key_target(&pk_target, &pk);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_verify_ecdsa_message_wrong_message() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let (msg, sig, pk) = k256_signature();
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let msg_target = builder.constant_nonnative(msg + Secp256K1Scalar::ONE);
+        let sig_target = builder.add_virtual_ecdsa_signature_target();
+        let pk_target = builder.add_virtual_ecdsa_public_key_target();
+        builder.verify_ecdsa_message(&msg_target, &sig_target, &pk_target);
+
+        let mut pw = PartialWitness::new();
+        pw.set_ecdsa_signature_target(&sig_target, &sig);
+        pw.set_ecdsa_public_key_target(&pk_target, &pk);
+
+        // The signature check fails during witness generation, so there is no need to commit to
+        // the circuit and prove it.
+        let data = builder.mock_build::<C>();
+        data.generate_witness(pw);
+    }
+}
@@ -0,0 +1,248 @@
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::field::extension::Extendable;
+use crate::field::secp256k1_base::Secp256K1Base;
+use crate::field::secp256k1_scalar::Secp256K1Scalar;
+use crate::field::types::{Field, PrimeField};
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, WitnessWrite};
+use crate::nonnative::biguint::biguint::{GeneratedValuesBigUint, WitnessBigUint};
+use crate::nonnative::biguint::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+use crate::nonnative::biguint::serialization::{ReadBigUint, WriteBigUint};
+use crate::nonnative::curve::gadgets::curve::{AffinePointTarget, CircuitBuilderCurve};
+use crate::nonnative::curve::gadgets::curve_msm::CircuitBuilderCurveMsm;
+use crate::nonnative::curve::glv::{decompose_secp256k1_scalar, GLV_BETA, GLV_S};
+use crate::nonnative::curve::secp256k1::Secp256K1;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// Number of 32-bit limbs of the halves of a GLV decomposition.
+const GLV_HALF_LIMBS: usize = 4;
+
+/// A scalar `k` decomposed as `k = (-1)^k1_neg * k1 + GLV_S * (-1)^k2_neg * k2`, with `k1` and
+/// `k2` below `2^128`.
+#[derive(Clone, Debug)]
+pub struct GLVDecompositionTarget {
+    pub k1: NonNativeTarget<Secp256K1Scalar>,
+    pub k2: NonNativeTarget<Secp256K1Scalar>,
+    pub k1_neg: BoolTarget,
+    pub k2_neg: BoolTarget,
+}
+
+pub trait CircuitBuilderGlv<F: RichField + Extendable<D>, const D: usize> {
+    fn secp256k1_glv_beta(&mut self) -> NonNativeTarget<Secp256K1Base>;
+
+    fn decompose_secp256k1_scalar(
+        &mut self,
+        k: &NonNativeTarget<Secp256K1Scalar>,
+    ) -> GLVDecompositionTarget;
+
+    /// Maps `p` to `GLV_S * p`.
+    fn glv_endomorphism(
+        &mut self,
+        p: &AffinePointTarget<Secp256K1>,
+    ) -> AffinePointTarget<Secp256K1>;
+
+    /// Returns the two terms of a multi-scalar multiplication computing `k * p`, with 128-bit
+    /// scalars.
+    fn glv_msm_terms(
+        &mut self,
+        p: &AffinePointTarget<Secp256K1>,
+        k: &NonNativeTarget<Secp256K1Scalar>,
+    ) -> [(AffinePointTarget<Secp256K1>, Vec<Target>); 2];
+
+    /// Computes `k * p` using the GLV endomorphism to halve the number of doublings.
+    fn glv_mul(
+        &mut self,
+        p: &AffinePointTarget<Secp256K1>,
+        k: &NonNativeTarget<Secp256K1Scalar>,
+    ) -> AffinePointTarget<Secp256K1>;
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderGlv<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn secp256k1_glv_beta(&mut self) -> NonNativeTarget<Secp256K1Base> {
+        self.constant_nonnative(GLV_BETA)
+    }
+
+    fn decompose_secp256k1_scalar(
+        &mut self,
+        k: &NonNativeTarget<Secp256K1Scalar>,
+    ) -> GLVDecompositionTarget {
+        let k1 = self.add_virtual_nonnative_target_sized::<Secp256K1Scalar>(GLV_HALF_LIMBS);
+        let k2 = self.add_virtual_nonnative_target_sized::<Secp256K1Scalar>(GLV_HALF_LIMBS);
+        let k1_neg = self.add_virtual_bool_target_safe();
+        let k2_neg = self.add_virtual_bool_target_safe();
+
+        self.add_simple_generator(GLVDecompositionGenerator::<F, D> {
+            k: k.clone(),
+            k1: k1.clone(),
+            k2: k2.clone(),
+            k1_neg,
+            k2_neg,
+            _phantom: PhantomData,
+        });
+
+        // Check that `k1_raw + GLV_S * k2_raw == k`.
+        let k1_raw = self.nonnative_conditional_neg(&k1, k1_neg);
+        let k2_raw = self.nonnative_conditional_neg(&k2, k2_neg);
+        let s = self.constant_nonnative(GLV_S);
+        let k2_times_s = self.mul_nonnative(&k2_raw, &s);
+        let k_check = self.add_nonnative(&k1_raw, &k2_times_s);
+        self.connect_nonnative(k, &k_check);
+
+        GLVDecompositionTarget {
+            k1,
+            k2,
+            k1_neg,
+            k2_neg,
+        }
+    }
+
+    fn glv_endomorphism(
+        &mut self,
+        p: &AffinePointTarget<Secp256K1>,
+    ) -> AffinePointTarget<Secp256K1> {
+        let beta = self.secp256k1_glv_beta();
+        AffinePointTarget {
+            x: self.mul_nonnative(&p.x, &beta),
+            y: p.y.clone(),
+        }
+    }
+
+    fn glv_msm_terms(
+        &mut self,
+        p: &AffinePointTarget<Secp256K1>,
+        k: &NonNativeTarget<Secp256K1Scalar>,
+    ) -> [(AffinePointTarget<Secp256K1>, Vec<Target>); 2] {
+        let GLVDecompositionTarget {
+            k1,
+            k2,
+            k1_neg,
+            k2_neg,
+        } = self.decompose_secp256k1_scalar(k);
+
+        let p_endo = self.glv_endomorphism(p);
+        let p1 = self.curve_conditional_neg(p, k1_neg);
+        let p2 = self.curve_conditional_neg(&p_endo, k2_neg);
+        let k1_windows = self.split_nonnative_to_windows(&k1, GLV_HALF_LIMBS);
+        let k2_windows = self.split_nonnative_to_windows(&k2, GLV_HALF_LIMBS);
+
+        [(p1, k1_windows), (p2, k2_windows)]
+    }
+
+    fn glv_mul(
+        &mut self,
+        p: &AffinePointTarget<Secp256K1>,
+        k: &NonNativeTarget<Secp256K1Scalar>,
+    ) -> AffinePointTarget<Secp256K1> {
+        let terms = self.glv_msm_terms(p, k);
+        self.curve_msm_windowed(&terms)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GLVDecompositionGenerator<F: RichField + Extendable<D>, const D: usize> {
+    k: NonNativeTarget<Secp256K1Scalar>,
+    k1: NonNativeTarget<Secp256K1Scalar>,
+    k2: NonNativeTarget<Secp256K1Scalar>,
+    k1_neg: BoolTarget,
+    k2_neg: BoolTarget,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for GLVDecompositionGenerator<F, D>
+{
+    fn id(&self) -> String {
+        "GLVDecompositionGenerator".to_string()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_nonnative(&self.k)?;
+        dst.write_target_nonnative(&self.k1)?;
+        dst.write_target_nonnative(&self.k2)?;
+        dst.write_target_bool(self.k1_neg)?;
+        dst.write_target_bool(self.k2_neg)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let k = src.read_target_nonnative()?;
+        let k1 = src.read_target_nonnative()?;
+        let k2 = src.read_target_nonnative()?;
+        let k1_neg = src.read_target_bool()?;
+        let k2_neg = src.read_target_bool()?;
+        Ok(Self {
+            k,
+            k1,
+            k2,
+            k1_neg,
+            k2_neg,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.k.value.limbs.iter().map(|l| l.0).collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let k = Secp256K1Scalar::from_noncanonical_biguint(
+            witness.get_biguint_target(self.k.value.clone()),
+        );
+        let (k1, k2, k1_neg, k2_neg) = decompose_secp256k1_scalar(k);
+
+        out_buffer.set_biguint_target(&self.k1.value, &k1.to_canonical_biguint());
+        out_buffer.set_biguint_target(&self.k2.value, &k2.to_canonical_biguint());
+        out_buffer.set_bool_target(self.k1_neg, k1_neg);
+        out_buffer.set_bool_target(self.k2_neg, k2_neg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::iop::witness::PartialWitness;
+    use crate::nonnative::curve::curve_types::Curve;
+    use crate::nonnative::curve::gadgets::curve::WitnessAffinePoint;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn test_glv_mul() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p = Secp256K1::GENERATOR_AFFINE.mul(Secp256K1Scalar::rand());
+        let k = Secp256K1Scalar::rand();
+
+        let p_target = builder.add_virtual_affine_point_target::<Secp256K1>();
+        pw.set_affine_point_target(&p_target, &p);
+        let k_target = builder.add_virtual_nonnative_target::<Secp256K1Scalar>();
+        pw.set_biguint_target(&k_target.value, &k.to_canonical_biguint());
+
+        let product = builder.glv_mul(&p_target, &k_target);
+        let expected = builder.constant_affine_point(p.mul(k));
+        builder.connect_affine_point(&product, &expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod curve;
+pub mod curve_msm;
+pub mod ecdsa;
+pub mod glv;
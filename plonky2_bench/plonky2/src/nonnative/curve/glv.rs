@@ -0,0 +1,93 @@
+use crate::field::secp256k1_base::Secp256K1Base;
+use crate::field::secp256k1_scalar::Secp256K1Scalar;
+use crate::field::types::{Field, PrimeField};
+use crate::nonnative::curve::curve_types::AffinePoint;
+use crate::nonnative::curve::secp256k1::Secp256K1;
+
+/// A cube root of unity in the base field, such that `(x, y) -> (GLV_BETA * x, y)` is the
+/// endomorphism multiplying points by `GLV_S`.
+pub const GLV_BETA: Secp256K1Base = Secp256K1Base([
+    0xc1396c28719501ee,
+    0x9cf0497512f58995,
+    0x6e64479eac3434e9,
+    0x7ae96a2b657c0710,
+]);
+
+/// A cube root of unity in the scalar field, the eigenvalue of the GLV endomorphism.
+pub const GLV_S: Secp256K1Scalar = Secp256K1Scalar([
+    0xdf02967c1b23bd72,
+    0x122e22ea20816678,
+    0xa5261c028812645a,
+    0x5363ad4cc05c30e0,
+]);
+
+/// Short basis `(A1, B1), (A2, B2)` of the lattice of `(a, b)` with `a + b * GLV_S = 0`.
+const A1: Secp256K1Scalar = Secp256K1Scalar([0xe86c90e49284eb15, 0x3086d221a7d46bcd, 0, 0]);
+const MINUS_B1: Secp256K1Scalar = Secp256K1Scalar([0x6f547fa90abfe4c3, 0xe4437ed6010e8828, 0, 0]);
+const A2: Secp256K1Scalar = Secp256K1Scalar([0x57c1108d9d44cfd8, 0x14ca50f7a8e2f3f6, 1, 0]);
+const B2: Secp256K1Scalar = Secp256K1Scalar([0xe86c90e49284eb15, 0x3086d221a7d46bcd, 0, 0]);
+
+/// Decomposes `k` into `(k1, k2, k1_neg, k2_neg)` such that
+/// `k = (-1)^k1_neg * k1 + GLV_S * (-1)^k2_neg * k2`, where `k1` and `k2` are less than `2^128`.
+pub fn decompose_secp256k1_scalar(
+    k: Secp256K1Scalar,
+) -> (Secp256K1Scalar, Secp256K1Scalar, bool, bool) {
+    let p = Secp256K1Scalar::order();
+    let half_p = p.clone() >> 1;
+    let k_biguint = k.to_canonical_biguint();
+    // Rounded divisions by `p`.
+    let c1 = Secp256K1Scalar::from_noncanonical_biguint(
+        (B2.to_canonical_biguint() * &k_biguint + &half_p) / &p,
+    );
+    let c2 = Secp256K1Scalar::from_noncanonical_biguint(
+        (MINUS_B1.to_canonical_biguint() * &k_biguint + &half_p) / &p,
+    );
+
+    let k1_raw = k - c1 * A1 - c2 * A2;
+    let k2_raw = c1 * MINUS_B1 - c2 * B2;
+    debug_assert!(k1_raw + GLV_S * k2_raw == k);
+
+    let k1_neg = k1_raw.to_canonical_biguint() > half_p;
+    let k1 = if k1_neg { -k1_raw } else { k1_raw };
+    let k2_neg = k2_raw.to_canonical_biguint() > half_p;
+    let k2 = if k2_neg { -k2_raw } else { k2_raw };
+
+    (k1, k2, k1_neg, k2_neg)
+}
+
+/// Applies the GLV endomorphism, i.e. multiplies `p` by `GLV_S`.
+pub fn glv_endomorphism(p: AffinePoint<Secp256K1>) -> AffinePoint<Secp256K1> {
+    AffinePoint {
+        x: p.x * GLV_BETA,
+        y: p.y,
+        zero: p.zero,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::nonnative::curve::curve_types::Curve;
+
+    #[test]
+    fn test_glv_decompose() {
+        for _ in 0..32 {
+            let k = Secp256K1Scalar::rand();
+            let (k1, k2, k1_neg, k2_neg) = decompose_secp256k1_scalar(k);
+            let one = Secp256K1Scalar::ONE;
+            let m1 = if k1_neg { -one } else { one };
+            let m2 = if k2_neg { -one } else { one };
+
+            assert_eq!(k1 * m1 + GLV_S * k2 * m2, k);
+            assert!(k1.to_canonical_biguint().bits() <= 128);
+            assert!(k2.to_canonical_biguint().bits() <= 128);
+        }
+    }
+
+    #[test]
+    fn test_glv_endomorphism() {
+        let g = Secp256K1::GENERATOR_AFFINE;
+        assert_eq!(glv_endomorphism(g), g.mul(GLV_S));
+    }
+}
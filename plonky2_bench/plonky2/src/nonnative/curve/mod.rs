@@ -0,0 +1,5 @@
+pub mod curve_types;
+pub mod ecdsa;
+pub mod gadgets;
+pub mod glv;
+pub mod secp256k1;
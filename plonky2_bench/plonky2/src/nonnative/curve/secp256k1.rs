@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::field::secp256k1_base::Secp256K1Base;
+use crate::field::secp256k1_scalar::Secp256K1Scalar;
+use crate::field::types::Field;
+use crate::nonnative::curve::curve_types::{AffinePoint, Curve};
+
+/// The secp256k1 curve `y^2 = x^3 + 7`.
+#[derive(Debug, Copy, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Secp256K1;
+
+impl Curve for Secp256K1 {
+    type BaseField = Secp256K1Base;
+    type ScalarField = Secp256K1Scalar;
+
+    const A: Secp256K1Base = Secp256K1Base::ZERO;
+    const B: Secp256K1Base = Secp256K1Base([7, 0, 0, 0]);
+    const GENERATOR_AFFINE: AffinePoint<Self> = AffinePoint {
+        x: SECP256K1_GENERATOR_X,
+        y: SECP256K1_GENERATOR_Y,
+        zero: false,
+    };
+}
+
+/// 55066263022277343669578718895168534326250603453777594175500187360389116729240
+const SECP256K1_GENERATOR_X: Secp256K1Base = Secp256K1Base([
+    0x59F2815B16F81798,
+    0x029BFCDB2DCE28D9,
+    0x55A06295CE870B07,
+    0x79BE667EF9DCBBAC,
+]);
+
+/// 32670510020758816978083085130507043184471273380659243275938904335757337482424
+const SECP256K1_GENERATOR_Y: Secp256K1Base = Secp256K1Base([
+    0x9C47D08FFB10D4B8,
+    0xFD17B448A6855419,
+    0x5DA4FBFC0E1108A8,
+    0x483ADA7726A3C465,
+]);
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+
+    use super::*;
+    use crate::field::types::Sample;
+
+    #[test]
+    fn test_generator() {
+        let g = Secp256K1::GENERATOR_AFFINE;
+        assert!(g.is_valid());
+
+        let neg_g = g.mul(Secp256K1Scalar::NEG_ONE);
+        assert_eq!(neg_g, -g);
+        assert_eq!(neg_g + g, AffinePoint::ZERO);
+        assert_eq!(
+            g.mul(Secp256K1Scalar::from_canonical_u64(5)),
+            g.double().double() + g
+        );
+        assert_eq!(
+            Secp256K1Scalar::order(),
+            BigUint::parse_bytes(
+                b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141",
+                16
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_scalar_mul_linearity() {
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let a = Secp256K1Scalar::rand();
+        let b = Secp256K1Scalar::rand();
+        assert_eq!(g.mul(a) + g.mul(b), g.mul(a + b));
+        assert_eq!(g.mul(a).mul(b), g.mul(a * b));
+    }
+}
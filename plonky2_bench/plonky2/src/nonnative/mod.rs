@@ -1,2 +1,4 @@
 pub mod biguint;
+pub mod curve;
+pub mod serialization;
 pub mod u32;
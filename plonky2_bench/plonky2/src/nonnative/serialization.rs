@@ -0,0 +1,266 @@
+//! Gate and generator serializers covering the default `plonky2` gates and generators, along with
+//! the ones of the `nonnative` and `sha256` modules.
+
+use core::marker::PhantomData;
+
+use plonky2_field::extension::Extendable;
+
+use crate::field::secp256k1_base::Secp256K1Base;
+use crate::field::secp256k1_scalar::Secp256K1Scalar;
+use crate::gadgets::arithmetic::EqualityGenerator;
+use crate::gadgets::arithmetic_extension::QuotientGeneratorExtension;
+use crate::gadgets::range_check::LowHighGenerator;
+use crate::gadgets::split_base::BaseSumGenerator;
+use crate::gadgets::split_join::{SplitGenerator, WireSplitGenerator};
+use crate::gates::arithmetic_base::{ArithmeticBaseGenerator, ArithmeticGate};
+use crate::gates::arithmetic_extension::{ArithmeticExtensionGate, ArithmeticExtensionGenerator};
+use crate::gates::base_sum::{BaseSplitGenerator, BaseSumGate};
+use crate::gates::constant::ConstantGate;
+use crate::gates::coset_interpolation::{CosetInterpolationGate, InterpolationGenerator};
+use crate::gates::exponentiation::{ExponentiationGate, ExponentiationGenerator};
+use crate::gates::lookup::{LookupGate, LookupGenerator};
+use crate::gates::lookup_table::{LookupTableGate, LookupTableGenerator};
+use crate::gates::multiplication_extension::{MulExtensionGate, MulExtensionGenerator};
+use crate::gates::noop::NoopGate;
+use crate::gates::poseidon::{PoseidonGate, PoseidonGenerator};
+use crate::gates::poseidon_mds::{PoseidonMdsGate, PoseidonMdsGenerator};
+use crate::gates::public_input::PublicInputGate;
+use crate::gates::random_access::{RandomAccessGate, RandomAccessGenerator};
+use crate::gates::reducing::{ReducingGate, ReducingGenerator};
+use crate::gates::reducing_extension::{
+    ReducingExtensionGate, ReducingGenerator as ReducingExtensionGenerator,
+};
+use crate::hash::hash_types::RichField;
+use crate::hash::sha256::split_base::BaseSumGenerator as Sha256BaseSumGenerator;
+use crate::iop::generator::{
+    ConstantGenerator, CopyGenerator, NonzeroTestGenerator, RandomValueGenerator,
+};
+use crate::nonnative::biguint::biguint::BigUintDivRemGenerator;
+use crate::nonnative::biguint::nonnative::{
+    NonNativeAdditionGenerator, NonNativeInverseGenerator, NonNativeMultipleAddsGenerator,
+    NonNativeMultiplicationGenerator, NonNativeSubtractionGenerator,
+};
+use crate::nonnative::curve::gadgets::glv::GLVDecompositionGenerator;
+use crate::nonnative::u32::gadgets::bitwise_u32::U32LimbSplitGenerator;
+use crate::nonnative::u32::gates::add_many_u32::{U32AddManyGate, U32AddManyGenerator};
+use crate::nonnative::u32::gates::arithmetic_u32::{U32ArithmeticGate, U32ArithmeticGenerator};
+use crate::nonnative::u32::gates::comparison::{ComparisonGate, ComparisonGenerator};
+use crate::nonnative::u32::gates::range_check_u32::{U32RangeCheckGate, U32RangeCheckGenerator};
+use crate::nonnative::u32::gates::subtraction_u32::{U32SubtractionGate, U32SubtractionGenerator};
+use crate::plonk::config::{AlgebraicHasher, GenericConfig};
+use crate::recursion::dummy_circuit::DummyProofGenerator;
+use crate::util::serialization::{GateSerializer, WitnessGeneratorSerializer};
+use crate::{
+    get_gate_tag_impl, get_generator_tag_impl, impl_gate_serializer, impl_generator_serializer,
+    read_gate_impl, read_generator_impl,
+};
+
+/// A gate serializer supporting the default gates, the `u32` gates and the base 4 `BaseSumGate`
+/// used to split nonnative values.
+#[derive(Debug)]
+pub struct NonnativeGateSerializer;
+
+impl<F: RichField + Extendable<D>, const D: usize> GateSerializer<F, D>
+    for NonnativeGateSerializer
+{
+    impl_gate_serializer! {
+        NonnativeGateSerializer,
+        ArithmeticGate,
+        ArithmeticExtensionGate<D>,
+        BaseSumGate<2>,
+        BaseSumGate<4>,
+        ComparisonGate<F, D>,
+        ConstantGate,
+        CosetInterpolationGate<F, D>,
+        ExponentiationGate<F, D>,
+        LookupGate,
+        LookupTableGate,
+        MulExtensionGate<D>,
+        NoopGate,
+        PoseidonMdsGate<F, D>,
+        PoseidonGate<F, D>,
+        PublicInputGate,
+        RandomAccessGate<F, D>,
+        ReducingExtensionGate<D>,
+        ReducingGate<D>,
+        U32AddManyGate<F, D>,
+        U32ArithmeticGate<F, D>,
+        U32RangeCheckGate<F, D>,
+        U32SubtractionGate<F, D>
+    }
+}
+
+/// A generator serializer supporting the default generators, along with the ones of the `u32`,
+/// `biguint`, `curve` and `sha256` gadgets.
+///
+/// Nonnative generators are generic over the emulated field, so only the fields used in this
+/// crate are supported: the native field itself and the secp256k1 base and scalar fields.
+#[derive(Debug, Default)]
+pub struct NonnativeGeneratorSerializer<C: GenericConfig<D>, const D: usize> {
+    pub _phantom: PhantomData<C>,
+}
+
+impl<F, C, const D: usize> WitnessGeneratorSerializer<F, D> for NonnativeGeneratorSerializer<C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F> + 'static,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    impl_generator_serializer! {
+        NonnativeGeneratorSerializer,
+        ArithmeticBaseGenerator<F, D>,
+        ArithmeticExtensionGenerator<F, D>,
+        BaseSplitGenerator<2>,
+        BaseSplitGenerator<4>,
+        BaseSumGenerator<2>,
+        ConstantGenerator<F>,
+        CopyGenerator,
+        DummyProofGenerator<F, C, D>,
+        EqualityGenerator,
+        ExponentiationGenerator<F, D>,
+        InterpolationGenerator<F, D>,
+        LookupGenerator,
+        LookupTableGenerator,
+        LowHighGenerator,
+        MulExtensionGenerator<F, D>,
+        NonzeroTestGenerator,
+        PoseidonGenerator<F, D>,
+        PoseidonMdsGenerator<D>,
+        QuotientGeneratorExtension<D>,
+        RandomAccessGenerator<F, D>,
+        RandomValueGenerator,
+        ReducingGenerator<D>,
+        ReducingExtensionGenerator<D>,
+        SplitGenerator,
+        WireSplitGenerator,
+        BigUintDivRemGenerator<F, D>,
+        ComparisonGenerator<F, D>,
+        GLVDecompositionGenerator<F, D>,
+        NonNativeAdditionGenerator<F, D, F>,
+        NonNativeAdditionGenerator<F, D, Secp256K1Base>,
+        NonNativeAdditionGenerator<F, D, Secp256K1Scalar>,
+        NonNativeInverseGenerator<F, D, F>,
+        NonNativeInverseGenerator<F, D, Secp256K1Base>,
+        NonNativeInverseGenerator<F, D, Secp256K1Scalar>,
+        NonNativeMultipleAddsGenerator<F, D, F>,
+        NonNativeMultipleAddsGenerator<F, D, Secp256K1Base>,
+        NonNativeMultipleAddsGenerator<F, D, Secp256K1Scalar>,
+        NonNativeMultiplicationGenerator<F, D, F>,
+        NonNativeMultiplicationGenerator<F, D, Secp256K1Base>,
+        NonNativeMultiplicationGenerator<F, D, Secp256K1Scalar>,
+        NonNativeSubtractionGenerator<F, D, F>,
+        NonNativeSubtractionGenerator<F, D, Secp256K1Base>,
+        NonNativeSubtractionGenerator<F, D, Secp256K1Scalar>,
+        Sha256BaseSumGenerator<2>,
+        U32AddManyGenerator<F, D>,
+        U32ArithmeticGenerator<F, D>,
+        U32LimbSplitGenerator<F, D>,
+        U32RangeCheckGenerator<F, D>,
+        U32SubtractionGenerator<F, D>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use num::BigUint;
+
+    use super::*;
+    use crate::field::types::{Field, Sample};
+    use crate::hash::sha256::circuit::{array_to_bits, make_circuits};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::nonnative::biguint::biguint::{CircuitBuilderBiguint, WitnessBigUint};
+    use crate::nonnative::biguint::nonnative::CircuitBuilderNonNative;
+    use crate::nonnative::u32::gadgets::arithmetic_u32::CircuitBuilderU32;
+    use crate::nonnative::u32::gadgets::bitwise_u32::CircuitBuilderBitwiseU32;
+    use crate::nonnative::u32::witness::WitnessU32;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    #[test]
+    fn test_nonnative_serialization_round_trip() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = Secp256K1Base;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_ecc_config());
+        let mut pw = PartialWitness::new();
+
+        // BigUint arithmetic, backed by the u32 gates.
+        let x_value = BigUint::from(u128::MAX) * 12345u32;
+        let y_value = BigUint::from(u64::MAX);
+        let x = builder.add_virtual_biguint_target(x_value.to_u32_digits().len());
+        let y = builder.add_virtual_biguint_target(y_value.to_u32_digits().len());
+        pw.set_biguint_target(&x, &x_value);
+        pw.set_biguint_target(&y, &y_value);
+        let sum = builder.add_biguint(&x, &y);
+        let diff = builder.sub_biguint(&sum, &y);
+        builder.connect_biguint(&diff, &x);
+        let product = builder.mul_biguint(&x, &y);
+        let (div, rem) = builder.div_rem_biguint(&x, &y);
+        let expected_product = builder.constant_biguint(&(&x_value * &y_value));
+        let expected_div = builder.constant_biguint(&(&x_value / &y_value));
+        let expected_rem = builder.constant_biguint(&(&x_value % &y_value));
+        builder.connect_biguint(&product, &expected_product);
+        builder.connect_biguint(&div, &expected_div);
+        builder.connect_biguint(&rem, &expected_rem);
+        let y_le_x = builder.cmp_biguint(&y, &x);
+        builder.assert_one(y_le_x.target);
+
+        // Lookup-backed bitwise operations.
+        let a_u32 = builder.add_virtual_u32_target();
+        let b_u32 = builder.add_virtual_u32_target();
+        pw.set_u32_target(a_u32, 0xdeadbeef);
+        pw.set_u32_target(b_u32, 0x12345678);
+        let and = builder.and_u32(a_u32, b_u32);
+        let rotated = builder.rotr_u32(and, 7);
+        let expected_rotated = builder.constant_u32((0xdeadbeefu32 & 0x12345678).rotate_right(7));
+        builder.connect_u32(rotated, expected_rotated);
+
+        // Nonnative arithmetic.
+        let a_value = FF::rand();
+        let b_value = FF::rand();
+        let a = builder.constant_nonnative(a_value);
+        let b = builder.constant_nonnative(b_value);
+        let a_plus_b = builder.add_nonnative(&a, &b);
+        let a_minus_b = builder.sub_nonnative(&a, &b);
+        let a_times_b = builder.mul_nonnative(&a, &b);
+        let b_inv = builder.inv_nonnative(&b);
+        let many = builder.add_many_nonnative(&[a.clone(), b.clone(), a_plus_b.clone()]);
+        for (target, value) in [
+            (a_plus_b, a_value + b_value),
+            (a_minus_b, a_value - b_value),
+            (a_times_b, a_value * b_value),
+            (b_inv, b_value.inverse()),
+            (many, (a_value + b_value).double()),
+        ] {
+            let expected = builder.constant_nonnative(value);
+            builder.connect_nonnative(&target, &expected);
+        }
+
+        // SHA-256.
+        let msg = b"plonky2";
+        let msg_bits = array_to_bits(msg);
+        let sha256 = make_circuits(&mut builder, msg_bits.len() as u64);
+        for (&target, &bit) in sha256.message.iter().zip(&msg_bits) {
+            pw.set_bool_target(target, bit);
+        }
+
+        let data = builder.build::<C>();
+
+        let gate_serializer = NonnativeGateSerializer;
+        let generator_serializer = NonnativeGeneratorSerializer::<C, D>::default();
+        let bytes = data
+            .to_bytes(&gate_serializer, &generator_serializer)
+            .map_err(|_| anyhow::Error::msg("CircuitData serialization failed."))?;
+        let data_from_bytes =
+            CircuitData::<F, C, D>::from_bytes(&bytes, &gate_serializer, &generator_serializer)
+                .map_err(|_| anyhow::Error::msg("CircuitData deserialization failed."))?;
+        assert_eq!(data, data_from_bytes);
+
+        let proof = data_from_bytes.prove(pw)?;
+        data.verify(proof)
+    }
+}
@@ -20,7 +20,7 @@ use crate::nonnative::u32::gates::subtraction_u32::U32SubtractionGate;
 use crate::nonnative::u32::serialization::{ReadU32, WriteU32};
 use crate::nonnative::u32::witness::GeneratedValuesU32;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct U32Target(pub Target);
 
 pub trait CircuitBuilderU32<F: RichField + Extendable<D>, const D: usize> {
@@ -0,0 +1,378 @@
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::marker::PhantomData;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::nonnative::u32::gadgets::arithmetic_u32::U32Target;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// Bitwise operations and range checks on `U32Target`s, backed by lookup tables.
+///
+/// Values are split into bytes, range-checked against a 256-entry table. Binary operations look up
+/// each pair of bytes in a 16-bit table keyed by `256 * a + b`, while shifts and rotations look up
+/// each byte in tables returning its low and high bits, which also range-checks the bytes. Each
+/// table is built and added once per circuit, then shared by every call using it, so the 16-bit
+/// tables only pay off when used by many operations.
+pub trait CircuitBuilderBitwiseU32<F: RichField + Extendable<D>, const D: usize> {
+    /// Asserts that `x < 2^16` by splitting it into two lookup-checked bytes.
+    fn range_check_u16(&mut self, x: Target);
+
+    /// Asserts that `x < 2^32` by splitting it into four lookup-checked bytes.
+    fn range_check_u32(&mut self, x: U32Target);
+
+    /// Splits `x` into four little-endian bytes, each range-checked with a lookup.
+    fn split_u32_to_bytes(&mut self, x: U32Target) -> [Target; 4];
+
+    fn and_u32(&mut self, a: U32Target, b: U32Target) -> U32Target;
+
+    fn xor_u32(&mut self, a: U32Target, b: U32Target) -> U32Target;
+
+    /// Computes `!x` as `2^32 - 1 - x`, which needs no lookup since `x` is assumed to be
+    /// range-checked.
+    fn not_u32(&mut self, x: U32Target) -> U32Target;
+
+    /// Rotates `x` right by the constant `n`.
+    fn rotr_u32(&mut self, x: U32Target, n: usize) -> U32Target;
+
+    /// Shifts `x` right by the constant `n`.
+    fn shr_u32(&mut self, x: U32Target, n: usize) -> U32Target;
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderBitwiseU32<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn range_check_u16(&mut self, x: Target) {
+        let bytes = split_into_limbs(self, x, 8, 2);
+        range_check_bytes(self, &bytes);
+    }
+
+    fn range_check_u32(&mut self, x: U32Target) {
+        self.split_u32_to_bytes(x);
+    }
+
+    fn split_u32_to_bytes(&mut self, x: U32Target) -> [Target; 4] {
+        let bytes = split_into_bytes(self, x);
+        range_check_bytes(self, &bytes);
+        bytes
+    }
+
+    fn and_u32(&mut self, a: U32Target, b: U32Target) -> U32Target {
+        let lut = self.named_lut("u32 and", |builder| {
+            builder.add_lookup_table_from_fn(|ab| (ab >> 8) & ab & 0xff, &all_u16())
+        });
+        byte_pairs_lookup(self, a, b, lut)
+    }
+
+    fn xor_u32(&mut self, a: U32Target, b: U32Target) -> U32Target {
+        let lut = self.named_lut("u32 xor", |builder| {
+            builder.add_lookup_table_from_fn(|ab| ((ab >> 8) ^ ab) & 0xff, &all_u16())
+        });
+        byte_pairs_lookup(self, a, b, lut)
+    }
+
+    fn not_u32(&mut self, x: U32Target) -> U32Target {
+        let max = self.constant(F::from_canonical_u32(u32::MAX));
+        U32Target(self.sub(max, x.0))
+    }
+
+    fn rotr_u32(&mut self, x: U32Target, n: usize) -> U32Target {
+        let n = n % 32;
+        let (byte_shift, bit_shift) = (n / 8, n % 8);
+
+        if bit_shift == 0 {
+            let bytes = self.split_u32_to_bytes(x);
+            let terms = (0..4)
+                .map(|i| (bytes[i], 8 * ((i + 4 - byte_shift) % 4)))
+                .collect::<Vec<_>>();
+            return U32Target(weighted_sum(self, &terms));
+        }
+
+        // Byte `i` is split into `low_i = byte_i mod 2^bit_shift` and `high_i = byte_i >> bit_shift`,
+        // whose bits respectively land at positions `8 * (i - byte_shift) - bit_shift` and
+        // `8 * (i - byte_shift)`, modulo 32.
+        let bytes = split_into_bytes(self, x);
+        let (low_lut, high_lut) = bit_split_luts(self, bit_shift);
+        let mut terms = Vec::with_capacity(8);
+        for (i, &byte) in bytes.iter().enumerate() {
+            let low = self.add_lookup_from_index(byte, low_lut);
+            let high = self.add_lookup_from_index(byte, high_lut);
+            let position = 8 * ((i + 4 - byte_shift) % 4);
+            terms.push((high, position));
+            terms.push((low, (position + 32 - bit_shift) % 32));
+        }
+        U32Target(weighted_sum(self, &terms))
+    }
+
+    fn shr_u32(&mut self, x: U32Target, n: usize) -> U32Target {
+        assert!(n < 32, "Shift amount {} is too large", n);
+        let (byte_shift, bit_shift) = (n / 8, n % 8);
+
+        if bit_shift == 0 {
+            let bytes = self.split_u32_to_bytes(x);
+            let terms = (byte_shift..4)
+                .map(|i| (bytes[i], 8 * (i - byte_shift)))
+                .collect::<Vec<_>>();
+            return U32Target(weighted_sum(self, &terms));
+        }
+
+        // Bytes below `byte_shift` are shifted out, and only need a range check. The low bits of
+        // byte `byte_shift` are shifted out as well, so only its high bits are looked up.
+        let bytes = split_into_bytes(self, x);
+        let range_lut = byte_range_lut(self);
+        let (low_lut, high_lut) = bit_split_luts(self, bit_shift);
+        let mut terms = Vec::with_capacity(8);
+        for (i, &byte) in bytes.iter().enumerate() {
+            if i < byte_shift {
+                self.add_lookup_from_index(byte, range_lut);
+                continue;
+            }
+            let high = self.add_lookup_from_index(byte, high_lut);
+            terms.push((high, 8 * (i - byte_shift)));
+            if i > byte_shift {
+                let low = self.add_lookup_from_index(byte, low_lut);
+                terms.push((low, 8 * (i - byte_shift) - bit_shift));
+            }
+        }
+        U32Target(weighted_sum(self, &terms))
+    }
+}
+
+fn all_u16() -> Vec<u16> {
+    (0..=u16::MAX).collect()
+}
+
+fn all_u8() -> Vec<u16> {
+    (0..=u8::MAX as u16).collect()
+}
+
+fn byte_range_lut<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+) -> usize {
+    builder.named_lut("u8 range", |builder| {
+        builder.add_lookup_table_from_fn(|x| x, &all_u8())
+    })
+}
+
+fn range_check_bytes<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bytes: &[Target],
+) {
+    let lut = byte_range_lut(builder);
+    for &byte in bytes {
+        builder.add_lookup_from_index(byte, lut);
+    }
+}
+
+/// Returns the indices of the tables mapping a byte to its `bits` low bits, and to its remaining
+/// high bits.
+fn bit_split_luts<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bits: usize,
+) -> (usize, usize) {
+    let low_lut = builder.named_lut(&format!("u8 low {} bits", bits), |builder| {
+        let inputs = all_u8();
+        let lows = inputs
+            .iter()
+            .map(|&x| x & ((1 << bits) - 1))
+            .collect::<Vec<_>>();
+        builder.add_lookup_table_from_table(&inputs, &lows)
+    });
+    let high_lut = builder.named_lut(&format!("u8 bits from {}", bits), |builder| {
+        let inputs = all_u8();
+        let highs = inputs.iter().map(|&x| x >> bits).collect::<Vec<_>>();
+        builder.add_lookup_table_from_table(&inputs, &highs)
+    });
+    (low_lut, high_lut)
+}
+
+/// Applies a byte-wise binary operation, given as a table keyed by `256 * a + b`.
+fn byte_pairs_lookup<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: U32Target,
+    b: U32Target,
+    lut: usize,
+) -> U32Target {
+    let a_bytes = builder.split_u32_to_bytes(a);
+    let b_bytes = builder.split_u32_to_bytes(b);
+    let terms = a_bytes
+        .iter()
+        .zip(&b_bytes)
+        .enumerate()
+        .map(|(i, (&a_byte, &b_byte))| {
+            let key = builder.mul_const_add(F::from_canonical_u32(1 << 8), a_byte, b_byte);
+            (builder.add_lookup_from_index(key, lut), 8 * i)
+        })
+        .collect::<Vec<_>>();
+    U32Target(weighted_sum(builder, &terms))
+}
+
+/// Returns the bytes of `x`, without range-checking them.
+fn split_into_bytes<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    x: U32Target,
+) -> [Target; 4] {
+    split_into_limbs(builder, x.0, 8, 4)
+        .try_into()
+        .expect("Four limbs")
+}
+
+/// Returns the little-endian `limb_bits`-bit limbs of `x`, and asserts that they recombine into
+/// `x`. The limbs are not range-checked.
+fn split_into_limbs<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    x: Target,
+    limb_bits: usize,
+    num_limbs: usize,
+) -> Vec<Target> {
+    let limbs = builder.add_virtual_targets(num_limbs);
+    builder.add_simple_generator(U32LimbSplitGenerator::<F, D> {
+        x,
+        limb_bits,
+        limbs: limbs.clone(),
+        _phantom: PhantomData,
+    });
+
+    let terms = limbs
+        .iter()
+        .enumerate()
+        .map(|(i, &limb)| (limb, limb_bits * i))
+        .collect::<Vec<_>>();
+    let recombined = weighted_sum(builder, &terms);
+    builder.connect(x, recombined);
+
+    limbs
+}
+
+/// Computes `sum_i t_i * 2^s_i` for the given `(t_i, s_i)` pairs.
+fn weighted_sum<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    terms: &[(Target, usize)],
+) -> Target {
+    let zero = builder.zero();
+    terms.iter().fold(zero, |acc, &(t, shift)| {
+        builder.mul_const_add(F::from_canonical_u64(1 << shift), t, acc)
+    })
+}
+
+#[derive(Debug, Default)]
+pub struct U32LimbSplitGenerator<F: RichField + Extendable<D>, const D: usize> {
+    x: Target,
+    limb_bits: usize,
+    limbs: Vec<Target>,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for U32LimbSplitGenerator<F, D>
+{
+    fn id(&self) -> String {
+        "U32LimbSplitGenerator".to_string()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target(self.x)?;
+        dst.write_usize(self.limb_bits)?;
+        dst.write_target_vec(&self.limbs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let x = src.read_target()?;
+        let limb_bits = src.read_usize()?;
+        let limbs = src.read_target_vec()?;
+        Ok(Self {
+            x,
+            limb_bits,
+            limbs,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.x]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let x = witness.get_target(self.x).to_canonical_u64();
+        let mask = (1 << self.limb_bits) - 1;
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            let limb_value = (x >> (self.limb_bits * i)) & mask;
+            out_buffer.set_target(limb, F::from_canonical_u64(limb_value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::types::Field;
+    use crate::iop::witness::PartialWitness;
+    use crate::nonnative::u32::gadgets::arithmetic_u32::CircuitBuilderU32;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_bitwise_u32() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        let a_value: u32 = rng.gen();
+        let b_value: u32 = rng.gen();
+        let a = builder.add_virtual_u32_target();
+        let b = builder.add_virtual_u32_target();
+        pw.set_target(a.0, F::from_canonical_u32(a_value));
+        pw.set_target(b.0, F::from_canonical_u32(b_value));
+
+        let mut expected = vec![
+            (builder.and_u32(a, b), a_value & b_value),
+            (builder.xor_u32(a, b), a_value ^ b_value),
+            (builder.not_u32(a), !a_value),
+        ];
+        for n in [0, 1, 7, 8, 13, 16, 24, 31] {
+            expected.push((builder.rotr_u32(a, n), a_value.rotate_right(n as u32)));
+            expected.push((builder.shr_u32(a, n), a_value >> n));
+        }
+        for (result, value) in expected {
+            let value = builder.constant_u32(value);
+            builder.connect_u32(result, value);
+        }
+        builder.range_check_u32(b);
+        let u16_max = builder.constant(F::from_canonical_u32(u16::MAX as u32));
+        builder.range_check_u16(u16_max);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_range_check_u16_fails() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.constant(F::from_canonical_u32(1 << 16));
+        builder.range_check_u16(x);
+
+        let data = builder.build::<C>();
+        data.prove(PartialWitness::new()).unwrap();
+    }
+}
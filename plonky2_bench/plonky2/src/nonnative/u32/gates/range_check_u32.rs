@@ -25,7 +25,7 @@ pub const fn ceil_div_usize(a: usize, b: usize) -> usize {
 }
 
 /// A gate which can decompose a number into base B little-endian limbs.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default)]
 pub struct U32RangeCheckGate<F: RichField + Extendable<D>, const D: usize> {
     pub num_input_limbs: usize,
     _phantom: PhantomData<F>,
@@ -180,7 +180,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for U32RangeCheckG
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct U32RangeCheckGenerator<F: RichField + Extendable<D>, const D: usize> {
     gate: U32RangeCheckGate<F, D>,
     row: usize,
@@ -1,7 +1,14 @@
 //! Logic for building plonky2 circuits.
 
 #[cfg(not(feature = "std"))]
-use alloc::{collections::BTreeMap, sync::Arc, vec, vec::Vec};
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
 use core::cmp::max;
 #[cfg(feature = "std")]
 use std::{collections::BTreeMap, sync::Arc, time::Instant};
@@ -9,13 +16,14 @@ use std::{collections::BTreeMap, sync::Arc, time::Instant};
 use hashbrown::{HashMap, HashSet};
 use itertools::Itertools;
 use log::{debug, info, warn, Level};
+use sha2::{Digest, Sha256};
 
 use crate::field::cosets::get_unique_coset_shifts;
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::fft::fft_root_table;
 use crate::field::polynomial::PolynomialValues;
-use crate::field::types::Field;
 use crate::fri::oracle::PolynomialBatch;
+use crate::fri::security::{conjectured_security_bits, fri_field_bits};
 use crate::fri::{FriConfig, FriParams};
 use crate::gadgets::arithmetic::BaseArithmeticOperation;
 use crate::gadgets::arithmetic_extension::ExtensionArithmeticOperation;
@@ -44,10 +52,11 @@ use crate::plonk::circuit_data::{
 };
 use crate::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut, Hasher};
 use crate::plonk::copy_constraint::CopyConstraint;
+use crate::plonk::cost_report::CostReport;
 use crate::plonk::permutation_argument::Forest;
 use crate::plonk::plonk_common::PlonkOracle;
 use crate::timed;
-use crate::util::context_tree::ContextTree;
+use crate::util::context_tree::{BuilderCounts, ContextTree};
 use crate::util::partial_products::num_partial_products;
 use crate::util::timing::TimingTree;
 use crate::util::{log2_ceil, log2_strict, transpose, transpose_poly_values};
@@ -190,6 +199,10 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     // Lookup tables in the form of `Vec<(input_value, output_value)>`.
     luts: Vec<LookupTable>,
 
+    /// Indices of the LUTs added through `named_lut`, so that gadgets share a table without
+    /// rebuilding it for each call.
+    named_luts: HashMap<String, usize>,
+
     /// Optional common data. When it is `Some(goal_data)`, the `build` function panics if the resulting
     /// common data doesn't equal `goal_data`.
     /// This is used in cyclic recursion.
@@ -225,6 +238,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             lookup_rows: Vec::new(),
             lut_to_lookups: Vec::new(),
             luts: Vec::new(),
+            named_luts: HashMap::new(),
             goal_common_data: None,
             verifier_data_public_input: None,
         };
@@ -248,12 +262,16 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         } = &self.config;
 
         // Conjectured FRI security; see the ethSTARK paper.
-        let fri_field_bits = F::Extension::order().bits() as usize;
-        let fri_query_security_bits = num_query_rounds * rate_bits + proof_of_work_bits as usize;
-        let fri_security_bits = fri_field_bits.min(fri_query_security_bits);
+        let fri_security_bits = conjectured_security_bits(
+            fri_field_bits::<F, D>(),
+            rate_bits,
+            num_query_rounds,
+            proof_of_work_bits,
+        );
         assert!(
             fri_security_bits >= security_bits,
-            "FRI params fall short of target security"
+            "FRI params fall short of target security {security_bits}, reaching only \
+             {fri_security_bits}"
         );
     }
 
@@ -685,11 +703,11 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     }
 
     pub fn push_context(&mut self, level: log::Level, ctx: &str) {
-        self.context_log.push(ctx, level, self.num_gates());
+        self.context_log.push(ctx, level, self.builder_counts());
     }
 
     pub fn pop_context(&mut self) {
-        self.context_log.pop(self.num_gates());
+        self.context_log.pop(self.builder_counts());
     }
 
     /// Returns the total number of LUTs.
@@ -713,6 +731,17 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.luts.iter().position(|elt| *elt == lut)
     }
 
+    /// Returns the index of the LUT registered under `name`, adding it with `add_lut` if this is
+    /// the first call with that name.
+    pub fn named_lut(&mut self, name: &str, add_lut: impl FnOnce(&mut Self) -> usize) -> usize {
+        if let Some(&idx) = self.named_luts.get(name) {
+            return idx;
+        }
+        let idx = add_lut(self);
+        self.named_luts.insert(name.to_string(), idx);
+        idx
+    }
+
     /// Returns the LUT at index `idx`.
     pub fn get_lut(&self, idx: usize) -> LookupTable {
         assert!(
@@ -1006,6 +1035,112 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         )
     }
 
+    fn builder_counts(&self) -> BuilderCounts {
+        BuilderCounts {
+            gates: self.num_gates(),
+            copy_constraints: self.copy_constraints.len(),
+            lookups: self.lut_to_lookups.iter().map(|l| l.len()).sum(),
+            generators: self.generators.len(),
+        }
+    }
+
+    /// Returns a SHA-256 digest of the circuit as laid out so far: the config, the gates with their
+    /// constants, the copy constraints, the constants, the public inputs, the lookups and the
+    /// generators with their watch lists. Unlike the circuit digest it is known before `build`, so
+    /// it can key a cache of the built circuit data.
+    pub fn structure_digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        let hash_usize = |hasher: &mut Sha256, x: usize| hasher.update((x as u64).to_le_bytes());
+        let hash_target = |hasher: &mut Sha256, target: &Target| match *target {
+            Target::Wire(Wire { row, column }) => {
+                hasher.update([0]);
+                hasher.update((row as u64).to_le_bytes());
+                hasher.update((column as u64).to_le_bytes());
+            }
+            Target::VirtualTarget { index } => {
+                hasher.update([1]);
+                hasher.update((index as u64).to_le_bytes());
+            }
+        };
+
+        hasher.update(format!("{:?}", self.config));
+        hash_usize(&mut hasher, self.gate_instances.len());
+        for instance in self.gate_instances.iter() {
+            hasher.update(instance.gate_ref.0.id());
+            hash_usize(&mut hasher, instance.constants.len());
+            for c in instance.constants.iter() {
+                hasher.update(c.to_canonical_u64().to_le_bytes());
+            }
+        }
+        hash_usize(&mut hasher, self.copy_constraints.len());
+        for CopyConstraint { pair: (a, b), .. } in self.copy_constraints.iter() {
+            hash_target(&mut hasher, a);
+            hash_target(&mut hasher, b);
+        }
+        // the map isn't ordered, so the constants are sorted by value
+        let constants = self
+            .constants_to_targets
+            .iter()
+            .map(|(c, target)| (c.to_canonical_u64(), target))
+            .sorted_by_key(|(c, _)| *c)
+            .collect::<Vec<_>>();
+        hash_usize(&mut hasher, constants.len());
+        for (c, target) in constants {
+            hasher.update(c.to_le_bytes());
+            hash_target(&mut hasher, target);
+        }
+        hash_usize(&mut hasher, self.public_inputs.len());
+        for target in self.public_inputs.iter() {
+            hash_target(&mut hasher, target);
+        }
+        hash_usize(&mut hasher, self.luts.len());
+        for (lut, lookups) in self.luts.iter().zip(self.lut_to_lookups.iter()) {
+            hash_usize(&mut hasher, lut.len());
+            for (input, output) in lut.iter() {
+                hasher.update(input.to_le_bytes());
+                hasher.update(output.to_le_bytes());
+            }
+            hash_usize(&mut hasher, lookups.len());
+            for (looking_in, looking_out) in lookups.iter() {
+                hash_target(&mut hasher, looking_in);
+                hash_target(&mut hasher, looking_out);
+            }
+        }
+        hash_usize(&mut hasher, self.generators.len());
+        for generator in self.generators.iter() {
+            hasher.update(generator.0.id());
+            let watch_list = generator.0.watch_list();
+            hash_usize(&mut hasher, watch_list.len());
+            for target in watch_list.iter() {
+                hash_target(&mut hasher, target);
+            }
+        }
+
+        hasher.finalize().into()
+    }
+
+    /// Returns the rows, copy constraints, lookups and generators attributed to each context, as a
+    /// tree rooted at the whole circuit. Unlike [`Self::print_gate_counts`], the report is owned, so
+    /// it can be kept or exported after the builder is consumed.
+    pub fn cost_report(&self) -> CostReport {
+        let mut report = CostReport::new(
+            &self.context_log,
+            self.builder_counts(),
+            &self.gate_instances,
+            &self.copy_constraints,
+        );
+        let lookup_gates = self.num_lookup_gates();
+        if lookup_gates > 0 {
+            report.rows += lookup_gates;
+            report.children.push(CostReport {
+                name: "lookup gates".to_string(),
+                rows: lookup_gates,
+                ..Default::default()
+            });
+        }
+        report
+    }
+
     pub fn print_gate_counts(&self, min_delta: usize) {
         // Print gate counts for each context.
         self.context_log
@@ -1258,6 +1393,16 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             luts: self.luts,
         };
 
+        // Only the conjectured security level is enforced, by `check_config`. The provable one is
+        // below the target for the standard configurations, so it is reported at debug level
+        // rather than warned about; `CommonCircuitData::provable_security_bits` checks it.
+        #[cfg(feature = "std")]
+        debug!(
+            "FRI params reach {:.1} bits of provable security, for a target of {}",
+            common.provable_security_bits(),
+            common.config.security_bits
+        );
+
         let mut success = true;
 
         if let Some(goal_data) = self.goal_common_data {
@@ -1325,3 +1470,34 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         circuit_data.verifier_data()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::field::types::Field;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn test_structure_digest() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let circuit = |constant: u64, config: CircuitConfig| {
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+            let x = builder.add_virtual_target();
+            let y = builder.mul_const(F::from_canonical_u64(constant), x);
+            builder.register_public_input(y);
+            builder.structure_digest()
+        };
+
+        let config = CircuitConfig::standard_recursion_config();
+        assert_eq!(circuit(3, config.clone()), circuit(3, config.clone()));
+        assert_ne!(circuit(3, config.clone()), circuit(5, config));
+        assert_ne!(
+            circuit(3, CircuitConfig::standard_recursion_config()),
+            circuit(3, CircuitConfig::standard_recursion_zk_config())
+        );
+    }
+}
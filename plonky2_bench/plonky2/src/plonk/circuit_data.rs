@@ -18,7 +18,7 @@ use core::ops::{Range, RangeFrom};
 #[cfg(feature = "std")]
 use std::collections::BTreeMap;
 
-use anyhow::Result;
+use anyhow::{anyhow, ensure, Result};
 use serde::Serialize;
 
 use super::circuit_builder::LookupWire;
@@ -27,6 +27,8 @@ use crate::field::fft::FftRootTable;
 use crate::field::types::Field;
 use crate::fri::oracle::PolynomialBatch;
 use crate::fri::reduction_strategies::FriReductionStrategy;
+#[cfg(feature = "std")]
+use crate::fri::security::{fri_field_bits, provable_security_bits};
 use crate::fri::structure::{
     FriBatchInfo, FriBatchInfoTarget, FriInstanceInfo, FriInstanceInfoTarget, FriOracleInfo,
     FriPolynomialInfo,
@@ -527,6 +529,17 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
             ..self.num_zs_partial_products_polys() + i * self.num_lookup_polys + 2
     }
 
+    /// Bits of security of this circuit's FRI proofs in the provable (Johnson bound) regime, see
+    /// [`crate::fri::security`].
+    #[cfg(feature = "std")]
+    pub fn provable_security_bits(&self) -> f64 {
+        provable_security_bits(
+            fri_field_bits::<F, D>(),
+            &self.fri_params,
+            self.fri_all_polys().len(),
+        )
+    }
+
     pub(crate) fn get_fri_instance(&self, zeta: F::Extension) -> FriInstanceInfo<F, D> {
         // All polynomials are opened at zeta.
         let zeta_batch = FriBatchInfo {
@@ -575,6 +588,26 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
         }
     }
 
+    /// Returns the FRI parameters of a batch proof of circuits with the given common data, i.e.
+    /// those of the largest circuit. The circuits must agree on everything the shared challenges
+    /// and FRI proof depend on.
+    pub(crate) fn batch_fri_params(common_data: &[&Self]) -> Result<FriParams> {
+        let largest = common_data
+            .iter()
+            .max_by_key(|cd| cd.degree_bits())
+            .ok_or_else(|| anyhow!("A batch needs at least one circuit."))?;
+        for cd in common_data {
+            ensure!(
+                cd.config.fri_config == largest.config.fri_config
+                    && cd.config.num_challenges == largest.config.num_challenges
+                    && cd.config.zero_knowledge == largest.config.zero_knowledge,
+                "Circuits of a batch must have the same FRI configuration, number of challenges \
+                 and zero-knowledge setting."
+            );
+        }
+        Ok(largest.fri_params.clone())
+    }
+
     fn fri_oracles(&self) -> Vec<FriOracleInfo> {
         vec![
             FriOracleInfo {
@@ -73,6 +73,12 @@ pub trait Hasher<F: RichField>: Sized + Copy + Debug + Eq + PartialEq {
         }
     }
 
+    /// Same as `hash_or_noop` for two inputs. Hashers with a vectorized permutation override it to
+    /// hash both inputs together.
+    fn hash_or_noop_x2(inputs: [&[F]; 2]) -> [Self::Hash; 2] {
+        inputs.map(Self::hash_or_noop)
+    }
+
     fn two_to_one(left: Self::Hash, right: Self::Hash) -> Self::Hash;
 }
 
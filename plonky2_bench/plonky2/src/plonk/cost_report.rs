@@ -0,0 +1,189 @@
+//! Per-context cost accounting for circuits under construction.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use hashbrown::HashSet;
+use serde::Serialize;
+
+use crate::field::extension::Extendable;
+use crate::gates::gate::GateInstance;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::Target;
+use crate::plonk::copy_constraint::CopyConstraint;
+use crate::util::context_tree::{BuilderCounts, ContextTree};
+
+/// The resources attributed to a context pushed with `CircuitBuilder::push_context`, including
+/// those of its children.
+///
+/// Rows are attributed to the context which created them, so a row whose slots are shared by
+/// several contexts, like an `ArithmeticGate` row, counts towards the first one.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct CostReport {
+    pub name: String,
+    /// Number of rows, i.e. of gate instances.
+    pub rows: usize,
+    /// Number of rows of each gate type.
+    pub gates: BTreeMap<String, usize>,
+    /// Number of distinct routed wires constrained by copy constraints.
+    pub routed_wires: usize,
+    pub copy_constraints: usize,
+    /// Number of lookups. Their `LookupGate` and `LookupTableGate` rows are only placed when the
+    /// circuit is built, so the root reports them as a separate `lookup gates` child.
+    pub lookups: usize,
+    pub generators: usize,
+    pub children: Vec<CostReport>,
+}
+
+impl CostReport {
+    pub(crate) fn new<F: RichField + Extendable<D>, const D: usize>(
+        context: &ContextTree,
+        current_counts: BuilderCounts,
+        gate_instances: &[GateInstance<F, D>],
+        copy_constraints: &[CopyConstraint],
+    ) -> Self {
+        let enter = context.enter_counts();
+        let exit = context.exit_counts(current_counts);
+
+        let mut gates = BTreeMap::new();
+        for instance in &gate_instances[enter.gates..exit.gates] {
+            *gates.entry(instance.gate_ref.0.id()).or_insert(0) += 1;
+        }
+
+        let copy_constraints_range =
+            &copy_constraints[enter.copy_constraints..exit.copy_constraints];
+        let routed_wires = copy_constraints_range
+            .iter()
+            .flat_map(|c| [c.pair.0, c.pair.1])
+            .filter(|t| matches!(t, Target::Wire(_)))
+            .collect::<HashSet<_>>()
+            .len();
+
+        Self {
+            name: context.name().to_string(),
+            rows: exit.gates - enter.gates,
+            gates,
+            routed_wires,
+            copy_constraints: exit.copy_constraints - enter.copy_constraints,
+            lookups: exit.lookups - enter.lookups,
+            generators: exit.generators - enter.generators,
+            children: context
+                .children()
+                .iter()
+                .map(|c| Self::new(c, current_counts, gate_instances, copy_constraints))
+                .collect(),
+        }
+    }
+
+    /// Rows attributed to this context but to none of its children.
+    pub fn self_rows(&self) -> usize {
+        self.rows - self.children.iter().map(|c| c.rows).sum::<usize>()
+    }
+
+    /// Renders the tree in the folded stack format of `flamegraph.pl` and `inferno`, weighting
+    /// each stack by its number of rows.
+    pub fn to_folded(&self) -> String {
+        let mut folded = String::new();
+        self.folded_helper("", &mut folded);
+        folded
+    }
+
+    fn folded_helper(&self, prefix: &str, folded: &mut String) {
+        // Semicolons separate the frames of a stack.
+        let stack = format!("{}{}", prefix, self.name.replace(';', ":"));
+        let self_rows = self.self_rows();
+        if self_rows > 0 {
+            folded.push_str(&format!("{} {}\n", stack, self_rows));
+        }
+        for child in &self.children {
+            child.folded_helper(&format!("{};", stack), folded);
+        }
+    }
+
+    fn fmt_helper(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{}{} rows, {} copy constraints, {} lookups, {} generators to {}",
+            "| ".repeat(depth),
+            self.rows,
+            self.copy_constraints,
+            self.lookups,
+            self.generators,
+            self.name
+        )?;
+        for child in &self.children {
+            child.fmt_helper(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CostReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_helper(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn test_cost_report() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_target();
+        builder.push_context(log::Level::Debug, "outer");
+        builder.split_le(x, 64);
+        builder.push_context(log::Level::Debug, "inner");
+        let inputs = builder.add_virtual_targets(8);
+        builder.hash_n_to_hash_no_pad::<<C as GenericConfig<D>>::InnerHasher>(inputs);
+        builder.pop_context();
+        builder.pop_context();
+        builder.push_context(log::Level::Debug, "range");
+        let lut = builder.add_lookup_table_from_fn(|x| x, &[0, 1, 2, 3]);
+        builder.add_lookup_from_index(x, lut);
+        builder.pop_context();
+
+        let report = builder.cost_report();
+        assert_eq!(
+            report.rows,
+            builder.num_gates() + builder.num_lookup_gates()
+        );
+
+        let outer = &report.children[0];
+        let inner = &outer.children[0];
+        assert_eq!(outer.name, "outer");
+        assert_eq!(inner.name, "inner");
+        assert!(inner.gates.keys().any(|id| id.starts_with("PoseidonGate")));
+        assert_eq!(outer.rows, outer.gates.values().sum::<usize>());
+        assert!(outer.self_rows() > 0);
+        assert!(outer.copy_constraints >= inner.copy_constraints);
+        assert!(outer.generators > inner.generators);
+        assert_eq!(report.children[1].lookups, 1);
+        assert_eq!(report.lookups, 1);
+
+        let folded = report.to_folded();
+        assert!(folded.lines().any(|l| l.starts_with("root;outer;inner ")));
+        let total = folded
+            .lines()
+            .map(|l| l.rsplit(' ').next().unwrap().parse::<usize>().unwrap())
+            .sum::<usize>();
+        assert_eq!(total, report.rows);
+    }
+}
@@ -17,6 +17,7 @@ use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
 use crate::plonk::proof::{
+    BatchProof, BatchProofTarget, BatchProofWithPublicInputs, BatchProofWithPublicInputsTarget,
     CompressedProof, CompressedProofWithPublicInputs, FriInferredElements, OpeningSet,
     OpeningSetTarget, Proof, ProofChallenges, ProofChallengesTarget, ProofTarget,
     ProofWithPublicInputs, ProofWithPublicInputsTarget,
@@ -139,6 +140,91 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
     }
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    BatchProofWithPublicInputs<F, C, D>
+{
+    /// Computes all Fiat-Shamir challenges used in the batch proof, which are shared by all
+    /// circuits. The FRI challenges are those of the largest circuit.
+    pub fn get_challenges(
+        &self,
+        public_inputs_hashes: &[<<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash],
+        circuit_digests: &[<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash],
+        common_data: &[&CommonCircuitData<F, D>],
+    ) -> anyhow::Result<ProofChallenges<F, D>> {
+        let BatchProof {
+            instances,
+            opening_proof:
+                FriProof {
+                    commit_phase_merkle_caps,
+                    final_poly,
+                    pow_witness,
+                    ..
+                },
+        } = &self.proof;
+        let fri_params = CommonCircuitData::batch_fri_params(common_data)?;
+        let num_challenges = common_data[0].config.num_challenges;
+
+        let mut challenger = Challenger::<F, C::Hasher>::new();
+        let has_lookup = common_data.iter().any(|cd| cd.num_lookup_polys != 0);
+
+        // Observe the instances.
+        for (circuit_digest, public_inputs_hash) in circuit_digests.iter().zip(public_inputs_hashes)
+        {
+            challenger.observe_hash::<C::Hasher>(*circuit_digest);
+            challenger.observe_hash::<C::InnerHasher>(*public_inputs_hash);
+        }
+
+        for instance in instances {
+            challenger.observe_cap::<C::Hasher>(&instance.wires_cap);
+        }
+        let plonk_betas = challenger.get_n_challenges(num_challenges);
+        let plonk_gammas = challenger.get_n_challenges(num_challenges);
+
+        // The lookup challenges are drawn if any of the circuits has lookups.
+        let plonk_deltas = if has_lookup {
+            let num_lookup_challenges = NUM_COINS_LOOKUP * num_challenges;
+            let mut deltas = Vec::with_capacity(num_lookup_challenges);
+            let num_additional_challenges = num_lookup_challenges - 2 * num_challenges;
+            let additional = challenger.get_n_challenges(num_additional_challenges);
+            deltas.extend(&plonk_betas);
+            deltas.extend(&plonk_gammas);
+            deltas.extend(additional);
+            deltas
+        } else {
+            vec![]
+        };
+
+        for instance in instances {
+            challenger.observe_cap::<C::Hasher>(&instance.plonk_zs_partial_products_cap);
+        }
+        let plonk_alphas = challenger.get_n_challenges(num_challenges);
+
+        for instance in instances {
+            challenger.observe_cap::<C::Hasher>(&instance.quotient_polys_cap);
+        }
+        let plonk_zeta = challenger.get_extension_challenge::<D>();
+
+        for instance in instances {
+            challenger.observe_openings(&instance.openings.to_fri_openings());
+        }
+
+        Ok(ProofChallenges {
+            plonk_betas,
+            plonk_gammas,
+            plonk_alphas,
+            plonk_deltas,
+            plonk_zeta,
+            fri_challenges: challenger.fri_challenges::<C, D>(
+                commit_phase_merkle_caps,
+                final_poly,
+                *pow_witness,
+                fri_params.degree_bits,
+                &fri_params.config,
+            ),
+        })
+    }
+}
+
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
     CompressedProofWithPublicInputs<F, C, D>
 {
@@ -364,3 +450,89 @@ impl<const D: usize> ProofWithPublicInputsTarget<D> {
         )
     }
 }
+
+impl<const D: usize> BatchProofWithPublicInputsTarget<D> {
+    pub(crate) fn get_challenges<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        public_inputs_hashes: &[HashOutTarget],
+        inner_circuit_digests: &[HashOutTarget],
+        inner_common_data: &[&CommonCircuitData<F, D>],
+    ) -> ProofChallengesTarget<D>
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let BatchProofTarget {
+            instances,
+            opening_proof:
+                FriProofTarget {
+                    commit_phase_merkle_caps,
+                    final_poly,
+                    pow_witness,
+                    ..
+                },
+        } = &self.proof;
+        let config = &inner_common_data[0].config;
+        let num_challenges = config.num_challenges;
+
+        let mut challenger = RecursiveChallenger::<F, C::Hasher, D>::new(builder);
+        let has_lookup = inner_common_data.iter().any(|cd| cd.num_lookup_polys != 0);
+
+        // Observe the instances.
+        for (circuit_digest, public_inputs_hash) in
+            inner_circuit_digests.iter().zip(public_inputs_hashes)
+        {
+            challenger.observe_hash(circuit_digest);
+            challenger.observe_hash(public_inputs_hash);
+        }
+
+        for instance in instances {
+            challenger.observe_cap(&instance.wires_cap);
+        }
+        let plonk_betas = challenger.get_n_challenges(builder, num_challenges);
+        let plonk_gammas = challenger.get_n_challenges(builder, num_challenges);
+
+        // The lookup challenges are drawn if any of the circuits has lookups.
+        let plonk_deltas = if has_lookup {
+            let num_lookup_challenges = NUM_COINS_LOOKUP * num_challenges;
+            let mut deltas = Vec::with_capacity(num_lookup_challenges);
+            let num_additional_challenges = num_lookup_challenges - 2 * num_challenges;
+            let additional = challenger.get_n_challenges(builder, num_additional_challenges);
+            deltas.extend(&plonk_betas);
+            deltas.extend(&plonk_gammas);
+            deltas.extend(additional);
+            deltas
+        } else {
+            vec![]
+        };
+
+        for instance in instances {
+            challenger.observe_cap(&instance.plonk_zs_partial_products_cap);
+        }
+        let plonk_alphas = challenger.get_n_challenges(builder, num_challenges);
+
+        for instance in instances {
+            challenger.observe_cap(&instance.quotient_polys_cap);
+        }
+        let plonk_zeta = challenger.get_extension_challenge(builder);
+
+        for instance in instances {
+            challenger.observe_openings(&instance.openings.to_fri_openings());
+        }
+
+        ProofChallengesTarget {
+            plonk_betas,
+            plonk_gammas,
+            plonk_alphas,
+            plonk_deltas,
+            plonk_zeta,
+            fri_challenges: challenger.fri_challenges(
+                builder,
+                commit_phase_merkle_caps,
+                final_poly,
+                *pow_witness,
+                &config.fri_config,
+            ),
+        }
+    }
+}
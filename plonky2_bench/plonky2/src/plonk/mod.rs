@@ -7,6 +7,7 @@ pub mod circuit_builder;
 pub mod circuit_data;
 pub mod config;
 pub(crate) mod copy_constraint;
+pub mod cost_report;
 mod get_challenges;
 pub(crate) mod permutation_argument;
 pub mod plonk_common;
@@ -127,6 +127,80 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
     }
 }
 
+/// The commitments and openings of one of the circuits of a [`BatchProof`].
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(bound = "")]
+pub struct BatchProofInstance<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    /// Merkle cap of LDEs of wire values.
+    pub wires_cap: MerkleCap<F, C::Hasher>,
+    /// Merkle cap of LDEs of Z, in the context of Plonk's permutation argument.
+    pub plonk_zs_partial_products_cap: MerkleCap<F, C::Hasher>,
+    /// Merkle cap of LDEs of the quotient polynomial components.
+    pub quotient_polys_cap: MerkleCap<F, C::Hasher>,
+    /// Purported values of each polynomial at the challenge point.
+    pub openings: OpeningSet<F, D>,
+}
+
+/// A proof of several circuits, which share their Fiat-Shamir challenges and a single FRI
+/// argument for the openings of all of them.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(bound = "")]
+pub struct BatchProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    /// The commitments and openings of each circuit.
+    pub instances: Vec<BatchProofInstance<F, C, D>>,
+    /// A batch FRI argument for the openings of all circuits, see
+    /// [`PolynomialBatch::prove_batch_openings`].
+    pub opening_proof: FriProof<F, C::Hasher, D>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(bound = "")]
+pub struct BatchProofWithPublicInputs<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    pub proof: BatchProof<F, C, D>,
+    /// The public inputs of each circuit.
+    pub public_inputs: Vec<Vec<F>>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    BatchProofWithPublicInputs<F, C, D>
+{
+    pub fn get_public_inputs_hashes(
+        &self,
+    ) -> Vec<<<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash> {
+        self.public_inputs
+            .iter()
+            .map(|pis| C::InnerHasher::hash_no_pad(pis))
+            .collect()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_batch_proof_with_public_inputs(self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    pub fn from_bytes(
+        bytes: Vec<u8>,
+        common_data: &[&CommonCircuitData<F, D>],
+    ) -> anyhow::Result<Self> {
+        let mut buffer = Buffer::new(&bytes);
+        let proof = buffer
+            .read_batch_proof_with_public_inputs(common_data)
+            .map_err(anyhow::Error::msg)?;
+        Ok(proof)
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 #[serde(bound = "")]
 pub struct CompressedProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
@@ -296,6 +370,26 @@ pub struct ProofWithPublicInputsTarget<const D: usize> {
     pub public_inputs: Vec<Target>,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchProofInstanceTarget<const D: usize> {
+    pub wires_cap: MerkleCapTarget,
+    pub plonk_zs_partial_products_cap: MerkleCapTarget,
+    pub quotient_polys_cap: MerkleCapTarget,
+    pub openings: OpeningSetTarget<D>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchProofTarget<const D: usize> {
+    pub instances: Vec<BatchProofInstanceTarget<D>>,
+    pub opening_proof: FriProofTarget<D>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchProofWithPublicInputsTarget<const D: usize> {
+    pub proof: BatchProofTarget<D>,
+    pub public_inputs: Vec<Vec<Target>>,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
 /// The purported values of each polynomial at a single point.
 pub struct OpeningSet<F: RichField + Extendable<D>, const D: usize> {
@@ -24,10 +24,13 @@ use crate::iop::generator::generate_partial_witness;
 use crate::iop::target::Target;
 use crate::iop::witness::{MatrixWitness, PartialWitness, PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::NUM_COINS_LOOKUP;
-use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::circuit_data::{CommonCircuitData, ProverCircuitData, ProverOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::PlonkOracle;
-use crate::plonk::proof::{OpeningSet, Proof, ProofWithPublicInputs};
+use crate::plonk::proof::{
+    BatchProof, BatchProofInstance, BatchProofWithPublicInputs, OpeningSet, Proof,
+    ProofWithPublicInputs,
+};
 use crate::plonk::vanishing_poly::{eval_vanishing_poly_base_batch, get_lut_poly};
 use crate::plonk::vars::EvaluationVarsBaseBatch;
 use crate::timed;
@@ -135,23 +138,338 @@ pub fn prove_with_partition_witness<
 >(
     prover_data: &ProverOnlyCircuitData<F, C, D>,
     common_data: &CommonCircuitData<F, D>,
-    mut partition_witness: PartitionWitness<F>,
+    partition_witness: PartitionWitness<F>,
     timing: &mut TimingTree,
 ) -> Result<ProofWithPublicInputs<F, C, D>>
 where
     C::Hasher: Hasher<F>,
     C::InnerHasher: Hasher<F>,
 {
-    let has_lookup = !common_data.luts.is_empty();
     let config = &common_data.config;
     let num_challenges = config.num_challenges;
-    let quotient_degree = common_data.quotient_degree();
-    let degree = common_data.degree();
+
+    let (public_inputs, witness, wires_commitment) =
+        commit_to_wires(prover_data, common_data, partition_witness, timing);
+    let public_inputs_hash = C::InnerHasher::hash_no_pad(&public_inputs);
+
+    let mut challenger = Challenger::<F, C::Hasher>::new();
+
+    // Observe the instance.
+    challenger.observe_hash::<C::Hasher>(prover_data.circuit_digest);
+    challenger.observe_hash::<C::InnerHasher>(public_inputs_hash);
+
+    challenger.observe_cap::<C::Hasher>(&wires_commitment.merkle_tree.cap);
+
+    let betas = challenger.get_n_challenges(num_challenges);
+    let gammas = challenger.get_n_challenges(num_challenges);
+
+    let deltas = if !common_data.luts.is_empty() {
+        get_lookup_deltas(&mut challenger, &betas, &gammas)
+    } else {
+        vec![]
+    };
+
+    let partial_products_zs_and_lookup_commitment = commit_to_zs_partial_products_and_lookups(
+        &witness,
+        &betas,
+        &gammas,
+        &deltas,
+        prover_data,
+        common_data,
+        timing,
+    );
+
+    challenger.observe_cap::<C::Hasher>(&partial_products_zs_and_lookup_commitment.merkle_tree.cap);
+
+    let alphas = challenger.get_n_challenges(num_challenges);
+
+    let quotient_polys_commitment = commit_to_quotient_polys(
+        prover_data,
+        common_data,
+        &public_inputs_hash,
+        &wires_commitment,
+        &partial_products_zs_and_lookup_commitment,
+        &betas,
+        &gammas,
+        &deltas,
+        &alphas,
+        timing,
+    );
+
+    challenger.observe_cap::<C::Hasher>(&quotient_polys_commitment.merkle_tree.cap);
+
+    let zeta = challenger.get_extension_challenge::<D>();
+    // To avoid leaking witness data, we want to ensure that our opening locations, `zeta` and
+    // `g * zeta`, are not in our subgroup `H`. It suffices to check `zeta` only, since
+    // `(g * zeta)^n = zeta^n`, where `n` is the order of `g`.
+    let g = F::Extension::primitive_root_of_unity(common_data.degree_bits());
+    ensure!(
+        zeta.exp_power_of_2(common_data.degree_bits()) != F::Extension::ONE,
+        "Opening point is in the subgroup."
+    );
+
+    let openings = timed!(
+        timing,
+        "construct the opening set, including lookups",
+        OpeningSet::new(
+            zeta,
+            g,
+            &prover_data.constants_sigmas_commitment,
+            &wires_commitment,
+            &partial_products_zs_and_lookup_commitment,
+            &quotient_polys_commitment,
+            common_data
+        )
+    );
+    challenger.observe_openings(&openings.to_fri_openings());
+    let instance = common_data.get_fri_instance(zeta);
+
+    let opening_proof = timed!(
+        timing,
+        "compute opening proofs",
+        PolynomialBatch::<F, C, D>::prove_openings(
+            &instance,
+            &[
+                &prover_data.constants_sigmas_commitment,
+                &wires_commitment,
+                &partial_products_zs_and_lookup_commitment,
+                &quotient_polys_commitment,
+            ],
+            &mut challenger,
+            &common_data.fri_params,
+            timing,
+        )
+    );
+
+    let proof = Proof::<F, C, D> {
+        wires_cap: wires_commitment.merkle_tree.cap,
+        plonk_zs_partial_products_cap: partial_products_zs_and_lookup_commitment.merkle_tree.cap,
+        quotient_polys_cap: quotient_polys_commitment.merkle_tree.cap,
+        openings,
+        opening_proof,
+    };
+    Ok(ProofWithPublicInputs::<F, C, D> {
+        proof,
+        public_inputs,
+    })
+}
+
+/// Proves several circuits at once. The circuits share their Fiat-Shamir challenges, and the
+/// openings of all of them are proven by a single FRI argument over the degree of the largest
+/// circuit, so the batch proof is smaller and cheaper to verify than separate proofs.
+///
+/// The circuits must have the same FRI configuration, number of challenges and zero-knowledge
+/// setting, but their degrees may differ.
+pub fn prove_batch<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    circuits: &[&ProverCircuitData<F, C, D>],
+    inputs: Vec<PartialWitness<F>>,
+    timing: &mut TimingTree,
+) -> Result<BatchProofWithPublicInputs<F, C, D>>
+where
+    C::Hasher: Hasher<F>,
+    C::InnerHasher: Hasher<F>,
+{
+    ensure!(
+        circuits.len() == inputs.len(),
+        "Expected one witness per circuit."
+    );
+    let common_data = circuits.iter().map(|c| &c.common).collect::<Vec<_>>();
+    let fri_params = CommonCircuitData::batch_fri_params(&common_data)?;
+    let num_challenges = common_data[0].config.num_challenges;
+
+    let mut public_inputs = Vec::with_capacity(circuits.len());
+    let mut witnesses = Vec::with_capacity(circuits.len());
+    let mut wires_commitments = Vec::with_capacity(circuits.len());
+    for (circuit, inputs) in circuits.iter().zip(inputs) {
+        let partition_witness = timed!(
+            timing,
+            &format!("run {} generators", circuit.prover_only.generators.len()),
+            generate_partial_witness(inputs, &circuit.prover_only, &circuit.common)
+        );
+        let (pis, witness, wires_commitment) = commit_to_wires(
+            &circuit.prover_only,
+            &circuit.common,
+            partition_witness,
+            timing,
+        );
+        public_inputs.push(pis);
+        witnesses.push(witness);
+        wires_commitments.push(wires_commitment);
+    }
+    let public_inputs_hashes = public_inputs
+        .iter()
+        .map(|pis| C::InnerHasher::hash_no_pad(pis))
+        .collect::<Vec<_>>();
+
+    let mut challenger = Challenger::<F, C::Hasher>::new();
+
+    // Observe the instances.
+    for (circuit, &public_inputs_hash) in circuits.iter().zip(&public_inputs_hashes) {
+        challenger.observe_hash::<C::Hasher>(circuit.prover_only.circuit_digest);
+        challenger.observe_hash::<C::InnerHasher>(public_inputs_hash);
+    }
+
+    for wires_commitment in &wires_commitments {
+        challenger.observe_cap::<C::Hasher>(&wires_commitment.merkle_tree.cap);
+    }
+
+    let betas = challenger.get_n_challenges(num_challenges);
+    let gammas = challenger.get_n_challenges(num_challenges);
+
+    let has_lookup = common_data.iter().any(|cd| !cd.luts.is_empty());
+    let deltas = if has_lookup {
+        get_lookup_deltas(&mut challenger, &betas, &gammas)
+    } else {
+        vec![]
+    };
+    // Only circuits with lookups use the lookup challenges.
+    let circuit_deltas = |cd: &CommonCircuitData<F, D>| {
+        if cd.luts.is_empty() {
+            &[][..]
+        } else {
+            &deltas[..]
+        }
+    };
+
+    let zs_commitments = circuits
+        .iter()
+        .zip(&witnesses)
+        .map(|(circuit, witness)| {
+            commit_to_zs_partial_products_and_lookups(
+                witness,
+                &betas,
+                &gammas,
+                circuit_deltas(&circuit.common),
+                &circuit.prover_only,
+                &circuit.common,
+                timing,
+            )
+        })
+        .collect::<Vec<_>>();
+    for zs_commitment in &zs_commitments {
+        challenger.observe_cap::<C::Hasher>(&zs_commitment.merkle_tree.cap);
+    }
+
+    let alphas = challenger.get_n_challenges(num_challenges);
+
+    let mut quotient_polys_commitments = Vec::with_capacity(circuits.len());
+    for (i, circuit) in circuits.iter().enumerate() {
+        quotient_polys_commitments.push(commit_to_quotient_polys(
+            &circuit.prover_only,
+            &circuit.common,
+            &public_inputs_hashes[i],
+            &wires_commitments[i],
+            &zs_commitments[i],
+            &betas,
+            &gammas,
+            circuit_deltas(&circuit.common),
+            &alphas,
+            timing,
+        ));
+    }
+    for quotient_polys_commitment in &quotient_polys_commitments {
+        challenger.observe_cap::<C::Hasher>(&quotient_polys_commitment.merkle_tree.cap);
+    }
+
+    let zeta = challenger.get_extension_challenge::<D>();
+    // As for a single proof, `zeta` must be outside the subgroup of every circuit. Since the
+    // subgroups are nested, it suffices to check the largest one.
+    let max_degree_bits = fri_params.degree_bits;
+    ensure!(
+        zeta.exp_power_of_2(max_degree_bits) != F::Extension::ONE,
+        "Opening point is in the subgroup."
+    );
+
+    let openings = timed!(
+        timing,
+        "construct the opening sets, including lookups",
+        circuits
+            .iter()
+            .enumerate()
+            .map(|(i, circuit)| {
+                let g = F::Extension::primitive_root_of_unity(circuit.common.degree_bits());
+                OpeningSet::new(
+                    zeta,
+                    g,
+                    &circuit.prover_only.constants_sigmas_commitment,
+                    &wires_commitments[i],
+                    &zs_commitments[i],
+                    &quotient_polys_commitments[i],
+                    &circuit.common,
+                )
+            })
+            .collect::<Vec<_>>()
+    );
+    for opening_set in &openings {
+        challenger.observe_openings(&opening_set.to_fri_openings());
+    }
+
+    let instances = common_data
+        .iter()
+        .map(|cd| cd.get_fri_instance(zeta))
+        .collect::<Vec<_>>();
+    let oracles = circuits
+        .iter()
+        .enumerate()
+        .map(|(i, circuit)| {
+            [
+                &circuit.prover_only.constants_sigmas_commitment,
+                &wires_commitments[i],
+                &zs_commitments[i],
+                &quotient_polys_commitments[i],
+            ]
+        })
+        .collect::<Vec<_>>();
+    let opening_proof = timed!(
+        timing,
+        "compute batch opening proof",
+        PolynomialBatch::<F, C, D>::prove_batch_openings(
+            &instances,
+            &oracles.iter().map(|o| &o[..]).collect::<Vec<_>>(),
+            &mut challenger,
+            &fri_params,
+            timing,
+        )
+    );
+
+    let instances = wires_commitments
+        .into_iter()
+        .zip(zs_commitments)
+        .zip(quotient_polys_commitments)
+        .zip(openings)
+        .map(
+            |(((wires_commitment, zs_commitment), quotient_polys_commitment), openings)| {
+                BatchProofInstance {
+                    wires_cap: wires_commitment.merkle_tree.cap,
+                    plonk_zs_partial_products_cap: zs_commitment.merkle_tree.cap,
+                    quotient_polys_cap: quotient_polys_commitment.merkle_tree.cap,
+                    openings,
+                }
+            },
+        )
+        .collect();
+    Ok(BatchProofWithPublicInputs {
+        proof: BatchProof {
+            instances,
+            opening_proof,
+        },
+        public_inputs,
+    })
+}
+
+/// Sets the lookup wires of a generated witness, and commits to the wire polynomials. Returns
+/// the public inputs, the full witness and the wires commitment.
+fn commit_to_wires<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    mut partition_witness: PartitionWitness<F>,
+    timing: &mut TimingTree,
+) -> (Vec<F>, MatrixWitness<F>, PolynomialBatch<F, C, D>) {
+    let config = &common_data.config;
 
     set_lookup_wires(prover_data, common_data, &mut partition_witness);
 
     let public_inputs = partition_witness.get_targets(&prover_data.public_inputs);
-    let public_inputs_hash = C::InnerHasher::hash_no_pad(&public_inputs);
 
     let witness = timed!(
         timing,
@@ -182,32 +500,45 @@ where
         )
     );
 
-    let mut challenger = Challenger::<F, C::Hasher>::new();
-
-    // Observe the instance.
-    challenger.observe_hash::<C::Hasher>(prover_data.circuit_digest);
-    challenger.observe_hash::<C::InnerHasher>(public_inputs_hash);
-
-    challenger.observe_cap::<C::Hasher>(&wires_commitment.merkle_tree.cap);
+    (public_inputs, witness, wires_commitment)
+}
 
+/// Draws the challenges of the lookup argument.
+fn get_lookup_deltas<F: RichField, H: Hasher<F>>(
+    challenger: &mut Challenger<F, H>,
+    betas: &[F],
+    gammas: &[F],
+) -> Vec<F> {
     // We need 4 values per challenge: 2 for the combos, 1 for (X-combo) in the accumulators and 1 to prove that the lookup table was computed correctly.
     // We can reuse betas and gammas for two of them.
+    let num_challenges = betas.len();
     let num_lookup_challenges = NUM_COINS_LOOKUP * num_challenges;
+    let mut deltas = Vec::with_capacity(num_lookup_challenges);
+    let num_additional_challenges = num_lookup_challenges - 2 * num_challenges;
+    let additional = challenger.get_n_challenges(num_additional_challenges);
+    deltas.extend(betas);
+    deltas.extend(gammas);
+    deltas.extend(additional);
+    deltas
+}
 
-    let betas = challenger.get_n_challenges(num_challenges);
-    let gammas = challenger.get_n_challenges(num_challenges);
-
-    let deltas = if has_lookup {
-        let mut delts = Vec::with_capacity(2 * num_challenges);
-        let num_additional_challenges = num_lookup_challenges - 2 * num_challenges;
-        let additional = challenger.get_n_challenges(num_additional_challenges);
-        delts.extend(&betas);
-        delts.extend(&gammas);
-        delts.extend(additional);
-        delts
-    } else {
-        vec![]
-    };
+/// Commits to the `Z`s and partial products of the permutation argument and, if any, to the
+/// lookup polynomials.
+fn commit_to_zs_partial_products_and_lookups<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    witness: &MatrixWitness<F>,
+    betas: &[F],
+    gammas: &[F],
+    deltas: &[F],
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    timing: &mut TimingTree,
+) -> PolynomialBatch<F, C, D> {
+    let has_lookup = !common_data.luts.is_empty();
+    let config = &common_data.config;
 
     assert!(
         common_data.quotient_degree_factor < common_data.config.num_routed_wires,
@@ -216,7 +547,7 @@ where
     let mut partial_products_and_zs = timed!(
         timing,
         "compute partial products",
-        all_wires_permutation_partial_products(&witness, &betas, &gammas, prover_data, common_data)
+        all_wires_permutation_partial_products(witness, betas, gammas, prover_data, common_data)
     );
 
     // Z is expected at the front of our batch; see `zs_range` and `partial_products_range`.
@@ -228,7 +559,7 @@ where
 
     // All lookup polys: RE and partial SLDCs.
     let lookup_polys =
-        compute_all_lookup_polys(&witness, &deltas, prover_data, common_data, has_lookup);
+        compute_all_lookup_polys(witness, deltas, prover_data, common_data, has_lookup);
 
     let zs_partial_products_lookups = if has_lookup {
         [zs_partial_products, lookup_polys].concat()
@@ -236,7 +567,7 @@ where
         zs_partial_products
     };
 
-    let partial_products_zs_and_lookup_commitment = timed!(
+    timed!(
         timing,
         "commit to partial products, Z's and, if any, lookup polynomials",
         PolynomialBatch::from_values(
@@ -247,11 +578,29 @@ where
             timing,
             prover_data.fft_root_table.as_ref(),
         )
-    );
-
-    challenger.observe_cap::<C::Hasher>(&partial_products_zs_and_lookup_commitment.merkle_tree.cap);
+    )
+}
 
-    let alphas = challenger.get_n_challenges(num_challenges);
+/// Computes the quotient polynomials, splits them into degree-`n` chunks and commits to them.
+fn commit_to_quotient_polys<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    public_inputs_hash: &<<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash,
+    wires_commitment: &PolynomialBatch<F, C, D>,
+    zs_partial_products_and_lookup_commitment: &PolynomialBatch<F, C, D>,
+    betas: &[F],
+    gammas: &[F],
+    deltas: &[F],
+    alphas: &[F],
+    timing: &mut TimingTree,
+) -> PolynomialBatch<F, C, D> {
+    let config = &common_data.config;
+    let quotient_degree = common_data.quotient_degree();
+    let degree = common_data.degree();
 
     let quotient_polys = timed!(
         timing,
@@ -259,13 +608,13 @@ where
         compute_quotient_polys::<F, C, D>(
             common_data,
             prover_data,
-            &public_inputs_hash,
-            &wires_commitment,
-            &partial_products_zs_and_lookup_commitment,
-            &betas,
-            &gammas,
-            &deltas,
-            &alphas,
+            public_inputs_hash,
+            wires_commitment,
+            zs_partial_products_and_lookup_commitment,
+            betas,
+            gammas,
+            deltas,
+            alphas,
         )
     );
 
@@ -284,7 +633,7 @@ where
             .collect()
     );
 
-    let quotient_polys_commitment = timed!(
+    timed!(
         timing,
         "commit to quotient polys",
         PolynomialBatch::<F, C, D>::from_coeffs(
@@ -295,64 +644,7 @@ where
             timing,
             prover_data.fft_root_table.as_ref(),
         )
-    );
-
-    challenger.observe_cap::<C::Hasher>(&quotient_polys_commitment.merkle_tree.cap);
-
-    let zeta = challenger.get_extension_challenge::<D>();
-    // To avoid leaking witness data, we want to ensure that our opening locations, `zeta` and
-    // `g * zeta`, are not in our subgroup `H`. It suffices to check `zeta` only, since
-    // `(g * zeta)^n = zeta^n`, where `n` is the order of `g`.
-    let g = F::Extension::primitive_root_of_unity(common_data.degree_bits());
-    ensure!(
-        zeta.exp_power_of_2(common_data.degree_bits()) != F::Extension::ONE,
-        "Opening point is in the subgroup."
-    );
-
-    let openings = timed!(
-        timing,
-        "construct the opening set, including lookups",
-        OpeningSet::new(
-            zeta,
-            g,
-            &prover_data.constants_sigmas_commitment,
-            &wires_commitment,
-            &partial_products_zs_and_lookup_commitment,
-            &quotient_polys_commitment,
-            common_data
-        )
-    );
-    challenger.observe_openings(&openings.to_fri_openings());
-    let instance = common_data.get_fri_instance(zeta);
-
-    let opening_proof = timed!(
-        timing,
-        "compute opening proofs",
-        PolynomialBatch::<F, C, D>::prove_openings(
-            &instance,
-            &[
-                &prover_data.constants_sigmas_commitment,
-                &wires_commitment,
-                &partial_products_zs_and_lookup_commitment,
-                &quotient_polys_commitment,
-            ],
-            &mut challenger,
-            &common_data.fri_params,
-            timing,
-        )
-    );
-
-    let proof = Proof::<F, C, D> {
-        wires_cap: wires_commitment.merkle_tree.cap,
-        plonk_zs_partial_products_cap: partial_products_zs_and_lookup_commitment.merkle_tree.cap,
-        quotient_polys_cap: quotient_polys_commitment.merkle_tree.cap,
-        openings,
-        opening_proof,
-    };
-    Ok(ProofWithPublicInputs::<F, C, D> {
-        proof,
-        public_inputs,
-    })
+    )
 }
 
 /// Compute the partial products used in the `Z` polynomials.
@@ -2,9 +2,12 @@ use anyhow::ensure;
 
 use crate::field::extension::Extendable;
 use crate::hash::hash_types::RichField;
+use crate::hash::merkle_tree::MerkleCap;
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::plonk::config::GenericConfig;
-use crate::plonk::proof::{OpeningSet, Proof, ProofWithPublicInputs};
+use crate::plonk::proof::{
+    BatchProofInstance, BatchProofWithPublicInputs, OpeningSet, Proof, ProofWithPublicInputs,
+};
 
 pub(crate) fn validate_proof_with_pis_shape<F, C, const D: usize>(
     proof_with_pis: &ProofWithPublicInputs<F, C, D>,
@@ -26,6 +29,46 @@ where
     Ok(())
 }
 
+pub(crate) fn validate_batch_proof_with_pis_shape<F, C, const D: usize>(
+    proof_with_pis: &BatchProofWithPublicInputs<F, C, D>,
+    common_data: &[&CommonCircuitData<F, D>],
+) -> anyhow::Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let BatchProofWithPublicInputs {
+        proof,
+        public_inputs,
+    } = proof_with_pis;
+    ensure!(
+        proof.instances.len() == common_data.len() && public_inputs.len() == common_data.len(),
+        "Number of circuits doesn't match circuit data."
+    );
+    for ((instance, public_inputs), common_data) in
+        proof.instances.iter().zip(public_inputs).zip(common_data)
+    {
+        let BatchProofInstance {
+            wires_cap,
+            plonk_zs_partial_products_cap,
+            quotient_polys_cap,
+            openings,
+        } = instance;
+        validate_commitments_and_openings_shape::<F, C, D>(
+            wires_cap,
+            plonk_zs_partial_products_cap,
+            quotient_polys_cap,
+            openings,
+            common_data,
+        )?;
+        ensure!(
+            public_inputs.len() == common_data.num_public_inputs,
+            "Number of public inputs doesn't match circuit data."
+        );
+    }
+    Ok(())
+}
+
 fn validate_proof_shape<F, C, const D: usize>(
     proof: &Proof<F, C, D>,
     common_data: &CommonCircuitData<F, D>,
@@ -34,7 +77,6 @@ where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
 {
-    let config = &common_data.config;
     let Proof {
         wires_cap,
         plonk_zs_partial_products_cap,
@@ -44,6 +86,27 @@ where
         // validate_fri_proof_shape), so we ignore it here.
         opening_proof: _,
     } = proof;
+    validate_commitments_and_openings_shape::<F, C, D>(
+        wires_cap,
+        plonk_zs_partial_products_cap,
+        quotient_polys_cap,
+        openings,
+        common_data,
+    )
+}
+
+fn validate_commitments_and_openings_shape<F, C, const D: usize>(
+    wires_cap: &MerkleCap<F, C::Hasher>,
+    plonk_zs_partial_products_cap: &MerkleCap<F, C::Hasher>,
+    quotient_polys_cap: &MerkleCap<F, C::Hasher>,
+    openings: &OpeningSet<F, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> anyhow::Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let config = &common_data.config;
     let OpeningSet {
         constants,
         plonk_sigmas,
@@ -1,16 +1,23 @@
 //! plonky2 verifier implementation.
 
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
 use anyhow::{ensure, Result};
 
 use crate::field::extension::Extendable;
 use crate::field::types::Field;
-use crate::fri::verifier::verify_fri_proof;
+use crate::fri::verifier::{verify_batch_fri_proof, verify_fri_proof};
 use crate::hash::hash_types::RichField;
-use crate::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
+use crate::plonk::circuit_data::{CommonCircuitData, VerifierCircuitData, VerifierOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::reduce_with_powers;
-use crate::plonk::proof::{Proof, ProofChallenges, ProofWithPublicInputs};
-use crate::plonk::validate_shape::validate_proof_with_pis_shape;
+use crate::plonk::proof::{
+    BatchProofWithPublicInputs, OpeningSet, Proof, ProofChallenges, ProofWithPublicInputs,
+};
+use crate::plonk::validate_shape::{
+    validate_batch_proof_with_pis_shape, validate_proof_with_pis_shape,
+};
 use crate::plonk::vanishing_poly::eval_vanishing_poly;
 use crate::plonk::vars::EvaluationVars;
 
@@ -48,19 +55,141 @@ pub(crate) fn verify_with_challenges<
     verifier_data: &VerifierOnlyCircuitData<C, D>,
     common_data: &CommonCircuitData<F, D>,
 ) -> Result<()> {
-    let local_constants = &proof.openings.constants;
-    let local_wires = &proof.openings.wires;
+    check_vanishing_at_zeta::<F, C, D>(
+        &proof.openings,
+        &public_inputs_hash,
+        &challenges,
+        &challenges.plonk_deltas,
+        common_data,
+    )?;
+
+    let merkle_caps = &[
+        verifier_data.constants_sigmas_cap.clone(),
+        proof.wires_cap,
+        // In the lookup case, `plonk_zs_partial_products_cap` should also include the lookup commitment.
+        proof.plonk_zs_partial_products_cap,
+        proof.quotient_polys_cap,
+    ];
+
+    verify_fri_proof::<F, C, D>(
+        &common_data.get_fri_instance(challenges.plonk_zeta),
+        &proof.openings.to_fri_openings(),
+        &challenges.fri_challenges,
+        merkle_caps,
+        &proof.opening_proof,
+        &common_data.fri_params,
+    )?;
+
+    Ok(())
+}
+
+/// Verifies a proof of several circuits produced by
+/// [`prove_batch`](crate::plonk::prover::prove_batch).
+pub fn verify_batch<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    proof_with_pis: BatchProofWithPublicInputs<F, C, D>,
+    verifier_data: &[&VerifierCircuitData<F, C, D>],
+) -> Result<()> {
+    let common_data = verifier_data
+        .iter()
+        .map(|vd| &vd.common)
+        .collect::<Vec<_>>();
+    let fri_params = CommonCircuitData::batch_fri_params(&common_data)?;
+    validate_batch_proof_with_pis_shape(&proof_with_pis, &common_data)?;
+
+    let public_inputs_hashes = proof_with_pis.get_public_inputs_hashes();
+    let circuit_digests = verifier_data
+        .iter()
+        .map(|vd| vd.verifier_only.circuit_digest)
+        .collect::<Vec<_>>();
+    let challenges =
+        proof_with_pis.get_challenges(&public_inputs_hashes, &circuit_digests, &common_data)?;
+
+    let proof = proof_with_pis.proof;
+    for ((instance, public_inputs_hash), common_data) in proof
+        .instances
+        .iter()
+        .zip(&public_inputs_hashes)
+        .zip(&common_data)
+    {
+        // Only circuits with lookups use the lookup challenges.
+        let deltas = if common_data.num_lookup_polys != 0 {
+            &challenges.plonk_deltas[..]
+        } else {
+            &[]
+        };
+        check_vanishing_at_zeta::<F, C, D>(
+            &instance.openings,
+            public_inputs_hash,
+            &challenges,
+            deltas,
+            common_data,
+        )?;
+    }
+
+    let instances = common_data
+        .iter()
+        .map(|cd| cd.get_fri_instance(challenges.plonk_zeta))
+        .collect::<Vec<_>>();
+    let openings = proof
+        .instances
+        .iter()
+        .map(|instance| instance.openings.to_fri_openings())
+        .collect::<Vec<_>>();
+    let degree_bits = common_data
+        .iter()
+        .map(|cd| cd.degree_bits())
+        .collect::<Vec<_>>();
+    let merkle_caps = proof
+        .instances
+        .into_iter()
+        .zip(verifier_data)
+        .map(|(instance, vd)| {
+            vec![
+                vd.verifier_only.constants_sigmas_cap.clone(),
+                instance.wires_cap,
+                instance.plonk_zs_partial_products_cap,
+                instance.quotient_polys_cap,
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    verify_batch_fri_proof::<F, C, D>(
+        &instances,
+        &openings,
+        &degree_bits,
+        &challenges.fri_challenges,
+        &merkle_caps,
+        &proof.opening_proof,
+        &fri_params,
+    )
+}
+
+/// Checks each polynomial identity of the circuit, of the form `vanishing(x) = Z_H(x) quotient(x)`,
+/// at `zeta`.
+fn check_vanishing_at_zeta<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    openings: &OpeningSet<F, D>,
+    public_inputs_hash: &<<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash,
+    challenges: &ProofChallenges<F, D>,
+    plonk_deltas: &[F],
+    common_data: &CommonCircuitData<F, D>,
+) -> Result<()> {
+    let local_constants = &openings.constants;
+    let local_wires = &openings.wires;
     let vars = EvaluationVars {
         local_constants,
         local_wires,
-        public_inputs_hash: &public_inputs_hash,
+        public_inputs_hash,
     };
-    let local_zs = &proof.openings.plonk_zs;
-    let next_zs = &proof.openings.plonk_zs_next;
-    let local_lookup_zs = &proof.openings.lookup_zs;
-    let next_lookup_zs = &proof.openings.lookup_zs_next;
-    let s_sigmas = &proof.openings.plonk_sigmas;
-    let partial_products = &proof.openings.partial_products;
+    let local_zs = &openings.plonk_zs;
+    let next_zs = &openings.plonk_zs_next;
+    let local_lookup_zs = &openings.lookup_zs;
+    let next_lookup_zs = &openings.lookup_zs_next;
+    let s_sigmas = &openings.plonk_sigmas;
+    let partial_products = &openings.partial_products;
 
     // Evaluate the vanishing polynomial at our challenge point, zeta.
     let vanishing_polys_zeta = eval_vanishing_poly::<F, D>(
@@ -76,11 +205,11 @@ pub(crate) fn verify_with_challenges<
         &challenges.plonk_betas,
         &challenges.plonk_gammas,
         &challenges.plonk_alphas,
-        &challenges.plonk_deltas,
+        plonk_deltas,
     );
 
     // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
-    let quotient_polys_zeta = &proof.openings.quotient_polys;
+    let quotient_polys_zeta = &openings.quotient_polys;
     let zeta_pow_deg = challenges
         .plonk_zeta
         .exp_power_of_2(common_data.degree_bits());
@@ -97,22 +226,5 @@ pub(crate) fn verify_with_challenges<
         ensure!(vanishing_polys_zeta[i] == z_h_zeta * reduce_with_powers(chunk, zeta_pow_deg));
     }
 
-    let merkle_caps = &[
-        verifier_data.constants_sigmas_cap.clone(),
-        proof.wires_cap,
-        // In the lookup case, `plonk_zs_partial_products_cap` should also include the lookup commitment.
-        proof.plonk_zs_partial_products_cap,
-        proof.quotient_polys_cap,
-    ];
-
-    verify_fri_proof::<F, C, D>(
-        &common_data.get_fri_instance(challenges.plonk_zeta),
-        &proof.openings.to_fri_openings(),
-        &challenges.fri_challenges,
-        merkle_caps,
-        &proof.opening_proof,
-        &common_data.fri_params,
-    )?;
-
     Ok(())
 }
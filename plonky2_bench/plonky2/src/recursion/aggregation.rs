@@ -0,0 +1,304 @@
+//! Binary-tree aggregation of proofs of possibly different circuits.
+//!
+//! Proofs are folded pairwise by wrapper circuits, each of which recursively verifies two inner
+//! proofs, until a single root proof remains. When a level has an odd number of proofs, the last
+//! one is carried over to the next level unchanged.
+//!
+//! Every wrapper proof has a single public input hash, committing to both of its children. The
+//! commitment to a child is the hash of its verifier data (circuit digest and constants/sigmas
+//! cap) followed by its public inputs, so the root commitment binds every aggregated circuit and
+//! every public input. [`ProofAggregator::verify`] recomputes it from the original leaves.
+//!
+//! Wrapper circuits only depend on the `CommonCircuitData` of their two children, since the inner
+//! verifier data is provided as witness. They are therefore cached and built once per shape.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use anyhow::{ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::{HashOut, HashOutTarget, RichField};
+use crate::iop::witness::{PartialWitness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{
+    CircuitConfig, CircuitData, CommonCircuitData, VerifierCircuitData, VerifierCircuitTarget,
+    VerifierOnlyCircuitData,
+};
+use crate::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
+use crate::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+
+/// A proof together with the verifier data of the circuit it was generated for.
+#[derive(Debug, Clone)]
+pub struct AggregationInput<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    pub proof: ProofWithPublicInputs<F, C, D>,
+    pub verifier_data: VerifierCircuitData<F, C, D>,
+}
+
+/// Targets of one of the two proofs verified by an aggregation circuit.
+#[derive(Debug)]
+struct AggregationChildTarget<const D: usize> {
+    proof_with_pis: ProofWithPublicInputsTarget<D>,
+    verifier_data: VerifierCircuitTarget,
+}
+
+/// A wrapper circuit verifying two proofs with fixed `CommonCircuitData`.
+#[derive(Debug)]
+struct AggregationCircuit<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+{
+    left_common: CommonCircuitData<F, D>,
+    right_common: CommonCircuitData<F, D>,
+    data: CircuitData<F, C, D>,
+    left: AggregationChildTarget<D>,
+    right: AggregationChildTarget<D>,
+}
+
+/// Aggregates proofs into a binary tree of recursive proofs, caching wrapper circuits by shape.
+#[derive(Debug)]
+pub struct ProofAggregator<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+{
+    config: CircuitConfig,
+    circuits: Vec<AggregationCircuit<F, C, D>>,
+}
+
+impl<F, C, const D: usize> ProofAggregator<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    /// Creates an aggregator whose wrapper circuits are built with `config`.
+    pub fn new(config: CircuitConfig) -> Self {
+        Self {
+            config,
+            circuits: Vec::new(),
+        }
+    }
+
+    /// Number of distinct wrapper circuits built so far.
+    pub fn num_circuits(&self) -> usize {
+        self.circuits.len()
+    }
+
+    /// Commitment to a proof with the given public inputs and verifier data, as computed by the
+    /// wrapper circuit verifying it.
+    pub fn child_commitment(
+        public_inputs: &[F],
+        verifier_only: &VerifierOnlyCircuitData<C, D>,
+    ) -> HashOut<F> {
+        let mut inputs = verifier_only.circuit_digest.elements.to_vec();
+        inputs.extend(verifier_only.constants_sigmas_cap.flatten());
+        inputs.extend_from_slice(public_inputs);
+        C::InnerHasher::hash_no_pad(&inputs)
+    }
+
+    /// Folds `inputs` pairwise up to a single root proof, whose public inputs are the root
+    /// commitment.
+    pub fn aggregate(
+        &mut self,
+        inputs: Vec<AggregationInput<F, C, D>>,
+    ) -> Result<AggregationInput<F, C, D>> {
+        self.fold(inputs, |aggregator, left, right| {
+            let circuit_index =
+                aggregator.circuit_index(&left.verifier_data.common, &right.verifier_data.common);
+            let circuit = &aggregator.circuits[circuit_index];
+
+            let mut pw = PartialWitness::new();
+            for (child, input) in [(&circuit.left, &left), (&circuit.right, &right)] {
+                pw.set_proof_with_pis_target(&child.proof_with_pis, &input.proof);
+                pw.set_verifier_data_target(
+                    &child.verifier_data,
+                    &input.verifier_data.verifier_only,
+                );
+            }
+
+            Ok(AggregationInput {
+                proof: circuit.data.prove(pw)?,
+                verifier_data: circuit.data.verifier_data(),
+            })
+        })
+    }
+
+    /// Verifies a root proof returned by [`Self::aggregate`] against the public inputs and
+    /// verifier data of the aggregated leaves.
+    pub fn verify(
+        &mut self,
+        root: AggregationInput<F, C, D>,
+        leaves: Vec<(Vec<F>, VerifierCircuitData<F, C, D>)>,
+    ) -> Result<()> {
+        let (expected_public_inputs, expected_verifier_data) =
+            self.fold(leaves, |aggregator, left, right| {
+                let circuit_index = aggregator.circuit_index(&left.1.common, &right.1.common);
+                let circuit = &aggregator.circuits[circuit_index];
+                let commitment = C::InnerHasher::hash_no_pad(
+                    &[
+                        Self::child_commitment(&left.0, &left.1.verifier_only).elements,
+                        Self::child_commitment(&right.0, &right.1.verifier_only).elements,
+                    ]
+                    .concat(),
+                );
+                Ok((commitment.elements.to_vec(), circuit.data.verifier_data()))
+            })?;
+
+        ensure!(
+            root.verifier_data == expected_verifier_data,
+            "Root verifier data does not match the aggregation tree"
+        );
+        ensure!(
+            root.proof.public_inputs == expected_public_inputs,
+            "Root commitment does not match the aggregated public inputs"
+        );
+        root.verifier_data.verify(root.proof)
+    }
+
+    /// Folds `nodes` level by level with `combine`, carrying the last node of odd levels over.
+    fn fold<N>(
+        &mut self,
+        mut nodes: Vec<N>,
+        mut combine: impl FnMut(&mut Self, N, N) -> Result<N>,
+    ) -> Result<N> {
+        ensure!(nodes.len() >= 2, "Aggregation needs at least two proofs");
+        while nodes.len() > 1 {
+            let mut next_level = Vec::with_capacity(nodes.len().div_ceil(2));
+            let mut level = nodes.into_iter();
+            while let Some(left) = level.next() {
+                match level.next() {
+                    Some(right) => next_level.push(combine(self, left, right)?),
+                    None => next_level.push(left),
+                }
+            }
+            nodes = next_level;
+        }
+        Ok(nodes.pop().unwrap())
+    }
+
+    /// Returns the index of the wrapper circuit for the given shapes, building it if needed.
+    fn circuit_index(
+        &mut self,
+        left_common: &CommonCircuitData<F, D>,
+        right_common: &CommonCircuitData<F, D>,
+    ) -> usize {
+        if let Some(index) = self.circuits.iter().position(|circuit| {
+            &circuit.left_common == left_common && &circuit.right_common == right_common
+        }) {
+            return index;
+        }
+
+        let mut builder = CircuitBuilder::<F, D>::new(self.config.clone());
+        let left = Self::add_child::<C>(&mut builder, left_common);
+        let right = Self::add_child::<C>(&mut builder, right_common);
+        let left_commitment = Self::child_commitment_circuit(&mut builder, &left);
+        let right_commitment = Self::child_commitment_circuit(&mut builder, &right);
+        let commitment = builder.hash_n_to_hash_no_pad::<C::InnerHasher>(
+            [left_commitment.elements, right_commitment.elements].concat(),
+        );
+        builder.register_public_inputs(&commitment.elements);
+
+        self.circuits.push(AggregationCircuit {
+            left_common: left_common.clone(),
+            right_common: right_common.clone(),
+            data: builder.build::<C>(),
+            left,
+            right,
+        });
+        self.circuits.len() - 1
+    }
+
+    fn add_child<InnerC: GenericConfig<D, F = F>>(
+        builder: &mut CircuitBuilder<F, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> AggregationChildTarget<D>
+    where
+        InnerC::Hasher: AlgebraicHasher<F>,
+    {
+        let proof_with_pis = builder.add_virtual_proof_with_pis(common_data);
+        let verifier_data =
+            builder.add_virtual_verifier_data(common_data.config.fri_config.cap_height);
+        builder.verify_proof::<InnerC>(&proof_with_pis, &verifier_data, common_data);
+        AggregationChildTarget {
+            proof_with_pis,
+            verifier_data,
+        }
+    }
+
+    fn child_commitment_circuit(
+        builder: &mut CircuitBuilder<F, D>,
+        child: &AggregationChildTarget<D>,
+    ) -> HashOutTarget {
+        let mut inputs = child.verifier_data.circuit_digest.elements.to_vec();
+        for hash in &child.verifier_data.constants_sigmas_cap.0 {
+            inputs.extend(hash.elements);
+        }
+        inputs.extend(&child.proof_with_pis.public_inputs);
+        builder.hash_n_to_hash_no_pad::<C::InnerHasher>(inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::vec;
+
+    use super::*;
+    use crate::field::types::Field;
+    use crate::gates::noop::NoopGate;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    /// Builds and proves a circuit with `num_gates` gates and the given public inputs.
+    fn leaf<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+        num_gates: usize,
+        public_inputs: &[F],
+    ) -> Result<AggregationInput<F, C, D>> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let targets = builder.add_virtual_targets(public_inputs.len());
+        builder.register_public_inputs(&targets);
+        for _ in 0..num_gates {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&targets, public_inputs);
+        Ok(AggregationInput {
+            proof: data.prove(pw)?,
+            verifier_data: data.verifier_data(),
+        })
+    }
+
+    #[test]
+    fn test_aggregation() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let leaves = vec![
+            leaf::<F, C, D>(1 << 10, &[F::ONE])?,
+            leaf::<F, C, D>(1 << 12, &[F::TWO, F::ONE])?,
+            leaf::<F, C, D>(1 << 10, &[F::ONE])?,
+            leaf::<F, C, D>(1 << 12, &[F::ZERO, F::TWO])?,
+            leaf::<F, C, D>(1 << 11, &[])?,
+        ];
+        let leaf_data = leaves
+            .iter()
+            .map(|leaf| (leaf.proof.public_inputs.clone(), leaf.verifier_data.clone()))
+            .collect::<Vec<_>>();
+
+        let mut aggregator =
+            ProofAggregator::<F, C, D>::new(CircuitConfig::standard_recursion_config());
+        let root = aggregator.aggregate(leaves)?;
+        // The two pairs of leaves share a wrapper, the second level and the root need their own.
+        assert_eq!(aggregator.num_circuits(), 3);
+
+        aggregator.verify(root.clone(), leaf_data.clone())?;
+
+        let mut tampered = leaf_data;
+        tampered[0].0[0] = F::TWO;
+        assert!(aggregator.verify(root, tampered).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+//! Incrementally verifiable computation (IVC) on top of cyclic recursion.
+//!
+//! An [`IvcCircuit`] wraps a user supplied step circuit mapping a running state hash `state_in`
+//! to `state_out`. Every proof of the IVC circuit attests that the step circuit has been applied
+//! `num_steps` times starting from `initial_state`, by verifying the proof of the previous step
+//! (or a dummy proof for the very first step) inside the circuit. Each step is proven with its
+//! own witness for the step circuit, so that consecutive steps can work on different inputs.
+//!
+//! The public inputs of an IVC proof have the following structure:
+//! - Initial state (4)
+//! - Current state, i.e. the output of the latest step (4)
+//! - Number of steps applied so far (1)
+//! - Public inputs registered by the step circuit (?)
+//! - VK for cyclic recursion (?)
+
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+
+use anyhow::{anyhow, Result};
+use hashbrown::HashMap;
+use log::debug;
+
+use crate::field::extension::Extendable;
+use crate::gates::noop::NoopGate;
+use crate::hash::hash_types::{HashOut, HashOutTarget, RichField};
+use crate::iop::target::BoolTarget;
+use crate::iop::witness::{PartialWitness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{
+    CircuitConfig, CircuitData, CommonCircuitData, VerifierCircuitTarget,
+};
+use crate::plonk::config::{AlgebraicHasher, GenericConfig};
+use crate::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+use crate::recursion::cyclic_recursion::check_cyclic_proof_verifier_data;
+use crate::recursion::dummy_circuit::cyclic_base_proof;
+
+/// Maximum number of attempts at finding `CommonCircuitData` which is a fixed point of the
+/// cyclic circuit construction.
+const MAX_SHAPE_ITERATIONS: usize = 8;
+
+/// Offsets of the IVC values within the public inputs.
+const INITIAL_STATE_OFFSET: usize = 0;
+const STATE_OFFSET: usize = 4;
+const NUM_STEPS_OFFSET: usize = 8;
+
+/// A cyclic recursive circuit applying one step of a computation to a running state hash.
+///
+/// `T` is returned by the step circuit, to set its witness anew for each step.
+#[derive(Debug)]
+pub struct IvcCircuit<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize, T> {
+    pub data: CircuitData<F, C, D>,
+    pub step_targets: T,
+    condition: BoolTarget,
+    inner_proof_with_pis: ProofWithPublicInputsTarget<D>,
+    verifier_data_target: VerifierCircuitTarget,
+}
+
+/// The IVC values exposed in the public inputs of an [`IvcCircuit`] proof.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct IvcPublicInputs<F: RichField> {
+    pub initial_state: HashOut<F>,
+    pub state: HashOut<F>,
+    pub num_steps: u64,
+}
+
+/// Targets of a single instance of the IVC circuit, before it is built.
+#[derive(Debug)]
+struct IvcTargets<const D: usize, T> {
+    step_targets: T,
+    condition: BoolTarget,
+    inner_proof_with_pis: ProofWithPublicInputsTarget<D>,
+    verifier_data_target: VerifierCircuitTarget,
+}
+
+impl<F, C, const D: usize, T> IvcCircuit<F, C, D, T>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F> + 'static,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    /// Builds the IVC circuit for `step_circuit`, which returns `state_out` along with what is
+    /// needed to set its witness, typically its input targets.
+    ///
+    /// The step circuit is synthesized several times while searching for the common data of the
+    /// cyclic circuit. Only what the last synthesis returns is kept, in `step_targets`.
+    pub fn new<S>(config: CircuitConfig, step_circuit: S) -> Result<Self>
+    where
+        S: Fn(&mut CircuitBuilder<F, D>, HashOutTarget) -> (HashOutTarget, T),
+    {
+        let mut common_data = Self::initial_common_data(&config);
+        for i in 0..MAX_SHAPE_ITERATIONS {
+            let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+            let targets = Self::add_ivc_targets(&mut builder, &step_circuit, &mut common_data)?;
+            // Pad so that the circuit reaches the expected degree once `build` adds its own gates.
+            while builder.num_gates() < 1 << (common_data.degree_bits() - 1) {
+                builder.add_gate(NoopGate, vec![]);
+            }
+
+            let (data, success) = builder.try_build_with_options::<C>(true);
+            if success {
+                debug!("IVC circuit shape converged after {} iterations", i + 1);
+                return Ok(Self {
+                    data,
+                    step_targets: targets.step_targets,
+                    condition: targets.condition,
+                    inner_proof_with_pis: targets.inner_proof_with_pis,
+                    verifier_data_target: targets.verifier_data_target,
+                });
+            }
+            common_data = data.common;
+        }
+
+        Err(anyhow!(
+            "IVC circuit shape did not converge after {} iterations",
+            MAX_SHAPE_ITERATIONS
+        ))
+    }
+
+    /// Generates `CommonCircuitData` for a circuit verifying a recursive proof, used as the
+    /// starting point of the search for the shape of the IVC circuit.
+    fn initial_common_data(config: &CircuitConfig) -> CommonCircuitData<F, D> {
+        let builder = CircuitBuilder::<F, D>::new(config.clone());
+        let data = builder.build::<C>();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let proof = builder.add_virtual_proof_with_pis(&data.common);
+        let verifier_data =
+            builder.add_virtual_verifier_data(data.common.config.fri_config.cap_height);
+        builder.verify_proof::<C>(&proof, &verifier_data, &data.common);
+        let data = builder.build::<C>();
+
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let proof = builder.add_virtual_proof_with_pis(&data.common);
+        let verifier_data =
+            builder.add_virtual_verifier_data(data.common.config.fri_config.cap_height);
+        builder.verify_proof::<C>(&proof, &verifier_data, &data.common);
+        while builder.num_gates() < 1 << 12 {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        builder.build::<C>().common
+    }
+
+    fn add_ivc_targets<S>(
+        builder: &mut CircuitBuilder<F, D>,
+        step_circuit: &S,
+        common_data: &mut CommonCircuitData<F, D>,
+    ) -> Result<IvcTargets<D, T>>
+    where
+        S: Fn(&mut CircuitBuilder<F, D>, HashOutTarget) -> (HashOutTarget, T),
+    {
+        let one = builder.one();
+
+        let initial_state = builder.add_virtual_hash();
+        builder.register_public_inputs(&initial_state.elements);
+        let state = builder.add_virtual_hash();
+        builder.register_public_inputs(&state.elements);
+        let num_steps = builder.add_virtual_public_input();
+
+        // The step circuit comes after the IVC values, so that the public inputs it registers
+        // don't move them.
+        let state_in = builder.add_virtual_hash();
+        let (state_out, step_targets) = step_circuit(builder, state_in);
+        builder.connect_hashes(state, state_out);
+
+        let verifier_data_target = builder.add_verifier_data_public_inputs();
+        common_data.num_public_inputs = builder.num_public_inputs();
+
+        let condition = builder.add_virtual_bool_target_safe();
+
+        // Unpack inner proof's public inputs.
+        let inner_proof_with_pis = builder.add_virtual_proof_with_pis(common_data);
+        let inner_pis = &inner_proof_with_pis.public_inputs;
+        let inner_initial_state =
+            HashOutTarget::try_from(&inner_pis[INITIAL_STATE_OFFSET..INITIAL_STATE_OFFSET + 4])
+                .unwrap();
+        let inner_state =
+            HashOutTarget::try_from(&inner_pis[STATE_OFFSET..STATE_OFFSET + 4]).unwrap();
+        let inner_num_steps = inner_pis[NUM_STEPS_OFFSET];
+
+        // The initial state is carried over from the inner proof. In the base case, the dummy
+        // proof is generated with the initial state we want.
+        builder.connect_hashes(initial_state, inner_initial_state);
+
+        // The step consumes the latest state of the inner proof, or the initial state in the
+        // base case.
+        let actual_state_in = builder.select_hash(condition, inner_state, initial_state);
+        builder.connect_hashes(state_in, actual_state_in);
+
+        // Our step count is inner_num_steps + 1 if we have an inner proof, or 1 if not.
+        let new_num_steps = builder.mul_add(condition.target, inner_num_steps, one);
+        builder.connect(num_steps, new_num_steps);
+
+        builder.conditionally_verify_cyclic_proof_or_dummy::<C>(
+            condition,
+            &inner_proof_with_pis,
+            common_data,
+        )?;
+
+        Ok(IvcTargets {
+            step_targets,
+            condition,
+            inner_proof_with_pis,
+            verifier_data_target,
+        })
+    }
+
+    /// Proves the first step of the computation, starting from `initial_state`, with `pw` setting
+    /// the `step_targets` of this step.
+    pub fn prove_first_step(
+        &self,
+        initial_state: HashOut<F>,
+        mut pw: PartialWitness<F>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        let initial_state_pis: HashMap<_, _> = (INITIAL_STATE_OFFSET..)
+            .zip(initial_state.elements)
+            .collect();
+        let base_proof = cyclic_base_proof(
+            &self.data.common,
+            &self.data.verifier_only,
+            initial_state_pis,
+        );
+
+        pw.set_bool_target(self.condition, false);
+        pw.set_proof_with_pis_target::<C, D>(&self.inner_proof_with_pis, &base_proof);
+        pw.set_verifier_data_target(&self.verifier_data_target, &self.data.verifier_only);
+        self.data.prove(pw)
+    }
+
+    /// Proves one more step of the computation on top of the proof of the previous step, with
+    /// `pw` setting the `step_targets` of this step.
+    pub fn prove_step(
+        &self,
+        previous: &ProofWithPublicInputs<F, C, D>,
+        mut pw: PartialWitness<F>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        pw.set_bool_target(self.condition, true);
+        pw.set_proof_with_pis_target(&self.inner_proof_with_pis, previous);
+        pw.set_verifier_data_target(&self.verifier_data_target, &self.data.verifier_only);
+        self.data.prove(pw)
+    }
+
+    /// Verifies an IVC proof, including the check that its cyclic verifier data is ours.
+    pub fn verify(&self, proof: ProofWithPublicInputs<F, C, D>) -> Result<()> {
+        check_cyclic_proof_verifier_data(&proof, &self.data.verifier_only, &self.data.common)?;
+        self.data.verify(proof)
+    }
+
+    /// Extracts the IVC values from the public inputs of a proof.
+    pub fn public_inputs(proof: &ProofWithPublicInputs<F, C, D>) -> IvcPublicInputs<F> {
+        let pis = &proof.public_inputs;
+        IvcPublicInputs {
+            initial_state: HashOut::from_partial(
+                &pis[INITIAL_STATE_OFFSET..INITIAL_STATE_OFFSET + 4],
+            ),
+            state: HashOut::from_partial(&pis[STATE_OFFSET..STATE_OFFSET + 4]),
+            num_steps: pis[NUM_STEPS_OFFSET].to_canonical_u64(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Field;
+    use crate::hash::hashing::hash_n_to_hash_no_pad;
+    use crate::hash::poseidon::{PoseidonHash, PoseidonPermutation};
+    use crate::iop::target::Target;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    #[test]
+    fn test_ivc_hash_chain() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        // Each step absorbs its own input into the running state.
+        let config = CircuitConfig::standard_recursion_config();
+        let ivc = IvcCircuit::<F, C, D, _>::new(config, |builder, state_in| {
+            let input = builder.add_virtual_target();
+            let mut inputs = state_in.elements.to_vec();
+            inputs.push(input);
+            (builder.hash_n_to_hash_no_pad::<PoseidonHash>(inputs), input)
+        })?;
+        let step_witness = |step: usize| {
+            let mut pw = PartialWitness::new();
+            pw.set_target(ivc.step_targets, F::from_canonical_usize(step));
+            pw
+        };
+
+        let initial_state = HashOut {
+            elements: [F::ZERO, F::ONE, F::TWO, F::from_canonical_usize(3)],
+        };
+        let mut proof = ivc.prove_first_step(initial_state, step_witness(0))?;
+        ivc.verify(proof.clone())?;
+        for step in 1..3 {
+            proof = ivc.prove_step(&proof, step_witness(step))?;
+            ivc.verify(proof.clone())?;
+        }
+
+        let mut expected_state = initial_state;
+        for step in 0..3 {
+            let mut inputs = expected_state.elements.to_vec();
+            inputs.push(F::from_canonical_usize(step));
+            expected_state = hash_n_to_hash_no_pad::<F, PoseidonPermutation<F>>(&inputs);
+        }
+        assert_eq!(
+            IvcCircuit::<F, C, D, Target>::public_inputs(&proof),
+            IvcPublicInputs {
+                initial_state,
+                state: expected_state,
+                num_steps: 3,
+            }
+        );
+
+        Ok(())
+    }
+}
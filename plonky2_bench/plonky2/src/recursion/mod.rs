@@ -4,7 +4,9 @@
 //! (between two different circuits, depending on a condition), and cyclic
 //! recursion where a circuit implements its own verification logic.
 
+pub mod aggregation;
 pub mod conditional_recursive_verifier;
 pub mod cyclic_recursion;
 pub mod dummy_circuit;
+pub mod ivc;
 pub mod recursive_verifier;
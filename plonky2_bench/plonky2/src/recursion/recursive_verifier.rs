@@ -1,14 +1,16 @@
 #[cfg(not(feature = "std"))]
-use alloc::vec;
+use alloc::{vec, vec::Vec};
 
 use crate::field::extension::Extendable;
 use crate::hash::hash_types::{HashOutTarget, RichField};
+use crate::iop::target::Target;
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::{CommonCircuitData, VerifierCircuitTarget};
 use crate::plonk::config::{AlgebraicHasher, GenericConfig};
 use crate::plonk::plonk_common::salt_size;
 use crate::plonk::proof::{
-    OpeningSetTarget, ProofChallengesTarget, ProofTarget, ProofWithPublicInputsTarget,
+    BatchProofInstanceTarget, BatchProofTarget, BatchProofWithPublicInputsTarget, OpeningSetTarget,
+    ProofChallengesTarget, ProofTarget, ProofWithPublicInputsTarget,
 };
 use crate::plonk::vanishing_poly::eval_vanishing_poly_circuit;
 use crate::plonk::vars::EvaluationTargets;
@@ -58,60 +60,14 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     ) where
         C::Hasher: AlgebraicHasher<F>,
     {
-        let one = self.one_extension();
-
-        let local_constants = &proof.openings.constants;
-        let local_wires = &proof.openings.wires;
-        let vars = EvaluationTargets {
-            local_constants,
-            local_wires,
-            public_inputs_hash: &public_inputs_hash,
-        };
-        let local_zs = &proof.openings.plonk_zs;
-        let next_zs = &proof.openings.plonk_zs_next;
-        let local_lookup_zs = &proof.openings.lookup_zs;
-        let next_lookup_zs = &proof.openings.next_lookup_zs;
-        let s_sigmas = &proof.openings.plonk_sigmas;
-        let partial_products = &proof.openings.partial_products;
-
-        let zeta_pow_deg =
-            self.exp_power_of_2_extension(challenges.plonk_zeta, inner_common_data.degree_bits());
-        let vanishing_polys_zeta = with_context!(
-            self,
-            "evaluate the vanishing polynomial at our challenge point, zeta.",
-            eval_vanishing_poly_circuit::<F, D>(
-                self,
-                inner_common_data,
-                challenges.plonk_zeta,
-                zeta_pow_deg,
-                vars,
-                local_zs,
-                next_zs,
-                local_lookup_zs,
-                next_lookup_zs,
-                partial_products,
-                s_sigmas,
-                &challenges.plonk_betas,
-                &challenges.plonk_gammas,
-                &challenges.plonk_alphas,
-                &challenges.plonk_deltas,
-            )
+        self.check_vanishing_at_zeta(
+            &proof.openings,
+            public_inputs_hash,
+            &challenges,
+            &challenges.plonk_deltas,
+            inner_common_data,
         );
 
-        with_context!(self, "check vanishing and quotient polynomials.", {
-            let quotient_polys_zeta = &proof.openings.quotient_polys;
-            let mut scale = ReducingFactorTarget::new(zeta_pow_deg);
-            let z_h_zeta = self.sub_extension(zeta_pow_deg, one);
-            for (i, chunk) in quotient_polys_zeta
-                .chunks(inner_common_data.quotient_degree_factor)
-                .enumerate()
-            {
-                let recombined_quotient = scale.reduce(chunk, self);
-                let computed_vanishing_poly = self.mul_extension(z_h_zeta, recombined_quotient);
-                self.connect_extension(vanishing_polys_zeta[i], computed_vanishing_poly);
-            }
-        });
-
         let merkle_caps = &[
             inner_verifier_data.constants_sigmas_cap.clone(),
             proof.wires_cap.clone(),
@@ -134,6 +90,107 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         );
     }
 
+    /// Recursively verifies an inner batch proof of several circuits, see
+    /// [`prove_batch`](crate::plonk::prover::prove_batch).
+    pub fn verify_batch_proof<C: GenericConfig<D, F = F>>(
+        &mut self,
+        proof_with_pis: &BatchProofWithPublicInputsTarget<D>,
+        inner_verifier_data: &[&VerifierCircuitTarget],
+        inner_common_data: &[&CommonCircuitData<F, D>],
+    ) where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        assert_eq!(
+            proof_with_pis.proof.instances.len(),
+            inner_common_data.len()
+        );
+        assert_eq!(inner_verifier_data.len(), inner_common_data.len());
+        let fri_params = CommonCircuitData::batch_fri_params(inner_common_data)
+            .expect("Inner circuits cannot be batched");
+
+        let public_inputs_hashes = proof_with_pis
+            .public_inputs
+            .iter()
+            .zip(inner_common_data)
+            .map(|(public_inputs, common_data)| {
+                assert_eq!(public_inputs.len(), common_data.num_public_inputs);
+                self.hash_n_to_hash_no_pad::<C::InnerHasher>(public_inputs.clone())
+            })
+            .collect::<Vec<_>>();
+        let circuit_digests = inner_verifier_data
+            .iter()
+            .map(|vd| vd.circuit_digest)
+            .collect::<Vec<_>>();
+        let challenges = proof_with_pis.get_challenges::<F, C>(
+            self,
+            &public_inputs_hashes,
+            &circuit_digests,
+            inner_common_data,
+        );
+
+        let proof = &proof_with_pis.proof;
+        for ((instance, &public_inputs_hash), common_data) in proof
+            .instances
+            .iter()
+            .zip(&public_inputs_hashes)
+            .zip(inner_common_data)
+        {
+            // Only circuits with lookups use the lookup challenges.
+            let deltas = if common_data.num_lookup_polys != 0 {
+                &challenges.plonk_deltas[..]
+            } else {
+                &[]
+            };
+            self.check_vanishing_at_zeta(
+                &instance.openings,
+                public_inputs_hash,
+                &challenges,
+                deltas,
+                common_data,
+            );
+        }
+
+        let fri_instances = inner_common_data
+            .iter()
+            .map(|cd| cd.get_fri_instance_target(self, challenges.plonk_zeta))
+            .collect::<Vec<_>>();
+        let openings = proof
+            .instances
+            .iter()
+            .map(|instance| instance.openings.to_fri_openings())
+            .collect::<Vec<_>>();
+        let degree_bits = inner_common_data
+            .iter()
+            .map(|cd| cd.degree_bits())
+            .collect::<Vec<_>>();
+        let merkle_caps = proof
+            .instances
+            .iter()
+            .zip(inner_verifier_data)
+            .map(|(instance, vd)| {
+                vec![
+                    vd.constants_sigmas_cap.clone(),
+                    instance.wires_cap.clone(),
+                    instance.plonk_zs_partial_products_cap.clone(),
+                    instance.quotient_polys_cap.clone(),
+                ]
+            })
+            .collect::<Vec<_>>();
+        with_context!(
+            self,
+            "verify batch FRI proof",
+            self.verify_batch_fri_proof::<C>(
+                &fri_instances,
+                &openings,
+                &degree_bits,
+                &challenges.fri_challenges,
+                &merkle_caps,
+                &proof.opening_proof,
+                &fri_params,
+            )
+        );
+    }
+
     pub fn add_virtual_proof_with_pis(
         &mut self,
         common_data: &CommonCircuitData<F, D>,
@@ -147,12 +204,68 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     }
 
     fn add_virtual_proof(&mut self, common_data: &CommonCircuitData<F, D>) -> ProofTarget<D> {
-        let config = &common_data.config;
         let fri_params = &common_data.fri_params;
         let cap_height = fri_params.config.cap_height;
+        let num_leaves_per_oracle = &Self::num_leaves_per_oracle(common_data);
+
+        ProofTarget {
+            wires_cap: self.add_virtual_cap(cap_height),
+            plonk_zs_partial_products_cap: self.add_virtual_cap(cap_height),
+            quotient_polys_cap: self.add_virtual_cap(cap_height),
+            openings: self.add_opening_set(common_data),
+            opening_proof: self.add_virtual_fri_proof(num_leaves_per_oracle, fri_params),
+        }
+    }
+
+    pub fn add_virtual_batch_proof_with_pis(
+        &mut self,
+        common_data: &[&CommonCircuitData<F, D>],
+    ) -> BatchProofWithPublicInputsTarget<D> {
+        let fri_params =
+            CommonCircuitData::batch_fri_params(common_data).expect("Circuits cannot be batched");
+        let cap_height = fri_params.config.cap_height;
+
+        let mut instances = Vec::with_capacity(common_data.len());
+        let mut num_leaves_per_oracle = Vec::new();
+        let mut initial_lift_bits = Vec::new();
+        for cd in common_data {
+            let num_leaves = Self::num_leaves_per_oracle(cd);
+            initial_lift_bits.extend(
+                num_leaves
+                    .iter()
+                    .map(|_| fri_params.degree_bits - cd.degree_bits()),
+            );
+            num_leaves_per_oracle.extend(num_leaves);
+            instances.push(BatchProofInstanceTarget {
+                wires_cap: self.add_virtual_cap(cap_height),
+                plonk_zs_partial_products_cap: self.add_virtual_cap(cap_height),
+                quotient_polys_cap: self.add_virtual_cap(cap_height),
+                openings: self.add_opening_set(cd),
+            });
+        }
+        let opening_proof = self.add_virtual_lifted_fri_proof(
+            &num_leaves_per_oracle,
+            &initial_lift_bits,
+            &fri_params,
+        );
+        let public_inputs = common_data
+            .iter()
+            .map(|cd| self.add_virtual_targets(cd.num_public_inputs))
+            .collect();
+        BatchProofWithPublicInputsTarget {
+            proof: BatchProofTarget {
+                instances,
+                opening_proof,
+            },
+            public_inputs,
+        }
+    }
 
+    /// Returns the number of leaves of each oracle of a proof, including salts.
+    fn num_leaves_per_oracle(common_data: &CommonCircuitData<F, D>) -> Vec<usize> {
+        let config = &common_data.config;
         let salt = salt_size(common_data.fri_params.hiding);
-        let num_leaves_per_oracle = &mut vec![
+        let mut num_leaves_per_oracle = vec![
             common_data.num_preprocessed_polys(),
             config.num_wires + salt,
             common_data.num_zs_partial_products_polys() + common_data.num_all_lookup_polys() + salt,
@@ -161,14 +274,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         if common_data.num_quotient_polys() > 0 {
             num_leaves_per_oracle.push(common_data.num_quotient_polys() + salt);
         }
-
-        ProofTarget {
-            wires_cap: self.add_virtual_cap(cap_height),
-            plonk_zs_partial_products_cap: self.add_virtual_cap(cap_height),
-            quotient_polys_cap: self.add_virtual_cap(cap_height),
-            openings: self.add_opening_set(common_data),
-            opening_proof: self.add_virtual_fri_proof(num_leaves_per_oracle, fri_params),
-        }
+        num_leaves_per_oracle
     }
 
     fn add_opening_set(&mut self, common_data: &CommonCircuitData<F, D>) -> OpeningSetTarget<D> {
@@ -193,6 +299,71 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             quotient_polys: self.add_virtual_extension_targets(common_data.num_quotient_polys()),
         }
     }
+
+    /// Checks each polynomial identity of the inner circuit, of the form
+    /// `vanishing(x) = Z_H(x) quotient(x)`, at `zeta`.
+    fn check_vanishing_at_zeta(
+        &mut self,
+        openings: &OpeningSetTarget<D>,
+        public_inputs_hash: HashOutTarget,
+        challenges: &ProofChallengesTarget<D>,
+        plonk_deltas: &[Target],
+        inner_common_data: &CommonCircuitData<F, D>,
+    ) {
+        let one = self.one_extension();
+
+        let local_constants = &openings.constants;
+        let local_wires = &openings.wires;
+        let vars = EvaluationTargets {
+            local_constants,
+            local_wires,
+            public_inputs_hash: &public_inputs_hash,
+        };
+        let local_zs = &openings.plonk_zs;
+        let next_zs = &openings.plonk_zs_next;
+        let local_lookup_zs = &openings.lookup_zs;
+        let next_lookup_zs = &openings.next_lookup_zs;
+        let s_sigmas = &openings.plonk_sigmas;
+        let partial_products = &openings.partial_products;
+
+        let zeta_pow_deg =
+            self.exp_power_of_2_extension(challenges.plonk_zeta, inner_common_data.degree_bits());
+        let vanishing_polys_zeta = with_context!(
+            self,
+            "evaluate the vanishing polynomial at our challenge point, zeta.",
+            eval_vanishing_poly_circuit::<F, D>(
+                self,
+                inner_common_data,
+                challenges.plonk_zeta,
+                zeta_pow_deg,
+                vars,
+                local_zs,
+                next_zs,
+                local_lookup_zs,
+                next_lookup_zs,
+                partial_products,
+                s_sigmas,
+                &challenges.plonk_betas,
+                &challenges.plonk_gammas,
+                &challenges.plonk_alphas,
+                plonk_deltas,
+            )
+        );
+
+        with_context!(self, "check vanishing and quotient polynomials.", {
+            let quotient_polys_zeta = &openings.quotient_polys;
+            let mut scale = ReducingFactorTarget::new(zeta_pow_deg);
+            let z_h_zeta = self.sub_extension(zeta_pow_deg, one);
+            for (i, chunk) in quotient_polys_zeta
+                .chunks(inner_common_data.quotient_degree_factor)
+                .enumerate()
+            {
+                let recombined_quotient = scale.reduce(chunk, self);
+                let computed_vanishing_poly = self.mul_extension(z_h_zeta, recombined_quotient);
+                self.connect_extension(vanishing_polys_zeta[i], computed_vanishing_poly);
+            }
+        });
+    }
 }
 
 #[cfg(test)]
@@ -207,16 +378,20 @@ mod tests {
     use log::{info, Level};
 
     use super::*;
+    use crate::field::types::Field;
     use crate::fri::reduction_strategies::FriReductionStrategy;
     use crate::fri::FriConfig;
     use crate::gadgets::lookup::{OTHER_TABLE, TIP5_TABLE};
     use crate::gates::lookup_table::LookupTable;
     use crate::gates::noop::NoopGate;
     use crate::iop::witness::{PartialWitness, WitnessWrite};
-    use crate::plonk::circuit_data::{CircuitConfig, VerifierOnlyCircuitData};
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData, VerifierOnlyCircuitData};
     use crate::plonk::config::{KeccakGoldilocksConfig, PoseidonGoldilocksConfig};
-    use crate::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
-    use crate::plonk::prover::prove;
+    use crate::plonk::proof::{
+        BatchProofWithPublicInputs, CompressedProofWithPublicInputs, ProofWithPublicInputs,
+    };
+    use crate::plonk::prover::{prove, prove_batch};
+    use crate::plonk::verifier::verify_batch;
     use crate::util::timing::TimingTree;
 
     #[test]
@@ -311,6 +486,124 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_batch_prove_and_verify() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+
+        // Circuits of degrees 2^12, 2^10 and 2^7, the last one with lookups.
+        let (circuits, witnesses): (Vec<_>, Vec<_>) = [(4_000, false), (1_000, false), (100, true)]
+            .into_iter()
+            .map(|(num_dummy_gates, with_lookup)| {
+                dummy_batch_circuit::<F, C, D>(&config, num_dummy_gates, with_lookup)
+            })
+            .unzip();
+        let verifier_data = circuits
+            .iter()
+            .map(|data| data.verifier_data())
+            .collect::<Vec<_>>();
+        let prover_data = circuits
+            .into_iter()
+            .map(|data| data.prover_data())
+            .collect::<Vec<_>>();
+        let common_data = verifier_data
+            .iter()
+            .map(|vd| &vd.common)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            common_data
+                .iter()
+                .map(|cd| cd.degree_bits())
+                .collect::<Vec<_>>(),
+            [12, 10, 7]
+        );
+
+        let proof = prove_batch(
+            &prover_data.iter().collect::<Vec<_>>(),
+            witnesses,
+            &mut TimingTree::default(),
+        )?;
+        let verifier_data = verifier_data.iter().collect::<Vec<_>>();
+        verify_batch(proof.clone(), &verifier_data)?;
+
+        let proof_bytes = proof.to_bytes();
+        info!("Batch proof length: {} bytes", proof_bytes.len());
+        let proof_from_bytes = BatchProofWithPublicInputs::from_bytes(proof_bytes, &common_data)?;
+        assert_eq!(proof, proof_from_bytes);
+
+        // Changing a public input of any circuit breaks the proof.
+        let mut bad_proof = proof.clone();
+        bad_proof.public_inputs[1][0] += <F as Field>::ONE;
+        assert!(verify_batch(bad_proof, &verifier_data).is_err());
+
+        // So does changing an opening of the smallest circuit.
+        let mut bad_proof = proof.clone();
+        bad_proof.proof.instances[2].openings.wires[0] +=
+            <<F as Extendable<D>>::Extension as Field>::ONE;
+        assert!(verify_batch(bad_proof, &verifier_data).is_err());
+
+        // The circuits must be given in the order they were proven in.
+        let swapped = [verifier_data[1], verifier_data[0], verifier_data[2]];
+        assert!(verify_batch(proof, &swapped).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recursive_batch_verifier() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+
+        let (circuits, witnesses): (Vec<_>, Vec<_>) = [(2_000, false), (100, true)]
+            .into_iter()
+            .map(|(num_dummy_gates, with_lookup)| {
+                dummy_batch_circuit::<F, C, D>(&config, num_dummy_gates, with_lookup)
+            })
+            .unzip();
+        let verifier_data = circuits
+            .iter()
+            .map(|data| data.verifier_data())
+            .collect::<Vec<_>>();
+        let prover_data = circuits
+            .into_iter()
+            .map(|data| data.prover_data())
+            .collect::<Vec<_>>();
+        let common_data = verifier_data
+            .iter()
+            .map(|vd| &vd.common)
+            .collect::<Vec<_>>();
+        let proof = prove_batch(
+            &prover_data.iter().collect::<Vec<_>>(),
+            witnesses,
+            &mut TimingTree::default(),
+        )?;
+
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let mut pw = PartialWitness::new();
+        let pt = builder.add_virtual_batch_proof_with_pis(&common_data);
+        pw.set_batch_proof_with_pis_target(&pt, &proof);
+        let inner_data = verifier_data
+            .iter()
+            .map(|vd| {
+                let vdt = builder.add_virtual_verifier_data(config.fri_config.cap_height);
+                pw.set_verifier_data_target(&vdt, &vd.verifier_only);
+                vdt
+            })
+            .collect::<Vec<_>>();
+        builder.verify_batch_proof::<C>(&pt, &inner_data.iter().collect::<Vec<_>>(), &common_data);
+        builder.print_gate_counts(0);
+
+        let data = builder.build::<C>();
+        let recursive_proof = data.prove(pw)?;
+        data.verify(recursive_proof)
+    }
+
     /// Creates a chain of recursive proofs where the last proof is made as small as reasonably
     /// possible, using a high rate, high PoW bits, etc.
     #[test]
@@ -435,6 +728,39 @@ mod tests {
         Ok((proof, data.verifier_only, data.common))
     }
 
+    /// Creates a circuit with a public input, optionally doing a lookup, padded with
+    /// `num_dummy_gates` no-ops, along with a satisfying witness.
+    fn dummy_batch_circuit<
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        const D: usize,
+    >(
+        config: &CircuitConfig,
+        num_dummy_gates: u64,
+        with_lookup: bool,
+    ) -> (CircuitData<F, C, D>, PartialWitness<F>) {
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let mut pw = PartialWitness::new();
+        let x = builder.add_virtual_target();
+        let x_squared = builder.square(x);
+        builder.register_public_input(x_squared);
+        pw.set_target(x, F::from_canonical_u64(num_dummy_gates));
+
+        if with_lookup {
+            let table: LookupTable = Arc::new((0..256).zip_eq(TIP5_TABLE).collect());
+            let tip5_index = builder.add_lookup_table_from_pairs(table);
+            let input = builder.constant(F::from_canonical_u16(3));
+            let output = builder.add_lookup_from_index(input, tip5_index);
+            builder.register_public_input(output);
+        }
+
+        for _ in 0..num_dummy_gates {
+            builder.add_gate(NoopGate, vec![]);
+        }
+
+        (builder.build::<C>(), pw)
+    }
+
     /// Creates a dummy lookup proof which does one lookup to one LUT.
     fn dummy_lookup_proof<
         F: RichField + Extendable<D>,
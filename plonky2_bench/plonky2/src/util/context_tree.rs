@@ -7,6 +7,17 @@ use alloc::{
 
 use log::{log, Level};
 
+/// Running totals of the resources allocated by a `CircuitBuilder`, recorded when a context is
+/// entered and exited. Rows and copy constraints are appended in order, so the ones allocated
+/// within a context are those between its two snapshots.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct BuilderCounts {
+    pub gates: usize,
+    pub copy_constraints: usize,
+    pub lookups: usize,
+    pub generators: usize,
+}
+
 /// The hierarchy of contexts, and the gate count contributed by each one. Useful for debugging.
 #[derive(Debug)]
 pub(crate) struct ContextTree {
@@ -14,10 +25,10 @@ pub(crate) struct ContextTree {
     name: String,
     /// The level at which to log this scope and its children.
     level: log::Level,
-    /// The gate count when this scope was created.
-    enter_gate_count: usize,
-    /// The gate count when this scope was destroyed, or None if it has not yet been destroyed.
-    exit_gate_count: Option<usize>,
+    /// The builder counts when this scope was created.
+    enter_counts: BuilderCounts,
+    /// The builder counts when this scope was destroyed, or None if it has not yet been destroyed.
+    exit_counts: Option<BuilderCounts>,
     /// Any child contexts.
     children: Vec<ContextTree>,
 }
@@ -27,15 +38,32 @@ impl ContextTree {
         Self {
             name: "root".to_string(),
             level: Level::Debug,
-            enter_gate_count: 0,
-            exit_gate_count: None,
+            enter_counts: BuilderCounts::default(),
+            exit_counts: None,
             children: vec![],
         }
     }
 
     /// Whether this context is still in scope.
     const fn is_open(&self) -> bool {
-        self.exit_gate_count.is_none()
+        self.exit_counts.is_none()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn children(&self) -> &[ContextTree] {
+        &self.children
+    }
+
+    pub const fn enter_counts(&self) -> BuilderCounts {
+        self.enter_counts
+    }
+
+    /// The builder counts when this scope was destroyed, or `current_counts` if it is still open.
+    pub fn exit_counts(&self, current_counts: BuilderCounts) -> BuilderCounts {
+        self.exit_counts.unwrap_or(current_counts)
     }
 
     /// A description of the stack of currently-open scopes.
@@ -54,7 +82,7 @@ impl ContextTree {
         }
     }
 
-    pub fn push(&mut self, ctx: &str, mut level: log::Level, current_gate_count: usize) {
+    pub fn push(&mut self, ctx: &str, mut level: log::Level, current_counts: BuilderCounts) {
         assert!(self.is_open());
 
         // We don't want a scope's log level to be stronger than that of its parent.
@@ -62,7 +90,7 @@ impl ContextTree {
 
         if let Some(last_child) = self.children.last_mut() {
             if last_child.is_open() {
-                last_child.push(ctx, level, current_gate_count);
+                last_child.push(ctx, level, current_counts);
                 return;
             }
         }
@@ -70,28 +98,28 @@ impl ContextTree {
         self.children.push(ContextTree {
             name: ctx.to_string(),
             level,
-            enter_gate_count: current_gate_count,
-            exit_gate_count: None,
+            enter_counts: current_counts,
+            exit_counts: None,
             children: vec![],
         })
     }
 
     /// Close the deepest open context from this tree.
-    pub fn pop(&mut self, current_gate_count: usize) {
+    pub fn pop(&mut self, current_counts: BuilderCounts) {
         assert!(self.is_open());
 
         if let Some(last_child) = self.children.last_mut() {
             if last_child.is_open() {
-                last_child.pop(current_gate_count);
+                last_child.pop(current_counts);
                 return;
             }
         }
 
-        self.exit_gate_count = Some(current_gate_count);
+        self.exit_counts = Some(current_counts);
     }
 
     fn gate_count_delta(&self, current_gate_count: usize) -> usize {
-        self.exit_gate_count.unwrap_or(current_gate_count) - self.enter_gate_count
+        self.exit_counts.map_or(current_gate_count, |c| c.gates) - self.enter_counts.gates
     }
 
     /// Filter out children with a low gate count.
@@ -99,8 +127,8 @@ impl ContextTree {
         Self {
             name: self.name.clone(),
             level: self.level,
-            enter_gate_count: self.enter_gate_count,
-            exit_gate_count: self.exit_gate_count,
+            enter_counts: self.enter_counts,
+            exit_counts: self.exit_counts,
             children: self
                 .children
                 .iter()
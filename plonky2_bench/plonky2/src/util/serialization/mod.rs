@@ -48,8 +48,9 @@ use crate::plonk::circuit_data::{
 use crate::plonk::config::{GenericConfig, GenericHashOut, Hasher};
 use crate::plonk::plonk_common::salt_size;
 use crate::plonk::proof::{
-    CompressedProof, CompressedProofWithPublicInputs, OpeningSet, OpeningSetTarget, Proof,
-    ProofTarget, ProofWithPublicInputs, ProofWithPublicInputsTarget,
+    BatchProof, BatchProofInstance, BatchProofWithPublicInputs, CompressedProof,
+    CompressedProofWithPublicInputs, OpeningSet, OpeningSetTarget, Proof, ProofTarget,
+    ProofWithPublicInputs, ProofWithPublicInputsTarget,
 };
 
 /// A no_std compatible variant of `std::io::Error`
@@ -1026,6 +1027,78 @@ pub trait Read {
         })
     }
 
+    /// Reads a value of type [`BatchProofWithPublicInputs`] from `self` with the `common_data` of
+    /// each circuit of the batch.
+    #[inline]
+    fn read_batch_proof_with_public_inputs<F, C, const D: usize>(
+        &mut self,
+        common_data: &[&CommonCircuitData<F, D>],
+    ) -> IoResult<BatchProofWithPublicInputs<F, C, D>>
+    where
+        Self: Remaining,
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let fri_params = CommonCircuitData::batch_fri_params(common_data).map_err(|_| IoError)?;
+        let cap_height = fri_params.config.cap_height;
+
+        let mut instances = Vec::with_capacity(common_data.len());
+        for cd in common_data {
+            let wires_cap = self.read_merkle_cap(cap_height)?;
+            let plonk_zs_partial_products_cap = self.read_merkle_cap(cap_height)?;
+            let quotient_polys_cap = self.read_merkle_cap(cap_height)?;
+            let openings = self.read_opening_set::<F, C, D>(cd)?;
+            instances.push(BatchProofInstance {
+                wires_cap,
+                plonk_zs_partial_products_cap,
+                quotient_polys_cap,
+                openings,
+            });
+        }
+
+        let commit_phase_merkle_caps = (0..fri_params.reduction_arity_bits.len())
+            .map(|_| self.read_merkle_cap(cap_height))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut query_round_proofs = Vec::with_capacity(fri_params.config.num_query_rounds);
+        for _ in 0..fri_params.config.num_query_rounds {
+            let mut evals_proofs = Vec::new();
+            for cd in common_data {
+                evals_proofs.extend(self.read_fri_initial_proof::<F, C, D>(cd)?.evals_proofs);
+            }
+            let steps = fri_params
+                .reduction_arity_bits
+                .iter()
+                .map(|&ar| self.read_fri_query_step::<F, C, D>(1 << ar, false))
+                .collect::<Result<_, _>>()?;
+            query_round_proofs.push(FriQueryRound {
+                initial_trees_proof: FriInitialTreeProof { evals_proofs },
+                steps,
+            });
+        }
+        let final_poly =
+            PolynomialCoeffs::new(self.read_field_ext_vec::<F, D>(fri_params.final_poly_len())?);
+        let pow_witness = self.read_field()?;
+
+        let mut public_inputs = Vec::with_capacity(common_data.len());
+        for _ in common_data {
+            let pi_len = self.read_usize()?;
+            public_inputs.push(self.read_field_vec(pi_len)?);
+        }
+
+        Ok(BatchProofWithPublicInputs {
+            proof: BatchProof {
+                instances,
+                opening_proof: FriProof {
+                    commit_phase_merkle_caps,
+                    query_round_proofs,
+                    final_poly,
+                    pow_witness,
+                },
+            },
+            public_inputs,
+        })
+    }
+
     /// Reads a value of type [`ProofWithPublicInputsTarget`] from `self`.
     #[inline]
     fn read_target_proof_with_public_inputs<const D: usize>(
@@ -2015,6 +2088,34 @@ pub trait Write {
         self.write_field_vec(public_inputs)
     }
 
+    /// Writes a value `proof_with_pis` of type [`BatchProofWithPublicInputs`] to `self.`
+    #[inline]
+    fn write_batch_proof_with_public_inputs<F, C, const D: usize>(
+        &mut self,
+        proof_with_pis: &BatchProofWithPublicInputs<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let BatchProofWithPublicInputs {
+            proof,
+            public_inputs,
+        } = proof_with_pis;
+        for instance in &proof.instances {
+            self.write_merkle_cap(&instance.wires_cap)?;
+            self.write_merkle_cap(&instance.plonk_zs_partial_products_cap)?;
+            self.write_merkle_cap(&instance.quotient_polys_cap)?;
+            self.write_opening_set(&instance.openings)?;
+        }
+        self.write_fri_proof::<F, C, D>(&proof.opening_proof)?;
+        for pis in public_inputs {
+            self.write_usize(pis.len())?;
+            self.write_field_vec(pis)?;
+        }
+        Ok(())
+    }
+
     /// Writes a value `proof_with_pis` of type [`ProofWithPublicInputsTarget`] to `self.`
     #[inline]
     fn write_target_proof_with_public_inputs<const D: usize>(
@@ -9,8 +9,10 @@ use alloc::format;
 
 use anyhow::{anyhow, Result};
 use plonky2::field::extension::Extendable;
-use plonky2::field::types::Field;
 use plonky2::fri::reduction_strategies::FriReductionStrategy;
+#[cfg(feature = "std")]
+use plonky2::fri::security::provable_security_bits;
+use plonky2::fri::security::{conjectured_security_bits, fri_field_bits};
 use plonky2::fri::{FriConfig, FriParams};
 use plonky2::hash::hash_types::RichField;
 
@@ -81,9 +83,12 @@ impl StarkConfig {
         } = &self;
 
         // Conjectured FRI security; see the ethSTARK paper.
-        let fri_field_bits = F::Extension::order().bits() as usize;
-        let fri_query_security_bits = num_query_rounds * rate_bits + *proof_of_work_bits as usize;
-        let fri_security_bits = fri_field_bits.min(fri_query_security_bits);
+        let fri_security_bits = conjectured_security_bits(
+            fri_field_bits::<F, D>(),
+            *rate_bits,
+            *num_query_rounds,
+            *proof_of_work_bits,
+        );
 
         if fri_security_bits < *security_bits {
             Err(anyhow!(format!(
@@ -94,6 +99,21 @@ impl StarkConfig {
             Ok(())
         }
     }
+
+    /// Bits of security of the FRI sub-protocol in the provable (Johnson bound) regime, for a
+    /// STARK of degree `2^degree_bits` whose proof opens `num_polys` polynomials.
+    #[cfg(feature = "std")]
+    pub fn provable_security_bits<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        degree_bits: usize,
+        num_polys: usize,
+    ) -> f64 {
+        provable_security_bits(
+            fri_field_bits::<F, D>(),
+            &self.fri_params(degree_bits),
+            num_polys,
+        )
+    }
 }
 
 #[cfg(test)]
@@ -144,4 +164,19 @@ mod tests {
         // bits of security for FRI, which falls short of the 100 bits of security target.
         assert!(too_few_queries_config.check_config::<F, D>().is_err());
     }
+
+    #[test]
+    fn test_provable_security() {
+        type F = GoldilocksField;
+        const D: usize = 2;
+
+        // The default configuration only targets conjectured security.
+        let config = StarkConfig::standard_fast_config();
+        let provable = config.provable_security_bits::<F, D>(20, 100);
+        assert!(provable < config.security_bits as f64);
+
+        let mut more_queries_config = config.clone();
+        more_queries_config.fri_config.num_query_rounds *= 2;
+        assert!(more_queries_config.provable_security_bits::<F, D>(20, 100) > provable);
+    }
 }
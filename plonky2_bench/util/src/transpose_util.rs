@@ -1,3 +1,5 @@
+//! Cache-friendly transposes of square matrices stored in a slice, blocked recursively.
+
 use core::ptr::swap;
 
 const LB_BLOCK_SIZE: usize = 3;
@@ -91,10 +93,10 @@ unsafe fn transpose_swap_square<T>(
 /// `M[i, j] == arr[(i + x << lb_stride) + j + x]` for `0 <= i, j < 1 << lb_size`. The transposition
 /// swaps `M[i, j]` and `M[j, i]`.
 ///
-/// SAFETY:
+/// # Safety
 /// Make sure that `(i + x << lb_stride) + j + x` is a valid index in `arr` for all
 /// `0 <= i, j < 1 << lb_size`. Ensure also that `lb_size <= lb_stride` to prevent overlap.
-pub(crate) unsafe fn transpose_in_place_square<T>(
+pub unsafe fn transpose_in_place_square<T>(
     arr: &mut [T],
     lb_stride: usize,
     lb_size: usize,